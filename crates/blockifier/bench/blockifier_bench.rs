@@ -4,32 +4,41 @@
 //!
 //! The main benchmark function is `transfers_benchmark`, which measures the performance
 //! of transfers between randomly created accounts, which are iterated over round-robin.
+//! The other benchmarks cover nested calls, storage accesses, class loading and state diff
+//! computation.
 //!
 //! Run the benchmarks using `cargo bench --bench blockifier_bench`.
 
 use blockifier::abi::abi_utils::selector_from_name;
 use blockifier::context::BlockContext;
+use blockifier::execution::contract_class::{ContractClassV0, ContractClassV1};
 use blockifier::invoke_tx_args;
 use blockifier::state::cached_state::CachedState;
+use blockifier::state::state_api::State;
 use blockifier::test_utils::contracts::FeatureContract;
 use blockifier::test_utils::dict_state_reader::DictStateReader;
 use blockifier::test_utils::initial_test_state::test_state;
 use blockifier::test_utils::invoke::invoke_tx;
-use blockifier::test_utils::{CairoVersion, NonceManager, BALANCE, MAX_FEE};
+use blockifier::test_utils::{create_calldata, CairoVersion, NonceManager, BALANCE, MAX_FEE};
 use blockifier::transaction::account_transaction::AccountTransaction;
+use blockifier::transaction::test_utils::{create_test_init_data, TestInitData};
 use blockifier::transaction::transactions::ExecutableTransaction;
 use criterion::{criterion_group, criterion_main, Criterion};
 use rand::{Rng, SeedableRng};
-use starknet_api::core::ContractAddress;
-use starknet_api::hash::StarkFelt;
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
 use starknet_api::transaction::{Calldata, Fee, TransactionVersion};
-use starknet_api::{calldata, stark_felt};
+use starknet_api::{calldata, contract_address, patricia_key, stark_felt};
 
 const N_ACCOUNTS: u16 = 10000;
 const RANDOMIZATION_SEED: u64 = 0;
 const CHARGE_FEE: bool = false;
 const RUN_VALIDATION: bool = false;
 const TRANSACTION_VERSION: TransactionVersion = TransactionVersion(StarkFelt::ONE);
+const RECURSION_DEPTH: u8 = 10;
+const N_CONTRACTS: u128 = 100;
+const N_STORAGE_UPDATES_PER_CONTRACT: u128 = 100;
 
 pub fn transfers_benchmark(c: &mut Criterion) {
     let account_contract = FeatureContract::AccountWithLongValidate(CairoVersion::Cairo0);
@@ -108,5 +117,117 @@ fn do_transfer(
     account_tx.execute(state, block_context, charge_fee, validate).unwrap();
 }
 
-criterion_group!(benches, transfers_benchmark);
+/// Executes an invoke transaction of the given account, without fee and validation.
+fn do_invoke(
+    sender_address: ContractAddress,
+    execute_calldata: Calldata,
+    nonce_manager: &mut NonceManager,
+    block_context: &BlockContext,
+    state: &mut CachedState<DictStateReader>,
+) {
+    let tx = invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address,
+        calldata: execute_calldata,
+        version: TRANSACTION_VERSION,
+        nonce: nonce_manager.next(sender_address),
+    });
+    let account_tx = AccountTransaction::Invoke(tx);
+    account_tx.execute(state, block_context, CHARGE_FEE, RUN_VALIDATION).unwrap();
+}
+
+/// Measures transactions whose call to a Cairo 1 contract recurses through `call_contract`
+/// syscalls.
+pub fn nested_calls_benchmark(c: &mut Criterion) {
+    let block_context = &BlockContext::create_for_account_testing();
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(block_context.chain_info(), CairoVersion::Cairo1);
+    let execute_calldata = create_calldata(
+        contract_address,
+        "recursive_syscall",
+        &[
+            *contract_address.0.key(),
+            selector_from_name("recursive_syscall").0,
+            stark_felt!(RECURSION_DEPTH),
+        ],
+    );
+
+    c.bench_function("nested_calls", |benchmark| {
+        benchmark.iter(|| {
+            do_invoke(
+                account_address,
+                execute_calldata.clone(),
+                &mut nonce_manager,
+                block_context,
+                &mut state,
+            )
+        })
+    });
+}
+
+/// Measures transactions that write and read a storage key of a Cairo 1 contract, a new key at
+/// each iteration.
+pub fn storage_read_write_benchmark(c: &mut Criterion) {
+    let block_context = &BlockContext::create_for_account_testing();
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(block_context.chain_info(), CairoVersion::Cairo1);
+
+    let mut key: u128 = 0;
+    c.bench_function("storage_read_write", |benchmark| {
+        benchmark.iter(|| {
+            key += 1;
+            let execute_calldata = create_calldata(
+                contract_address,
+                "test_storage_read_write",
+                &[stark_felt!(key), stark_felt!(1_u8)],
+            );
+            do_invoke(
+                account_address,
+                execute_calldata,
+                &mut nonce_manager,
+                block_context,
+                &mut state,
+            )
+        })
+    });
+}
+
+/// Measures the deserialization of compiled contract classes, as done on class cache misses.
+pub fn class_loading_benchmark(c: &mut Criterion) {
+    let raw_class_v0 = FeatureContract::TestContract(CairoVersion::Cairo0).get_raw_class();
+    c.bench_function("class_loading_cairo0", |benchmark| {
+        benchmark.iter(|| ContractClassV0::try_from_json_string(&raw_class_v0).unwrap())
+    });
+    let raw_class_v1 = FeatureContract::TestContract(CairoVersion::Cairo1).get_raw_class();
+    c.bench_function("class_loading_cairo1", |benchmark| {
+        benchmark.iter(|| ContractClassV1::try_from_json_string(&raw_class_v1).unwrap())
+    });
+}
+
+/// Measures the computation of the state diff of a state with many storage updates.
+pub fn state_diff_benchmark(c: &mut Criterion) {
+    let mut state = CachedState::from(DictStateReader::default());
+    for address in 0..N_CONTRACTS {
+        for key in 0..N_STORAGE_UPDATES_PER_CONTRACT {
+            state
+                .set_storage_at(
+                    contract_address!(address),
+                    StorageKey(patricia_key!(key)),
+                    stark_felt!(key + 1),
+                )
+                .unwrap();
+        }
+    }
+
+    c.bench_function("state_diff", |benchmark| benchmark.iter(|| state.to_state_diff()));
+}
+
+criterion_group!(
+    benches,
+    transfers_benchmark,
+    nested_calls_benchmark,
+    storage_read_write_benchmark,
+    class_loading_benchmark,
+    state_diff_benchmark
+);
 criterion_main!(benches);
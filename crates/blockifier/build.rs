@@ -0,0 +1,9 @@
+fn main() {
+    // The protobuf types are generated only with the `proto` feature, which requires `protoc`.
+    #[cfg(feature = "proto")]
+    {
+        println!("cargo:rerun-if-changed=proto/execution.proto");
+        prost_build::compile_protos(&["proto/execution.proto"], &["proto/"])
+            .expect("Failed to compile the protobuf definitions.");
+    }
+}
@@ -0,0 +1,8 @@
+#![no_main]
+
+use blockifier::test_utils::state_model::{check_cached_state_operations, StateOperation};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    check_cached_state_operations(&StateOperation::decode_all(data));
+});
@@ -49,5 +49,9 @@ pub const N_STEPS_PER_PEDERSEN: usize = 8;
 //      pub static BLOCK_HASH_CONTRACT_ADDRESS: Lazy<ContractAddress> = ...
 pub const BLOCK_HASH_CONTRACT_ADDRESS: u64 = 1;
 
+// This contract stores the aliases of contract addresses and storage keys, used to compress state
+// diffs (see `stateful_compression`).
+pub const ALIAS_CONTRACT_ADDRESS: u64 = 2;
+
 // The block number -> block hash mapping is written for the current block number minus this number.
 pub const STORED_BLOCK_HASH_BUFFER: u64 = 10;
@@ -1,4 +1,8 @@
 pub mod block;
 pub mod config;
+pub mod execution_cache;
+pub mod os_input;
+pub mod replay;
 pub mod stateful_validator;
 pub mod transaction_executor;
+pub mod view_call;
@@ -1,12 +1,18 @@
 use std::num::NonZeroU128;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use cairo_felt::Felt252;
 use starknet_api::block::{BlockHash, BlockNumber, BlockTimestamp};
-use starknet_api::core::ContractAddress;
+use starknet_api::core::{ContractAddress, L2_ADDRESS_UPPER_BOUND};
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
+use thiserror::Error;
 
 use crate::abi::constants;
-use crate::context::{BlockContext, ChainInfo};
+use crate::context::{BlockContext, CancellationToken, ChainInfo};
+use crate::execution::entry_point::ReentrancyPolicy;
+use crate::execution::execution_utils::stark_felt_to_felt;
 use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateResult};
 use crate::transaction::objects::FeeType;
@@ -27,6 +33,105 @@ pub struct BlockInfo {
     pub use_kzg_da: bool,
 }
 
+impl BlockInfo {
+    /// Overrides the block timestamp with the one given by the clock for this block number.
+    pub fn with_timestamp_from(self, clock: &dyn Clock) -> Self {
+        Self { block_timestamp: clock.block_timestamp(self.block_number), ..self }
+    }
+
+    /// Sanity-checks the block info, against that of the previous block (if any): block numbers
+    /// must be consecutive, timestamps non-decreasing, and the sequencer address a valid L2
+    /// address. Note that gas prices are nonzero by construction.
+    pub fn validate(&self, previous_block_info: Option<&BlockInfo>) -> BlockInfoResult<()> {
+        if let Some(previous_block_info) = previous_block_info {
+            let previous_block_number = previous_block_info.block_number;
+            if previous_block_number.0.checked_add(1) != Some(self.block_number.0) {
+                return Err(BlockInfoError::NonConsecutiveBlockNumber {
+                    block_number: self.block_number,
+                    previous_block_number,
+                });
+            }
+
+            let previous_block_timestamp = previous_block_info.block_timestamp;
+            if self.block_timestamp.0 < previous_block_timestamp.0 {
+                return Err(BlockInfoError::DecreasingBlockTimestamp {
+                    block_timestamp: self.block_timestamp,
+                    previous_block_timestamp,
+                });
+            }
+        }
+
+        let l2_address_upper_bound = Felt252::from_bytes_be(&L2_ADDRESS_UPPER_BOUND.to_bytes_be());
+        if stark_felt_to_felt(*self.sequencer_address.0.key()) >= l2_address_upper_bound {
+            return Err(BlockInfoError::SequencerAddressOutOfRange(self.sequencer_address));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum BlockInfoError {
+    #[error(
+        "Block number {block_number:?} does not follow the previous block number \
+         {previous_block_number:?}."
+    )]
+    NonConsecutiveBlockNumber { block_number: BlockNumber, previous_block_number: BlockNumber },
+    #[error(
+        "Block timestamp {block_timestamp:?} precedes the previous block timestamp \
+         {previous_block_timestamp:?}."
+    )]
+    DecreasingBlockTimestamp {
+        block_timestamp: BlockTimestamp,
+        previous_block_timestamp: BlockTimestamp,
+    },
+    #[error("Sequencer address {0:?} is out of the range of L2 addresses.")]
+    SequencerAddressOutOfRange(ContractAddress),
+}
+
+pub type BlockInfoResult<T> = Result<T, BlockInfoError>;
+
+/// A source of block timestamps.
+/// Injecting a deterministic implementation (e.g., in devnets and tests) makes block creation, and
+/// thus execution, reproducible.
+pub trait Clock {
+    fn block_timestamp(&self, block_number: BlockNumber) -> BlockTimestamp;
+}
+
+/// Reads the block timestamp from the system time; unavailable on `wasm32-unknown-unknown`, which
+/// has no system clock.
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+impl Clock for SystemClock {
+    fn block_timestamp(&self, _block_number: BlockNumber) -> BlockTimestamp {
+        let seconds_since_epoch = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("System time must be later than the UNIX epoch.")
+            .as_secs();
+        BlockTimestamp(seconds_since_epoch)
+    }
+}
+
+/// Derives the block timestamp from the block number alone:
+/// `genesis_timestamp + block_number * block_time` (saturating on overflow).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DeterministicClock {
+    pub genesis_timestamp: BlockTimestamp,
+    // In seconds.
+    pub block_time: u64,
+}
+
+impl Clock for DeterministicClock {
+    fn block_timestamp(&self, block_number: BlockNumber) -> BlockTimestamp {
+        BlockTimestamp(
+            self.genesis_timestamp.0.saturating_add(block_number.0.saturating_mul(self.block_time)),
+        )
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct GasPrices {
     pub eth_l1_gas_price: NonZeroU128,       // In wei.
@@ -81,7 +186,19 @@ pub fn pre_process_block(
         return Err(StateError::OldBlockHashNotProvided);
     }
 
-    Ok(BlockContext { block_info, chain_info, versioned_constants, concurrency_mode })
+    Ok(BlockContext {
+        block_info,
+        chain_info,
+        versioned_constants,
+        concurrency_mode,
+        disable_fee_charge: false,
+        cancellation_token: CancellationToken::default(),
+        profiler: None,
+        event_sink: None,
+        reentrancy_policy: ReentrancyPolicy::default(),
+        skip_compiled_class_hash_verification: false,
+        dev_mode: false,
+    })
 }
 
 pub struct BlockNumberHashPair {
@@ -1,10 +1,14 @@
-use starknet_api::block::BlockNumber;
-use starknet_api::core::ContractAddress;
-use starknet_api::hash::StarkFelt;
+use assert_matches::assert_matches;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
 use starknet_api::state::StorageKey;
+use starknet_api::{contract_address, patricia_key};
 
 use crate::abi::constants;
-use crate::blockifier::block::{pre_process_block, BlockInfo, BlockNumberHashPair};
+use crate::blockifier::block::{
+    pre_process_block, BlockInfo, BlockInfoError, BlockNumberHashPair, Clock, DeterministicClock,
+};
 use crate::context::ChainInfo;
 use crate::state::state_api::StateReader;
 use crate::test_utils::contracts::FeatureContract;
@@ -73,3 +77,59 @@ fn test_pre_process_block() {
         format!("{}", error.unwrap_err())
     );
 }
+
+#[test]
+fn test_deterministic_clock() {
+    let clock = DeterministicClock { genesis_timestamp: BlockTimestamp(1000), block_time: 6 };
+    assert_eq!(clock.block_timestamp(BlockNumber(0)), BlockTimestamp(1000));
+    assert_eq!(clock.block_timestamp(BlockNumber(10)), BlockTimestamp(1060));
+    assert_eq!(clock.block_timestamp(BlockNumber(u64::MAX)), BlockTimestamp(u64::MAX));
+
+    // Block info created twice with the same clock is identical.
+    let block_info = BlockInfo::create_for_testing().with_timestamp_from(&clock);
+    let expected_timestamp = clock.block_timestamp(block_info.block_number);
+    assert_eq!(block_info.block_timestamp, expected_timestamp);
+    assert_eq!(
+        BlockInfo::create_for_testing().with_timestamp_from(&clock).block_timestamp,
+        expected_timestamp
+    );
+}
+
+#[test]
+fn test_block_info_validation() {
+    let previous_block_info = BlockInfo::create_for_testing();
+    let block_info = BlockInfo {
+        block_number: BlockNumber(previous_block_info.block_number.0 + 1),
+        ..BlockInfo::create_for_testing()
+    };
+    block_info.validate(None).unwrap();
+    block_info.validate(Some(&previous_block_info)).unwrap();
+
+    // Block numbers must be consecutive.
+    assert_matches!(
+        previous_block_info.validate(Some(&previous_block_info)).unwrap_err(),
+        BlockInfoError::NonConsecutiveBlockNumber { .. }
+    );
+
+    // Timestamps must not decrease.
+    let earlier_block_info = BlockInfo {
+        block_timestamp: BlockTimestamp(previous_block_info.block_timestamp.0 - 1),
+        ..block_info.clone()
+    };
+    assert_matches!(
+        earlier_block_info.validate(Some(&previous_block_info)).unwrap_err(),
+        BlockInfoError::DecreasingBlockTimestamp { .. }
+    );
+
+    // The sequencer address must be below the L2 address upper bound (2^251 - 256).
+    let invalid_sequencer_block_info = BlockInfo {
+        sequencer_address: contract_address!(
+            "0x7ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+        ),
+        ..block_info
+    };
+    assert_matches!(
+        invalid_sequencer_block_info.validate(None).unwrap_err(),
+        BlockInfoError::SequencerAddressOutOfRange(_)
+    );
+}
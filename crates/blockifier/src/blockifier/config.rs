@@ -1,6 +1,14 @@
 #[derive(Debug, Default, Clone)]
 pub struct TransactionExecutorConfig {
     pub concurrency_config: ConcurrencyConfig,
+    /// If set, transactions whose hash was already executed in the current block are skipped.
+    pub skip_duplicate_txs: bool,
+    /// If set, the input of the Starknet OS for proving the block is collected during execution,
+    /// and returned on finalization.
+    pub collect_os_input: bool,
+    /// If set, query transactions (see `TransactionInfo::only_query`) are rejected; e.g., when
+    /// building a block, as opposed to simulation and fee estimation.
+    pub reject_query_txs: bool,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -0,0 +1,65 @@
+use std::sync::{Arc, Mutex};
+
+use indexmap::IndexMap;
+use starknet_api::hash::StarkHash;
+use starknet_api::transaction::{TransactionHash, TransactionSignature};
+
+use crate::transaction::objects::TransactionExecutionInfo;
+
+#[cfg(test)]
+#[path = "execution_cache_test.rs"]
+pub mod test;
+
+/// Identifies the state (and block context) transactions are simulated on; e.g., the state root of
+/// the parent block, or the hash of the pending block.
+pub type BaseStateId = StarkHash;
+
+/// The parameters that determine the execution info of a simulated transaction.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct SimulationKey {
+    pub tx_hash: TransactionHash,
+    /// Not covered by the transaction hash, but checked by the account validation.
+    pub signature: TransactionSignature,
+    pub base_state_id: BaseStateId,
+    pub charge_fee: bool,
+    pub validate: bool,
+}
+
+/// Memoizes the execution infos of simulated transactions, so that repeated simulations (or fee
+/// estimations) of the same transaction on the same state are not re-executed.
+/// Shared between executors (and threads); holds up to `capacity` entries, evicting the oldest.
+#[derive(Debug)]
+pub struct ExecutionCache {
+    capacity: usize,
+    entries: Mutex<IndexMap<SimulationKey, Arc<TransactionExecutionInfo>>>,
+}
+
+impl ExecutionCache {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, entries: Mutex::new(IndexMap::with_capacity(capacity)) }
+    }
+
+    pub fn get(&self, key: &SimulationKey) -> Option<Arc<TransactionExecutionInfo>> {
+        self.entries.lock().expect("Execution cache lock is poisoned.").get(key).cloned()
+    }
+
+    pub fn insert(&self, key: SimulationKey, tx_execution_info: Arc<TransactionExecutionInfo>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().expect("Execution cache lock is poisoned.");
+        if entries.len() >= self.capacity && !entries.contains_key(&key) {
+            entries.shift_remove_index(0);
+        }
+        entries.insert(key, tx_execution_info);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("Execution cache lock is poisoned.").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::{TransactionHash, TransactionSignature};
+
+use crate::blockifier::execution_cache::{ExecutionCache, SimulationKey};
+use crate::transaction::objects::TransactionExecutionInfo;
+
+fn simulation_key(tx_hash: u8) -> SimulationKey {
+    SimulationKey {
+        tx_hash: TransactionHash(stark_felt!(tx_hash)),
+        signature: TransactionSignature::default(),
+        base_state_id: stark_felt!(1_u8),
+        charge_fee: true,
+        validate: true,
+    }
+}
+
+#[test]
+fn test_execution_cache_eviction() {
+    let execution_cache = ExecutionCache::new(2);
+    let tx_execution_info = Arc::new(TransactionExecutionInfo::default());
+    for tx_hash in 0..3 {
+        execution_cache.insert(simulation_key(tx_hash), tx_execution_info.clone());
+    }
+
+    // The oldest entry is evicted.
+    assert_eq!(execution_cache.len(), 2);
+    assert!(execution_cache.get(&simulation_key(0)).is_none());
+    assert!(Arc::ptr_eq(&execution_cache.get(&simulation_key(2)).unwrap(), &tx_execution_info));
+
+    // Reinserting an entry does not evict another one.
+    execution_cache.insert(simulation_key(1), tx_execution_info.clone());
+    assert_eq!(execution_cache.len(), 2);
+
+    // A cache with no capacity stores nothing.
+    let disabled_cache = ExecutionCache::new(0);
+    disabled_cache.insert(simulation_key(0), tx_execution_info);
+    assert!(disabled_cache.is_empty());
+}
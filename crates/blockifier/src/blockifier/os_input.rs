@@ -0,0 +1,68 @@
+use std::collections::{HashMap, HashSet};
+
+use starknet_api::core::{ClassHash, ContractAddress, EthAddress};
+use starknet_api::transaction::{L2ToL1Payload, TransactionHash};
+
+use crate::execution::contract_class::ContractClass;
+use crate::state::cached_state::StateMaps;
+use crate::transaction::objects::TransactionExecutionInfo;
+
+/// A message sent to L1 by a contract.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct L2ToL1Message {
+    pub from_address: ContractAddress,
+    pub to_address: EthAddress,
+    pub payload: L2ToL1Payload,
+}
+
+/// The input of the Starknet OS for proving a block, collected while executing it (see
+/// `TransactionExecutorConfig::collect_os_input`). Messages from L1 are given by the L1 handler
+/// transactions of the block.
+#[derive(Debug, Default)]
+pub struct OsInput {
+    /// The values of the state cells accessed by the block, prior to the block.
+    pub initial_reads: StateMaps,
+    /// The writes of each transaction included in the block, in execution order.
+    pub tx_state_maps: Vec<(TransactionHash, StateMaps)>,
+    /// The writes made to the alias contract at the end of the block, following the transactions
+    /// (see `allocate_aliases`); empty without stateful compression.
+    pub alias_writes: StateMaps,
+    /// The PCs visited in each executed class.
+    pub visited_pcs: HashMap<ClassHash, HashSet<usize>>,
+    /// The compiled classes executed or declared in the block.
+    pub compiled_classes: HashMap<ClassHash, ContractClass>,
+    /// The messages sent to L1 by the transactions, in execution order.
+    pub l2_to_l1_messages: Vec<L2ToL1Message>,
+}
+
+impl OsInput {
+    /// Records a transaction included in the block, given its writes.
+    pub(crate) fn add_tx(
+        &mut self,
+        tx_hash: TransactionHash,
+        state_maps: StateMaps,
+        tx_execution_info: &TransactionExecutionInfo,
+    ) {
+        self.tx_state_maps.push((tx_hash, state_maps));
+
+        // Messages are ordered within each top-level call.
+        for call_info in tx_execution_info.non_optional_call_infos() {
+            let mut messages: Vec<_> = call_info
+                .iter()
+                .flat_map(|inner_call_info| {
+                    let from_address = inner_call_info.call.storage_address;
+                    inner_call_info.execution.l2_to_l1_messages.iter().map(move |message| {
+                        let l2_to_l1_message = L2ToL1Message {
+                            from_address,
+                            to_address: message.message.to_address,
+                            payload: message.message.payload.clone(),
+                        };
+                        (message.order, l2_to_l1_message)
+                    })
+                })
+                .collect();
+            messages.sort_by_key(|(order, _)| *order);
+            self.l2_to_l1_messages.extend(messages.into_iter().map(|(_, message)| message));
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! Deterministic replay of historical blocks: executes the transactions of a block on its
+//! pre-state, with the versioned constants of its Starknet version, and compares the receipts and
+//! the state diff to the on-chain ones. The block data and a reader of its pre-state are provided
+//! by the caller, e.g., fetched from a full node.
+
+use std::hash::Hash;
+
+use indexmap::IndexMap;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{Fee, TransactionHash};
+
+use crate::blockifier::block::{pre_process_block, BlockInfo, BlockNumberHashPair};
+use crate::blockifier::config::TransactionExecutorConfig;
+use crate::blockifier::transaction_executor::{TransactionExecutor, TransactionExecutorResult};
+use crate::bouncer::BouncerConfig;
+use crate::context::ChainInfo;
+use crate::state::cached_state::{CachedState, CommitmentStateDiff};
+use crate::state::state_api::StateReader;
+use crate::transaction::transaction_execution::Transaction;
+use crate::versioned_constants::{StarknetVersion, VersionedConstants};
+
+#[cfg(test)]
+#[path = "replay_test.rs"]
+pub mod test;
+
+/// A transaction of a historical block, with its on-chain receipt.
+#[derive(Debug)]
+pub struct ReplayTransaction {
+    pub tx: Transaction,
+    pub actual_fee: Fee,
+    pub reverted: bool,
+}
+
+/// A historical block, with its on-chain results.
+#[derive(Debug)]
+pub struct ReplayBlock {
+    pub block_info: BlockInfo,
+    pub chain_info: ChainInfo,
+    pub starknet_version: StarknetVersion,
+    pub old_block_number_and_hash: Option<BlockNumberHashPair>,
+    pub txs: Vec<ReplayTransaction>,
+    pub state_diff: CommitmentStateDiff,
+}
+
+/// A difference between the replayed block and the on-chain one.
+/// In state diff mismatches, `None` stands for an entry missing from the diff.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ReplayMismatch {
+    /// A transaction included in the block failed, and is therefore excluded from the replay.
+    TransactionFailed {
+        tx_hash: TransactionHash,
+        error: String,
+    },
+    RevertStatus {
+        tx_hash: TransactionHash,
+        expected_reverted: bool,
+        revert_error: Option<String>,
+    },
+    ActualFee {
+        tx_hash: TransactionHash,
+        expected: Fee,
+        actual: Fee,
+    },
+    ClassHash {
+        address: ContractAddress,
+        expected: Option<ClassHash>,
+        actual: Option<ClassHash>,
+    },
+    Nonce {
+        address: ContractAddress,
+        expected: Option<Nonce>,
+        actual: Option<Nonce>,
+    },
+    StorageValue {
+        address: ContractAddress,
+        key: StorageKey,
+        expected: Option<StarkFelt>,
+        actual: Option<StarkFelt>,
+    },
+    CompiledClassHash {
+        class_hash: ClassHash,
+        expected: Option<CompiledClassHash>,
+        actual: Option<CompiledClassHash>,
+    },
+}
+
+/// Replays the given block on top of its pre-state, and returns the mismatches with the on-chain
+/// results, in transaction order, followed by the state diff mismatches.
+pub fn replay_block<S: StateReader>(
+    pre_state_reader: S,
+    block: ReplayBlock,
+) -> TransactionExecutorResult<Vec<ReplayMismatch>> {
+    let mut state = CachedState::from(pre_state_reader);
+    let block_context = pre_process_block(
+        &mut state,
+        block.old_block_number_and_hash,
+        block.block_info,
+        block.chain_info,
+        VersionedConstants::get(block.starknet_version).clone(),
+        false,
+    )?;
+    // The block was already sealed on-chain; its capacity is not rechecked.
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::max(),
+        TransactionExecutorConfig::default(),
+    );
+
+    let mut mismatches = vec![];
+    for ReplayTransaction { tx, actual_fee: expected_fee, reverted } in &block.txs {
+        let tx_hash = tx.tx_hash();
+        let charge_fee = true;
+        let tx_execution_info = match tx_executor.execute(tx, charge_fee) {
+            Ok(tx_execution_info) => tx_execution_info,
+            Err(error) => {
+                mismatches
+                    .push(ReplayMismatch::TransactionFailed { tx_hash, error: error.to_string() });
+                continue;
+            }
+        };
+        if tx_execution_info.is_reverted() != *reverted {
+            mismatches.push(ReplayMismatch::RevertStatus {
+                tx_hash,
+                expected_reverted: *reverted,
+                revert_error: tx_execution_info.revert_error,
+            });
+        }
+        if tx_execution_info.actual_fee != *expected_fee {
+            mismatches.push(ReplayMismatch::ActualFee {
+                tx_hash,
+                expected: *expected_fee,
+                actual: tx_execution_info.actual_fee,
+            });
+        }
+    }
+
+    let state_diff = tx_executor.finalize()?.state_diff;
+    mismatches.extend(state_diff_mismatches(&block.state_diff, &state_diff));
+    Ok(mismatches)
+}
+
+fn state_diff_mismatches(
+    expected: &CommitmentStateDiff,
+    actual: &CommitmentStateDiff,
+) -> Vec<ReplayMismatch> {
+    let flat_storage_updates = |state_diff: &CommitmentStateDiff| -> IndexMap<_, _> {
+        state_diff
+            .storage_updates
+            .iter()
+            .flat_map(|(address, updates)| {
+                updates.iter().map(move |(key, value)| ((*address, *key), *value))
+            })
+            .collect()
+    };
+
+    let mut mismatches = vec![];
+    mismatches.extend(
+        map_mismatches(&expected.address_to_class_hash, &actual.address_to_class_hash).map(
+            |(address, expected, actual)| ReplayMismatch::ClassHash { address, expected, actual },
+        ),
+    );
+    mismatches
+        .extend(map_mismatches(&expected.address_to_nonce, &actual.address_to_nonce).map(
+            |(address, expected, actual)| ReplayMismatch::Nonce { address, expected, actual },
+        ));
+    mismatches.extend(
+        map_mismatches(&flat_storage_updates(expected), &flat_storage_updates(actual)).map(
+            |((address, key), expected, actual)| ReplayMismatch::StorageValue {
+                address,
+                key,
+                expected,
+                actual,
+            },
+        ),
+    );
+    mismatches.extend(
+        map_mismatches(
+            &expected.class_hash_to_compiled_class_hash,
+            &actual.class_hash_to_compiled_class_hash,
+        )
+        .map(|(class_hash, expected, actual)| ReplayMismatch::CompiledClassHash {
+            class_hash,
+            expected,
+            actual,
+        }),
+    );
+    mismatches
+}
+
+/// Returns the keys whose values differ, with their expected and actual values; expected keys
+/// first, in order.
+fn map_mismatches<'a, K: Copy + Eq + Hash, V: Copy + Eq>(
+    expected: &'a IndexMap<K, V>,
+    actual: &'a IndexMap<K, V>,
+) -> impl Iterator<Item = (K, Option<V>, Option<V>)> + 'a {
+    expected.keys().chain(actual.keys().filter(|key| !expected.contains_key(*key))).filter_map(
+        |key| {
+            let (expected_value, actual_value) = (expected.get(key), actual.get(key));
+            (expected_value != actual_value)
+                .then(|| (*key, expected_value.copied(), actual_value.copied()))
+        },
+    )
+}
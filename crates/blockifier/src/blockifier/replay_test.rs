@@ -0,0 +1,121 @@
+use pretty_assertions::assert_eq;
+use starknet_api::core::ContractAddress;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::Fee;
+
+use crate::abi::constants::STORED_BLOCK_HASH_BUFFER;
+use crate::blockifier::block::{pre_process_block, BlockInfo, BlockNumberHashPair};
+use crate::blockifier::config::TransactionExecutorConfig;
+use crate::blockifier::replay::{replay_block, ReplayBlock, ReplayMismatch, ReplayTransaction};
+use crate::blockifier::transaction_executor::TransactionExecutor;
+use crate::bouncer::BouncerConfig;
+use crate::context::ChainInfo;
+use crate::invoke_tx_args;
+use crate::state::cached_state::{CachedState, CommitmentStateDiff};
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::{create_calldata, CairoVersion, CURRENT_BLOCK_NUMBER, MAX_FEE};
+use crate::transaction::test_utils::{account_invoke_tx, create_test_init_data, TestInitData};
+use crate::transaction::transaction_execution::Transaction;
+use crate::versioned_constants::{StarknetVersion, VersionedConstants};
+
+fn old_block_number_and_hash() -> Option<BlockNumberHashPair> {
+    Some(BlockNumberHashPair::new(
+        CURRENT_BLOCK_NUMBER - STORED_BLOCK_HASH_BUFFER,
+        stark_felt!(7_u8),
+    ))
+}
+
+/// Returns the pre-state and the single transaction of a test block.
+fn pre_state_and_tx() -> (DictStateReader, Transaction) {
+    let TestInitData { state, account_address, contract_address, .. } =
+        create_test_init_data(&ChainInfo::create_for_testing(), CairoVersion::Cairo1);
+    let tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account_address,
+        calldata: create_calldata(
+            contract_address,
+            "test_storage_read_write",
+            &[stark_felt!(15_u8), stark_felt!(18_u8)],
+        ),
+    }));
+    (state.state, tx)
+}
+
+/// Returns the actual fee of the test transaction and the state diff of the test block, executed
+/// directly.
+fn reference_results() -> (Fee, CommitmentStateDiff) {
+    let (pre_state_reader, tx) = pre_state_and_tx();
+    let mut state = CachedState::from(pre_state_reader);
+    let block_context = pre_process_block(
+        &mut state,
+        old_block_number_and_hash(),
+        BlockInfo::create_for_testing(),
+        ChainInfo::create_for_testing(),
+        VersionedConstants::get(StarknetVersion::Latest).clone(),
+        false,
+    )
+    .unwrap();
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::max(),
+        TransactionExecutorConfig::default(),
+    );
+    let actual_fee = tx_executor.execute(&tx, true).unwrap().actual_fee;
+    (actual_fee, tx_executor.finalize().unwrap().state_diff)
+}
+
+fn replay_test_block(
+    actual_fee: Fee,
+    reverted: bool,
+    state_diff: CommitmentStateDiff,
+) -> Vec<ReplayMismatch> {
+    let (pre_state_reader, tx) = pre_state_and_tx();
+    let block = ReplayBlock {
+        block_info: BlockInfo::create_for_testing(),
+        chain_info: ChainInfo::create_for_testing(),
+        starknet_version: StarknetVersion::Latest,
+        old_block_number_and_hash: old_block_number_and_hash(),
+        txs: vec![ReplayTransaction { tx, actual_fee, reverted }],
+        state_diff,
+    };
+    replay_block(pre_state_reader, block).unwrap()
+}
+
+#[test]
+fn test_replay_block() {
+    let (actual_fee, state_diff) = reference_results();
+    assert_eq!(replay_test_block(actual_fee, false, state_diff), vec![]);
+}
+
+#[test]
+fn test_replay_block_mismatches() {
+    let (actual_fee, state_diff) = reference_results();
+    let tx_hash = pre_state_and_tx().1.tx_hash();
+
+    // Drop an actual storage update, and expect an extra one.
+    let mut expected_state_diff = state_diff.clone();
+    let (&address, storage_updates) = expected_state_diff.storage_updates.first_mut().unwrap();
+    let (key, value) = storage_updates.pop().unwrap();
+    let extra_address = ContractAddress::from(1234_u64);
+    expected_state_diff
+        .storage_updates
+        .insert(extra_address, [(key, stark_felt!(1_u8))].into_iter().collect());
+
+    let wrong_fee = Fee(actual_fee.0 + 1);
+    assert_eq!(
+        replay_test_block(wrong_fee, true, expected_state_diff),
+        vec![
+            ReplayMismatch::RevertStatus { tx_hash, expected_reverted: true, revert_error: None },
+            ReplayMismatch::ActualFee { tx_hash, expected: wrong_fee, actual: actual_fee },
+            ReplayMismatch::StorageValue {
+                address: extra_address,
+                key,
+                expected: Some(stark_felt!(1_u8)),
+                actual: None,
+            },
+            ReplayMismatch::StorageValue { address, key, expected: None, actual: Some(value) },
+        ]
+    );
+}
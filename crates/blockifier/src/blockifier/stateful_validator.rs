@@ -13,9 +13,9 @@ use crate::fee::fee_checks::PostValidationReport;
 use crate::state::cached_state::CachedState;
 use crate::state::errors::StateError;
 use crate::state::state_api::StateReader;
-use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::account_transaction::{AccountTransaction, NonceCheckPolicy};
 use crate::transaction::errors::{TransactionExecutionError, TransactionPreValidationError};
-use crate::transaction::objects::TransactionInfo;
+use crate::transaction::objects::{TransactionInfo, TransactionPreValidationResult};
 use crate::transaction::transaction_execution::Transaction;
 
 #[cfg(test)]
@@ -36,6 +36,31 @@ pub enum StatefulValidatorError {
 
 pub type StatefulValidatorResult<T> = Result<T, StatefulValidatorError>;
 
+/// Performs the checks done before running `__validate__`, without running the VM: transaction
+/// version, nonce (a future nonce is accepted), fee bounds and balance. The state is left
+/// unchanged.
+pub fn perform_pre_validation_checks<S: StateReader>(
+    tx: &AccountTransaction,
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+) -> TransactionPreValidationResult<()> {
+    let tx_context = block_context.to_tx_context(tx);
+    // Any future nonce is accepted.
+    let nonce_check = NonceCheckPolicy::AllowFutureUpTo(u64::MAX);
+    let charge_fee = true;
+
+    let mut transactional_state = CachedState::create_transactional(state);
+    let result = tx.perform_pre_validation_stage(
+        &mut transactional_state,
+        &tx_context,
+        charge_fee,
+        nonce_check,
+    );
+    transactional_state.abort();
+
+    result
+}
+
 /// Manages state related transaction validations for pre-execution flows.
 pub struct StatefulValidator<S: StateReader> {
     tx_executor: TransactionExecutor<S>,
@@ -86,9 +111,8 @@ impl<S: StateReader> StatefulValidator<S> {
         }
 
         // `__validate__` call.
-        let versioned_constants = &tx_context.block_context.versioned_constants();
         let (_optional_call_info, actual_cost) =
-            self.validate(&tx, versioned_constants.tx_initial_gas())?;
+            self.validate(&tx, tx.initial_gas(&tx_context.block_context))?;
 
         // Post validations.
         PostValidationReport::verify(&tx_context, &actual_cost)?;
@@ -106,14 +130,14 @@ impl<S: StateReader> StatefulValidator<S> {
         tx: &AccountTransaction,
         tx_context: &TransactionContext,
     ) -> StatefulValidatorResult<()> {
-        let strict_nonce_check = false;
+        let nonce_check = NonceCheckPolicy::AllowFutureUpTo(u64::MAX);
         // Run pre-validation in charge fee mode to perform fee and balance related checks.
         let charge_fee = true;
         tx.perform_pre_validation_stage(
             &mut self.tx_executor.state,
             tx_context,
             charge_fee,
-            strict_nonce_check,
+            nonce_check,
         )?;
 
         Ok(())
@@ -1,18 +1,22 @@
+use assert_matches::assert_matches;
 use rstest::rstest;
 use starknet_api::transaction::{Fee, TransactionVersion};
 
-use crate::blockifier::stateful_validator::StatefulValidator;
+use crate::blockifier::stateful_validator::{perform_pre_validation_checks, StatefulValidator};
 use crate::bouncer::BouncerConfig;
 use crate::context::BlockContext;
-use crate::nonce;
+use crate::state::state_api::StateReader;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::{fund_account, test_state};
 use crate::test_utils::{CairoVersion, NonceManager, BALANCE};
 use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::errors::TransactionPreValidationError;
 use crate::transaction::test_utils::{
-    block_context, create_account_tx_for_validate_test, FaultyAccountTxCreatorArgs, VALID,
+    account_invoke_tx, block_context, create_account_tx_for_validate_test,
+    FaultyAccountTxCreatorArgs, VALID,
 };
 use crate::transaction::transaction_types::TransactionType;
+use crate::{invoke_tx_args, nonce};
 
 #[rstest]
 #[case::validate_version_1(TransactionType::InvokeFunction, false, TransactionVersion::ONE)]
@@ -75,3 +79,24 @@ fn test_transaction_validator(
     let reuslt = stateful_validator.perform_validations(tx, None);
     assert!(reuslt.is_ok(), "Validation failed: {:?}", reuslt.unwrap_err());
 }
+
+#[rstest]
+fn test_pre_validation_checks(block_context: BlockContext) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let sender_address = account.get_instance_address(0);
+    let mut state = test_state(&block_context.chain_info, BALANCE, &[(account, 1)]);
+    state.state.address_to_nonce.insert(sender_address, nonce!(1_u8));
+    let tx_args = invoke_tx_args! { sender_address, max_fee: Fee(BALANCE) };
+
+    // A future nonce is accepted, and the state is left unchanged.
+    let tx = account_invoke_tx(invoke_tx_args! { nonce: nonce!(2_u8), ..tx_args.clone() });
+    perform_pre_validation_checks(&tx, &mut state, &block_context).unwrap();
+    assert_eq!(state.get_nonce_at(sender_address).unwrap(), nonce!(1_u8));
+
+    // A past nonce is rejected.
+    let tx = account_invoke_tx(invoke_tx_args! { nonce: nonce!(0_u8), ..tx_args });
+    assert_matches!(
+        perform_pre_validation_checks(&tx, &mut state, &block_context).unwrap_err(),
+        TransactionPreValidationError::InvalidNonce { .. }
+    );
+}
@@ -1,20 +1,35 @@
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Instant;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use starknet_api::core::ClassHash;
+use starknet_api::transaction::TransactionHash;
 use thiserror::Error;
 
 use crate::blockifier::config::TransactionExecutorConfig;
-use crate::bouncer::{Bouncer, BouncerConfig};
+use crate::blockifier::execution_cache::{BaseStateId, ExecutionCache, SimulationKey};
+use crate::blockifier::os_input::OsInput;
+use crate::bouncer::{Bouncer, BouncerConfig, BouncerWeights};
+#[cfg(feature = "concurrency")]
+use crate::concurrency::versioned_state_proxy::{ThreadSafeVersionedState, VersionedState};
+#[cfg(feature = "concurrency")]
+use crate::concurrency::worker_logic::WorkerExecutor;
 use crate::context::BlockContext;
 use crate::execution::call_info::CallInfo;
 use crate::fee::actual_cost::TransactionReceipt;
-use crate::state::cached_state::{CachedState, CommitmentStateDiff};
+#[cfg(feature = "concurrency")]
+use crate::state::cached_state::MutRefState;
+use crate::state::cached_state::{CachedState, CommitmentStateDiff, StateMaps};
 use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateReader};
+use crate::state::stateful_compression::{allocate_aliases, compress};
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::errors::TransactionExecutionError;
-use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::objects::{
+    TransactionExecutionInfo, TransactionExecutionResult, TransactionInfoCreator,
+    TransactionResources,
+};
 use crate::transaction::transaction_execution::Transaction;
 use crate::transaction::transactions::{ExecutableTransaction, ValidatableTransaction};
 
@@ -26,6 +41,12 @@ pub mod transaction_executor_test;
 pub enum TransactionExecutorError {
     #[error("Transaction cannot be added to the current block, block capacity reached.")]
     BlockFull,
+    #[error("Transaction {0:?} was already executed in the current block.")]
+    DuplicateTransaction(TransactionHash),
+    #[error(
+        "Transaction {0:?} has a query version; query transactions cannot be included in a block."
+    )]
+    QueryTransaction(TransactionHash),
     #[error(transparent)]
     StateError(#[from] StateError),
     #[error(transparent)]
@@ -35,6 +56,82 @@ pub enum TransactionExecutorError {
 pub type TransactionExecutorResult<T> = Result<T, TransactionExecutorError>;
 pub type VisitedSegmentsMapping = Vec<(ClassHash, Vec<usize>)>;
 
+/// The stage of the transaction flow at which a transaction was rejected.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RejectionStage {
+    PreValidation,
+    Validation,
+    Execution,
+    BlockCapacity,
+    /// The transaction was reverted; note that it is included in the block and charged for.
+    Revert,
+}
+
+impl From<&TransactionExecutionError> for RejectionStage {
+    fn from(error: &TransactionExecutionError) -> Self {
+        match error {
+            TransactionExecutionError::TransactionFeeError(_)
+            | TransactionExecutionError::TransactionPreValidationError(_) => Self::PreValidation,
+            TransactionExecutionError::InvalidValidateReturnData { .. }
+            | TransactionExecutionError::ValidateTransactionError { .. } => Self::Validation,
+            TransactionExecutionError::TransactionTooLarge => Self::BlockCapacity,
+            _ => Self::Execution,
+        }
+    }
+}
+
+/// Describes a transaction that failed or was reverted during block building, for mempools and
+/// monitoring.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RejectionReport {
+    pub tx_hash: TransactionHash,
+    pub stage: RejectionStage,
+    pub error: String,
+    // Only known for reverted transactions; failed transactions do not report their resources.
+    pub resources_wasted: Option<TransactionResources>,
+}
+
+impl RejectionReport {
+    fn from_error(tx_hash: TransactionHash, error: &TransactionExecutionError) -> Self {
+        Self { tx_hash, stage: error.into(), error: error.to_string(), resources_wasted: None }
+    }
+
+    fn from_reverted(
+        tx_hash: TransactionHash,
+        tx_execution_info: &TransactionExecutionInfo,
+    ) -> Option<Self> {
+        Some(Self {
+            tx_hash,
+            stage: RejectionStage::Revert,
+            error: tx_execution_info.revert_error.clone()?,
+            resources_wasted: Some(tx_execution_info.actual_resources.clone()),
+        })
+    }
+}
+
+/// The output of a chunk of transactions executed with
+/// [`TransactionExecutor::execute_chunk_with_state_diff`], for streaming the partial block.
+#[derive(Debug)]
+pub struct ChunkExecutionOutput {
+    pub results: Vec<TransactionExecutorResult<TransactionExecutionInfo>>,
+    /// The state diff since the end of the previous chunk.
+    pub state_diff: CommitmentStateDiff,
+    /// The weights of the block so far.
+    pub bouncer_weights: BouncerWeights,
+}
+
+/// The output of a finalized block.
+#[derive(Debug)]
+pub struct BlockExecutionSummary {
+    pub state_diff: CommitmentStateDiff,
+    /// The state diff with its contract addresses and storage keys replaced by their aliases; set
+    /// if stateful compression is enabled in the versioned constants.
+    pub compressed_state_diff: Option<CommitmentStateDiff>,
+    pub visited_segments: VisitedSegmentsMapping,
+    /// Set if the OS input is collected (see `TransactionExecutorConfig::collect_os_input`).
+    pub os_input: Option<OsInput>,
+}
+
 // TODO(Gilad): make this hold TransactionContext instead of BlockContext.
 pub struct TransactionExecutor<S: StateReader> {
     pub block_context: BlockContext,
@@ -44,6 +141,21 @@ pub struct TransactionExecutor<S: StateReader> {
 
     // State-related fields.
     pub state: CachedState<S>,
+    // Hashes of the transactions executed in the current block.
+    pub executed_tx_hashes: HashSet<TransactionHash>,
+    // Transactions that failed or were reverted in the current block, in execution order.
+    pub rejection_reports: Vec<RejectionReport>,
+    // If set, no new transactions are executed once this (wall-clock) deadline is reached.
+    // Unsupported on `wasm32-unknown-unknown`, which has no clock.
+    pub execution_deadline: Option<Instant>,
+    // The writes to the state at the end of the last chunk whose state diff was emitted.
+    streamed_writes: StateMaps,
+    // Memoizes simulations on the initial state of the executor, identified by the given ID.
+    execution_cache: Option<(Arc<ExecutionCache>, BaseStateId)>,
+    // The OS input collected so far, if enabled.
+    os_input: Option<OsInput>,
+    #[cfg(feature = "metrics")]
+    block_build_start: Instant,
 }
 
 impl<S: StateReader> TransactionExecutor<S> {
@@ -56,56 +168,266 @@ impl<S: StateReader> TransactionExecutor<S> {
         log::debug!("Initializing Transaction Executor...");
         // Note: the state might not be empty even at this point; it is the creator's
         // responsibility to tune the bouncer according to pre and post block process.
-        let tx_executor =
-            Self { block_context, bouncer: Bouncer::new(bouncer_config), config, state };
+        let os_input = config.collect_os_input.then(OsInput::default);
+        let tx_executor = Self {
+            block_context,
+            bouncer: Bouncer::new(bouncer_config),
+            config,
+            state,
+            executed_tx_hashes: HashSet::new(),
+            rejection_reports: Vec::new(),
+            execution_deadline: None,
+            streamed_writes: StateMaps::default(),
+            execution_cache: None,
+            os_input,
+            #[cfg(feature = "metrics")]
+            block_build_start: Instant::now(),
+        };
         log::debug!("Initialized Transaction Executor.");
 
         tx_executor
     }
 
+    /// Memoizes the results of `simulate` in the given (possibly shared) cache, while the state of
+    /// the executor is its initial state, identified by `base_state_id`.
+    pub fn with_execution_cache(
+        mut self,
+        execution_cache: Arc<ExecutionCache>,
+        base_state_id: BaseStateId,
+    ) -> Self {
+        self.execution_cache = Some((execution_cache, base_state_id));
+        self
+    }
+
     /// Executes the given transaction on the state maintained by the executor.
     /// Returns the execution result (info or error) if there is room for the transaction;
     /// Otherwise, returns BlockFull error.
+    /// If duplicates are skipped (see `TransactionExecutorConfig`), a transaction that was already
+    /// executed in the current block is not executed again, and DuplicateTransaction error is
+    /// returned.
+    /// If query transactions are rejected (see `TransactionExecutorConfig`), a query transaction
+    /// is not executed, and QueryTransaction error is returned.
     pub fn execute(
         &mut self,
         tx: &Transaction,
         charge_fee: bool,
     ) -> TransactionExecutorResult<TransactionExecutionInfo> {
+        let tx_hash = tx.tx_hash();
+        if self.config.skip_duplicate_txs && self.executed_tx_hashes.contains(&tx_hash) {
+            return Err(TransactionExecutorError::DuplicateTransaction(tx_hash));
+        }
+        if self.config.reject_query_txs && tx.create_tx_info().only_query() {
+            return Err(TransactionExecutorError::QueryTransaction(tx_hash));
+        }
+
         let mut transactional_state = CachedState::create_transactional(&mut self.state);
         let validate = true;
 
         let tx_execution_result =
             tx.execute_raw(&mut transactional_state, &self.block_context, charge_fee, validate);
+        self.reset_cancellation_if_aborted(&tx_execution_result);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_tx_execution(&tx_execution_result);
         match tx_execution_result {
             Ok(tx_execution_info) => {
-                self.bouncer.try_update(
+                if let Err(error) = self.bouncer.try_update(
                     &mut transactional_state,
                     &tx_execution_info.summarize(),
                     &tx_execution_info.actual_resources,
-                )?;
+                ) {
+                    if let TransactionExecutorError::TransactionExecutionError(error) = &error {
+                        self.rejection_reports.push(RejectionReport::from_error(tx_hash, error));
+                    }
+                    return Err(error);
+                }
+                if let Some(os_input) = &mut self.os_input {
+                    os_input.add_tx(tx_hash, transactional_state.writes(), &tx_execution_info);
+                }
                 transactional_state.commit();
+                self.executed_tx_hashes.insert(tx_hash);
+                self.rejection_reports
+                    .extend(RejectionReport::from_reverted(tx_hash, &tx_execution_info));
                 Ok(tx_execution_info)
             }
             Err(error) => {
                 transactional_state.abort();
+                self.rejection_reports.push(RejectionReport::from_error(tx_hash, &error));
                 Err(TransactionExecutorError::TransactionExecutionError(error))
             }
         }
     }
 
+    /// Executes the given transaction on the state maintained by the executor, and discards its
+    /// state changes; e.g., for simulation and fee estimation. Query transactions are allowed.
+    /// If an execution cache is set, and no transaction was executed yet, the execution info is
+    /// served from (or stored in) the cache.
+    /// Note: [`Self::validate`] modifies the state as well; do not mix it with cached simulations.
+    pub fn simulate(
+        &mut self,
+        tx: &Transaction,
+        charge_fee: bool,
+        validate: bool,
+    ) -> TransactionExecutorResult<Arc<TransactionExecutionInfo>> {
+        let cache_entry =
+            self.execution_cache.as_ref().filter(|_| self.executed_tx_hashes.is_empty()).map(
+                |(execution_cache, base_state_id)| {
+                    let key = SimulationKey {
+                        tx_hash: tx.tx_hash(),
+                        signature: tx.signature(),
+                        base_state_id: *base_state_id,
+                        charge_fee,
+                        validate,
+                    };
+                    (execution_cache.clone(), key)
+                },
+            );
+        if let Some(tx_execution_info) =
+            cache_entry.as_ref().and_then(|(execution_cache, key)| execution_cache.get(key))
+        {
+            return Ok(tx_execution_info);
+        }
+
+        let mut transactional_state = CachedState::create_transactional(&mut self.state);
+        let tx_execution_result =
+            tx.execute_raw(&mut transactional_state, &self.block_context, charge_fee, validate);
+        self.reset_cancellation_if_aborted(&tx_execution_result);
+        transactional_state.abort();
+        let tx_execution_info = Arc::new(tx_execution_result?);
+
+        if let Some((execution_cache, key)) = cache_entry {
+            execution_cache.insert(key, tx_execution_info.clone());
+        }
+        Ok(tx_execution_info)
+    }
+
     /// Executes the given transactions on the state maintained by the executor.
-    /// Stops if and when there is no more room in the block, and returns the executed transactions'
-    /// results.
+    /// Stops if and when there is no more room in the block, or the execution deadline is reached,
+    /// and returns the executed transactions' results; the transactions following them were not
+    /// executed.
+    /// The chunk is executed concurrently if enabled in the config (see
+    /// [`Self::concurrent_execution_enabled`]); the block context must then be in concurrency mode.
     pub fn execute_chunk(
         &mut self,
         txs: &[Transaction],
         charge_fee: bool,
-    ) -> Vec<TransactionExecutorResult<TransactionExecutionInfo>> {
-        if !self.config.concurrency_config.enabled {
-            self.execute_chunk_sequentially(txs, charge_fee)
-        } else {
-            todo!()
+    ) -> Vec<TransactionExecutorResult<TransactionExecutionInfo>>
+    where
+        S: Send,
+    {
+        #[cfg(feature = "concurrency")]
+        {
+            if self.concurrent_execution_enabled(charge_fee) {
+                return self.execute_chunk_concurrently(txs);
+            }
         }
+
+        self.execute_chunk_sequentially(txs, charge_fee)
+    }
+
+    /// Returns whether chunks are executed concurrently: requires the `concurrency` feature, and
+    /// fee charging, as the workers transfer the fees at commit. The duplicate and query
+    /// transaction checks, the execution deadline and the OS input collection are applied per
+    /// transaction, hence only by sequential execution.
+    pub fn concurrent_execution_enabled(&self, charge_fee: bool) -> bool {
+        let TransactionExecutorConfig {
+            concurrency_config,
+            skip_duplicate_txs,
+            collect_os_input,
+            reject_query_txs,
+        } = &self.config;
+
+        cfg!(feature = "concurrency")
+            && concurrency_config.enabled
+            && charge_fee
+            && !skip_duplicate_txs
+            && !collect_os_input
+            && !reject_query_txs
+            && self.execution_deadline.is_none()
+    }
+
+    /// Executes the given transactions on `n_workers` threads, over a versioned view of the state
+    /// maintained by the executor, and commits those that fit in the block to it, in order.
+    #[cfg(feature = "concurrency")]
+    fn execute_chunk_concurrently(
+        &mut self,
+        txs: &[Transaction],
+    ) -> Vec<TransactionExecutorResult<TransactionExecutionInfo>>
+    where
+        S: Send,
+    {
+        let (results, bouncer, chunk_writes, chunk_classes, chunk_visited_pcs) = {
+            let versioned_state = ThreadSafeVersionedState::new(VersionedState::new(
+                MutRefState::new(&mut self.state),
+            ));
+            // Collects the state changes of the committed transactions; never read from.
+            let mut chunk_state = CachedState::new(versioned_state.pin_version(txs.len()));
+            let worker_executor = WorkerExecutor::new(
+                versioned_state,
+                txs,
+                &self.block_context,
+                std::mem::take(&mut self.bouncer),
+            );
+            let metrics = worker_executor.run_in_threads(self.config.concurrency_config.n_workers);
+            log::debug!(
+                "Executed a chunk of {} transactions; worker metrics: {metrics:?}.",
+                txs.len()
+            );
+
+            worker_executor.commit_chunk_to_state(&mut chunk_state);
+            let (results, bouncer) = worker_executor.into_committed_results();
+            (
+                results,
+                bouncer,
+                chunk_state.cache.into_inner().writes,
+                chunk_state.class_hash_to_class.into_inner(),
+                chunk_state.visited_pcs,
+            )
+        };
+        self.bouncer = bouncer;
+        self.state.update_cache(chunk_writes);
+        self.state.update_contract_class_cache(chunk_classes);
+        self.state.update_visited_pcs_cache(&chunk_visited_pcs);
+
+        results
+            .into_iter()
+            .zip(txs)
+            .map(|(tx_execution_result, tx)| {
+                let tx_hash = tx.tx_hash();
+                match &tx_execution_result {
+                    Ok(tx_execution_info) => {
+                        self.executed_tx_hashes.insert(tx_hash);
+                        self.rejection_reports
+                            .extend(RejectionReport::from_reverted(tx_hash, tx_execution_info));
+                    }
+                    Err(error) => {
+                        self.rejection_reports.push(RejectionReport::from_error(tx_hash, error))
+                    }
+                }
+                tx_execution_result.map_err(TransactionExecutorError::from)
+            })
+            .collect()
+    }
+
+    /// Same as [`Self::execute_chunk`], but also returns the state diff of the chunk (relative to
+    /// the end of the previous chunk executed by this method) and the weights of the block so far,
+    /// so that the block can be streamed while it is being built.
+    pub fn execute_chunk_with_state_diff(
+        &mut self,
+        txs: &[Transaction],
+        charge_fee: bool,
+    ) -> TransactionExecutorResult<ChunkExecutionOutput>
+    where
+        S: Send,
+    {
+        let results = self.execute_chunk(txs, charge_fee);
+        let state_diff = self.state.to_state_diff_since(&self.streamed_writes)?;
+        self.streamed_writes = self.state.writes();
+
+        Ok(ChunkExecutionOutput {
+            results,
+            state_diff,
+            bouncer_weights: *self.bouncer.get_accumulated_weights(),
+        })
     }
 
     pub fn execute_chunk_sequentially(
@@ -115,6 +437,13 @@ impl<S: StateReader> TransactionExecutor<S> {
     ) -> Vec<TransactionExecutorResult<TransactionExecutionInfo>> {
         let mut results = Vec::new();
         for tx in txs {
+            if self.execution_deadline_reached() {
+                log::debug!(
+                    "Execution deadline reached; {} transactions were not executed.",
+                    txs.len() - results.len()
+                );
+                break;
+            }
             match self.execute(tx, charge_fee) {
                 Ok(tx_execution_info) => results.push(Ok(tx_execution_info)),
                 Err(TransactionExecutorError::BlockFull) => break,
@@ -124,6 +453,21 @@ impl<S: StateReader> TransactionExecutor<S> {
         results
     }
 
+    pub fn execution_deadline_reached(&self) -> bool {
+        self.execution_deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Resets the cancellation token of the block context once a cancellation took effect, so that
+    /// it aborts only the transaction in flight.
+    fn reset_cancellation_if_aborted(
+        &self,
+        tx_execution_result: &TransactionExecutionResult<TransactionExecutionInfo>,
+    ) {
+        if let Err(TransactionExecutionError::ExecutionAborted) = tx_execution_result {
+            self.block_context.cancellation_token.reset();
+        }
+    }
+
     pub fn validate(
         &mut self,
         account_tx: &AccountTransaction,
@@ -161,11 +505,9 @@ impl<S: StateReader> TransactionExecutor<S> {
         Ok((validate_call_info, tx_receipt))
     }
 
-    /// Returns the state diff and a list of contract class hash with the corresponding list of
-    /// visited segment values.
-    pub fn finalize(
-        &mut self,
-    ) -> TransactionExecutorResult<(CommitmentStateDiff, VisitedSegmentsMapping)> {
+    /// Returns the state diff (and its compressed form, if enabled) and a list of contract class
+    /// hash with the corresponding list of visited segment values.
+    pub fn finalize(&mut self) -> TransactionExecutorResult<BlockExecutionSummary> {
         // Get the visited segments of each contract class.
         // This is done by taking all the visited PCs of each contract, and compress them to one
         // representative for each visited segment.
@@ -179,6 +521,34 @@ impl<S: StateReader> TransactionExecutor<S> {
             })
             .collect::<TransactionExecutorResult<_>>()?;
 
-        Ok((self.state.to_state_diff(), visited_segments))
+        // The aliases are written to the state, hence allocated before the state diff is taken.
+        let enable_stateful_compression =
+            self.block_context.versioned_constants.enable_stateful_compression;
+        let alias_writes = if enable_stateful_compression {
+            allocate_aliases(&mut self.state)?
+        } else {
+            StateMaps::default()
+        };
+        let state_diff = if self.config.concurrency_config.enabled {
+            self.state.to_state_diff_in_parallel(self.config.concurrency_config.n_workers)
+        } else {
+            self.state.to_state_diff()
+        };
+        let compressed_state_diff = if enable_stateful_compression {
+            Some(compress(&state_diff, &self.state)?)
+        } else {
+            None
+        };
+        // Taken after the state diff, which reads the initial values of write-only cells.
+        let os_input = self.os_input.take().map(|os_input| OsInput {
+            initial_reads: self.state.initial_reads(),
+            visited_pcs: self.state.visited_pcs.clone(),
+            compiled_classes: self.state.class_hash_to_class.borrow().clone(),
+            alias_writes,
+            ..os_input
+        });
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_block_build_time(self.block_build_start.elapsed());
+        Ok(BlockExecutionSummary { state_diff, compressed_state_diff, visited_segments, os_input })
     }
 }
@@ -1,22 +1,35 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
 use assert_matches::assert_matches;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
+use starknet_api::core::EthAddress;
 use starknet_api::hash::StarkFelt;
 use starknet_api::stark_felt;
-use starknet_api::transaction::{Fee, TransactionVersion};
+use starknet_api::transaction::{
+    Fee, L2ToL1Payload, TransactionHash, TransactionSignature, TransactionVersion,
+};
 
-use crate::blockifier::config::TransactionExecutorConfig;
-use crate::blockifier::transaction_executor::{TransactionExecutor, TransactionExecutorError};
+use crate::blockifier::config::{ConcurrencyConfig, TransactionExecutorConfig};
+use crate::blockifier::execution_cache::ExecutionCache;
+use crate::blockifier::os_input::L2ToL1Message;
+use crate::blockifier::transaction_executor::{
+    RejectionStage, TransactionExecutor, TransactionExecutorError,
+};
 use crate::bouncer::{Bouncer, BouncerConfig, BouncerWeights};
 use crate::context::BlockContext;
 use crate::state::cached_state::CachedState;
 use crate::state::state_api::StateReader;
+use crate::state::stateful_compression::alias_contract_address;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::declare::declare_tx;
 use crate::test_utils::deploy_account::deploy_account_tx;
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{
-    create_calldata, CairoVersion, NonceManager, BALANCE, DEFAULT_STRK_L1_GAS_PRICE,
+    create_calldata, create_trivial_calldata, CairoVersion, NonceManager, BALANCE,
+    DEFAULT_STRK_L1_GAS_PRICE, MAX_FEE,
 };
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::errors::TransactionExecutionError;
@@ -111,6 +124,7 @@ fn test_declare(
         declare_tx_args! {
             sender_address: account_contract.get_instance_address(0),
             class_hash: declared_contract.get_class_hash(),
+            compiled_class_hash: declared_contract.get_compiled_class_hash(),
             version: transaction_version,
             resource_bounds: l1_resource_bounds(0, DEFAULT_STRK_L1_GAS_PRICE),
         },
@@ -282,7 +296,8 @@ fn test_bouncing(
 }
 
 #[rstest]
-fn test_execute_chunk_bouncing(block_context: BlockContext) {
+fn test_execute_chunk_bouncing(#[values(false, true)] concurrency: bool) {
+    let block_context = BlockContext::create_for_account_testing_with_concurrency_mode(concurrency);
     let TestInitData { state, account_address, contract_address, .. } =
         create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
 
@@ -294,11 +309,17 @@ fn test_execute_chunk_bouncing(block_context: BlockContext) {
         },
         ..BouncerConfig::default()
     };
+    let concurrency_config =
+        ConcurrencyConfig { enabled: concurrency, n_workers: 2, chunk_size: 5 };
     let mut tx_executor = TransactionExecutor::new(
         state,
         block_context,
         bouncer_config.clone(),
-        TransactionExecutorConfig::default(),
+        TransactionExecutorConfig { concurrency_config, ..TransactionExecutorConfig::default() },
+    );
+    assert_eq!(
+        tx_executor.concurrent_execution_enabled(true),
+        cfg!(feature = "concurrency") && concurrency
     );
 
     let txs: Vec<Transaction> = [
@@ -353,3 +374,358 @@ fn test_execute_chunk_bouncing(block_context: BlockContext) {
     assert!(remaining_tx_results[1].is_ok());
     assert_eq!(tx_executor.state.get_nonce_at(account_address).unwrap(), nonce!(4_u32));
 }
+
+#[rstest]
+fn test_execute_chunk_with_state_diff(block_context: BlockContext) {
+    let TestInitData { state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::max(),
+        TransactionExecutorConfig::default(),
+    );
+    let emit_events_tx = |n_events, nonce| {
+        Transaction::AccountTransaction(emit_n_events_tx(
+            n_events,
+            account_address,
+            contract_address,
+            nonce,
+        ))
+    };
+
+    let first_chunk = [emit_events_tx(1, nonce!(0_u32)), emit_events_tx(2, nonce!(1_u32))];
+    let first_output = tx_executor.execute_chunk_with_state_diff(&first_chunk, true).unwrap();
+    assert!(first_output.results.iter().all(Result::is_ok));
+    assert_eq!(first_output.state_diff.address_to_nonce[&account_address], nonce!(2_u32));
+    assert_eq!(first_output.bouncer_weights.n_events, 3);
+
+    let second_chunk = [emit_events_tx(4, nonce!(2_u32))];
+    let second_output = tx_executor.execute_chunk_with_state_diff(&second_chunk, true).unwrap();
+    assert!(second_output.results.iter().all(Result::is_ok));
+    // Only the cells changed by the second chunk are emitted.
+    assert_eq!(
+        second_output.state_diff.address_to_nonce.into_iter().collect::<Vec<_>>(),
+        vec![(account_address, nonce!(3_u32))]
+    );
+    assert_eq!(second_output.bouncer_weights.n_events, 7);
+
+    // An empty chunk changes nothing.
+    let empty_output = tx_executor.execute_chunk_with_state_diff(&[], true).unwrap();
+    assert!(empty_output.state_diff.address_to_nonce.is_empty());
+    assert!(empty_output.state_diff.storage_updates.is_empty());
+}
+
+#[rstest]
+fn test_skip_duplicate_txs(
+    block_context: BlockContext,
+    #[values(true, false)] skip_duplicate_txs: bool,
+) {
+    let TestInitData { state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::max(),
+        TransactionExecutorConfig { skip_duplicate_txs, ..TransactionExecutorConfig::default() },
+    );
+
+    let tx_hash = TransactionHash(stark_felt!(1_u8));
+    let create_tx = || {
+        let mut account_tx = emit_n_events_tx(1, account_address, contract_address, nonce!(0_u32));
+        if let AccountTransaction::Invoke(invoke_tx) = &mut account_tx {
+            invoke_tx.tx_hash = tx_hash;
+        }
+        Transaction::AccountTransaction(account_tx)
+    };
+    let txs = [create_tx(), create_tx()];
+
+    let results = tx_executor.execute_chunk(&txs, true);
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    if skip_duplicate_txs {
+        assert_matches!(
+            results[1].as_ref().unwrap_err(),
+            TransactionExecutorError::DuplicateTransaction(hash) if *hash == tx_hash
+        );
+    } else {
+        // The duplicate is executed, and fails on the nonce check.
+        assert_matches!(
+            results[1].as_ref().unwrap_err(),
+            TransactionExecutorError::TransactionExecutionError(
+                TransactionExecutionError::TransactionPreValidationError(_)
+            )
+        );
+    }
+    assert_eq!(tx_executor.state.get_nonce_at(account_address).unwrap(), nonce!(1_u32));
+}
+
+#[rstest]
+fn test_simulate_with_execution_cache(block_context: BlockContext) {
+    let execution_cache = Arc::new(ExecutionCache::new(10));
+    let base_state_id = stark_felt!(7_u8);
+    let create_executor = || {
+        let TestInitData { state, .. } =
+            create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+        TransactionExecutor::new(
+            state,
+            block_context.clone(),
+            BouncerConfig::max(),
+            TransactionExecutorConfig::default(),
+        )
+        .with_execution_cache(execution_cache.clone(), base_state_id)
+    };
+    let TestInitData { account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+    let tx = Transaction::AccountTransaction(emit_n_events_tx(
+        1,
+        account_address,
+        contract_address,
+        nonce!(0_u32),
+    ));
+
+    // The state changes are discarded, and the result is shared between executors.
+    let mut tx_executor = create_executor();
+    let tx_execution_info = tx_executor.simulate(&tx, true, true).unwrap();
+    assert_eq!(tx_executor.state.get_nonce_at(account_address).unwrap(), nonce!(0_u32));
+    assert!(Arc::ptr_eq(&create_executor().simulate(&tx, true, true).unwrap(), &tx_execution_info));
+
+    // Simulations with other flags are cached separately.
+    let no_fee_execution_info = tx_executor.simulate(&tx, false, true).unwrap();
+    assert!(!Arc::ptr_eq(&no_fee_execution_info, &tx_execution_info));
+    assert_eq!(execution_cache.len(), 2);
+
+    // The signature is not covered by the transaction hash, but is part of the key.
+    let signed_tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        sender_address: account_address,
+        calldata: create_calldata(
+            contract_address,
+            "test_emit_events",
+            &[stark_felt!(1_u8), stark_felt!(0_u8), stark_felt!(0_u8)],
+        ),
+        version: TransactionVersion::THREE,
+        nonce: nonce!(0_u32),
+        signature: TransactionSignature(vec![stark_felt!(1_u8)]),
+    }));
+    assert_eq!(signed_tx.tx_hash(), tx.tx_hash());
+    let signed_execution_info = tx_executor.simulate(&signed_tx, true, true).unwrap();
+    assert!(!Arc::ptr_eq(&signed_execution_info, &tx_execution_info));
+    assert_eq!(execution_cache.len(), 3);
+
+    // Once a transaction is executed, the state of the executor is no longer the base state.
+    tx_executor.execute(&tx, true).unwrap();
+    assert_matches!(
+        tx_executor.simulate(&tx, true, true).unwrap_err(),
+        TransactionExecutorError::TransactionExecutionError(
+            TransactionExecutionError::TransactionPreValidationError(_)
+        )
+    );
+}
+
+#[rstest]
+fn test_cancellation_aborts_only_the_transaction_in_flight(block_context: BlockContext) {
+    let TestInitData { state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    let cancellation_token = block_context.cancellation_token().clone();
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::max(),
+        TransactionExecutorConfig::default(),
+    );
+    let tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account_address,
+        calldata: create_trivial_calldata(contract_address),
+        nonce: nonce_manager.next(account_address),
+    }));
+
+    cancellation_token.cancel();
+    assert_matches!(
+        tx_executor.execute(&tx, true).unwrap_err(),
+        TransactionExecutorError::TransactionExecutionError(
+            TransactionExecutionError::ExecutionAborted
+        )
+    );
+    assert!(!cancellation_token.is_cancelled());
+
+    // The aborted transaction did not change the state, so it can be executed again.
+    assert!(!tx_executor.execute(&tx, true).unwrap().is_reverted());
+    assert_eq!(tx_executor.state.get_nonce_at(account_address).unwrap(), nonce!(1_u32));
+}
+
+#[rstest]
+fn test_reject_query_txs(
+    block_context: BlockContext,
+    #[values(true, false)] reject_query_txs: bool,
+) {
+    let TestInitData { state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::max(),
+        TransactionExecutorConfig { reject_query_txs, ..TransactionExecutorConfig::default() },
+    );
+
+    let tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account_address,
+        calldata: create_trivial_calldata(contract_address),
+        only_query: true,
+    }));
+    let result = tx_executor.execute(&tx, true);
+    if reject_query_txs {
+        assert_matches!(result.unwrap_err(), TransactionExecutorError::QueryTransaction(_));
+        assert_eq!(tx_executor.state.get_nonce_at(account_address).unwrap(), nonce!(0_u32));
+    } else {
+        assert!(!result.unwrap().is_reverted());
+        assert_eq!(tx_executor.state.get_nonce_at(account_address).unwrap(), nonce!(1_u32));
+    }
+}
+
+#[rstest]
+fn test_rejection_reports(block_context: BlockContext) {
+    let TestInitData { state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::max(),
+        TransactionExecutorConfig::default(),
+    );
+
+    let invoke_tx = |function_name: &str, args: &[StarkFelt], nonce| {
+        Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+            max_fee: Fee(MAX_FEE),
+            sender_address: account_address,
+            calldata: create_calldata(contract_address, function_name, args),
+            nonce,
+        }))
+    };
+    let txs = [
+        invoke_tx("return_result", &[stark_felt!(2_u8)], nonce_manager.next(account_address)),
+        invoke_tx(
+            "write_and_revert",
+            &[stark_felt!(9_u8), stark_felt!(99_u8)],
+            nonce_manager.next(account_address),
+        ),
+        // Invalid nonce.
+        invoke_tx("return_result", &[stark_felt!(2_u8)], nonce!(7_u32)),
+    ];
+
+    let results = tx_executor.execute_chunk(&txs, true);
+    assert_eq!(results.len(), 3);
+    let [reverted_report, failed_report] = &tx_executor.rejection_reports[..] else {
+        panic!("Expected two rejection reports, got: {:?}.", tx_executor.rejection_reports);
+    };
+
+    let reverted_tx_execution_info = results[1].as_ref().unwrap();
+    assert_eq!(reverted_report.stage, RejectionStage::Revert);
+    assert_eq!(Some(&reverted_report.error), reverted_tx_execution_info.revert_error.as_ref());
+    assert_eq!(
+        reverted_report.resources_wasted.as_ref(),
+        Some(&reverted_tx_execution_info.actual_resources)
+    );
+
+    assert_eq!(failed_report.stage, RejectionStage::PreValidation);
+    assert_eq!(failed_report.error, results[2].as_ref().unwrap_err().to_string());
+    assert_eq!(failed_report.resources_wasted, None);
+}
+
+#[rstest]
+fn test_execute_chunk_deadline(block_context: BlockContext) {
+    let TestInitData { state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::max(),
+        TransactionExecutorConfig::default(),
+    );
+    let txs: Vec<Transaction> = (0..2)
+        .map(|_| {
+            Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+                max_fee: Fee(MAX_FEE),
+                sender_address: account_address,
+                calldata: create_trivial_calldata(contract_address),
+                nonce: nonce_manager.next(account_address),
+            }))
+        })
+        .collect();
+
+    // No transaction is executed once the deadline is reached.
+    tx_executor.execution_deadline = Some(Instant::now());
+    assert!(tx_executor.execution_deadline_reached());
+    assert!(tx_executor.execute_chunk(&txs, true).is_empty());
+    assert_eq!(tx_executor.state.get_nonce_at(account_address).unwrap(), nonce!(0_u32));
+
+    tx_executor.execution_deadline = Some(Instant::now() + Duration::from_secs(3600));
+    let results = tx_executor.execute_chunk(&txs, true);
+    assert_eq!(results.len(), txs.len());
+    assert_eq!(tx_executor.state.get_nonce_at(account_address).unwrap(), nonce!(2_u32));
+}
+
+#[rstest]
+fn test_collect_os_input(
+    mut block_context: BlockContext,
+    #[values(false, true)] enable_stateful_compression: bool,
+) {
+    block_context.versioned_constants.enable_stateful_compression = enable_stateful_compression;
+    let TestInitData { state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+    let mut tx_executor = TransactionExecutor::new(
+        state,
+        block_context,
+        BouncerConfig::max(),
+        TransactionExecutorConfig {
+            collect_os_input: true,
+            ..TransactionExecutorConfig::default()
+        },
+    );
+
+    let to_address = stark_felt!(85_u8);
+    let tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        sender_address: account_address,
+        calldata: create_calldata(contract_address, "send_message", &[to_address]),
+        nonce: nonce!(0_u8),
+    }));
+    tx_executor.execute(&tx, true).unwrap();
+    let block_execution_summary = tx_executor.finalize().unwrap();
+    let os_input = block_execution_summary.os_input.unwrap();
+
+    // The writes of the transaction, and the values prior to the block.
+    assert_eq!(os_input.tx_state_maps.len(), 1);
+    let (tx_hash, tx_state_maps) = &os_input.tx_state_maps[0];
+    assert_eq!(*tx_hash, tx.tx_hash());
+    assert_eq!(tx_state_maps.nonces[&account_address], nonce!(1_u8));
+    assert_eq!(os_input.initial_reads.nonces[&account_address], nonce!(0_u8));
+
+    // The executed classes.
+    let test_contract_class_hash =
+        FeatureContract::TestContract(CairoVersion::Cairo1).get_class_hash();
+    assert!(os_input.compiled_classes.contains_key(&test_contract_class_hash));
+    assert!(os_input.visited_pcs.contains_key(&test_contract_class_hash));
+
+    // The message sent by the test contract.
+    assert_eq!(
+        os_input.l2_to_l1_messages,
+        vec![L2ToL1Message {
+            from_address: contract_address,
+            to_address: EthAddress::try_from(to_address).unwrap(),
+            payload: L2ToL1Payload(vec![stark_felt!(12_u8), stark_felt!(34_u8)]),
+        }]
+    );
+
+    // The aliases allocated at the end of the block.
+    let alias_storage_updates: HashMap<_, _> = block_execution_summary
+        .state_diff
+        .storage_updates
+        .get(&alias_contract_address())
+        .into_iter()
+        .flatten()
+        .map(|(key, value)| ((alias_contract_address(), *key), *value))
+        .collect();
+    assert_eq!(alias_storage_updates.is_empty(), !enable_stateful_compression);
+    assert_eq!(os_input.alias_writes.storage, alias_storage_updates);
+}
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use cairo_vm::vm::runners::cairo_runner::{ExecutionResources, RunResources};
+use starknet_api::core::{ContractAddress, EntryPointSelector};
+use starknet_api::transaction::Calldata;
+
+use crate::context::{BlockContext, TransactionContext};
+use crate::execution::call_info::Retdata;
+use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
+use crate::execution::errors::EntryPointExecutionError;
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::StateReader;
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::{
+    DeprecatedTransactionInfo, TransactionExecutionResult, TransactionInfo,
+};
+
+#[cfg(test)]
+#[path = "view_call_test.rs"]
+pub mod test;
+
+/// The execution limits of a view call.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ViewCallLimits {
+    /// Defaults to the initial gas of a transaction under the block's versioned constants.
+    pub initial_gas: Option<u64>,
+    /// Defaults to the step limit of an invoke transaction.
+    pub max_n_steps: Option<usize>,
+}
+
+/// Executes an external call with no transaction around it, as the `starknet_call` RPC method
+/// does: there is no account, no validation and no fee, and the state changes made by the call are
+/// discarded. Returns the return data of the call; a failed (panicking) call is an error.
+pub fn execute_call<S: StateReader>(
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+    contract_address: ContractAddress,
+    selector: EntryPointSelector,
+    calldata: Calldata,
+    limits: ViewCallLimits,
+) -> TransactionExecutionResult<Retdata> {
+    let class_hash = state.get_class_hash_at(contract_address)?;
+    let initial_gas =
+        limits.initial_gas.unwrap_or_else(|| block_context.versioned_constants.tx_initial_gas());
+    let call = CallEntryPoint::external(contract_address, selector, calldata)
+        .with_initial_gas(initial_gas);
+
+    let tx_context = TransactionContext {
+        block_context: block_context.clone(),
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), false)?;
+    if let Some(max_n_steps) = limits.max_n_steps {
+        context.vm_run_resources = RunResources::new(max_n_steps);
+    }
+
+    let mut transactional_state = CachedState::create_transactional(state);
+    let execution_result =
+        call.execute(&mut transactional_state, &mut ExecutionResources::default(), &mut context);
+    transactional_state.abort();
+
+    let execution_error = |error| TransactionExecutionError::ExecutionError {
+        error,
+        class_hash,
+        storage_address: contract_address,
+        selector,
+    };
+    let call_info = execution_result.map_err(execution_error)?;
+    if call_info.execution.failed {
+        let error_data = call_info.execution.retdata.into_vec();
+        return Err(execution_error(EntryPointExecutionError::ExecutionFailed { error_data }));
+    }
+
+    Ok(call_info.execution.retdata)
+}
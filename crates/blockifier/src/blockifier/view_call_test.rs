@@ -0,0 +1,92 @@
+use assert_matches::assert_matches;
+use serde_json::Value;
+use starknet_api::core::PatriciaKey;
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::blockifier::block::BlockInfo;
+use crate::blockifier::view_call::{execute_call, ViewCallLimits};
+use crate::context::{BlockContext, ChainInfo};
+use crate::execution::errors::EntryPointExecutionError;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{CairoVersion, BALANCE};
+use crate::transaction::errors::TransactionExecutionError;
+use crate::versioned_constants::{VersionedConstants, DEFAULT_CONSTANTS_JSON};
+use crate::{retdata, storage_key};
+
+#[test]
+fn test_execute_call() {
+    let block_context = BlockContext::create_for_testing();
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let mut state = test_state(&block_context.chain_info, BALANCE, &[(test_contract, 1)]);
+    let address = test_contract.get_instance_address(0);
+    let call = |state: &mut _, entry_point_name, calldata, limits| {
+        execute_call(
+            state,
+            &block_context,
+            address,
+            selector_from_name(entry_point_name),
+            calldata,
+            limits,
+        )
+    };
+
+    // The return data is returned, and the state changes are discarded.
+    let (key, value) = (stark_felt!(1234_u16), stark_felt!(18_u8));
+    let limits = ViewCallLimits::default();
+    let retdata = call(&mut state, "test_storage_read_write", calldata![key, value], limits);
+    assert_eq!(retdata.unwrap(), retdata![value]);
+    assert_eq!(state.get_storage_at(address, storage_key!(1234_u16)).unwrap(), StarkFelt::ZERO);
+
+    // A failed call is an error.
+    let error = call(&mut state, "fail", Calldata::default(), limits).unwrap_err();
+    assert_matches!(
+        error,
+        TransactionExecutionError::ExecutionError {
+            error: EntryPointExecutionError::ExecutionFailed { .. },
+            ..
+        }
+    );
+
+    // The limits are applied.
+    let recurse_calldata = || calldata![stark_felt!(1000_u16)];
+    call(&mut state, "recurse", recurse_calldata(), limits).unwrap();
+    let step_limits = ViewCallLimits { max_n_steps: Some(100), ..Default::default() };
+    call(&mut state, "recurse", recurse_calldata(), step_limits).unwrap_err();
+    let gas_limits = ViewCallLimits { initial_gas: Some(10_000), ..Default::default() };
+    call(&mut state, "recurse", recurse_calldata(), gas_limits).unwrap_err();
+}
+
+#[test]
+fn test_execute_call_default_gas_follows_block_constants() {
+    // Versioned constants under which a transaction starts with only 10,000 gas.
+    let mut constants_json: Value = serde_json::from_str(DEFAULT_CONSTANTS_JSON).unwrap();
+    let transaction_gas_cost =
+        VersionedConstants::latest_constants().os_constants.gas_costs.transaction_gas_cost;
+    constants_json["os_constants"]["initial_gas_cost"] = (transaction_gas_cost + 10_000).into();
+    let versioned_constants: VersionedConstants = serde_json::from_value(constants_json).unwrap();
+    assert_eq!(versioned_constants.tx_initial_gas(), 10_000);
+
+    let chain_info = ChainInfo::create_for_testing();
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let mut state = test_state(&chain_info, BALANCE, &[(test_contract, 1)]);
+    let block_context = BlockContext::builder(BlockInfo::create_for_testing())
+        .chain_info(chain_info)
+        .versioned_constants(versioned_constants)
+        .build();
+
+    // The call runs out of gas without an explicit limit.
+    execute_call(
+        &mut state,
+        &block_context,
+        test_contract.get_instance_address(0),
+        selector_from_name("recurse"),
+        calldata![stark_felt!(1000_u16)],
+        ViewCallLimits::default(),
+    )
+    .unwrap_err();
+}
@@ -0,0 +1,422 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_crypto::{pedersen_hash, poseidon_hash, poseidon_hash_many, FieldElement};
+
+use crate::state::cached_state::CommitmentStateDiff;
+use crate::state::errors::StateError;
+use crate::state::state_api::{StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "commitment_test.rs"]
+pub mod test;
+
+const TREE_HEIGHT: usize = 251;
+const CONTRACT_STATE_HASH_VERSION: FieldElement = FieldElement::ZERO;
+const CONTRACT_CLASS_LEAF_VERSION: &str = "CONTRACT_CLASS_LEAF_V0";
+const GLOBAL_STATE_VERSION: &str = "STARKNET_STATE_V0";
+
+/// The hash function of the nodes of a Patricia tree: Pedersen for the contracts and storage
+/// tries, Poseidon for the classes trie.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TreeHashFunction {
+    Pedersen,
+    Poseidon,
+}
+
+impl TreeHashFunction {
+    fn hash(&self, x: &FieldElement, y: &FieldElement) -> FieldElement {
+        match self {
+            Self::Pedersen => pedersen_hash(x, y),
+            Self::Poseidon => poseidon_hash(*x, *y),
+        }
+    }
+}
+
+/// A trie of the state.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Trie {
+    Classes,
+    Contracts,
+    Storage(ContractAddress),
+}
+
+impl Trie {
+    pub fn hash_function(&self) -> TreeHashFunction {
+        match self {
+            Self::Classes => TreeHashFunction::Poseidon,
+            Self::Contracts | Self::Storage(_) => TreeHashFunction::Pedersen,
+        }
+    }
+}
+
+/// An inner node of a Patricia tree, by which the tree is stored: a binary node, given by the
+/// hashes of its children, or an edge node, given by its path (of the given length, in bits) to
+/// its child.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TrieNode {
+    Binary { left: StarkFelt, right: StarkFelt },
+    Edge { child: StarkFelt, path: StarkFelt, length: usize },
+}
+
+/// A reader of the tries of a committed state (e.g., the database of a node). Only the nodes along
+/// the paths to the modified leaves are read, so the state need not be held in memory.
+pub trait TrieReader {
+    /// Returns the inner node of the given hash in the given trie.
+    fn get_node(&self, trie: Trie, node_hash: StarkFelt) -> StateResult<TrieNode>;
+
+    /// Returns the root of the storage trie of the given contract; zero, if its storage is empty.
+    fn get_storage_root(&self, contract_address: ContractAddress) -> StateResult<StarkFelt>;
+}
+
+/// The tries of the empty state (e.g., prior to the genesis block of a devnet).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EmptyTrie;
+
+impl TrieReader for EmptyTrie {
+    fn get_node(&self, trie: Trie, node_hash: StarkFelt) -> StateResult<TrieNode> {
+        Err(StateError::StateReadError(format!(
+            "Node {node_hash} is not in the empty {trie:?} trie"
+        )))
+    }
+
+    fn get_storage_root(&self, _contract_address: ContractAddress) -> StateResult<StarkFelt> {
+        Ok(StarkFelt::ZERO)
+    }
+}
+
+/// The roots of the contracts and classes tries of a state.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct StateRoots {
+    pub contracts_root: StarkFelt,
+    pub classes_root: StarkFelt,
+}
+
+/// The commitment to the state following a state diff: its roots, and the storage roots and trie
+/// nodes created by the diff, to be stored for committing to the next diff.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct StateDiffCommitment {
+    pub roots: StateRoots,
+    pub storage_roots: BTreeMap<ContractAddress, StarkFelt>,
+    pub nodes: HashMap<(Trie, StarkFelt), TrieNode>,
+}
+
+impl StateRoots {
+    /// Returns the global state root; i.e., the state commitment of the network.
+    pub fn global_root(&self) -> StarkFelt {
+        global_root(self.contracts_root, self.classes_root)
+    }
+
+    /// Applies the given state diff to the state of these roots. The class hashes and nonces of
+    /// the modified contracts, not in the diff, are read from `state`, and the nodes of the
+    /// modified paths from `trie_reader`; both must read the state prior to the diff.
+    pub fn apply_state_diff(
+        &self,
+        state: &impl StateReader,
+        trie_reader: &impl TrieReader,
+        state_diff: &CommitmentStateDiff,
+    ) -> StateResult<StateDiffCommitment> {
+        let mut nodes = HashMap::new();
+        let mut storage_roots = BTreeMap::new();
+        for (contract_address, storage_updates) in &state_diff.storage_updates {
+            let storage_root =
+                TrieUpdate::new(trie_reader, Trie::Storage(*contract_address), &mut nodes)
+                    .update_root(
+                        trie_reader.get_storage_root(*contract_address)?,
+                        storage_updates.iter().map(|(key, value)| (*key.0.key(), *value)),
+                    )?;
+            storage_roots.insert(*contract_address, storage_root);
+        }
+
+        let contract_addresses: BTreeSet<ContractAddress> = state_diff
+            .storage_updates
+            .keys()
+            .chain(state_diff.address_to_class_hash.keys())
+            .chain(state_diff.address_to_nonce.keys())
+            .copied()
+            .collect();
+        let mut contract_leaves = Vec::with_capacity(contract_addresses.len());
+        for contract_address in contract_addresses {
+            let storage_root = match storage_roots.get(&contract_address) {
+                Some(storage_root) => *storage_root,
+                None => trie_reader.get_storage_root(contract_address)?,
+            };
+            let class_hash = match state_diff.address_to_class_hash.get(&contract_address) {
+                Some(class_hash) => *class_hash,
+                None => state.get_class_hash_at(contract_address)?,
+            };
+            let nonce = match state_diff.address_to_nonce.get(&contract_address) {
+                Some(nonce) => *nonce,
+                None => state.get_nonce_at(contract_address)?,
+            };
+            contract_leaves.push((
+                *contract_address.0.key(),
+                contract_state_hash(class_hash, storage_root, nonce),
+            ));
+        }
+        let contracts_root = TrieUpdate::new(trie_reader, Trie::Contracts, &mut nodes)
+            .update_root(self.contracts_root, contract_leaves)?;
+
+        let class_leaves = state_diff.class_hash_to_compiled_class_hash.iter().map(
+            |(class_hash, compiled_class_hash)| {
+                (class_hash.0, class_leaf_hash(*compiled_class_hash))
+            },
+        );
+        let classes_root = TrieUpdate::new(trie_reader, Trie::Classes, &mut nodes)
+            .update_root(self.classes_root, class_leaves)?;
+
+        Ok(StateDiffCommitment {
+            roots: StateRoots { contracts_root, classes_root },
+            storage_roots,
+            nodes,
+        })
+    }
+}
+
+/// Returns the hash of a contract instance; i.e., its leaf in the contracts trie.
+pub fn contract_state_hash(
+    class_hash: ClassHash,
+    storage_root: StarkFelt,
+    nonce: Nonce,
+) -> StarkFelt {
+    let hash = pedersen_hash(&class_hash.0.into(), &storage_root.into());
+    let hash = pedersen_hash(&hash, &nonce.0.into());
+    pedersen_hash(&hash, &CONTRACT_STATE_HASH_VERSION).into()
+}
+
+/// Returns the leaf of a declared class in the classes trie.
+pub fn class_leaf_hash(compiled_class_hash: CompiledClassHash) -> StarkFelt {
+    poseidon_hash(short_string(CONTRACT_CLASS_LEAF_VERSION), compiled_class_hash.0.into()).into()
+}
+
+/// Returns the global state root of the given tries' roots. States without a classes trie (i.e.,
+/// prior to Starknet 0.11.0) are committed to by their contracts trie alone.
+pub fn global_root(contracts_root: StarkFelt, classes_root: StarkFelt) -> StarkFelt {
+    if classes_root == StarkFelt::ZERO {
+        return contracts_root;
+    }
+
+    poseidon_hash_many(&[
+        short_string(GLOBAL_STATE_VERSION),
+        contracts_root.into(),
+        classes_root.into(),
+    ])
+    .into()
+}
+
+/// Returns the root of a Patricia tree of height 251, given all its leaves; zero leaves are
+/// omitted from the tree.
+pub fn patricia_root(
+    leaves: impl IntoIterator<Item = (StarkFelt, StarkFelt)>,
+    trie: Trie,
+) -> StarkFelt {
+    TrieUpdate::new(&EmptyTrie, trie, &mut HashMap::new())
+        .update_root(StarkFelt::ZERO, leaves)
+        .expect("The nodes of an empty tree are never read.")
+}
+
+/// A subtree: an edge (of length 0, if the subtree is rooted at a binary node or a leaf) leading
+/// to a node of the given hash.
+struct Subtree {
+    node_hash: FieldElement,
+    path: FieldElement,
+    length: usize,
+    // 2^length.
+    path_bound: FieldElement,
+    // The hash of the subtree, if it is unmodified; i.e., read from the trie.
+    hash: Option<FieldElement>,
+}
+
+impl Subtree {
+    fn node(node_hash: FieldElement) -> Self {
+        Self {
+            node_hash,
+            path: FieldElement::ZERO,
+            length: 0,
+            path_bound: FieldElement::ONE,
+            hash: None,
+        }
+    }
+
+    /// Returns this subtree, under an edge of one more bit, to the given side.
+    fn extend(self, is_right: bool) -> Self {
+        let Self { node_hash, path, length, path_bound, .. } = self;
+        Self {
+            node_hash,
+            path: if is_right { path + path_bound } else { path },
+            length: length + 1,
+            path_bound: path_bound + path_bound,
+            hash: None,
+        }
+    }
+}
+
+/// An update of the leaves of a trie, recording the nodes it creates.
+struct TrieUpdate<'a, R: TrieReader> {
+    trie_reader: &'a R,
+    trie: Trie,
+    nodes: &'a mut HashMap<(Trie, StarkFelt), TrieNode>,
+}
+
+impl<'a, R: TrieReader> TrieUpdate<'a, R> {
+    fn new(
+        trie_reader: &'a R,
+        trie: Trie,
+        nodes: &'a mut HashMap<(Trie, StarkFelt), TrieNode>,
+    ) -> Self {
+        Self { trie_reader, trie, nodes }
+    }
+
+    /// Returns the root of the trie of the given root, after setting the given leaves.
+    fn update_root(
+        &mut self,
+        root: StarkFelt,
+        leaves: impl IntoIterator<Item = (StarkFelt, StarkFelt)>,
+    ) -> StateResult<StarkFelt> {
+        let mut leaves: Vec<(StarkFelt, FieldElement)> =
+            leaves.into_iter().map(|(key, value)| (key, value.into())).collect();
+        if leaves.is_empty() {
+            return Ok(root);
+        }
+
+        leaves.sort_unstable_by_key(|(key, _)| *key);
+        let root = if root == StarkFelt::ZERO { None } else { Some(self.read_subtree(root, 0)?) };
+
+        Ok(match self.update(root, &leaves, 0)? {
+            Some(root) => self.hash(root).into(),
+            None => StarkFelt::ZERO,
+        })
+    }
+
+    /// Returns the given subtree, rooted at the given height, after setting the given (sorted)
+    /// leaves; `None`, if the subtree is empty.
+    fn update(
+        &mut self,
+        subtree: Option<Subtree>,
+        leaves: &[(StarkFelt, FieldElement)],
+        height: usize,
+    ) -> StateResult<Option<Subtree>> {
+        if leaves.is_empty() {
+            return Ok(subtree);
+        }
+        if height == TREE_HEIGHT {
+            let value = leaves[0].1;
+            return Ok((value != FieldElement::ZERO).then(|| Subtree::node(value)));
+        }
+
+        let (left, right) = match subtree {
+            Some(subtree) => self.children(subtree, height)?,
+            None => (None, None),
+        };
+        let n_left_leaves = leaves.partition_point(|(key, _)| !key_bit(key, height));
+        let (left_leaves, right_leaves) = leaves.split_at(n_left_leaves);
+        let left = self.update(left, left_leaves, height + 1)?;
+        let right = self.update(right, right_leaves, height + 1)?;
+
+        Ok(match (left, right) {
+            (None, None) => None,
+            (Some(left), None) => Some(left.extend(false)),
+            (None, Some(right)) => Some(right.extend(true)),
+            (Some(left), Some(right)) => {
+                let (left, right) = (self.hash(left), self.hash(right));
+                let node_hash = self.trie.hash_function().hash(&left, &right);
+                self.nodes.insert(
+                    (self.trie, node_hash.into()),
+                    TrieNode::Binary { left: left.into(), right: right.into() },
+                );
+                Some(Subtree::node(node_hash))
+            }
+        })
+    }
+
+    /// Returns the hash of the given subtree, recording its edge node, if it is new.
+    fn hash(&mut self, subtree: Subtree) -> FieldElement {
+        if let Some(hash) = subtree.hash {
+            return hash;
+        }
+        if subtree.length == 0 {
+            return subtree.node_hash;
+        }
+
+        let hash = self.trie.hash_function().hash(&subtree.node_hash, &subtree.path)
+            + FieldElement::from(subtree.length);
+        self.nodes.insert(
+            (self.trie, hash.into()),
+            TrieNode::Edge {
+                child: subtree.node_hash.into(),
+                path: subtree.path.into(),
+                length: subtree.length,
+            },
+        );
+        hash
+    }
+
+    /// Returns the subtree of the given hash, rooted at the given height, read from the trie.
+    fn read_subtree(&self, hash: StarkFelt, height: usize) -> StateResult<Subtree> {
+        let subtree = if height == TREE_HEIGHT {
+            Subtree::node(hash.into())
+        } else {
+            match self.trie_reader.get_node(self.trie, hash)? {
+                TrieNode::Binary { .. } => Subtree::node(hash.into()),
+                TrieNode::Edge { child, path, length } => Subtree {
+                    node_hash: child.into(),
+                    path: path.into(),
+                    length,
+                    path_bound: (0..length)
+                        .fold(FieldElement::ONE, |path_bound, _| path_bound + path_bound),
+                    hash: None,
+                },
+            }
+        };
+
+        Ok(Subtree { hash: Some(hash.into()), ..subtree })
+    }
+
+    /// Returns the left and right subtrees of the given subtree, rooted at the given height.
+    fn children(
+        &self,
+        subtree: Subtree,
+        height: usize,
+    ) -> StateResult<(Option<Subtree>, Option<Subtree>)> {
+        if subtree.length == 0 {
+            let node_hash = subtree.node_hash.into();
+            let TrieNode::Binary { left, right } =
+                self.trie_reader.get_node(self.trie, node_hash)?
+            else {
+                return Err(StateError::StateReadError(format!(
+                    "Node {node_hash} of the {:?} trie is not a binary node",
+                    self.trie
+                )));
+            };
+            return Ok((
+                Some(self.read_subtree(left, height + 1)?),
+                Some(self.read_subtree(right, height + 1)?),
+            ));
+        }
+
+        // Remove the first bit of the edge.
+        let path_bound = subtree.path_bound.floor_div(FieldElement::TWO);
+        let is_right = subtree.path & path_bound != FieldElement::ZERO;
+        let child = Subtree {
+            node_hash: subtree.node_hash,
+            path: if is_right { subtree.path - path_bound } else { subtree.path },
+            length: subtree.length - 1,
+            path_bound,
+            hash: None,
+        };
+
+        Ok(if is_right { (None, Some(child)) } else { (Some(child), None) })
+    }
+}
+
+/// Returns the bit of the key at the given height, from the most significant of its 251 bits.
+fn key_bit(key: &StarkFelt, height: usize) -> bool {
+    let bit_index = 256 - TREE_HEIGHT + height;
+    key.bytes()[bit_index / 8] & (0x80 >> (bit_index % 8)) != 0
+}
+
+fn short_string(short_string: &str) -> FieldElement {
+    FieldElement::from_byte_slice_be(short_string.as_bytes())
+        .expect("A short string fits in a field element.")
+}
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+
+use indexmap::indexmap;
+use pretty_assertions::assert_eq;
+use starknet_api::core::{ClassHash, ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
+use starknet_crypto::{pedersen_hash, poseidon_hash, poseidon_hash_many, FieldElement};
+
+use crate::abi::abi_utils::get_fee_token_var_address;
+use crate::commitment::{
+    class_leaf_hash, contract_state_hash, global_root, patricia_root, EmptyTrie,
+    StateDiffCommitment, StateRoots, Trie, TrieNode, TrieReader,
+};
+use crate::state::cached_state::CommitmentStateDiff;
+use crate::state::errors::StateError;
+use crate::state::state_api::StateResult;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::{compiled_class_hash, nonce, storage_key};
+
+/// The tries of the committed states, kept in memory.
+#[derive(Default)]
+struct DictTrieReader {
+    nodes: HashMap<(Trie, StarkFelt), TrieNode>,
+    storage_roots: HashMap<ContractAddress, StarkFelt>,
+}
+
+impl DictTrieReader {
+    /// Stores the given commitment, and returns its roots.
+    fn commit(&mut self, commitment: StateDiffCommitment) -> StateRoots {
+        self.nodes.extend(commitment.nodes);
+        self.storage_roots.extend(commitment.storage_roots);
+        commitment.roots
+    }
+}
+
+impl TrieReader for DictTrieReader {
+    fn get_node(&self, trie: Trie, node_hash: StarkFelt) -> StateResult<TrieNode> {
+        self.nodes.get(&(trie, node_hash)).copied().ok_or_else(|| {
+            StateError::StateReadError(format!("Node {node_hash} is not in the {trie:?} trie"))
+        })
+    }
+
+    fn get_storage_root(&self, contract_address: ContractAddress) -> StateResult<StarkFelt> {
+        Ok(self.storage_roots.get(&contract_address).copied().unwrap_or(StarkFelt::ZERO))
+    }
+}
+
+/// Returns the commitment to the given state diff, applied to the empty state.
+fn commit_from_empty_state(state_diff: &CommitmentStateDiff) -> StateDiffCommitment {
+    StateRoots::default()
+        .apply_state_diff(&DictStateReader::default(), &EmptyTrie, state_diff)
+        .unwrap()
+}
+
+/// Returns the hash of an edge node.
+fn edge_hash(child_hash: FieldElement, path: impl Into<FieldElement>, length: u64) -> FieldElement {
+    pedersen_hash(&child_hash, &path.into()) + FieldElement::from(length)
+}
+
+#[test]
+fn test_patricia_root() {
+    let root = |leaves: &[(StarkFelt, StarkFelt)]| {
+        FieldElement::from(patricia_root(leaves.iter().copied(), Trie::Contracts))
+    };
+    let (value0, value1) = (stark_felt!(7_u8), stark_felt!(9_u8));
+    let (hash0, hash1) = (FieldElement::from(value0), FieldElement::from(value1));
+
+    // Empty trees, and zero leaves.
+    assert_eq!(root(&[]), FieldElement::ZERO);
+    assert_eq!(root(&[(stark_felt!(1_u8), StarkFelt::ZERO)]), FieldElement::ZERO);
+
+    // A single leaf is reached by an edge from the root.
+    assert_eq!(root(&[(stark_felt!(5_u8), value0)]), edge_hash(hash0, 5_u64, 251));
+
+    // Sibling leaves, under an edge from the root.
+    let binary_hash = pedersen_hash(&hash0, &hash1);
+    assert_eq!(
+        root(&[(stark_felt!(0_u8), value0), (stark_felt!(1_u8), value1)]),
+        edge_hash(binary_hash, 0_u64, 250)
+    );
+
+    // Leaves in different halves of the tree, each under an edge from a binary root.
+    let right_key =
+        stark_felt!("0x400000000000000000000000000000000000000000000000000000000000003");
+    assert_eq!(
+        root(&[(stark_felt!(0_u8), value0), (right_key, value1)]),
+        pedersen_hash(&edge_hash(hash0, 0_u64, 250), &edge_hash(hash1, 3_u64, 250))
+    );
+}
+
+#[test]
+fn test_global_root() {
+    let contract_address = contract_address!("0x100");
+    let (class_hash, compiled_class_hash) = (class_hash!("0x10"), compiled_class_hash!("0x11"));
+    assert_eq!(StateRoots::default().global_root(), StarkFelt::ZERO);
+
+    let roots = commit_from_empty_state(&CommitmentStateDiff {
+        address_to_class_hash: indexmap! { contract_address => class_hash },
+        address_to_nonce: indexmap! { contract_address => nonce!(1_u8) },
+        storage_updates: indexmap! {
+            contract_address => indexmap! { storage_key!(5_u8) => stark_felt!(7_u8) },
+        },
+        class_hash_to_compiled_class_hash: indexmap! {},
+    })
+    .roots;
+    let storage_root =
+        patricia_root([(stark_felt!(5_u8), stark_felt!(7_u8))], Trie::Storage(contract_address));
+    let contracts_root = patricia_root(
+        [(*contract_address.0.key(), contract_state_hash(class_hash, storage_root, nonce!(1_u8)))],
+        Trie::Contracts,
+    );
+    assert_eq!(roots.contracts_root, contracts_root);
+    // Without classes, the state is committed to by its contracts trie alone.
+    assert_eq!(roots.global_root(), contracts_root);
+
+    // Only the classes trie is modified, hence no nodes are read.
+    let roots = roots
+        .apply_state_diff(
+            &DictStateReader::default(),
+            &EmptyTrie,
+            &CommitmentStateDiff {
+                address_to_class_hash: indexmap! {},
+                address_to_nonce: indexmap! {},
+                storage_updates: indexmap! {},
+                class_hash_to_compiled_class_hash: indexmap! { class_hash => compiled_class_hash },
+            },
+        )
+        .unwrap()
+        .roots;
+    let classes_root =
+        patricia_root([(class_hash.0, class_leaf_hash(compiled_class_hash))], Trie::Classes);
+    assert_eq!(roots.classes_root, classes_root);
+    assert_eq!(roots.global_root(), global_root(contracts_root, classes_root));
+    assert_ne!(roots.global_root(), contracts_root);
+}
+
+#[test]
+fn test_apply_state_diff() {
+    let (address0, address1, address2) =
+        (contract_address!("0x100"), contract_address!("0x200"), contract_address!("0x300"));
+    let (class_hash0, class_hash1) = (class_hash!("0x10"), class_hash!("0x20"));
+    let (compiled_class_hash0, compiled_class_hash1) =
+        (compiled_class_hash!("0x11"), compiled_class_hash!("0x21"));
+    let right_key =
+        storage_key!("0x400000000000000000000000000000000000000000000000000000000000003");
+    let first_diff = CommitmentStateDiff {
+        address_to_class_hash: indexmap! { address0 => class_hash0, address1 => class_hash0 },
+        address_to_nonce: indexmap! { address0 => nonce!(1_u8) },
+        storage_updates: indexmap! {
+            address0 => indexmap! {
+                storage_key!(5_u8) => stark_felt!(7_u8),
+                storage_key!(6_u8) => stark_felt!(8_u8),
+                right_key => stark_felt!(9_u8),
+            },
+            address1 => indexmap! { storage_key!(5_u8) => stark_felt!(9_u8) },
+        },
+        class_hash_to_compiled_class_hash: indexmap! { class_hash0 => compiled_class_hash0 },
+    };
+    let second_diff = CommitmentStateDiff {
+        address_to_class_hash: indexmap! { address2 => class_hash1 },
+        // A contract modified by its nonce alone.
+        address_to_nonce: indexmap! { address1 => nonce!(1_u8) },
+        storage_updates: indexmap! {
+            // An update, a deletion, collapsing the binary node of keys 5 and 6, and an insertion.
+            address0 => indexmap! {
+                storage_key!(5_u8) => stark_felt!(3_u8),
+                storage_key!(6_u8) => StarkFelt::ZERO,
+                storage_key!(9_u8) => stark_felt!(4_u8),
+            },
+            address2 => indexmap! { storage_key!(1_u8) => stark_felt!(1_u8) },
+        },
+        class_hash_to_compiled_class_hash: indexmap! { class_hash1 => compiled_class_hash1 },
+    };
+
+    let mut trie_reader = DictTrieReader::default();
+    let roots = trie_reader.commit(commit_from_empty_state(&first_diff));
+    let state = DictStateReader {
+        address_to_class_hash: first_diff.address_to_class_hash.into_iter().collect(),
+        address_to_nonce: first_diff.address_to_nonce.into_iter().collect(),
+        ..Default::default()
+    };
+    let commitment = roots.apply_state_diff(&state, &trie_reader, &second_diff).unwrap();
+    let roots = trie_reader.commit(commitment);
+
+    // The same state, committed to from scratch.
+    let expected_commitment = commit_from_empty_state(&CommitmentStateDiff {
+        address_to_class_hash: indexmap! {
+            address0 => class_hash0, address1 => class_hash0, address2 => class_hash1,
+        },
+        address_to_nonce: indexmap! { address0 => nonce!(1_u8), address1 => nonce!(1_u8) },
+        storage_updates: indexmap! {
+            address0 => indexmap! {
+                storage_key!(5_u8) => stark_felt!(3_u8),
+                storage_key!(9_u8) => stark_felt!(4_u8),
+                right_key => stark_felt!(9_u8),
+            },
+            address1 => indexmap! { storage_key!(5_u8) => stark_felt!(9_u8) },
+            address2 => indexmap! { storage_key!(1_u8) => stark_felt!(1_u8) },
+        },
+        class_hash_to_compiled_class_hash: indexmap! {
+            class_hash0 => compiled_class_hash0, class_hash1 => compiled_class_hash1,
+        },
+    });
+    assert_eq!(roots, expected_commitment.roots);
+    assert_eq!(trie_reader.storage_roots, expected_commitment.storage_roots.into_iter().collect());
+}
+
+/// Returns the hash of a contract (with a zero nonce) in the contracts trie.
+fn contract_leaf_hash(class_hash: ClassHash, storage_root: FieldElement) -> FieldElement {
+    let contract_state_hash_version = FieldElement::ZERO;
+    pedersen_hash(
+        &pedersen_hash(&pedersen_hash(&class_hash.0.into(), &storage_root), &FieldElement::ZERO),
+        &contract_state_hash_version,
+    )
+}
+
+#[test]
+fn test_genesis_root() {
+    let fee_token_address = contract_address!("0x1001");
+    let account_address = contract_address!("0x101");
+    let (fee_token_class_hash, account_class_hash) = (class_hash!("0x1010"), class_hash!("0x111"));
+    let compiled_class_hash = compiled_class_hash!("0x1111");
+    let balance_key = get_fee_token_var_address(account_address);
+    let balance = stark_felt!("0x3635c9adc5dea00000");
+    let genesis_diff = CommitmentStateDiff {
+        address_to_class_hash: indexmap! {
+            fee_token_address => fee_token_class_hash,
+            account_address => account_class_hash,
+        },
+        address_to_nonce: indexmap! {},
+        storage_updates: indexmap! {
+            fee_token_address => indexmap! { balance_key => balance },
+        },
+        // The account is a Cairo 1 class; the fee token, a Cairo 0 class.
+        class_hash_to_compiled_class_hash: indexmap! { account_class_hash => compiled_class_hash },
+    };
+
+    // The balance is the single leaf of the storage trie of the fee token.
+    let fee_token_storage_root = edge_hash(balance.into(), *balance_key.0.key(), 251);
+    // The keys of the contracts (0x101 and 0x1001) first differ at bit 12: their leaves are
+    // reached by edges of length 12 from a binary node, under an edge of length 238 from the root.
+    let account_leaf = contract_leaf_hash(account_class_hash, FieldElement::ZERO);
+    let fee_token_leaf = contract_leaf_hash(fee_token_class_hash, fee_token_storage_root);
+    let contracts_root = edge_hash(
+        pedersen_hash(
+            &edge_hash(account_leaf, 0x101_u64, 12),
+            &edge_hash(fee_token_leaf, 0x1_u64, 12),
+        ),
+        0_u64,
+        238,
+    );
+    let class_leaf = poseidon_hash(
+        FieldElement::from_byte_slice_be(b"CONTRACT_CLASS_LEAF_V0").unwrap(),
+        compiled_class_hash.0.into(),
+    );
+    // The classes trie hashes its nodes with Poseidon.
+    let classes_root =
+        poseidon_hash(class_leaf, FieldElement::from(0x111_u64)) + FieldElement::from(251_u64);
+    let expected_global_root = poseidon_hash_many(&[
+        FieldElement::from_byte_slice_be(b"STARKNET_STATE_V0").unwrap(),
+        contracts_root,
+        classes_root,
+    ]);
+
+    let roots = commit_from_empty_state(&genesis_diff).roots;
+    assert_eq!(FieldElement::from(roots.contracts_root), contracts_root);
+    assert_eq!(FieldElement::from(roots.classes_root), classes_root);
+    assert_eq!(FieldElement::from(roots.global_root()), expected_global_root);
+}
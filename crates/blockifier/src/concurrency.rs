@@ -1,7 +1,9 @@
+pub mod fee_utils;
 pub mod scheduler;
 #[cfg(any(feature = "testing", test))]
 pub mod test_utils;
 pub mod versioned_state_proxy;
 pub mod versioned_storage;
+pub mod worker_logic;
 
 type TxIndex = usize;
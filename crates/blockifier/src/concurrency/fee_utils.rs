@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use num_traits::ToPrimitive;
+use starknet_api::core::ContractAddress;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Fee;
+
+use crate::abi::abi_utils::get_fee_token_var_address;
+use crate::abi::sierra_types::next_storage_key;
+use crate::concurrency::versioned_state_proxy::VersionedStateProxy;
+use crate::context::TransactionContext;
+use crate::execution::call_info::{CallInfo, StorageAccessType};
+use crate::execution::execution_utils::stark_felt_to_felt;
+use crate::state::cached_state::{ContractClassMapping, StateMaps};
+use crate::state::state_api::{StateReader, StateResult};
+use crate::transaction::errors::TransactionFeeError;
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionFeeResult};
+
+#[cfg(test)]
+#[path = "fee_utils_test.rs"]
+pub mod test;
+
+// The ERC20 fee transfer reads the sender balance (low, high) and then the recipient balance.
+pub const STORAGE_READ_SEQUENCER_BALANCE_INDICES: (usize, usize) = (2, 3);
+
+/// Completes the fee transfer of a concurrently executed transaction, at its (sequential) commit
+/// stage: reads the sequencer balance left by the preceding transactions, adds the fee to it, and
+/// fills both balances into the fee transfer call info.
+/// The sequencer balance is not part of the transaction's read set (see
+/// `AccountTransaction::concurrency_execute_fee_transfer`), so racing fee transfers do not
+/// invalidate each other; transactions that read the balance otherwise are still invalidated by
+/// the new balance, and re-executed.
+/// Fails if the fee transfer did not read the sequencer balance, or if the fee cannot be added to
+/// it; the transaction is then rejected by the caller.
+pub fn complete_fee_transfer_flow<S: StateReader>(
+    tx_context: &TransactionContext,
+    tx_execution_info: &mut TransactionExecutionInfo,
+    state: &mut VersionedStateProxy<S>,
+) -> TransactionFeeResult<()> {
+    let sequencer_address = tx_context.block_context.block_info.sequencer_address;
+    if sequencer_address == tx_context.tx_info.sender_address() {
+        // The sequencer balance was updated by the (sequential) fee transfer itself.
+        return Ok(());
+    }
+
+    let actual_fee = tx_execution_info.actual_fee;
+    let Some(fee_transfer_call_info) = tx_execution_info.fee_transfer_call_info.as_mut() else {
+        assert_eq!(actual_fee, Fee(0), "A charged fee must have a fee transfer call info.");
+        return Ok(());
+    };
+
+    let fee_token_address = tx_context.fee_token_address();
+    let sequencer_balance = state.get_fee_token_balance(sequencer_address, fee_token_address)?;
+    fill_sequencer_balance_reads(fee_transfer_call_info, sequencer_balance)?;
+    let new_sequencer_balance = add_fee_to_sequencer_balance(
+        fee_token_address,
+        state,
+        actual_fee,
+        sequencer_address,
+        sequencer_balance,
+    )?;
+    fill_sequencer_balance_writes(
+        fee_transfer_call_info,
+        sequencer_address,
+        new_sequencer_balance,
+    )?;
+
+    Ok(())
+}
+
+/// Sets the sequencer balance, read at the commit stage, as the value read by the fee transfer.
+pub fn fill_sequencer_balance_reads(
+    fee_transfer_call_info: &mut CallInfo,
+    sequencer_balance: (StarkFelt, StarkFelt),
+) -> TransactionFeeResult<()> {
+    let storage_read_values = &mut fee_transfer_call_info.storage_read_values;
+    let (low_index, high_index) = STORAGE_READ_SEQUENCER_BALANCE_INDICES;
+    if storage_read_values.len() <= high_index {
+        return Err(TransactionFeeError::MissingSequencerBalanceRead);
+    }
+    storage_read_values[low_index] = sequencer_balance.0;
+    storage_read_values[high_index] = sequencer_balance.1;
+
+    let mut reads = fee_transfer_call_info
+        .storage_accesses
+        .iter_mut()
+        .filter(|access| access.access_type == StorageAccessType::Read);
+    if let Some(low_read) = reads.nth(low_index) {
+        low_read.value = sequencer_balance.0;
+    }
+    if let Some(high_read) = reads.nth(high_index - low_index - 1) {
+        high_read.value = sequencer_balance.1;
+    }
+
+    Ok(())
+}
+
+/// Sets the sequencer balance with the fee added, written at the commit stage, as the value written
+/// by the fee transfer.
+pub fn fill_sequencer_balance_writes(
+    fee_transfer_call_info: &mut CallInfo,
+    sequencer_address: ContractAddress,
+    new_sequencer_balance: (StarkFelt, StarkFelt),
+) -> StateResult<()> {
+    let sequencer_balance_key_low = get_fee_token_var_address(sequencer_address);
+    let sequencer_balance_key_high = next_storage_key(&sequencer_balance_key_low)?;
+    let writes = fee_transfer_call_info
+        .storage_accesses
+        .iter_mut()
+        .filter(|access| access.access_type == StorageAccessType::Write);
+    for write in writes {
+        if write.key == sequencer_balance_key_low {
+            write.value = new_sequencer_balance.0;
+        } else if write.key == sequencer_balance_key_high {
+            write.value = new_sequencer_balance.1;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the sequencer balance with the fee added, as a write of the pinned transaction, and
+/// returns it.
+pub fn add_fee_to_sequencer_balance<S: StateReader>(
+    fee_token_address: ContractAddress,
+    state: &VersionedStateProxy<S>,
+    actual_fee: Fee,
+    sequencer_address: ContractAddress,
+    (sequencer_balance_low, sequencer_balance_high): (StarkFelt, StarkFelt),
+) -> TransactionFeeResult<(StarkFelt, StarkFelt)> {
+    let to_u128 = |value: StarkFelt| {
+        stark_felt_to_felt(value).to_u128().ok_or(TransactionFeeError::InvalidSequencerBalance {
+            balance_low: sequencer_balance_low,
+            balance_high: sequencer_balance_high,
+        })
+    };
+    let (new_balance_low, carry) = to_u128(sequencer_balance_low)?.overflowing_add(actual_fee.0);
+    let (new_balance_high, overflow) =
+        to_u128(sequencer_balance_high)?.overflowing_add(u128::from(carry));
+    if overflow {
+        return Err(TransactionFeeError::SequencerBalanceOverflow {
+            actual_fee,
+            balance_low: sequencer_balance_low,
+            balance_high: sequencer_balance_high,
+        });
+    }
+
+    let sequencer_balance_key_low = get_fee_token_var_address(sequencer_address);
+    let sequencer_balance_key_high = next_storage_key(&sequencer_balance_key_low)?;
+    let new_balance = (StarkFelt::from(new_balance_low), StarkFelt::from(new_balance_high));
+    let writes = StateMaps {
+        storage: HashMap::from([
+            ((fee_token_address, sequencer_balance_key_low), new_balance.0),
+            ((fee_token_address, sequencer_balance_key_high), new_balance.1),
+        ]),
+        ..StateMaps::default()
+    };
+    state.apply_writes(&writes, &ContractClassMapping::default());
+
+    Ok(new_balance)
+}
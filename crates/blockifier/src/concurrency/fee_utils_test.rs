@@ -0,0 +1,153 @@
+use assert_matches::assert_matches;
+use rstest::rstest;
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::Fee;
+use starknet_api::{contract_address, patricia_key, stark_felt};
+
+use crate::abi::abi_utils::get_fee_token_var_address;
+use crate::abi::sierra_types::next_storage_key;
+use crate::concurrency::fee_utils::{
+    add_fee_to_sequencer_balance, fill_sequencer_balance_reads, fill_sequencer_balance_writes,
+};
+use crate::concurrency::test_utils::safe_versioned_state_for_testing;
+use crate::execution::call_info::{CallInfo, StorageAccess};
+use crate::state::state_api::StateReader;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::transaction::errors::TransactionFeeError;
+
+#[test]
+fn test_fill_sequencer_balance_reads() {
+    let key = StorageKey::default();
+    let storage_accesses = vec![
+        StorageAccess::read(key, StarkFelt::ZERO),
+        StorageAccess::read(key, StarkFelt::ZERO),
+        StorageAccess::write(key, StarkFelt::ZERO),
+        StorageAccess::read(key, StarkFelt::ZERO),
+        StorageAccess::read(key, StarkFelt::ZERO),
+    ];
+    let mut fee_transfer_call_info = CallInfo {
+        storage_read_values: vec![StarkFelt::ZERO; 4],
+        storage_accesses,
+        ..Default::default()
+    };
+    let sequencer_balance = (StarkFelt::from(1_u8), StarkFelt::from(2_u8));
+
+    fill_sequencer_balance_reads(&mut fee_transfer_call_info, sequencer_balance).unwrap();
+    assert_eq!(
+        fee_transfer_call_info.storage_read_values,
+        vec![StarkFelt::ZERO, StarkFelt::ZERO, sequencer_balance.0, sequencer_balance.1]
+    );
+    let accessed_values: Vec<StarkFelt> =
+        fee_transfer_call_info.storage_accesses.iter().map(|access| access.value).collect();
+    assert_eq!(
+        accessed_values,
+        vec![
+            StarkFelt::ZERO,
+            StarkFelt::ZERO,
+            StarkFelt::ZERO,
+            sequencer_balance.0,
+            sequencer_balance.1
+        ]
+    );
+}
+
+#[test]
+fn test_fill_sequencer_balance_writes() {
+    let sequencer_address = contract_address!("0x2");
+    let sequencer_balance_key_low = get_fee_token_var_address(sequencer_address);
+    let sequencer_balance_key_high = next_storage_key(&sequencer_balance_key_low).unwrap();
+    let sender_balance_key = get_fee_token_var_address(contract_address!("0x3"));
+    let storage_accesses = vec![
+        StorageAccess::read(sequencer_balance_key_low, StarkFelt::ZERO),
+        StorageAccess::write(sender_balance_key, StarkFelt::ZERO),
+        StorageAccess::write(sequencer_balance_key_low, StarkFelt::ZERO),
+        StorageAccess::write(sequencer_balance_key_high, StarkFelt::ZERO),
+    ];
+    let mut fee_transfer_call_info = CallInfo { storage_accesses, ..Default::default() };
+    let new_sequencer_balance = (StarkFelt::from(1_u8), StarkFelt::from(2_u8));
+
+    fill_sequencer_balance_writes(
+        &mut fee_transfer_call_info,
+        sequencer_address,
+        new_sequencer_balance,
+    )
+    .unwrap();
+    let accessed_values: Vec<StarkFelt> =
+        fee_transfer_call_info.storage_accesses.iter().map(|access| access.value).collect();
+    assert_eq!(
+        accessed_values,
+        vec![StarkFelt::ZERO, StarkFelt::ZERO, new_sequencer_balance.0, new_sequencer_balance.1]
+    );
+}
+
+#[rstest]
+#[case::no_carry(10, 0, 5, (15, 0))]
+#[case::carry(u128::MAX, 1, 1, (0, 2))]
+fn test_add_fee_to_sequencer_balance(
+    #[case] balance_low: u128,
+    #[case] balance_high: u128,
+    #[case] actual_fee: u128,
+    #[case] expected_balance: (u128, u128),
+) {
+    let fee_token_address = contract_address!("0x1");
+    let sequencer_address = contract_address!("0x2");
+    let versioned_state = safe_versioned_state_for_testing(DictStateReader::default());
+    let tx_index = 0;
+
+    let new_balance = add_fee_to_sequencer_balance(
+        fee_token_address,
+        &versioned_state.pin_version(tx_index),
+        Fee(actual_fee),
+        sequencer_address,
+        (StarkFelt::from(balance_low), StarkFelt::from(balance_high)),
+    )
+    .unwrap();
+    let expected_balance =
+        (StarkFelt::from(expected_balance.0), StarkFelt::from(expected_balance.1));
+    assert_eq!(new_balance, expected_balance);
+
+    // The new balance is seen by the following transactions.
+    let mut next_tx_state = versioned_state.pin_version(tx_index + 1);
+    assert_eq!(
+        next_tx_state.get_fee_token_balance(sequencer_address, fee_token_address).unwrap(),
+        expected_balance
+    );
+}
+
+#[test]
+fn test_fill_sequencer_balance_reads_missing_read() {
+    let mut fee_transfer_call_info =
+        CallInfo { storage_read_values: vec![StarkFelt::ZERO; 2], ..Default::default() };
+    let sequencer_balance = (StarkFelt::from(1_u8), StarkFelt::from(2_u8));
+
+    let error =
+        fill_sequencer_balance_reads(&mut fee_transfer_call_info, sequencer_balance).unwrap_err();
+    assert_matches!(error, TransactionFeeError::MissingSequencerBalanceRead);
+}
+
+#[rstest]
+#[case::invalid_balance(stark_felt!("0x100000000000000000000000000000000"), StarkFelt::ZERO, false)]
+#[case::overflow(StarkFelt::from(u128::MAX), StarkFelt::from(u128::MAX), true)]
+fn test_add_fee_to_sequencer_balance_failure(
+    #[case] balance_low: StarkFelt,
+    #[case] balance_high: StarkFelt,
+    #[case] expect_overflow: bool,
+) {
+    let versioned_state = safe_versioned_state_for_testing(DictStateReader::default());
+
+    let error = add_fee_to_sequencer_balance(
+        contract_address!("0x1"),
+        &versioned_state.pin_version(0),
+        Fee(1),
+        contract_address!("0x2"),
+        (balance_low, balance_high),
+    )
+    .unwrap_err();
+    if expect_overflow {
+        assert_matches!(error, TransactionFeeError::SequencerBalanceOverflow { .. });
+    } else {
+        assert_matches!(error, TransactionFeeError::InvalidSequencerBalance { .. });
+    }
+}
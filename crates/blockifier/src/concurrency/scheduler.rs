@@ -1,6 +1,6 @@
 use std::cmp::min;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Mutex, MutexGuard};
+use std::sync::{Mutex, MutexGuard, TryLockError};
 
 use crate::concurrency::TxIndex;
 
@@ -25,6 +25,10 @@ pub struct Scheduler {
     /// Updated by the `check_done` procedure, providing a cheap way for all threads to exit their
     /// main loops.
     done_marker: AtomicBool,
+    /// The index of the next transaction to commit; held by the (single) committing thread.
+    commit_index: Mutex<TxIndex>,
+    /// Set by `halt`; no further transactions are committed.
+    halt_marker: AtomicBool,
 }
 
 // TODO(Avi, 01/04/2024): Remove dead_code attribute.
@@ -41,6 +45,8 @@ impl Scheduler {
                 .take(chunk_size)
                 .collect(),
             done_marker: AtomicBool::new(false),
+            commit_index: Mutex::new(0),
+            halt_marker: AtomicBool::new(false),
         }
     }
 
@@ -49,6 +55,39 @@ impl Scheduler {
         self.done_marker.load(Ordering::Acquire)
     }
 
+    /// Returns whether the scheduler was halted.
+    pub fn halted(&self) -> bool {
+        self.halt_marker.load(Ordering::Acquire)
+    }
+
+    /// Stops the scheduling of tasks, e.g., when the block is closed mid-chunk; transactions that
+    /// are not committed yet will not be.
+    pub fn halt(&self) {
+        self.halt_marker.store(true, Ordering::Release);
+        self.done_marker.store(true, Ordering::Release);
+    }
+
+    /// Returns the number of committed transactions (a prefix of the chunk).
+    pub fn n_committed_txs(&self) -> usize {
+        *self.lock_commit_index()
+    }
+
+    /// Returns a committer, unless another thread is committing.
+    pub fn try_enter_commit_phase(&self) -> Option<TransactionCommitter<'_>> {
+        match self.commit_index.try_lock() {
+            Ok(commit_index) => Some(TransactionCommitter { scheduler: self, commit_index }),
+            Err(TryLockError::WouldBlock) => None,
+            Err(TryLockError::Poisoned(error)) => {
+                panic!("Commit index is poisoned. Data: {:?}.", *error.get_ref())
+            }
+        }
+    }
+
+    /// Returns a committer, waiting for other committing threads.
+    pub fn enter_commit_phase(&self) -> TransactionCommitter<'_> {
+        TransactionCommitter { scheduler: self, commit_index: self.lock_commit_index() }
+    }
+
     pub fn next_task(&self) -> Task {
         if self.done() {
             return Task::Done;
@@ -140,6 +179,12 @@ impl Scheduler {
         })
     }
 
+    fn lock_commit_index(&self) -> MutexGuard<'_, TxIndex> {
+        self.commit_index.lock().unwrap_or_else(|error| {
+            panic!("Commit index is poisoned. Data: {:?}.", *error.get_ref())
+        })
+    }
+
     fn set_executed_status(&self, tx_index: TxIndex) {
         let mut status = self.lock_tx_status(tx_index);
         assert_eq!(
@@ -232,6 +277,36 @@ impl Scheduler {
     }
 }
 
+/// Grants the exclusive right to commit transactions, in order.
+pub struct TransactionCommitter<'a> {
+    scheduler: &'a Scheduler,
+    commit_index: MutexGuard<'a, TxIndex>,
+}
+
+impl TransactionCommitter<'_> {
+    /// Returns the index of the next transaction to commit if it is executed, and marks it as
+    /// committed, so that it is no longer aborted by validation tasks. Must be followed by
+    /// `commit`, unless the scheduler is halted.
+    pub fn try_lock_next_tx(&self) -> Option<TxIndex> {
+        let tx_index = *self.commit_index;
+        if tx_index >= self.scheduler.chunk_size || self.scheduler.halted() {
+            return None;
+        }
+
+        let mut status = self.scheduler.lock_tx_status(tx_index);
+        if *status != TransactionStatus::Executed {
+            return None;
+        }
+        *status = TransactionStatus::Committed;
+        Some(tx_index)
+    }
+
+    /// Advances the commit index past the transaction returned by `try_lock_next_tx`.
+    pub fn commit(&mut self) {
+        *self.commit_index += 1;
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Task {
     ExecutionTask(TxIndex),
@@ -248,4 +323,5 @@ enum TransactionStatus {
     Executing,
     Executed,
     Aborting,
+    Committed,
 }
@@ -24,6 +24,8 @@ fn test_new(#[values(0, 1, 32)] chunk_size: usize) {
         assert_eq!(*scheduler.tx_statuses[i].lock().unwrap(), TransactionStatus::ReadyToExecute);
     }
     assert_eq!(scheduler.done_marker.into_inner(), false);
+    assert_eq!(scheduler.commit_index.into_inner().unwrap(), 0);
+    assert_eq!(scheduler.halt_marker.into_inner(), false);
 }
 
 #[rstest]
@@ -219,3 +221,34 @@ fn test_next_version_to_execute(
     let expected_n_active_tasks = if expected_output.is_some() { 1 } else { 0 };
     assert_eq!(scheduler.n_active_tasks.load(Ordering::Acquire), expected_n_active_tasks);
 }
+
+#[rstest]
+#[case::executed(TransactionStatus::Executed, Some(0))]
+#[case::executing(TransactionStatus::Executing, None)]
+#[case::aborting(TransactionStatus::Aborting, None)]
+fn test_try_lock_next_tx(#[case] tx_status: TransactionStatus, #[case] expected: Option<TxIndex>) {
+    let scheduler = Scheduler::new(DEFAULT_CHUNK_SIZE);
+    scheduler.set_tx_status(0, tx_status);
+    let mut committer = scheduler.try_enter_commit_phase().unwrap();
+    // Only one thread may commit at a time.
+    assert!(scheduler.try_enter_commit_phase().is_none());
+
+    assert_eq!(committer.try_lock_next_tx(), expected);
+    if expected.is_some() {
+        assert_eq!(*scheduler.lock_tx_status(0), TransactionStatus::Committed);
+        // Committed transactions cannot be aborted.
+        assert!(!scheduler.try_validation_abort(0));
+        committer.commit();
+    }
+    drop(committer);
+    assert_eq!(scheduler.n_committed_txs(), usize::from(expected.is_some()));
+}
+
+#[test]
+fn test_halt() {
+    let scheduler = Scheduler::new(DEFAULT_CHUNK_SIZE);
+    scheduler.set_tx_status(0, TransactionStatus::Executed);
+    scheduler.halt();
+    assert_eq!(scheduler.next_task(), Task::Done);
+    assert_eq!(scheduler.enter_commit_phase().try_lock_next_tx(), None);
+}
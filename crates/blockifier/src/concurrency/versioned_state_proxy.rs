@@ -139,6 +139,29 @@ impl<S: StateReader> VersionedState<S> {
             self.compiled_contract_classes.write(tx_index, key, value.clone());
         }
     }
+
+    fn delete_writes(
+        &mut self,
+        tx_index: TxIndex,
+        writes: &StateMaps,
+        class_hash_to_class: &ContractClassMapping,
+    ) {
+        for &key in writes.storage.keys() {
+            self.storage.delete_write(tx_index, key);
+        }
+        for &key in writes.nonces.keys() {
+            self.nonces.delete_write(tx_index, key);
+        }
+        for &key in writes.class_hashes.keys() {
+            self.class_hashes.delete_write(tx_index, key);
+        }
+        for &key in writes.compiled_class_hashes.keys() {
+            self.compiled_class_hashes.delete_write(tx_index, key);
+        }
+        for &key in class_hash_to_class.keys() {
+            self.compiled_contract_classes.delete_write(tx_index, key);
+        }
+    }
 }
 
 pub struct ThreadSafeVersionedState<S: StateReader>(Arc<Mutex<VersionedState<S>>>);
@@ -149,6 +172,10 @@ impl<S: StateReader> ThreadSafeVersionedState<S> {
         ThreadSafeVersionedState(Arc::new(Mutex::new(versioned_state)))
     }
 
+    pub fn lock(&self) -> LockedVersionedState<'_, S> {
+        self.0.lock().expect("Failed to acquire state lock.")
+    }
+
     pub fn pin_version(&self, tx_index: TxIndex) -> VersionedStateProxy<S> {
         VersionedStateProxy { tx_index, state: self.0.clone() }
     }
@@ -171,6 +198,11 @@ impl<S: StateReader> VersionedStateProxy<S> {
     pub fn apply_writes(&self, writes: &StateMaps, class_hash_to_class: &ContractClassMapping) {
         self.state().apply_writes(self.tx_index, writes, class_hash_to_class)
     }
+
+    /// Removes the writes of the pinned transaction, e.g., after its execution was aborted.
+    pub fn delete_writes(&self, writes: &StateMaps, class_hash_to_class: &ContractClassMapping) {
+        self.state().delete_writes(self.tx_index, writes, class_hash_to_class)
+    }
 }
 
 impl<S: StateReader> StateReader for VersionedStateProxy<S> {
@@ -50,6 +50,13 @@ where
         cell.insert(tx_index, value);
     }
 
+    /// Removes the write of the given transaction, e.g., after its execution was aborted.
+    pub fn delete_write(&mut self, tx_index: TxIndex, key: K) {
+        if let Some(cell) = self.writes.get_mut(&key) {
+            cell.remove(&tx_index);
+        }
+    }
+
     /// This method inserts the provided key-value pair into the cached initial values map.
     /// It is typically used when reading a value that is not found in the versioned storage. In
     /// such a scenario, the value is retrieved from the initial storage and written to the
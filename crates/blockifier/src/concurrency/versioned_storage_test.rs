@@ -37,3 +37,20 @@ fn test_versioned_storage() {
     // Test the write.
     assert_eq!(storage.read(50, 100).unwrap(), 194);
 }
+
+#[test]
+fn test_delete_write() {
+    let mut storage = VersionedStorage::default();
+    storage.set_initial_value(1, 31);
+    storage.write(1, 1, 42);
+    storage.write(2, 1, 43);
+
+    storage.delete_write(2, 1);
+    assert_eq!(storage.read(3, 1).unwrap(), 42);
+    storage.delete_write(1, 1);
+    assert_eq!(storage.read(3, 1).unwrap(), 31);
+
+    // Deleting a missing write is a no-op.
+    storage.delete_write(1, 7);
+    assert!(storage.read(3, 7).is_none());
+}
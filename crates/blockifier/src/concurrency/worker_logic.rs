@@ -0,0 +1,322 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+
+use starknet_api::core::ClassHash;
+
+use crate::blockifier::transaction_executor::TransactionExecutorError;
+use crate::bouncer::Bouncer;
+use crate::concurrency::fee_utils::complete_fee_transfer_flow;
+use crate::concurrency::scheduler::{Scheduler, Task};
+use crate::concurrency::versioned_state_proxy::ThreadSafeVersionedState;
+use crate::concurrency::TxIndex;
+use crate::context::BlockContext;
+use crate::state::cached_state::{CachedState, ContractClassMapping, StateMaps};
+use crate::state::state_api::StateReader;
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[cfg(test)]
+#[path = "worker_logic_test.rs"]
+pub mod test;
+
+const EXECUTION_OUTPUTS_UNWRAP_ERROR: &str = "Execution task outputs should not be None.";
+
+#[derive(Debug)]
+pub struct ExecutionTaskOutput {
+    pub reads: StateMaps,
+    pub writes: StateMaps,
+    pub contract_classes: ContractClassMapping,
+    pub visited_pcs: HashMap<ClassHash, HashSet<usize>>,
+    pub result: TransactionExecutionResult<TransactionExecutionInfo>,
+}
+
+/// Counters of the tasks performed by a single worker.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct WorkerMetrics {
+    pub n_executions: usize,
+    pub n_validations: usize,
+    pub n_validation_aborts: usize,
+    pub n_commits: usize,
+    /// Transactions re-executed at commit, as their reads were invalidated by the commit of
+    /// the preceding transactions.
+    pub n_commit_reexecutions: usize,
+}
+
+/// Executes a chunk of transactions concurrently, on top of a versioned state, and commits them
+/// in order.
+/// Workers are driven by calling `run` from each thread, e.g., of a custom or a `rayon` thread
+/// pool; `run_in_threads` spawns the workers on scoped threads.
+pub struct WorkerExecutor<'a, S: StateReader> {
+    scheduler: Scheduler,
+    state: ThreadSafeVersionedState<S>,
+    chunk: &'a [Transaction],
+    execution_outputs: Box<[Mutex<Option<ExecutionTaskOutput>>]>,
+    block_context: &'a BlockContext,
+    bouncer: Mutex<Bouncer>,
+}
+
+impl<'a, S: StateReader> WorkerExecutor<'a, S> {
+    pub fn new(
+        state: ThreadSafeVersionedState<S>,
+        chunk: &'a [Transaction],
+        block_context: &'a BlockContext,
+        bouncer: Bouncer,
+    ) -> Self {
+        assert!(
+            block_context.concurrency_mode,
+            "Concurrent execution requires a block context in concurrency mode."
+        );
+        WorkerExecutor {
+            scheduler: Scheduler::new(chunk.len()),
+            state,
+            chunk,
+            execution_outputs: std::iter::repeat_with(|| Mutex::new(None))
+                .take(chunk.len())
+                .collect(),
+            block_context,
+            bouncer: Mutex::new(bouncer),
+        }
+    }
+
+    /// Runs a worker until all the transactions of the chunk are committed, or the execution is
+    /// halted. May be called concurrently from any number of threads.
+    pub fn run(&self) -> WorkerMetrics {
+        let mut metrics = WorkerMetrics::default();
+        let mut task = Task::NoTask;
+        loop {
+            self.commit_while_possible(false, &mut metrics);
+            task = match task {
+                Task::ExecutionTask(tx_index) => {
+                    self.execute(tx_index);
+                    metrics.n_executions += 1;
+                    Task::NoTask
+                }
+                Task::ValidationTask(tx_index) => self.validate(tx_index, &mut metrics),
+                Task::NoTask => self.scheduler.next_task(),
+                Task::Done => break,
+            };
+        }
+        // Another worker may be in the middle of committing; wait for it.
+        self.commit_while_possible(true, &mut metrics);
+
+        metrics
+    }
+
+    /// Runs the given number of workers on scoped threads; returns the metrics of each worker.
+    pub fn run_in_threads(&self, n_workers: usize) -> Vec<WorkerMetrics>
+    where
+        Self: Sync,
+    {
+        assert!(n_workers > 0, "At least one worker is required.");
+        thread::scope(|scope| {
+            let workers: Vec<_> = (0..n_workers).map(|_| scope.spawn(|| self.run())).collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("Worker thread panicked."))
+                .collect()
+        })
+    }
+
+    /// Gracefully stops the workers; committed transactions are kept, and the rest are dropped.
+    pub fn halt(&self) {
+        self.scheduler.halt();
+    }
+
+    pub fn n_committed_txs(&self) -> usize {
+        self.scheduler.n_committed_txs()
+    }
+
+    /// Writes the state changes and the visited PCs of the committed transactions to the given
+    /// state.
+    pub fn commit_chunk_to_state<T: StateReader>(&self, parent_state: &mut CachedState<T>) {
+        let n_committed_txs = self.n_committed_txs();
+        if n_committed_txs == 0 {
+            return;
+        }
+        self.state.lock().commit(n_committed_txs - 1, parent_state);
+        for tx_index in 0..n_committed_txs {
+            let execution_output = self.lock_execution_output(tx_index);
+            let execution_output = execution_output.as_ref().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR);
+            parent_state.update_visited_pcs_cache(&execution_output.visited_pcs);
+        }
+    }
+
+    /// Returns the results of the committed transactions, in order, and the updated bouncer.
+    pub fn into_committed_results(
+        self,
+    ) -> (Vec<TransactionExecutionResult<TransactionExecutionInfo>>, Bouncer) {
+        let n_committed_txs = self.n_committed_txs();
+        let results = self
+            .execution_outputs
+            .into_vec()
+            .into_iter()
+            .take(n_committed_txs)
+            .map(|execution_output| {
+                execution_output
+                    .into_inner()
+                    .expect("Failed to consume execution output.")
+                    .expect(EXECUTION_OUTPUTS_UNWRAP_ERROR)
+                    .result
+            })
+            .collect();
+        let bouncer = self.bouncer.into_inner().expect("Failed to consume bouncer.");
+
+        (results, bouncer)
+    }
+
+    fn commit_while_possible(&self, wait: bool, metrics: &mut WorkerMetrics) {
+        let transaction_committer = if wait {
+            Some(self.scheduler.enter_commit_phase())
+        } else {
+            self.scheduler.try_enter_commit_phase()
+        };
+        let Some(mut transaction_committer) = transaction_committer else {
+            return;
+        };
+
+        while let Some(tx_index) = transaction_committer.try_lock_next_tx() {
+            if !self.commit_tx(tx_index, metrics) {
+                // The block is full.
+                self.scheduler.halt();
+                return;
+            }
+            transaction_committer.commit();
+            metrics.n_commits += 1;
+        }
+    }
+
+    fn execute(&self, tx_index: TxIndex) {
+        self.execute_tx(tx_index);
+        self.scheduler.finish_execution(tx_index)
+    }
+
+    fn execute_tx(&self, tx_index: TxIndex) {
+        let mut tx_state = CachedState::new(self.state.pin_version(tx_index));
+        let mut transactional_state = CachedState::create_transactional(&mut tx_state);
+        let tx = &self.chunk[tx_index];
+        let charge_fee = true;
+        let validate = true;
+        let result =
+            tx.execute_raw(&mut transactional_state, self.block_context, charge_fee, validate);
+
+        let tx_reads_writes = transactional_state.cache.take();
+        // Failed transactions do not change the state.
+        let (writes, contract_classes, visited_pcs) = if result.is_ok() {
+            (
+                tx_reads_writes.writes,
+                transactional_state.class_hash_to_class.take(),
+                std::mem::take(&mut transactional_state.visited_pcs),
+            )
+        } else {
+            Default::default()
+        };
+        self.state.pin_version(tx_index).apply_writes(&writes, &contract_classes);
+
+        let execution_output = ExecutionTaskOutput {
+            reads: tx_reads_writes.initial_reads,
+            writes,
+            contract_classes,
+            visited_pcs,
+            result,
+        };
+        *self.lock_execution_output(tx_index) = Some(execution_output);
+    }
+
+    fn validate(&self, tx_index: TxIndex, metrics: &mut WorkerMetrics) -> Task {
+        metrics.n_validations += 1;
+        let tx_versioned_state = self.state.pin_version(tx_index);
+        let execution_output = self.lock_execution_output(tx_index);
+        let execution_output = execution_output.as_ref().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR);
+        let reads_valid = tx_versioned_state.validate_read_set(&execution_output.reads);
+
+        let aborted = !reads_valid && self.scheduler.try_validation_abort(tx_index);
+        if aborted {
+            tx_versioned_state
+                .delete_writes(&execution_output.writes, &execution_output.contract_classes);
+            metrics.n_validation_aborts += 1;
+        }
+
+        self.scheduler.finish_validation(tx_index, aborted).unwrap_or(Task::NoTask)
+    }
+
+    /// Commits the given transaction, whose predecessors are committed; returns `false` if it
+    /// does not fit the block.
+    fn commit_tx(&self, tx_index: TxIndex, metrics: &mut WorkerMetrics) -> bool {
+        let mut tx_versioned_state = self.state.pin_version(tx_index);
+        let reads_valid = {
+            let execution_output = self.lock_execution_output(tx_index);
+            let execution_output = execution_output.as_ref().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR);
+            let reads_valid = tx_versioned_state.validate_read_set(&execution_output.reads);
+            if !reads_valid {
+                tx_versioned_state
+                    .delete_writes(&execution_output.writes, &execution_output.contract_classes);
+            }
+            reads_valid
+        };
+        if !reads_valid {
+            // The predecessors are committed, so the re-execution result is final.
+            self.execute_tx(tx_index);
+            metrics.n_commit_reexecutions += 1;
+        }
+
+        let mut execution_output = self.lock_execution_output(tx_index);
+        let execution_output = execution_output.as_mut().expect(EXECUTION_OUTPUTS_UNWRAP_ERROR);
+        let Ok(tx_execution_info) = execution_output.result.as_mut() else {
+            return true;
+        };
+
+        let mut tx_state = CachedState::new(self.state.pin_version(tx_index));
+        let mut transactional_state = CachedState::create_transactional(&mut tx_state);
+        transactional_state.update_cache(execution_output.writes.clone());
+        let mut bouncer = self.bouncer.lock().expect("Failed to acquire bouncer lock.");
+        let bouncer_result = bouncer
+            .summarize_tx(&mut transactional_state, tx_execution_info)
+            .and_then(|tx_execution_summary| {
+                bouncer.try_update(
+                    &mut transactional_state,
+                    &tx_execution_summary,
+                    &tx_execution_info.actual_resources,
+                )
+            });
+        let rejection_error = match bouncer_result {
+            Ok(()) => {
+                let tx_context = self.block_context.to_tx_context(&self.chunk[tx_index]);
+                complete_fee_transfer_flow(&tx_context, tx_execution_info, &mut tx_versioned_state)
+                    .err()
+                    .map(TransactionExecutionError::from)
+            }
+            Err(TransactionExecutorError::BlockFull) => return false,
+            Err(TransactionExecutorError::TransactionExecutionError(error)) => Some(error),
+            Err(TransactionExecutorError::StateError(error)) => Some(error.into()),
+            // The bouncer fails only on block capacity, transaction and state errors.
+            Err(
+                error @ (TransactionExecutorError::DuplicateTransaction(_)
+                | TransactionExecutorError::QueryTransaction(_)),
+            ) => unreachable!("The bouncer does not check transaction hashes: {error}."),
+        };
+
+        if let Some(error) = rejection_error {
+            // The transaction is excluded from the block, as if it failed.
+            tx_versioned_state
+                .delete_writes(&execution_output.writes, &execution_output.contract_classes);
+            execution_output.writes = StateMaps::default();
+            execution_output.contract_classes = ContractClassMapping::default();
+            execution_output.visited_pcs = HashMap::default();
+            execution_output.result = Err(error);
+        }
+
+        true
+    }
+
+    fn lock_execution_output(
+        &self,
+        tx_index: TxIndex,
+    ) -> MutexGuard<'_, Option<ExecutionTaskOutput>> {
+        self.execution_outputs[tx_index].lock().unwrap_or_else(|error| {
+            panic!("Execution output of transaction index {} is poisoned: {:?}.", tx_index, error)
+        })
+    }
+}
@@ -0,0 +1,170 @@
+use rstest::{fixture, rstest};
+use starknet_api::core::ContractAddress;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Fee;
+
+use crate::abi::abi_utils::get_fee_token_var_address;
+use crate::bouncer::{Bouncer, BouncerConfig};
+use crate::concurrency::test_utils::safe_versioned_state_for_testing;
+use crate::concurrency::worker_logic::WorkerExecutor;
+use crate::context::BlockContext;
+use crate::execution::call_info::StorageAccess;
+use crate::invoke_tx_args;
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::test_state_reader;
+use crate::test_utils::{create_trivial_calldata, CairoVersion, NonceManager, BALANCE, MAX_FEE};
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::FeeType;
+use crate::transaction::test_utils::account_invoke_tx;
+use crate::transaction::transaction_execution::Transaction;
+
+const N_TXS: usize = 4;
+
+struct TestInitData {
+    block_context: BlockContext,
+    state_reader: DictStateReader,
+    account_address: ContractAddress,
+    txs: Vec<Transaction>,
+}
+
+/// Consecutive invoke transactions of the same account, which conflict on the account nonce and
+/// balance.
+#[fixture]
+fn test_init_data() -> TestInitData {
+    let block_context = BlockContext::create_for_account_testing_with_concurrency_mode(true);
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let state_reader =
+        test_state_reader(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]);
+    let account_address = account.get_instance_address(0);
+    let mut nonce_manager = NonceManager::default();
+    let txs = (0..N_TXS)
+        .map(|_| {
+            Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+                max_fee: Fee(MAX_FEE),
+                sender_address: account_address,
+                calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+                nonce: nonce_manager.next(account_address),
+            }))
+        })
+        .collect();
+
+    TestInitData { block_context, state_reader, account_address, txs }
+}
+
+#[rstest]
+fn test_worker_executor(test_init_data: TestInitData, #[values(1, 3)] n_workers: usize) {
+    let TestInitData { block_context, state_reader, account_address, txs } = test_init_data;
+    let worker_executor = WorkerExecutor::new(
+        safe_versioned_state_for_testing(state_reader),
+        &txs,
+        &block_context,
+        Bouncer::new(BouncerConfig::max()),
+    );
+
+    let metrics = worker_executor.run_in_threads(n_workers);
+    assert_eq!(metrics.len(), n_workers);
+    assert_eq!(metrics.iter().map(|worker_metrics| worker_metrics.n_commits).sum::<usize>(), N_TXS);
+    assert_eq!(worker_executor.n_committed_txs(), N_TXS);
+
+    let mut block_state = CachedState::from(DictStateReader::default());
+    worker_executor.commit_chunk_to_state(&mut block_state);
+    assert_eq!(
+        block_state.get_nonce_at(account_address).unwrap().0,
+        StarkFelt::from(u64::try_from(N_TXS).unwrap())
+    );
+
+    let (results, _bouncer) = worker_executor.into_committed_results();
+    let mut total_fee = 0;
+    for result in results {
+        let tx_execution_info = result.unwrap();
+        assert!(!tx_execution_info.is_reverted());
+        total_fee += tx_execution_info.actual_fee.0;
+    }
+    // The fee transfers are completed at commit, so no fee is lost.
+    let fee_token_address = block_context.chain_info.fee_token_address(&FeeType::Eth);
+    let sequencer_address = block_context.block_info.sequencer_address;
+    assert_eq!(
+        block_state.get_fee_token_balance(sequencer_address, fee_token_address).unwrap(),
+        (StarkFelt::from(total_fee), StarkFelt::ZERO)
+    );
+}
+
+#[rstest]
+fn test_worker_executor_rejected_txs(test_init_data: TestInitData) {
+    let TestInitData { block_context, state_reader, txs, .. } = test_init_data;
+    // No transaction fits an empty block.
+    let worker_executor = WorkerExecutor::new(
+        safe_versioned_state_for_testing(state_reader),
+        &txs,
+        &block_context,
+        Bouncer::new(BouncerConfig::default()),
+    );
+
+    worker_executor.run_in_threads(2);
+    assert_eq!(worker_executor.n_committed_txs(), N_TXS);
+    let (results, _bouncer) = worker_executor.into_committed_results();
+    assert!(matches!(results[0], Err(TransactionExecutionError::TransactionTooLarge)));
+    // Following the rejection, the nonces of the rest of the transactions are invalid.
+    assert!(results[1..].iter().all(|result| matches!(
+        result,
+        Err(TransactionExecutionError::TransactionPreValidationError(_))
+    )));
+}
+
+#[rstest]
+fn test_halt(test_init_data: TestInitData) {
+    let TestInitData { block_context, state_reader, txs, .. } = test_init_data;
+    let worker_executor = WorkerExecutor::new(
+        safe_versioned_state_for_testing(state_reader),
+        &txs,
+        &block_context,
+        Bouncer::new(BouncerConfig::max()),
+    );
+
+    worker_executor.halt();
+    let metrics = worker_executor.run();
+    assert_eq!(metrics.n_commits, 0);
+    assert!(worker_executor.into_committed_results().0.is_empty());
+}
+
+/// The fee transfers of a block read and write the sequencer balance as if they ran sequentially.
+#[rstest]
+fn test_fee_transfer_sequencer_balance_accesses(test_init_data: TestInitData) {
+    let TestInitData { block_context, state_reader, txs, .. } = test_init_data;
+    let txs = &txs[..2];
+    let worker_executor = WorkerExecutor::new(
+        safe_versioned_state_for_testing(state_reader),
+        txs,
+        &block_context,
+        Bouncer::new(BouncerConfig::max()),
+    );
+
+    worker_executor.run_in_threads(2);
+    let sequencer_address = block_context.block_info.sequencer_address;
+    let sequencer_balance_key = get_fee_token_var_address(sequencer_address);
+    let (results, _bouncer) = worker_executor.into_committed_results();
+    let mut sequencer_balance = 0;
+    for result in results {
+        let tx_execution_info = result.unwrap();
+        let sequencer_balance_accesses: Vec<StorageAccess> = tx_execution_info
+            .fee_transfer_call_info
+            .unwrap()
+            .storage_accesses
+            .into_iter()
+            .filter(|access| access.key == sequencer_balance_key)
+            .collect();
+        let new_sequencer_balance = sequencer_balance + tx_execution_info.actual_fee.0;
+        assert_eq!(
+            sequencer_balance_accesses,
+            vec![
+                StorageAccess::read(sequencer_balance_key, StarkFelt::from(sequencer_balance)),
+                StorageAccess::write(sequencer_balance_key, StarkFelt::from(new_sequencer_balance))
+            ]
+        );
+        sequencer_balance = new_sequencer_balance;
+    }
+}
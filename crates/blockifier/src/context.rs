@@ -1,11 +1,23 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
 use starknet_api::core::{ChainId, ContractAddress};
 
 use crate::blockifier::block::BlockInfo;
+use crate::event_log::{ExecutionEvent, ExecutionEventSink};
+use crate::execution::entry_point::ReentrancyPolicy;
+use crate::execution::execution_policy::ExecutionPolicy;
+use crate::execution::profiler::ExecutionProfiler;
+use crate::execution::signature_verifier::SignatureVerifier;
 use crate::transaction::objects::{
     FeeType, HasRelatedFeeType, TransactionInfo, TransactionInfoCreator,
 };
 use crate::versioned_constants::VersionedConstants;
 
+#[cfg(test)]
+#[path = "context_test.rs"]
+pub mod test;
+
 /// Create via [`crate::blockifier::block::pre_process_block`] to ensure correctness.
 #[derive(Clone, Debug)]
 pub struct TransactionContext {
@@ -25,6 +37,13 @@ pub struct BlockContext {
     pub(crate) chain_info: ChainInfo,
     pub(crate) versioned_constants: VersionedConstants,
     pub(crate) concurrency_mode: bool,
+    pub(crate) disable_fee_charge: bool,
+    pub(crate) cancellation_token: CancellationToken,
+    pub(crate) profiler: Option<ExecutionProfiler>,
+    pub(crate) event_sink: Option<Arc<dyn ExecutionEventSink>>,
+    pub(crate) reentrancy_policy: ReentrancyPolicy,
+    pub(crate) skip_compiled_class_hash_verification: bool,
+    pub(crate) dev_mode: bool,
 }
 
 impl BlockContext {
@@ -41,6 +60,13 @@ impl BlockContext {
             chain_info: chain_info.clone(),
             versioned_constants: versioned_constants.clone(),
             concurrency_mode: false,
+            disable_fee_charge: false,
+            cancellation_token: CancellationToken::default(),
+            profiler: None,
+            event_sink: None,
+            reentrancy_policy: ReentrancyPolicy::default(),
+            skip_compiled_class_hash_verification: false,
+            dev_mode: false,
         }
     }
 
@@ -59,9 +85,44 @@ impl BlockContext {
     pub fn concurrency_mode(&self) -> bool {
         self.concurrency_mode
     }
+
+    pub fn disable_fee_charge(&self) -> bool {
+        self.disable_fee_charge
+    }
+
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation_token
+    }
+
+    pub fn profiler(&self) -> Option<&ExecutionProfiler> {
+        self.profiler.as_ref()
+    }
+
+    pub fn reentrancy_policy(&self) -> ReentrancyPolicy {
+        self.reentrancy_policy
+    }
+
+    pub fn skip_compiled_class_hash_verification(&self) -> bool {
+        self.skip_compiled_class_hash_verification
+    }
+
+    pub fn dev_mode(&self) -> bool {
+        self.dev_mode
+    }
+
+    /// Records an event to the event sink, if set; the event is only created if so.
+    pub(crate) fn record_event(&self, create_event: impl FnOnce() -> ExecutionEvent) {
+        if let Some(event_sink) = &self.event_sink {
+            event_sink.record(create_event());
+        }
+    }
 }
 
 impl BlockContext {
+    pub fn builder(block_info: BlockInfo) -> BlockContextBuilder {
+        BlockContextBuilder::new(block_info)
+    }
+
     pub fn to_tx_context(
         &self,
         tx_info_creator: &impl TransactionInfoCreator,
@@ -73,10 +134,184 @@ impl BlockContext {
     }
 }
 
+/// Aborts in-flight executions externally, e.g., on RPC timeouts or shutdown; clones share the
+/// cancellation. Executions check the token at syscalls and periodically during VM runs, and fail
+/// with an `ExecutionAborted` error.
+/// A cancellation stays in effect until the token is reset; the transaction executor resets it
+/// once a transaction was aborted, so that it does not abort the following ones.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn reset(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Builds a [`BlockContext`]; unset fields take the default chain info and the latest versioned
+/// constants.
+/// Note: unlike [`crate::blockifier::block::pre_process_block`], building does not write the old
+/// block hash to the state.
+#[derive(Clone, Debug)]
+pub struct BlockContextBuilder {
+    block_info: BlockInfo,
+    chain_info: ChainInfo,
+    versioned_constants: VersionedConstants,
+    concurrency_mode: bool,
+    disable_fee_charge: bool,
+    cancellation_token: CancellationToken,
+    profiler: Option<ExecutionProfiler>,
+    event_sink: Option<Arc<dyn ExecutionEventSink>>,
+    reentrancy_policy: ReentrancyPolicy,
+    skip_compiled_class_hash_verification: bool,
+    dev_mode: bool,
+}
+
+impl BlockContextBuilder {
+    pub fn new(block_info: BlockInfo) -> Self {
+        Self {
+            block_info,
+            chain_info: ChainInfo::default(),
+            versioned_constants: VersionedConstants::latest_constants().clone(),
+            concurrency_mode: false,
+            disable_fee_charge: false,
+            cancellation_token: CancellationToken::default(),
+            profiler: None,
+            event_sink: None,
+            reentrancy_policy: ReentrancyPolicy::default(),
+            skip_compiled_class_hash_verification: false,
+            dev_mode: false,
+        }
+    }
+
+    pub fn chain_info(mut self, chain_info: ChainInfo) -> Self {
+        self.chain_info = chain_info;
+        self
+    }
+
+    pub fn versioned_constants(mut self, versioned_constants: VersionedConstants) -> Self {
+        self.versioned_constants = versioned_constants;
+        self
+    }
+
+    /// Overrides the step limit of the execution phase of invoke transactions.
+    pub fn invoke_tx_max_n_steps(mut self, invoke_tx_max_n_steps: u32) -> Self {
+        self.versioned_constants.invoke_tx_max_n_steps = invoke_tx_max_n_steps;
+        self
+    }
+
+    /// Overrides the step limit of the validation phase of account transactions.
+    pub fn validate_max_n_steps(mut self, validate_max_n_steps: u32) -> Self {
+        self.versioned_constants.validate_max_n_steps = validate_max_n_steps;
+        self
+    }
+
+    /// Overrides the maximal depth of nested calls; deeper calls fail with
+    /// `RecursionDepthExceeded`.
+    pub fn max_recursion_depth(mut self, max_recursion_depth: usize) -> Self {
+        self.versioned_constants.max_recursion_depth = max_recursion_depth;
+        self
+    }
+
+    pub fn concurrency_mode(mut self, concurrency_mode: bool) -> Self {
+        self.concurrency_mode = concurrency_mode;
+        self
+    }
+
+    /// Executes transactions without charging fees, for development networks: balance checks
+    /// and fee transfers are skipped, while resources and fees are still computed and reported.
+    pub fn disable_fee_charge(mut self, disable_fee_charge: bool) -> Self {
+        self.disable_fee_charge = disable_fee_charge;
+        self
+    }
+
+    /// Sets the token through which executions using the block context can be aborted.
+    pub fn cancellation_token(mut self, cancellation_token: CancellationToken) -> Self {
+        self.cancellation_token = cancellation_token;
+        self
+    }
+
+    /// Sets a profiler, which aggregates the cost of the calls executed using the block context.
+    pub fn profiler(mut self, profiler: ExecutionProfiler) -> Self {
+        self.profiler = Some(profiler);
+        self
+    }
+
+    /// Sets a sink, which receives the events of the executions using the block context.
+    pub fn event_sink(mut self, event_sink: Arc<dyn ExecutionEventSink>) -> Self {
+        self.event_sink = Some(event_sink);
+        self
+    }
+
+    /// Sets how calls into a contract that is already executing (up the call stack) are handled.
+    pub fn reentrancy_policy(mut self, reentrancy_policy: ReentrancyPolicy) -> Self {
+        self.reentrancy_policy = reentrancy_policy;
+        self
+    }
+
+    /// Skips verifying that the compiled class hashes of declare transactions match their
+    /// classes; for trusted pipelines (e.g., re-executing accepted blocks), where it is redundant.
+    pub fn skip_compiled_class_hash_verification(
+        mut self,
+        skip_compiled_class_hash_verification: bool,
+    ) -> Self {
+        self.skip_compiled_class_hash_verification = skip_compiled_class_hash_verification;
+        self
+    }
+
+    /// Executes contracts in development mode: their debug prints (e.g., `core::debug::print`) are
+    /// captured into the debug logs of their call infos, instead of being written to stdout.
+    pub fn dev_mode(mut self, dev_mode: bool) -> Self {
+        self.dev_mode = dev_mode;
+        self
+    }
+
+    pub fn build(self) -> BlockContext {
+        let Self {
+            block_info,
+            chain_info,
+            versioned_constants,
+            concurrency_mode,
+            disable_fee_charge,
+            cancellation_token,
+            profiler,
+            event_sink,
+            reentrancy_policy,
+            skip_compiled_class_hash_verification,
+            dev_mode,
+        } = self;
+        BlockContext {
+            block_info,
+            chain_info,
+            versioned_constants,
+            concurrency_mode,
+            disable_fee_charge,
+            cancellation_token,
+            profiler,
+            event_sink,
+            reentrancy_policy,
+            skip_compiled_class_hash_verification,
+            dev_mode,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ChainInfo {
     pub chain_id: ChainId,
     pub fee_token_addresses: FeeTokenAddresses,
+    // Enables the `verify_signature` syscall; unset on Starknet.
+    pub signature_verifier: Option<Arc<dyn SignatureVerifier>>,
+    // Restricts the contracts that may be called or deployed; unset on Starknet.
+    pub execution_policy: Option<Arc<dyn ExecutionPolicy>>,
 }
 
 impl ChainInfo {
@@ -93,6 +328,8 @@ impl Default for ChainInfo {
         ChainInfo {
             chain_id: ChainId("0x0".to_string()),
             fee_token_addresses: FeeTokenAddresses::default(),
+            signature_verifier: None,
+            execution_policy: None,
         }
     }
 }
@@ -0,0 +1,56 @@
+use starknet_api::block::BlockNumber;
+
+use crate::blockifier::block::BlockInfo;
+use crate::context::{BlockContext, CancellationToken, ChainInfo};
+use crate::transaction::objects::FeeType;
+use crate::versioned_constants::VersionedConstants;
+
+#[test]
+fn test_block_context_builder() {
+    let block_info = BlockInfo { block_number: BlockNumber(7), ..BlockInfo::create_for_testing() };
+    let chain_info = ChainInfo::create_for_testing();
+
+    // Unset fields take their defaults.
+    let block_context = BlockContext::builder(block_info.clone()).build();
+    assert_eq!(block_context.block_info().block_number, BlockNumber(7));
+    assert_eq!(block_context.chain_info().chain_id, ChainInfo::default().chain_id);
+    assert_eq!(
+        block_context.versioned_constants().invoke_tx_max_n_steps,
+        VersionedConstants::latest_constants().invoke_tx_max_n_steps
+    );
+    assert!(!block_context.concurrency_mode());
+    assert!(!block_context.disable_fee_charge());
+
+    let block_context = BlockContext::builder(block_info)
+        .chain_info(chain_info.clone())
+        .versioned_constants(VersionedConstants::create_for_testing())
+        .invoke_tx_max_n_steps(10)
+        .validate_max_n_steps(5)
+        .max_recursion_depth(3)
+        .concurrency_mode(true)
+        .disable_fee_charge(true)
+        .build();
+    assert_eq!(block_context.chain_info().chain_id, chain_info.chain_id);
+    assert_eq!(
+        block_context.chain_info().fee_token_address(&FeeType::Strk),
+        chain_info.fee_token_address(&FeeType::Strk)
+    );
+    assert_eq!(block_context.versioned_constants().invoke_tx_max_n_steps, 10);
+    assert_eq!(block_context.versioned_constants().validate_max_n_steps, 5);
+    assert_eq!(block_context.versioned_constants().max_recursion_depth, 3);
+    assert!(block_context.concurrency_mode());
+    assert!(block_context.disable_fee_charge());
+}
+
+#[test]
+fn test_cancellation_token() {
+    let cancellation_token = CancellationToken::default();
+    let block_context = BlockContext::builder(BlockInfo::create_for_testing())
+        .cancellation_token(cancellation_token.clone())
+        .build();
+    assert!(!block_context.cancellation_token().is_cancelled());
+
+    // Clones share the cancellation.
+    cancellation_token.cancel();
+    assert!(block_context.clone().cancellation_token().is_cancelled());
+}
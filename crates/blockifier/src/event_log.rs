@@ -0,0 +1,125 @@
+//! Structured execution events, e.g., for offline analysis of a block execution. Events are
+//! recorded to the event sink of the block context, if set; see
+//! [`BlockContextBuilder::event_sink`](crate::context::BlockContextBuilder::event_sink).
+
+use std::fmt::Debug;
+use std::io::Write;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{Fee, TransactionHash};
+
+use crate::execution::entry_point::CallType;
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
+use crate::transaction::transaction_types::TransactionType;
+
+#[cfg(test)]
+#[path = "event_log_test.rs"]
+pub mod test;
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ExecutionEvent {
+    TxStarted {
+        tx_hash: TransactionHash,
+        tx_type: TransactionType,
+    },
+    /// `actual_fee` and `revert_error` are set if the transaction was executed (possibly
+    /// reverted); `error` is set if it failed.
+    TxFinished {
+        tx_hash: TransactionHash,
+        actual_fee: Option<Fee>,
+        revert_error: Option<String>,
+        error: Option<String>,
+    },
+    /// Recorded once the class of the call is resolved.
+    CallEntered {
+        storage_address: ContractAddress,
+        class_hash: ClassHash,
+        entry_point_selector: EntryPointSelector,
+        entry_point_type: EntryPointType,
+        call_type: CallType,
+        depth: usize,
+    },
+    /// `n_steps` (including inner calls) is set if the call succeeded; `error` is set otherwise.
+    CallExited {
+        storage_address: ContractAddress,
+        entry_point_selector: EntryPointSelector,
+        depth: usize,
+        n_steps: Option<usize>,
+        error: Option<String>,
+    },
+    /// Recorded when a syscall is invoked, before it is executed.
+    SyscallExecuted {
+        storage_address: ContractAddress,
+        selector: String,
+    },
+    StorageWritten {
+        contract_address: ContractAddress,
+        key: StorageKey,
+        value: StarkFelt,
+    },
+}
+
+impl ExecutionEvent {
+    pub(crate) fn tx_finished(
+        tx_hash: TransactionHash,
+        tx_execution_result: &TransactionExecutionResult<TransactionExecutionInfo>,
+    ) -> Self {
+        match tx_execution_result {
+            Ok(tx_execution_info) => Self::TxFinished {
+                tx_hash,
+                actual_fee: Some(tx_execution_info.actual_fee),
+                revert_error: tx_execution_info.revert_error.clone(),
+                error: None,
+            },
+            Err(error) => Self::TxFinished {
+                tx_hash,
+                actual_fee: None,
+                revert_error: None,
+                error: Some(error.to_string()),
+            },
+        }
+    }
+}
+
+/// Receives the events of the executions using the block context it is set on.
+pub trait ExecutionEventSink: Debug + Send + Sync {
+    fn record(&self, event: ExecutionEvent);
+}
+
+/// Writes each event as a JSON object, followed by a newline.
+pub struct JsonLinesSink<W: Write + Send>(Mutex<W>);
+
+impl<W: Write + Send> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self(Mutex::new(writer))
+    }
+
+    pub fn into_inner(self) -> W {
+        self.0.into_inner().expect("JSON lines sink is poisoned.")
+    }
+}
+
+impl<W: Write + Send> Debug for JsonLinesSink<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonLinesSink").finish_non_exhaustive()
+    }
+}
+
+impl<W: Write + Send> ExecutionEventSink for JsonLinesSink<W> {
+    fn record(&self, event: ExecutionEvent) {
+        let mut writer = self.0.lock().expect("JSON lines sink is poisoned.");
+        let write_result = serde_json::to_writer(&mut *writer, &event)
+            .map_err(std::io::Error::from)
+            .and_then(|()| writeln!(writer));
+        // Events are diagnostics; failing to write them does not fail the execution.
+        if let Err(error) = write_result {
+            log::warn!("Failed to write execution event: {error}.");
+        }
+    }
+}
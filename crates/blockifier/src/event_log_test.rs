@@ -0,0 +1,119 @@
+use std::sync::{Arc, Mutex};
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use starknet_api::core::PatriciaKey;
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{Calldata, TransactionHash};
+use starknet_api::{calldata, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
+use crate::event_log::{ExecutionEvent, ExecutionEventSink, JsonLinesSink};
+use crate::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
+use crate::storage_key;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
+use crate::transaction::transaction_types::TransactionType;
+
+#[derive(Debug, Default)]
+struct RecordingSink(Mutex<Vec<ExecutionEvent>>);
+
+impl ExecutionEventSink for RecordingSink {
+    fn record(&self, event: ExecutionEvent) {
+        self.0.lock().unwrap().push(event);
+    }
+}
+
+#[test]
+fn test_call_events() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+
+    let sink = Arc::new(RecordingSink::default());
+    let block_context =
+        BlockContext { event_sink: Some(sink.clone()), ..BlockContext::create_for_testing() };
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+
+    let (key, value) = (stark_felt!(1234_u16), stark_felt!(18_u8));
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![key, value],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let call_info = entry_point_call
+        .clone()
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap();
+
+    let storage_address = entry_point_call.storage_address;
+    let entry_point_selector = entry_point_call.entry_point_selector;
+    let expected_events = vec![
+        ExecutionEvent::CallEntered {
+            storage_address,
+            class_hash: test_contract.get_class_hash(),
+            entry_point_selector,
+            entry_point_type: entry_point_call.entry_point_type,
+            call_type: CallType::Call,
+            depth: 1,
+        },
+        ExecutionEvent::SyscallExecuted { storage_address, selector: "StorageWrite".to_string() },
+        ExecutionEvent::StorageWritten {
+            contract_address: storage_address,
+            key: storage_key!(key),
+            value,
+        },
+        ExecutionEvent::SyscallExecuted { storage_address, selector: "StorageRead".to_string() },
+        ExecutionEvent::CallExited {
+            storage_address,
+            entry_point_selector,
+            depth: 1,
+            n_steps: Some(call_info.resources.n_steps),
+            error: None,
+        },
+    ];
+    assert_eq!(*sink.0.lock().unwrap(), expected_events);
+}
+
+#[test]
+fn test_json_lines_sink() {
+    let sink = JsonLinesSink::new(vec![]);
+    let tx_hash = TransactionHash(stark_felt!(1_u8));
+    sink.record(ExecutionEvent::TxStarted { tx_hash, tx_type: TransactionType::InvokeFunction });
+    sink.record(ExecutionEvent::TxFinished {
+        tx_hash,
+        actual_fee: None,
+        revert_error: None,
+        error: Some("Failure.".to_string()),
+    });
+
+    let lines: Vec<serde_json::Value> = String::from_utf8(sink.into_inner())
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            serde_json::json!({
+                "event": "tx_started",
+                "tx_hash": tx_hash,
+                "tx_type": "InvokeFunction",
+            }),
+            serde_json::json!({
+                "event": "tx_finished",
+                "tx_hash": tx_hash,
+                "actual_fee": null,
+                "revert_error": null,
+                "error": "Failure.",
+            }),
+        ]
+    );
+}
@@ -7,6 +7,13 @@ pub mod deprecated_syscalls;
 pub mod entry_point;
 pub mod entry_point_execution;
 pub mod errors;
+pub mod execution_policy;
 pub mod execution_utils;
 pub mod hint_code;
+pub mod profiler;
+#[cfg(feature = "scale-codec")]
+pub mod scale_codec;
+pub mod sierra_compilation;
+pub mod signature_verifier;
+pub mod stack_trace;
 pub mod syscalls;
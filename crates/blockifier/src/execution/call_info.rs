@@ -10,17 +10,53 @@ use starknet_api::patricia_key;
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{EventContent, L2ToL1Payload};
 
+use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
 use crate::execution::entry_point::CallEntryPoint;
-use crate::fee::gas_usage::get_message_segment_length;
+use crate::fee::eth_gas_constants;
+use crate::fee::gas_usage::{
+    get_consumed_message_to_l2_emissions_cost, get_log_message_to_l1_emissions_cost,
+    get_message_segment_length,
+};
 use crate::state::cached_state::StorageEntry;
+use crate::transaction::objects::GasVector;
+use crate::utils::u128_from_usize;
+
+#[cfg(test)]
+#[path = "call_info_test.rs"]
+pub mod test;
+
+/// The felts of a return data; most return data are up to 4 felts long, and are stored inline
+/// with the `small-retdata` feature.
+#[cfg(not(feature = "small-retdata"))]
+pub type RetdataFelts = Vec<StarkFelt>;
+#[cfg(feature = "small-retdata")]
+pub type RetdataFelts = smallvec::SmallVec<[StarkFelt; 4]>;
 
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
-pub struct Retdata(pub Vec<StarkFelt>);
+pub struct Retdata(pub RetdataFelts);
+
+impl Retdata {
+    pub fn into_vec(self) -> Vec<StarkFelt> {
+        self.0.into_iter().collect()
+    }
+}
+
+impl From<Vec<StarkFelt>> for Retdata {
+    fn from(felts: Vec<StarkFelt>) -> Self {
+        Self(felts.into_iter().collect())
+    }
+}
+
+impl FromIterator<StarkFelt> for Retdata {
+    fn from_iter<I: IntoIterator<Item = StarkFelt>>(felts: I) -> Self {
+        Self(felts.into_iter().collect())
+    }
+}
 
 #[macro_export]
 macro_rules! retdata {
     ( $( $x:expr ),* ) => {
-        Retdata(vec![$($x),*])
+        Retdata::from(vec![$($x),*])
     };
 }
 
@@ -31,10 +67,47 @@ pub struct OrderedEvent {
     pub event: EventContent,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum StorageAccessType {
+    Read,
+    Write,
+}
+
+/// A storage read or write of a call: the key, and the value read or written.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct StorageAccess {
+    pub key: StorageKey,
+    pub value: StarkFelt,
+    pub access_type: StorageAccessType,
+}
+
+impl StorageAccess {
+    pub fn read(key: StorageKey, value: StarkFelt) -> Self {
+        Self { key, value, access_type: StorageAccessType::Read }
+    }
+
+    pub fn write(key: StorageKey, value: StarkFelt) -> Self {
+        Self { key, value, access_type: StorageAccessType::Write }
+    }
+}
+
+/// The L1<>L2 messages of a transaction: the L2-to-L1 messages it sent, and the L1-to-L2 message
+/// it consumed (if it is an L1 handler). Computes the message segment length and the L1 gas costs.
+/// Costs of several transactions can be added up.
 #[derive(Debug, Default, Eq, PartialEq, Clone)]
 pub struct MessageL1CostInfo {
     pub l2_to_l1_payload_lengths: Vec<usize>,
     pub message_segment_length: usize,
+    /// The total payload size of the consumed L1-to-L2 messages; for a single transaction, the
+    /// payload size of the L1 handler.
+    pub l1_handler_payload_size: Option<usize>,
+    pub n_l2_to_l1_messages: usize,
+    pub n_l1_to_l2_messages: usize,
+    /// The gas of the `LogMessageToL1` events, which carry the (hashed) payloads of the L2-to-L1
+    /// messages.
+    pub l2_to_l1_payloads_gas: GasVector,
+    /// The gas of the `ConsumedMessageToL2` events.
+    pub l1_to_l2_payloads_gas: GasVector,
 }
 
 impl MessageL1CostInfo {
@@ -50,7 +123,68 @@ impl MessageL1CostInfo {
         let message_segment_length =
             get_message_segment_length(&l2_to_l1_payload_lengths, l1_handler_payload_size);
 
-        Self { l2_to_l1_payload_lengths, message_segment_length }
+        Self {
+            n_l2_to_l1_messages: l2_to_l1_payload_lengths.len(),
+            n_l1_to_l2_messages: usize::from(l1_handler_payload_size.is_some()),
+            l2_to_l1_payloads_gas: get_log_message_to_l1_emissions_cost(&l2_to_l1_payload_lengths),
+            l1_to_l2_payloads_gas: get_consumed_message_to_l2_emissions_cost(
+                l1_handler_payload_size,
+            ),
+            l2_to_l1_payload_lengths,
+            message_segment_length,
+            l1_handler_payload_size,
+        }
+    }
+
+    /// Returns an estimation of the gas usage for processing the messages on L1. Accounts for
+    /// Starknet contract only.
+    pub fn starknet_gas_usage(&self) -> GasVector {
+        GasVector::from_l1_gas(
+            // Starknet's updateState gets the message segment as an argument.
+            u128_from_usize(
+                self.message_segment_length * eth_gas_constants::GAS_PER_MEMORY_WORD
+                // Starknet's updateState increases a (storage) counter for each L2-to-L1 message.
+                + self.n_l2_to_l1_messages * eth_gas_constants::GAS_PER_ZERO_TO_NONZERO_STORAGE_SET
+                // Starknet's updateState decreases a (storage) counter for each L1-to-L2 consumed
+                // message (note that we will probably get a refund of 15,000 gas for each consumed
+                // message but we ignore it since refunded gas cannot be used for the current
+                // transaction execution).
+                + self.n_l1_to_l2_messages * eth_gas_constants::GAS_PER_COUNTER_DECREASE,
+            ),
+        ) + self.l1_to_l2_payloads_gas
+            + self.l2_to_l1_payloads_gas
+    }
+
+    /// Returns the gas usage of the SHARP contract for the message segment.
+    pub fn sharp_gas_usage(&self) -> GasVector {
+        GasVector::from_l1_gas(u128_from_usize(
+            self.message_segment_length * eth_gas_constants::SHARP_GAS_PER_MEMORY_WORD,
+        ))
+    }
+
+    /// Returns an estimation of the gas usage for processing the messages on L1. Accounts for
+    /// both Starknet and SHARP contracts.
+    pub fn to_gas_vector(&self) -> GasVector {
+        self.starknet_gas_usage() + self.sharp_gas_usage()
+    }
+}
+
+impl Add for MessageL1CostInfo {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self.l2_to_l1_payload_lengths.extend(other.l2_to_l1_payload_lengths);
+        self.message_segment_length += other.message_segment_length;
+        self.l1_handler_payload_size =
+            match (self.l1_handler_payload_size, other.l1_handler_payload_size) {
+                (Some(size), Some(other_size)) => Some(size + other_size),
+                (size, other_size) => size.or(other_size),
+            };
+        self.n_l2_to_l1_messages += other.n_l2_to_l1_messages;
+        self.n_l1_to_l2_messages += other.n_l1_to_l2_messages;
+        self.l2_to_l1_payloads_gas = self.l2_to_l1_payloads_gas + other.l2_to_l1_payloads_gas;
+        self.l1_to_l2_payloads_gas = self.l1_to_l2_payloads_gas + other.l1_to_l2_payloads_gas;
+        self
     }
 }
 
@@ -86,7 +220,7 @@ pub struct CallExecution {
 // This struct is used to implement `serde` functionality in a remote `ExecutionResources` Struct.
 #[derive(Debug, Default, Deserialize, derive_more::From, Eq, PartialEq, Serialize)]
 #[serde(remote = "ExecutionResources")]
-struct ExecutionResourcesDef {
+pub(crate) struct ExecutionResourcesDef {
     n_steps: usize,
     n_memory_holes: usize,
     builtin_instance_counter: HashMap<String, usize>,
@@ -97,7 +231,12 @@ pub struct ExecutionSummary {
     pub executed_class_hashes: HashSet<ClassHash>,
     pub visited_storage_entries: HashSet<StorageEntry>,
     pub l2_to_l1_payload_lengths: Vec<usize>,
+    pub n_l2_to_l1_messages: usize,
+    /// The gas of the `LogMessageToL1` events of the L2-to-L1 messages (see `MessageL1CostInfo`).
+    pub l2_to_l1_payloads_gas: GasVector,
     pub n_events: usize,
+    /// The number of invocations of each syscall, summed over the calls.
+    pub syscall_counter: SyscallCounter,
 }
 
 impl Add for ExecutionSummary {
@@ -107,7 +246,10 @@ impl Add for ExecutionSummary {
         self.executed_class_hashes.extend(other.executed_class_hashes);
         self.visited_storage_entries.extend(other.visited_storage_entries);
         self.l2_to_l1_payload_lengths.extend(other.l2_to_l1_payload_lengths);
+        self.n_l2_to_l1_messages += other.n_l2_to_l1_messages;
+        self.l2_to_l1_payloads_gas = self.l2_to_l1_payloads_gas + other.l2_to_l1_payloads_gas;
         self.n_events += other.n_events;
+        add_syscall_counts(&mut self.syscall_counter, &other.syscall_counter);
         self
     }
 }
@@ -118,6 +260,12 @@ impl Sum for ExecutionSummary {
     }
 }
 
+fn add_syscall_counts(syscall_counter: &mut SyscallCounter, other: &SyscallCounter) {
+    for (selector, count) in other {
+        *syscall_counter.entry(*selector).or_default() += count;
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct TestExecutionSummary {
     pub num_of_events: usize,
@@ -182,6 +330,17 @@ pub struct CallInfo {
     // Additional information gathered during execution.
     pub storage_read_values: Vec<StarkFelt>,
     pub accessed_storage_keys: HashSet<StorageKey>,
+    /// The storage reads and writes of the call (excluding its inner calls), in execution order.
+    pub storage_accesses: Vec<StorageAccess>,
+    /// The number of invocations of each syscall by the call (excluding its inner calls); keccak
+    /// is counted by rounds.
+    pub syscall_counter: SyscallCounter,
+    /// The felt arrays printed by the call (excluding its inner calls), in order; only captured
+    /// in development mode (see `BlockContextBuilder::dev_mode`).
+    pub debug_logs: Vec<Vec<StarkFelt>>,
+    /// Whether the called contract was already executing, up the call stack; only set under
+    /// `ReentrancyPolicy::Flag`.
+    pub reentrant: bool,
 }
 
 impl CallInfo {
@@ -190,6 +349,81 @@ impl CallInfo {
         CallInfoIter { call_infos }
     }
 
+    /// Like `iter`, but also yields the position of each call in the call tree.
+    pub fn iter_with_depth(&self) -> CallInfoDepthIter<'_> {
+        CallInfoDepthIter { call_infos: vec![(self, 0, None)], next_index: 0 }
+    }
+
+    /// Returns the execution resources of the call, with a SCALE codec.
+    #[cfg(feature = "scale-codec")]
+    pub fn encodable_resources(
+        &self,
+    ) -> crate::execution::scale_codec::EncodableExecutionResources {
+        self.resources.clone().into()
+    }
+
+    /// Renders the call tree as a Graphviz (DOT) digraph; failed calls are colored red.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph calls {\n    node [shape=box];\n");
+        for node in self.iter_with_depth() {
+            let color =
+                if node.call_info.execution.failed { ", color=red, fontcolor=red" } else { "" };
+            dot.push_str(&format!(
+                "    call_{} [label=\"{}\"{color}];\n",
+                node.index,
+                node.call_info.call_graph_label("\\n")
+            ));
+            if let Some(parent_index) = node.parent_index {
+                dot.push_str(&format!("    call_{parent_index} -> call_{};\n", node.index));
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Renders the call tree as a Mermaid flowchart; failed calls are colored red.
+    pub fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("flowchart TD\n");
+        let mut failed_calls = Vec::new();
+        for node in self.iter_with_depth() {
+            mermaid.push_str(&format!(
+                "    call_{}[\"{}\"]\n",
+                node.index,
+                node.call_info.call_graph_label("<br/>")
+            ));
+            if let Some(parent_index) = node.parent_index {
+                mermaid.push_str(&format!("    call_{parent_index} --> call_{}\n", node.index));
+            }
+            if node.call_info.execution.failed {
+                failed_calls.push(format!("call_{}", node.index));
+            }
+        }
+        if !failed_calls.is_empty() {
+            mermaid.push_str("    classDef failed fill:#fdd,stroke:#d00,color:#d00\n");
+            mermaid.push_str(&format!("    class {} failed\n", failed_calls.join(",")));
+        }
+        mermaid
+    }
+
+    fn call_graph_label(&self, line_separator: &str) -> String {
+        [
+            format!("address: {}", self.call.storage_address.0.key()),
+            format!("selector: {}", self.call.entry_point_selector.0),
+            format!("steps: {}", self.resources.n_steps),
+            format!("gas: {}", self.execution.gas_consumed),
+        ]
+        .join(line_separator)
+    }
+
+    /// Returns the events emitted in the call tree, in emission order. The `order` of an event
+    /// counts the events emitted before it in the same execution context, across all calls.
+    pub fn ordered_events(&self) -> Vec<&EventContent> {
+        let mut events: Vec<&OrderedEvent> =
+            self.iter().flat_map(|call_info| &call_info.execution.events).collect();
+        events.sort_by_key(|ordered_event| ordered_event.order);
+        events.into_iter().map(|ordered_event| &ordered_event.event).collect()
+    }
+
     pub fn get_l2_to_l1_payload_lengths(&self) -> Vec<usize> {
         self.iter().fold(Vec::new(), |mut acc, call_info| {
             acc.extend(get_payload_lengths(&call_info.execution.l2_to_l1_messages));
@@ -202,6 +436,7 @@ impl CallInfo {
         let mut visited_storage_entries: HashSet<StorageEntry> = HashSet::new();
         let mut n_events: usize = 0;
         let mut l2_to_l1_payload_lengths = Vec::new();
+        let mut syscall_counter = SyscallCounter::new();
 
         for call_info in self.iter() {
             let class_hash =
@@ -218,13 +453,18 @@ impl CallInfo {
 
             l2_to_l1_payload_lengths
                 .extend(get_payload_lengths(&call_info.execution.l2_to_l1_messages));
+
+            add_syscall_counts(&mut syscall_counter, &call_info.syscall_counter);
         }
 
         ExecutionSummary {
             executed_class_hashes,
             visited_storage_entries,
+            n_l2_to_l1_messages: l2_to_l1_payload_lengths.len(),
+            l2_to_l1_payloads_gas: get_log_message_to_l1_emissions_cost(&l2_to_l1_payload_lengths),
             l2_to_l1_payload_lengths,
             n_events,
+            syscall_counter,
         }
     }
 }
@@ -244,3 +484,40 @@ impl<'a> Iterator for CallInfoIter<'a> {
         Some(call_info)
     }
 }
+
+/// A call in the call tree, as yielded by `CallInfo::iter_with_depth`.
+#[derive(Debug, Eq, PartialEq)]
+pub struct CallInfoNode<'a> {
+    pub call_info: &'a CallInfo,
+    /// The depth of the call; the root call is at depth 0.
+    pub depth: usize,
+    /// The index of the call in the iteration order (pre-order).
+    pub index: usize,
+    /// The index of the calling call; [None] for the root call.
+    pub parent_index: Option<usize>,
+}
+
+pub struct CallInfoDepthIter<'a> {
+    call_infos: Vec<(&'a CallInfo, usize, Option<usize>)>,
+    next_index: usize,
+}
+
+impl<'a> Iterator for CallInfoDepthIter<'a> {
+    type Item = CallInfoNode<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (call_info, depth, parent_index) = self.call_infos.pop()?;
+        let index = self.next_index;
+        self.next_index += 1;
+
+        // Push order is right to left.
+        self.call_infos.extend(
+            call_info
+                .inner_calls
+                .iter()
+                .rev()
+                .map(|inner_call| (inner_call, depth + 1, Some(index))),
+        );
+        Some(CallInfoNode { call_info, depth, index, parent_index })
+    }
+}
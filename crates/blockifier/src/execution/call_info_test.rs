@@ -0,0 +1,209 @@
+use std::collections::{HashMap, HashSet};
+
+use cairo_vm::vm::runners::builtin_runner::RANGE_CHECK_BUILTIN_NAME;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, PatriciaKey};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{Calldata, EventContent, EventData, EventKey, L2ToL1Payload};
+use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
+
+use crate::abi::constants;
+use crate::execution::call_info::{
+    CallExecution, CallInfo, MessageL1CostInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message,
+    Retdata, StorageAccess,
+};
+use crate::execution::entry_point::{CallEntryPoint, CallType};
+use crate::execution::syscalls::SyscallSelector;
+use crate::fee::eth_gas_constants;
+use crate::fee::gas_usage::get_log_message_to_l1_emissions_cost;
+use crate::test_utils::golden::assert_json_golden;
+use crate::transaction::objects::GasVector;
+use crate::utils::u128_from_usize;
+use crate::{retdata, storage_key};
+
+fn call_info(address: &str, inner_calls: Vec<CallInfo>) -> CallInfo {
+    CallInfo {
+        call: CallEntryPoint { storage_address: contract_address!(address), ..Default::default() },
+        inner_calls,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_retdata_conversions() {
+    let felts = vec![stark_felt!(1_u8), stark_felt!(2_u8)];
+    let retdata = retdata![stark_felt!(1_u8), stark_felt!(2_u8)];
+    assert_eq!(Retdata::from(felts.clone()), retdata);
+    assert_eq!(felts.iter().copied().collect::<Retdata>(), retdata);
+    assert_eq!(retdata.into_vec(), felts);
+}
+
+#[test]
+fn test_iter_with_depth() {
+    // Call tree: 0x1 -> (0x2 -> 0x3, 0x4).
+    let root = call_info(
+        "0x1",
+        vec![call_info("0x2", vec![call_info("0x3", vec![])]), call_info("0x4", vec![])],
+    );
+
+    let nodes: Vec<_> = root
+        .iter_with_depth()
+        .map(|node| {
+            (node.call_info.call.storage_address, node.index, node.depth, node.parent_index)
+        })
+        .collect();
+    assert_eq!(
+        nodes,
+        vec![
+            (contract_address!("0x1"), 0, 0, None),
+            (contract_address!("0x2"), 1, 1, Some(0)),
+            (contract_address!("0x3"), 2, 2, Some(1)),
+            (contract_address!("0x4"), 3, 1, Some(0)),
+        ]
+    );
+
+    // Same order as the flat iterator.
+    assert!(root.iter().eq(root.iter_with_depth().map(|node| node.call_info)));
+}
+
+#[test]
+fn test_call_graph() {
+    let mut failed_call = call_info("0x2", vec![]);
+    failed_call.execution.failed = true;
+    failed_call.resources.n_steps = 7;
+    let root = call_info("0x1", vec![failed_call, call_info("0x3", vec![])]);
+    let failed_call_label = format!(
+        "address: {}{{separator}}selector: {}{{separator}}steps: 7{{separator}}gas: 0",
+        contract_address!("0x2").0.key(),
+        StarkHash::default()
+    );
+
+    let dot = root.to_dot();
+    assert!(dot.starts_with("digraph calls {"));
+    assert!(dot.contains(&format!(
+        "call_1 [label=\"{}\", color=red, fontcolor=red];",
+        failed_call_label.replace("{separator}", "\\n")
+    )));
+    assert!(dot.contains("call_0 -> call_1;") && dot.contains("call_0 -> call_2;"));
+    assert!(!dot.contains("call_1 -> call_2;"));
+
+    let mermaid = root.to_mermaid();
+    assert!(mermaid.starts_with("flowchart TD"));
+    assert!(
+        mermaid.contains(&format!(
+            "call_1[\"{}\"]",
+            failed_call_label.replace("{separator}", "<br/>")
+        ))
+    );
+    assert!(mermaid.contains("call_0 --> call_1") && mermaid.contains("call_0 --> call_2"));
+    assert!(mermaid.ends_with("class call_1 failed\n"));
+}
+
+#[test]
+fn test_call_info_json_golden() {
+    let failed_inner_call = CallInfo {
+        execution: CallExecution { failed: true, ..Default::default() },
+        ..Default::default()
+    };
+    let call_info = CallInfo {
+        call: CallEntryPoint {
+            class_hash: Some(class_hash!("0x10")),
+            code_address: None,
+            entry_point_type: EntryPointType::External,
+            entry_point_selector: EntryPointSelector(stark_felt!("0x20")),
+            calldata: calldata![stark_felt!(1_u8), stark_felt!(2_u8)],
+            storage_address: contract_address!("0x30"),
+            caller_address: contract_address!("0x40"),
+            call_type: CallType::Delegate,
+            initial_gas: 100000,
+        },
+        execution: CallExecution {
+            retdata: retdata![stark_felt!(3_u8)],
+            events: vec![OrderedEvent {
+                order: 0,
+                event: EventContent {
+                    keys: vec![EventKey(stark_felt!(4_u8))],
+                    data: EventData(vec![stark_felt!(5_u8)]),
+                },
+            }],
+            l2_to_l1_messages: vec![],
+            failed: false,
+            gas_consumed: 2000,
+        },
+        resources: ExecutionResources {
+            n_steps: 100,
+            n_memory_holes: 5,
+            builtin_instance_counter: HashMap::from([(RANGE_CHECK_BUILTIN_NAME.to_string(), 3)]),
+        },
+        inner_calls: vec![failed_inner_call],
+        storage_read_values: vec![stark_felt!(6_u8)],
+        accessed_storage_keys: HashSet::from([storage_key!(7_u8)]),
+        storage_accesses: vec![StorageAccess::read(storage_key!(7_u8), stark_felt!(6_u8))],
+        syscall_counter: HashMap::from([(SyscallSelector::StorageRead, 1)]),
+        // A debug print of "hello".
+        debug_logs: vec![vec![stark_felt!(0x68656c6c6f_u64)]],
+        reentrant: false,
+    };
+    assert_json_golden("call_info", &call_info);
+}
+
+fn l2_to_l1_message(payload_length: usize) -> OrderedL2ToL1Message {
+    OrderedL2ToL1Message {
+        order: 0,
+        message: MessageToL1 {
+            payload: L2ToL1Payload(vec![stark_felt!(1_u8); payload_length]),
+            ..Default::default()
+        },
+    }
+}
+
+#[test]
+fn test_message_cost_info() {
+    let mut root = call_info("0x1", vec![call_info("0x2", vec![])]);
+    root.execution.l2_to_l1_messages = vec![l2_to_l1_message(2)];
+    root.inner_calls[0].execution.l2_to_l1_messages = vec![l2_to_l1_message(0)];
+
+    let l1_handler_payload_size = 3;
+    let cost_info = MessageL1CostInfo::calculate(root.iter(), Some(l1_handler_payload_size));
+    assert_eq!(cost_info.l2_to_l1_payload_lengths, vec![2, 0]);
+    let expected_segment_length = 2 * constants::L2_TO_L1_MSG_HEADER_SIZE
+        + 2
+        + constants::L1_TO_L2_MSG_HEADER_SIZE
+        + l1_handler_payload_size;
+    assert_eq!(cost_info.message_segment_length, expected_segment_length);
+
+    let expected_sharp_gas = GasVector::from_l1_gas(u128_from_usize(
+        expected_segment_length * eth_gas_constants::SHARP_GAS_PER_MEMORY_WORD,
+    ));
+    assert_eq!(cost_info.sharp_gas_usage(), expected_sharp_gas);
+    assert_eq!(cost_info.to_gas_vector(), cost_info.starknet_gas_usage() + expected_sharp_gas);
+    assert_eq!((cost_info.n_l2_to_l1_messages, cost_info.n_l1_to_l2_messages), (2, 1));
+    assert_eq!(cost_info.l2_to_l1_payloads_gas, get_log_message_to_l1_emissions_cost(&[2, 0]));
+}
+
+#[test]
+fn test_message_cost_info_add() {
+    let mut sender = call_info("0x1", vec![]);
+    sender.execution.l2_to_l1_messages = vec![l2_to_l1_message(1), l2_to_l1_message(4)];
+    let l1_handler = call_info("0x2", vec![]);
+
+    let sender_cost = MessageL1CostInfo::calculate(sender.iter(), None);
+    let l1_handler_cost = MessageL1CostInfo::calculate(l1_handler.iter(), Some(3));
+    let total_cost = sender_cost.clone() + l1_handler_cost.clone() + l1_handler_cost.clone();
+
+    assert_eq!(total_cost.l2_to_l1_payload_lengths, vec![1, 4]);
+    assert_eq!((total_cost.n_l2_to_l1_messages, total_cost.n_l1_to_l2_messages), (2, 2));
+    assert_eq!(total_cost.l1_handler_payload_size, Some(6));
+    assert_eq!(
+        total_cost.message_segment_length,
+        sender_cost.message_segment_length + 2 * l1_handler_cost.message_segment_length
+    );
+    // The gas costs are additive.
+    assert_eq!(
+        total_cost.to_gas_vector(),
+        sender_cost.to_gas_vector()
+            + l1_handler_cost.to_gas_vector()
+            + l1_handler_cost.to_gas_vector()
+    );
+}
@@ -1,3 +1,40 @@
+pub use starknet_api::core::calculate_contract_address;
+use starknet_api::core::{ClassHash, ContractAddress};
+use starknet_api::transaction::{Calldata, ContractAddressSalt};
+use starknet_api::StarknetApiError;
+
 #[cfg(test)]
 #[path = "contract_address_test.rs"]
 pub mod test;
+
+/// Calculates the address of a contract deployed by the `deploy` syscall of `deployer_address`.
+/// With `deploy_from_zero`, the deployer is not part of the calculation (see
+/// [`calculate_contract_address_from_zero`]).
+pub fn calculate_deployed_contract_address(
+    salt: ContractAddressSalt,
+    class_hash: ClassHash,
+    constructor_calldata: &Calldata,
+    deployer_address: ContractAddress,
+    deploy_from_zero: bool,
+) -> Result<ContractAddress, StarknetApiError> {
+    let deployer_address_for_calculation = match deploy_from_zero {
+        true => ContractAddress::default(),
+        false => deployer_address,
+    };
+    calculate_contract_address(
+        salt,
+        class_hash,
+        constructor_calldata,
+        deployer_address_for_calculation,
+    )
+}
+
+/// Calculates the address of a contract deployed from the zero address, which does not depend on
+/// the deployer; e.g., the address of the account of a deploy account transaction.
+pub fn calculate_contract_address_from_zero(
+    salt: ContractAddressSalt,
+    class_hash: ClassHash,
+    constructor_calldata: &Calldata,
+) -> Result<ContractAddress, StarknetApiError> {
+    calculate_contract_address(salt, class_hash, constructor_calldata, ContractAddress::default())
+}
@@ -1,5 +1,5 @@
 use rstest::rstest;
-use starknet_api::core::{calculate_contract_address, ClassHash, ContractAddress};
+use starknet_api::core::{ClassHash, ContractAddress};
 use starknet_api::hash::StarkFelt;
 use starknet_api::transaction::{Calldata, ContractAddressSalt};
 use starknet_api::{calldata, stark_felt};
@@ -7,6 +7,10 @@ use starknet_api::{calldata, stark_felt};
 use crate::abi::abi_utils::selector_from_name;
 use crate::context::ChainInfo;
 use crate::execution::call_info::{CallExecution, Retdata};
+use crate::execution::contract_address::{
+    calculate_contract_address, calculate_contract_address_from_zero,
+    calculate_deployed_contract_address,
+};
 use crate::execution::entry_point::CallEntryPoint;
 use crate::retdata;
 use crate::state::cached_state::CachedState;
@@ -73,3 +77,32 @@ fn test_calculate_contract_address() {
     ];
     run_test(salt, class_hash, &constructor_calldata, calldata, deployer_address, &mut state);
 }
+
+#[test]
+fn test_calculate_deployed_contract_address() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let salt = ContractAddressSalt(stark_felt!(7_u8));
+    let class_hash = test_contract.get_class_hash();
+    let constructor_calldata = calldata![stark_felt!(1_u8), stark_felt!(2_u8)];
+    let deployer_address = test_contract.get_instance_address(0);
+    let deployed_address = |deploy_from_zero| {
+        calculate_deployed_contract_address(
+            salt,
+            class_hash,
+            &constructor_calldata,
+            deployer_address,
+            deploy_from_zero,
+        )
+        .unwrap()
+    };
+
+    assert_eq!(
+        deployed_address(false),
+        calculate_contract_address(salt, class_hash, &constructor_calldata, deployer_address)
+            .unwrap()
+    );
+    let address_from_zero =
+        calculate_contract_address_from_zero(salt, class_hash, &constructor_calldata).unwrap();
+    assert_eq!(deployed_address(true), address_from_zero);
+    assert_ne!(address_from_zero, deployed_address(false));
+}
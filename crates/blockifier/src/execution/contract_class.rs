@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use std::io::{self, BufReader, Read};
 use std::ops::Deref;
 use std::sync::Arc;
 
@@ -18,11 +19,12 @@ use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use itertools::Itertools;
 use serde::de::Error as DeserializationError;
 use serde::{Deserialize, Deserializer};
-use starknet_api::core::EntryPointSelector;
+use starknet_api::core::{CompiledClassHash, EntryPointSelector};
 use starknet_api::deprecated_contract_class::{
     ContractClass as DeprecatedContractClass, EntryPoint, EntryPointOffset, EntryPointType,
     Program as DeprecatedProgram,
 };
+use starknet_crypto::{poseidon_hash_many, FieldElement};
 
 use super::execution_utils::poseidon_hash_many_cost;
 use crate::abi::abi_utils::selector_from_name;
@@ -37,6 +39,9 @@ use crate::transaction::errors::TransactionExecutionError;
 #[path = "contract_class_test.rs"]
 pub mod test;
 
+/// The version prefix of compiled class hashes.
+const COMPILED_CLASS_VERSION: &str = "COMPILED_CLASS_V1";
+
 /// Represents a runnable Starknet contract class (meaning, the program is runnable by the VM).
 /// We wrap the actual class in an Arc to avoid cloning the program when cloning the class.
 // Note: when deserializing from a SN API class JSON string, the ABI field is ignored
@@ -83,6 +88,48 @@ impl ContractClass {
             ContractClass::V1(class) => class.bytecode_length(),
         }
     }
+
+    /// Loads a class from either a deprecated (Cairo 0) class JSON or a CASM (Cairo 1) JSON.
+    pub fn try_from_json_string(raw_contract_class: &str) -> Result<ContractClass, ProgramError> {
+        let raw_contract_class: serde_json::Value = serde_json::from_str(raw_contract_class)?;
+        Ok(Self::try_from_json_value(raw_contract_class)?)
+    }
+
+    fn try_from_json_value(
+        raw_contract_class: serde_json::Value,
+    ) -> Result<ContractClass, serde_json::Error> {
+        // Only deprecated classes have a `program` field.
+        if raw_contract_class.get("program").is_some() {
+            let contract_class: ContractClassV0Inner = serde_json::from_value(raw_contract_class)?;
+            Ok(ContractClassV0(Arc::new(contract_class)).into())
+        } else {
+            Ok(ContractClassV1::deserialize(raw_contract_class)?.into())
+        }
+    }
+}
+
+impl TryFrom<DeprecatedContractClass> for ContractClass {
+    type Error = ProgramError;
+
+    fn try_from(class: DeprecatedContractClass) -> Result<Self, Self::Error> {
+        Ok(ContractClassV0::try_from(class)?.into())
+    }
+}
+
+impl TryFrom<CasmContractClass> for ContractClass {
+    type Error = ProgramError;
+
+    fn try_from(class: CasmContractClass) -> Result<Self, Self::Error> {
+        Ok(ContractClassV1::try_from(class)?.into())
+    }
+}
+
+/// Deserializes a class from its compiled artifact (a deprecated class or a CASM class).
+impl<'de> Deserialize<'de> for ContractClass {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw_contract_class = serde_json::Value::deserialize(deserializer)?;
+        Self::try_from_json_value(raw_contract_class).map_err(DeserializationError::custom)
+    }
 }
 
 // V0.
@@ -135,6 +182,23 @@ impl ContractClassV0 {
         let contract_class: ContractClassV0Inner = serde_json::from_str(raw_contract_class)?;
         Ok(ContractClassV0(Arc::new(contract_class)))
     }
+
+    /// Deserializes the class directly from the given reader, without loading the raw JSON into
+    /// memory first; useful for legacy classes with very large programs.
+    /// Fails if more than `max_size_in_bytes` bytes are read.
+    pub fn try_from_json_reader(
+        reader: impl Read,
+        max_size_in_bytes: usize,
+    ) -> ContractClassResult<ContractClassV0> {
+        let mut reader = SizeLimitedReader::new(BufReader::new(reader), max_size_in_bytes);
+        match serde_json::from_reader::<_, ContractClassV0Inner>(&mut reader) {
+            Ok(contract_class) => Ok(ContractClassV0(Arc::new(contract_class))),
+            Err(_) if reader.limit_exceeded => {
+                Err(ContractClassError::ContractClassTooLarge { max_size_in_bytes })
+            }
+            Err(error) => Err(ProgramError::from(error).into()),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq)]
@@ -179,6 +243,53 @@ impl ContractClassV1 {
         &self.bytecode_segment_lengths
     }
 
+    /// Computes the compiled class hash of the class, as committed to by declare transactions.
+    pub fn compiled_class_hash(&self) -> CompiledClassHash {
+        let entry_points_hash = |entry_point_type: EntryPointType| {
+            let entry_points_data = self
+                .entry_points_by_type
+                .get(&entry_point_type)
+                .into_iter()
+                .flatten()
+                .flat_map(|entry_point| {
+                    let builtins = entry_point
+                        .builtins
+                        .iter()
+                        .map(|builtin| {
+                            short_string_to_field_element(
+                                builtin.strip_suffix("_builtin").unwrap_or(builtin),
+                            )
+                        })
+                        .collect_vec();
+                    [
+                        FieldElement::from(entry_point.selector.0),
+                        FieldElement::from(entry_point.offset.0),
+                        poseidon_hash_many(&builtins),
+                    ]
+                })
+                .collect_vec();
+            poseidon_hash_many(&entry_points_data)
+        };
+
+        let mut bytecode = self.program.iter_data().map(|value| match value {
+            MaybeRelocatable::Int(felt) => FieldElement::from(felt_to_stark_felt(felt)),
+            MaybeRelocatable::RelocatableValue(_) => {
+                panic!("The bytecode of a Cairo 1 class consists of field elements.")
+            }
+        });
+        let (_, bytecode_hash) =
+            bytecode_segment_hash(&mut bytecode, &self.bytecode_segment_lengths);
+
+        let compiled_class_hash = poseidon_hash_many(&[
+            short_string_to_field_element(COMPILED_CLASS_VERSION),
+            entry_points_hash(EntryPointType::External),
+            entry_points_hash(EntryPointType::L1Handler),
+            entry_points_hash(EntryPointType::Constructor),
+            bytecode_hash,
+        ]);
+        CompiledClassHash(compiled_class_hash.into())
+    }
+
     pub fn get_entry_point(
         &self,
         call: &CallEntryPoint,
@@ -241,6 +352,35 @@ impl ContractClassV1 {
     }
 }
 
+/// Returns the length and hash of the given bytecode segment, consuming it from the bytecode.
+/// A segment consisting of sub-segments is hashed together with their lengths, plus one.
+fn bytecode_segment_hash(
+    bytecode: &mut impl Iterator<Item = FieldElement>,
+    bytecode_segment_lengths: &NestedIntList,
+) -> (usize, FieldElement) {
+    match bytecode_segment_lengths {
+        NestedIntList::Leaf(length) => {
+            let segment = bytecode.take(*length).collect_vec();
+            (*length, poseidon_hash_many(&segment))
+        }
+        NestedIntList::Node(segments) => {
+            let mut total_length = 0;
+            let mut segments_data = Vec::with_capacity(2 * segments.len());
+            for segment in segments {
+                let (length, hash) = bytecode_segment_hash(bytecode, segment);
+                total_length += length;
+                segments_data.extend([FieldElement::from(length), hash]);
+            }
+            (total_length, poseidon_hash_many(&segments_data) + FieldElement::ONE)
+        }
+    }
+}
+
+fn short_string_to_field_element(short_string: &str) -> FieldElement {
+    FieldElement::from_byte_slice_be(short_string.as_bytes())
+        .expect("A short string must fit in a field element.")
+}
+
 /// Returns the estimated VM resources required for computing Casm hash (for Cairo 1 contracts).
 ///
 /// Note: the function focuses on the bytecode size, and currently ignores the cost handling the
@@ -424,6 +564,14 @@ impl TryFrom<CasmContractClass> for ContractClassV1 {
     }
 }
 
+/// Deserializes a class from its CASM artifact.
+impl<'de> Deserialize<'de> for ContractClassV1 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let casm_contract_class = CasmContractClass::deserialize(deserializer)?;
+        Self::try_from(casm_contract_class).map_err(DeserializationError::custom)
+    }
+}
+
 // V0 utilities.
 
 /// Converts the program type from SN API into a Cairo VM-compatible type.
@@ -435,6 +583,35 @@ pub fn deserialize_program<'de, D: Deserializer<'de>>(
         .map_err(|err| DeserializationError::custom(err.to_string()))
 }
 
+/// A reader that fails once more than the given number of bytes is read from it.
+struct SizeLimitedReader<R> {
+    inner: R,
+    remaining_bytes: usize,
+    limit_exceeded: bool,
+}
+
+impl<R> SizeLimitedReader<R> {
+    fn new(inner: R, max_size_in_bytes: usize) -> Self {
+        Self { inner, remaining_bytes: max_size_in_bytes, limit_exceeded: false }
+    }
+}
+
+impl<R: Read> Read for SizeLimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n_read_bytes = self.inner.read(buf)?;
+        match self.remaining_bytes.checked_sub(n_read_bytes) {
+            Some(remaining_bytes) => {
+                self.remaining_bytes = remaining_bytes;
+                Ok(n_read_bytes)
+            }
+            None => {
+                self.limit_exceeded = true;
+                Err(io::Error::new(io::ErrorKind::InvalidData, "Size limit exceeded."))
+            }
+        }
+    }
+}
+
 // V1 utilities.
 
 // TODO(spapini): Share with cairo-lang-runner.
@@ -464,12 +641,22 @@ fn convert_entry_points_v1(
         .collect()
 }
 
+/// The version of the Sierra program of a class, as (major, minor, patch).
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct SierraVersion(pub u64, pub u64, pub u64);
+
+impl SierraVersion {
+    pub const MIN: Self = Self(0, 0, 0);
+    pub const MAX: Self = Self(u64::MAX, u64::MAX, u64::MAX);
+}
+
 #[derive(Clone, Debug)]
 // TODO(Ayelet,10/02/2024): Change to bytes.
 pub struct ClassInfo {
     contract_class: ContractClass,
     sierra_program_length: usize,
     abi_length: usize,
+    sierra_version: Option<SierraVersion>,
 }
 
 impl ClassInfo {
@@ -489,6 +676,11 @@ impl ClassInfo {
         self.abi_length
     }
 
+    /// The Sierra version of the class, if known; Cairo 0 classes have none.
+    pub fn sierra_version(&self) -> Option<SierraVersion> {
+        self.sierra_version
+    }
+
     pub fn code_size(&self) -> usize {
         (self.bytecode_length() + self.sierra_program_length())
             // We assume each felt is a word.
@@ -507,7 +699,12 @@ impl ClassInfo {
         };
 
         if condition {
-            Ok(Self { contract_class: contract_class.clone(), sierra_program_length, abi_length })
+            Ok(Self {
+                contract_class: contract_class.clone(),
+                sierra_program_length,
+                abi_length,
+                sierra_version: None,
+            })
         } else {
             Err(ContractClassError::ContractClassVersionSierraProgramLengthMismatch {
                 contract_class_version,
@@ -515,4 +712,8 @@ impl ClassInfo {
             })
         }
     }
+
+    pub fn with_sierra_version(self, sierra_version: SierraVersion) -> Self {
+        Self { sierra_version: Some(sierra_version), ..self }
+    }
 }
@@ -4,8 +4,15 @@ use std::sync::Arc;
 use assert_matches::assert_matches;
 use cairo_lang_starknet_classes::NestedIntList;
 use rstest::rstest;
+use starknet_api::core::CompiledClassHash;
 
-use crate::execution::contract_class::{ContractClassV1, ContractClassV1Inner};
+use crate::compiled_class_hash;
+use crate::execution::contract_class::{
+    ContractClass, ContractClassV0, ContractClassV1, ContractClassV1Inner,
+};
+use crate::execution::errors::ContractClassError;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::{get_raw_contract_class, CairoVersion, ERC20_CONTRACT_PATH};
 use crate::transaction::errors::TransactionExecutionError;
 
 #[rstest]
@@ -42,3 +49,55 @@ fn test_get_visited_segments() {
         TransactionExecutionError::InvalidSegmentStructure(907, 807)
     );
 }
+
+#[test]
+fn test_v0_class_from_json_reader() {
+    let raw_contract_class = get_raw_contract_class(ERC20_CONTRACT_PATH);
+    let size_in_bytes = raw_contract_class.len();
+
+    assert_eq!(
+        ContractClassV0::try_from_json_reader(raw_contract_class.as_bytes(), size_in_bytes)
+            .unwrap(),
+        ContractClassV0::try_from_json_string(&raw_contract_class).unwrap()
+    );
+
+    assert_matches!(
+        ContractClassV0::try_from_json_reader(raw_contract_class.as_bytes(), size_in_bytes - 1)
+            .unwrap_err(),
+        ContractClassError::ContractClassTooLarge { max_size_in_bytes }
+        if max_size_in_bytes == size_in_bytes - 1
+    );
+}
+
+#[rstest]
+fn test_class_from_compiled_artifact(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let raw_contract_class = test_contract.get_raw_class();
+
+    let contract_class = ContractClass::try_from_json_string(&raw_contract_class).unwrap();
+    assert_eq!(contract_class, test_contract.get_class());
+    assert_eq!(serde_json::from_str::<ContractClass>(&raw_contract_class).unwrap(), contract_class);
+}
+
+// The expected hashes are the CASM hashes of the compiled artifacts, as computed by cairo-lang's
+// `compute_compiled_class_hash` (reimplemented independently of `compiled_class_hash`).
+#[rstest]
+#[case::segmented(
+    FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1),
+    compiled_class_hash!("0x6320244253978f1e1e612fef177f7f6f3eb9d25536787bdbede184a31f3eb75")
+)]
+#[case::not_segmented(
+    FeatureContract::LegacyTestContract,
+    compiled_class_hash!("0x1e9f18319ec0f9a4158522e9ccf356c08e9a074609b972a3b8fb2a8e49a2994")
+)]
+fn test_compiled_class_hash(
+    #[case] contract: FeatureContract,
+    #[case] expected_compiled_class_hash: CompiledClassHash,
+) {
+    let ContractClass::V1(contract_class) = contract.get_class() else {
+        panic!("Expected a Cairo 1 class.");
+    };
+    assert_eq!(contract_class.compiled_class_hash(), expected_compiled_class_hash);
+}
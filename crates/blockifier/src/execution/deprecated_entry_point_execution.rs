@@ -14,7 +14,7 @@ use crate::execution::deprecated_syscalls::hint_processor::DeprecatedSyscallHint
 use crate::execution::entry_point::{
     CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
 };
-use crate::execution::errors::{PostExecutionError, PreExecutionError};
+use crate::execution::errors::{EntryPointExecutionError, PostExecutionError, PreExecutionError};
 use crate::execution::execution_utils::{
     read_execution_retdata, stark_felt_to_felt, Args, ReadOnlySegments,
 };
@@ -42,7 +42,7 @@ pub fn execute_entry_point_call(
         mut syscall_handler,
         initial_syscall_ptr,
         entry_point_pc,
-    } = initialize_execution_context(&call, contract_class, state, resources, context)?;
+    } = initialize_execution_context(&call, contract_class.clone(), state, resources, context)?;
 
     let (implicit_args, args) = prepare_call_arguments(
         &call,
@@ -57,6 +57,15 @@ pub fn execute_entry_point_call(
 
     // Execute.
     run_entry_point(&mut vm, &mut runner, &mut syscall_handler, entry_point_pc, args)?;
+    if let Some(profiler) = syscall_handler.context.pc_sampling_profiler() {
+        let class_hash = call.class_hash.ok_or(EntryPointExecutionError::InternalError(
+            "Class hash must not be None when executing an entry point.".into(),
+        ))?;
+        // Relocate the trace, putting the program segment at address 1 and the execution segment
+        // right after it.
+        vm.relocate_trace(&[1, 1 + contract_class.bytecode_length()])?;
+        profiler.record_pc_samples(class_hash, &contract_class.program, vm.get_relocated_trace()?);
+    }
 
     Ok(finalize_execution(
         vm,
@@ -83,7 +92,8 @@ pub fn initialize_execution_context<'a>(
     let proof_mode = false;
     let mut runner = CairoRunner::new(&contract_class.program, "starknet", proof_mode)?;
 
-    let trace_enabled = false;
+    // The trace is only used for PC sampling.
+    let trace_enabled = context.pc_sampling_profiler().is_some();
     let mut vm = VirtualMachine::new(trace_enabled);
 
     runner.initialize_builtins(&mut vm)?;
@@ -204,7 +214,7 @@ pub fn run_entry_point(
         hint_processor,
     );
 
-    Ok(result?)
+    result.map_err(EntryPointExecutionError::from_run_error)
 }
 
 pub fn finalize_execution(
@@ -240,8 +250,16 @@ pub fn finalize_execution(
     *syscall_handler.resources += &vm_resources_without_inner_calls;
     let versioned_constants = syscall_handler.context.versioned_constants();
     // Take into account the syscall resources of the current call.
-    *syscall_handler.resources += &versioned_constants
+    let syscall_resources = versioned_constants
         .get_additional_os_syscall_resources(&syscall_handler.syscall_counter)?;
+    *syscall_handler.resources += &syscall_resources;
+    if let Some(profiler) = &syscall_handler.context.tx_context.block_context.profiler {
+        profiler.record_call(
+            &call,
+            &(&vm_resources_without_inner_calls + &syscall_resources),
+            &syscall_handler.syscall_counter,
+        );
+    }
 
     let full_call_resources = &*syscall_handler.resources - &previous_resources;
     Ok(CallInfo {
@@ -257,6 +275,10 @@ pub fn finalize_execution(
         inner_calls: syscall_handler.inner_calls,
         storage_read_values: syscall_handler.read_values,
         accessed_storage_keys: syscall_handler.accessed_keys,
+        storage_accesses: syscall_handler.storage_accesses,
+        syscall_counter: syscall_handler.syscall_counter,
+        debug_logs: vec![],
+        reentrant: false,
     })
 }
 
@@ -18,7 +18,7 @@ use test_case::test_case;
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::context::ChainInfo;
-use crate::execution::call_info::{CallExecution, CallInfo, OrderedEvent, Retdata};
+use crate::execution::call_info::{CallExecution, CallInfo, OrderedEvent, Retdata, StorageAccess};
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
 use crate::execution::entry_point::{CallEntryPoint, CallType};
@@ -148,6 +148,14 @@ fn test_nested_library_call() {
         resources: storage_entry_point_resources.clone(),
         storage_read_values: vec![stark_felt!(value + 1)],
         accessed_storage_keys: HashSet::from([storage_key!(key + 1)]),
+        storage_accesses: vec![
+            StorageAccess::write(storage_key!(key + 1), stark_felt!(value + 1)),
+            StorageAccess::read(storage_key!(key + 1), stark_felt!(value + 1)),
+        ],
+        syscall_counter: HashMap::from([
+            (DeprecatedSyscallSelector::StorageWrite, 1),
+            (DeprecatedSyscallSelector::StorageRead, 1),
+        ]),
         ..Default::default()
     };
     let mut library_call_resources = &get_syscall_resources(DeprecatedSyscallSelector::LibraryCall)
@@ -162,6 +170,7 @@ fn test_nested_library_call() {
         execution: CallExecution::from_retdata(retdata![stark_felt!(value + 1)]),
         resources: library_call_resources.clone(),
         inner_calls: vec![nested_storage_call_info],
+        syscall_counter: HashMap::from([(DeprecatedSyscallSelector::LibraryCall, 1)]),
         ..Default::default()
     };
     let storage_call_info = CallInfo {
@@ -170,6 +179,14 @@ fn test_nested_library_call() {
         resources: storage_entry_point_resources.clone(),
         storage_read_values: vec![stark_felt!(value)],
         accessed_storage_keys: HashSet::from([storage_key!(key)]),
+        storage_accesses: vec![
+            StorageAccess::write(storage_key!(key), stark_felt!(value)),
+            StorageAccess::read(storage_key!(key), stark_felt!(value)),
+        ],
+        syscall_counter: HashMap::from([
+            (DeprecatedSyscallSelector::StorageWrite, 1),
+            (DeprecatedSyscallSelector::StorageRead, 1),
+        ]),
         ..Default::default()
     };
 
@@ -186,6 +203,7 @@ fn test_nested_library_call() {
         execution: CallExecution::from_retdata(retdata![stark_felt!(0_u8)]),
         resources: main_call_resources,
         inner_calls: vec![library_call_info, storage_call_info],
+        syscall_counter: HashMap::from([(DeprecatedSyscallSelector::LibraryCall, 2)]),
         ..Default::default()
     };
 
@@ -235,6 +253,14 @@ fn test_call_contract() {
         },
         storage_read_values: vec![stark_felt!(value)],
         accessed_storage_keys: HashSet::from([storage_key!(key)]),
+        storage_accesses: vec![
+            StorageAccess::write(storage_key!(key), stark_felt!(value)),
+            StorageAccess::read(storage_key!(key), stark_felt!(value)),
+        ],
+        syscall_counter: HashMap::from([
+            (DeprecatedSyscallSelector::StorageWrite, 1),
+            (DeprecatedSyscallSelector::StorageRead, 1),
+        ]),
         ..Default::default()
     };
     let expected_call_info = CallInfo {
@@ -255,6 +281,7 @@ fn test_call_contract() {
                     3,
                 )]),
             },
+        syscall_counter: HashMap::from([(DeprecatedSyscallSelector::CallContract, 1)]),
         ..Default::default()
     };
 
@@ -567,3 +594,13 @@ fn emit_events(
 
     entry_point_call.execute_directly(&mut state)
 }
+
+#[test]
+fn test_syscall_selector_name() {
+    assert_eq!(DeprecatedSyscallSelector::GetSequencerAddress.name(), "get_sequencer_address");
+    assert_eq!(DeprecatedSyscallSelector::SendMessageToL1.name(), "send_message_to_l1");
+    assert_eq!(
+        DeprecatedSyscallSelector::Secp256k1GetPointFromX.name(),
+        "secp256k1_get_point_from_x"
+    );
+}
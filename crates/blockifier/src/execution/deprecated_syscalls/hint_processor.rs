@@ -26,7 +26,8 @@ use thiserror::Error;
 
 use crate::blockifier::block::BlockInfo;
 use crate::context::TransactionContext;
-use crate::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message};
+use crate::event_log::ExecutionEvent;
+use crate::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message, StorageAccess};
 use crate::execution::common_hints::{
     extended_builtin_hint_processor, ExecutionMode, HintExecutionResult,
 };
@@ -155,6 +156,7 @@ pub struct DeprecatedSyscallHintProcessor<'a> {
     // Additional information gathered during execution.
     pub read_values: Vec<StarkFelt>,
     pub accessed_keys: HashSet<StorageKey>,
+    pub storage_accesses: Vec<StorageAccess>,
 
     // Additional fields.
     // Invariant: must only contain allowed hints.
@@ -187,6 +189,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
             syscall_ptr: initial_syscall_ptr,
             read_values: vec![],
             accessed_keys: HashSet::new(),
+            storage_accesses: vec![],
             builtin_hint_processor: extended_builtin_hint_processor(),
             tx_signature_start_ptr: None,
             tx_info_start_ptr: None,
@@ -201,11 +204,15 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         self.execution_mode() == ExecutionMode::Validate
     }
 
-    /// Returns an error if the syscall is run in validate mode.
-    pub fn verify_not_in_validate_mode(&self, syscall_name: &str) -> DeprecatedSyscallResult<()> {
-        if self.is_validate_mode() {
+    /// Returns an error if the syscall is forbidden in the current execution mode.
+    pub fn verify_syscall_allowed(
+        &self,
+        selector: DeprecatedSyscallSelector,
+    ) -> DeprecatedSyscallResult<()> {
+        let forbidden_syscalls = &self.context.versioned_constants().validate_forbidden_syscalls;
+        if self.is_validate_mode() && forbidden_syscalls.contains(&selector) {
             return Err(DeprecatedSyscallExecutionError::InvalidSyscallInExecutionMode {
-                syscall_name: syscall_name.to_string(),
+                syscall_name: selector.name(),
                 execution_mode: self.execution_mode(),
             });
         }
@@ -232,11 +239,26 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         ids_data: &HashMap<String, HintReference>,
         ap_tracking: &ApTracking,
     ) -> HintExecutionResult {
+        if self.context.execution_aborted() {
+            return Err(DeprecatedSyscallExecutionError::from(
+                EntryPointExecutionError::ExecutionAborted,
+            )
+            .into());
+        }
         let initial_syscall_ptr = get_ptr_from_var_name("syscall_ptr", vm, ids_data, ap_tracking)?;
         self.verify_syscall_ptr(initial_syscall_ptr)?;
 
         let selector = DeprecatedSyscallSelector::try_from(self.read_next_syscall_selector(vm)?)?;
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("syscall", ?selector).entered();
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_syscall(&selector);
+        self.context.tx_context.block_context.record_event(|| ExecutionEvent::SyscallExecuted {
+            storage_address: self.storage_address,
+            selector: format!("{selector:?}"),
+        });
         self.increment_syscall_count(&selector);
+        self.verify_syscall_allowed(selector)?;
 
         match selector {
             DeprecatedSyscallSelector::CallContract => self.execute_syscall(vm, call_contract),
@@ -382,6 +404,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         self.accessed_keys.insert(key);
         let value = self.state.get_storage_at(self.storage_address, key)?;
         self.read_values.push(value);
+        self.storage_accesses.push(StorageAccess::read(key, value));
 
         Ok(StorageReadResponse { value })
     }
@@ -393,6 +416,12 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
     ) -> DeprecatedSyscallResult<StorageWriteResponse> {
         self.accessed_keys.insert(key);
         self.state.set_storage_at(self.storage_address, key, value)?;
+        self.storage_accesses.push(StorageAccess::write(key, value));
+        self.context.tx_context.block_context.record_event(|| ExecutionEvent::StorageWritten {
+            contract_address: self.storage_address,
+            key,
+            value,
+        });
 
         Ok(StorageWriteResponse {})
     }
@@ -404,7 +433,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
 
 impl ResourceTracker for DeprecatedSyscallHintProcessor<'_> {
     fn consumed(&self) -> bool {
-        self.context.vm_run_resources.consumed()
+        self.context.vm_run_consumed()
     }
 
     fn consume_step(&mut self) {
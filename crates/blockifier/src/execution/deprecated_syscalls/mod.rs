@@ -1,11 +1,9 @@
 use cairo_felt::Felt252;
 use cairo_vm::types::relocatable::Relocatable;
 use cairo_vm::vm::vm_core::VirtualMachine;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use starknet_api::block::{BlockNumber, BlockTimestamp};
-use starknet_api::core::{
-    calculate_contract_address, ClassHash, ContractAddress, EntryPointSelector, EthAddress,
-};
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, EthAddress};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
@@ -21,7 +19,9 @@ use self::hint_processor::{
 use super::syscalls::exceeds_event_size_limit;
 use crate::execution::call_info::{MessageToL1, OrderedEvent, OrderedL2ToL1Message};
 use crate::execution::common_hints::ExecutionMode;
+use crate::execution::contract_address::calculate_deployed_contract_address;
 use crate::execution::entry_point::{CallEntryPoint, CallType, ConstructorContext};
+use crate::execution::execution_policy::{check_execution_policy, ContractAction};
 use crate::execution::execution_utils::{
     execute_deployment, stark_felt_from_ptr, write_maybe_relocatable, write_stark_felt,
     ReadOnlySegment,
@@ -35,7 +35,7 @@ pub mod hint_processor;
 pub type DeprecatedSyscallResult<T> = Result<T, DeprecatedSyscallExecutionError>;
 pub type WriteResponseResult = DeprecatedSyscallResult<()>;
 
-#[derive(Clone, Copy, Debug, Deserialize, EnumIter, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, EnumIter, Eq, Hash, PartialEq, Serialize)]
 pub enum DeprecatedSyscallSelector {
     CallContract,
     DelegateCall,
@@ -66,8 +66,24 @@ pub enum DeprecatedSyscallSelector {
     Secp256r1Mul,
     Secp256r1New,
     SendMessageToL1,
+    Sha256ProcessBlock,
     StorageRead,
     StorageWrite,
+    VerifySignature,
+}
+
+impl DeprecatedSyscallSelector {
+    /// Returns the snake-case name of the syscall, e.g., `get_block_hash`.
+    pub fn name(&self) -> String {
+        let mut name = String::new();
+        for (i, c) in format!("{self:?}").chars().enumerate() {
+            if c.is_ascii_uppercase() && i > 0 {
+                name.push('_');
+            }
+            name.push(c.to_ascii_lowercase());
+        }
+        name
+    }
 }
 
 impl TryFrom<StarkFelt> for DeprecatedSyscallSelector {
@@ -107,8 +123,10 @@ impl TryFrom<StarkFelt> for DeprecatedSyscallSelector {
             b"Secp256r1Mul" => Ok(Self::Secp256r1Mul),
             b"Secp256r1New" => Ok(Self::Secp256r1New),
             b"SendMessageToL1" => Ok(Self::SendMessageToL1),
+            b"Sha256ProcessBlock" => Ok(Self::Sha256ProcessBlock),
             b"StorageRead" => Ok(Self::StorageRead),
             b"StorageWrite" => Ok(Self::StorageWrite),
+            b"VerifySignature" => Ok(Self::VerifySignature),
             _ => {
                 Err(DeprecatedSyscallExecutionError::InvalidDeprecatedSyscallSelector(raw_selector))
             }
@@ -195,6 +213,12 @@ pub fn call_contract(
             execution_mode: syscall_handler.execution_mode(),
         });
     }
+    check_execution_policy(
+        &syscall_handler.context.tx_context.block_context.chain_info,
+        ContractAction::Call,
+        class_hash,
+        storage_address,
+    )?;
     let entry_point = CallEntryPoint {
         class_hash: None,
         code_address: Some(storage_address),
@@ -312,15 +336,18 @@ pub fn deploy(
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<DeployResponse> {
     let deployer_address = syscall_handler.storage_address;
-    let deployer_address_for_calculation = match request.deploy_from_zero {
-        true => ContractAddress::default(),
-        false => deployer_address,
-    };
-    let deployed_contract_address = calculate_contract_address(
+    let deployed_contract_address = calculate_deployed_contract_address(
         request.contract_address_salt,
         request.class_hash,
         &request.constructor_calldata,
-        deployer_address_for_calculation,
+        deployer_address,
+        request.deploy_from_zero,
+    )?;
+    check_execution_policy(
+        &syscall_handler.context.tx_context.block_context.chain_info,
+        ContractAction::Deploy,
+        request.class_hash,
+        deployed_contract_address,
     )?;
 
     let ctor_context = ConstructorContext {
@@ -504,7 +531,6 @@ pub fn get_sequencer_address(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<GetSequencerAddressResponse> {
-    syscall_handler.verify_not_in_validate_mode("get_sequencer_address")?;
     Ok(GetSequencerAddressResponse { address: syscall_handler.get_block_info().sequencer_address })
 }
 
@@ -577,6 +603,12 @@ pub fn library_call(
     vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<LibraryCallResponse> {
+    check_execution_policy(
+        &syscall_handler.context.tx_context.block_context.chain_info,
+        ContractAction::LibraryCall,
+        request.class_hash,
+        syscall_handler.storage_address,
+    )?;
     let call_to_external = true;
     let retdata_segment = execute_library_call(
         syscall_handler,
@@ -598,6 +630,12 @@ pub fn library_call_l1_handler(
     vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<LibraryCallResponse> {
+    check_execution_policy(
+        &syscall_handler.context.tx_context.block_context.chain_info,
+        ContractAction::LibraryCall,
+        request.class_hash,
+        syscall_handler.storage_address,
+    )?;
     let call_to_external = false;
     let retdata_segment = execute_library_call(
         syscall_handler,
@@ -13,12 +13,16 @@ use starknet_api::transaction::{Calldata, TransactionVersion};
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants;
 use crate::context::{BlockContext, TransactionContext};
+use crate::event_log::ExecutionEvent;
 use crate::execution::call_info::CallInfo;
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::errors::{
     ConstructorEntryPointExecutionError, EntryPointExecutionError, PreExecutionError,
 };
 use crate::execution::execution_utils::execute_entry_point_call;
+use crate::execution::profiler::ExecutionProfiler;
+use crate::execution::syscalls::cheatcodes::Cheatcodes;
+use crate::execution::syscalls::hook::SyscallHook;
 use crate::state::state_api::State;
 use crate::transaction::objects::{HasRelatedFeeType, TransactionExecutionResult, TransactionInfo};
 use crate::transaction::transaction_types::TransactionType;
@@ -32,6 +36,9 @@ pub mod test;
 pub const FAULTY_CLASS_HASH: &str =
     "0x1A7820094FEAF82D53F53F214B81292D717E7BB9A92BB2488092CD306F3993F";
 
+/// The interval, in VM steps, at which runs check whether the execution was aborted.
+pub const ABORT_CHECK_INTERVAL_N_STEPS: usize = 1000;
+
 pub type EntryPointExecutionResult<T> = Result<T, EntryPointExecutionError>;
 pub type ConstructorEntryPointExecutionResult<T> = Result<T, ConstructorEntryPointExecutionError>;
 
@@ -42,6 +49,19 @@ pub enum CallType {
     Call = 0,
     Delegate = 1,
 }
+
+/// How calls into a contract that is already executing, up the call stack, are handled. Library
+/// calls run in the context of their caller, and are not considered reentrant.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ReentrancyPolicy {
+    #[default]
+    Allow,
+    /// Allows reentrant calls, and marks them in their `CallInfo`.
+    Flag,
+    /// Fails reentrant calls with a `ReentrantCall` error.
+    Reject,
+}
+
 /// Represents a call to an entry point of a Starknet contract.
 #[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
 pub struct CallEntryPoint {
@@ -62,12 +82,90 @@ pub struct CallEntryPoint {
 }
 
 impl CallEntryPoint {
+    /// An external call to the contract deployed at `contract_address`, from no caller, with the
+    /// initial gas of a transaction under the latest constants (see `with_initial_gas`).
+    pub fn external(
+        contract_address: ContractAddress,
+        entry_point_selector: EntryPointSelector,
+        calldata: Calldata,
+    ) -> Self {
+        Self {
+            class_hash: None,
+            code_address: None,
+            entry_point_type: EntryPointType::External,
+            entry_point_selector,
+            calldata,
+            storage_address: contract_address,
+            caller_address: ContractAddress::default(),
+            call_type: CallType::Call,
+            initial_gas: VersionedConstants::latest_constants().tx_initial_gas(),
+        }
+    }
+
+    /// Like `external`, but calls an L1 handler; the calldata starts with the L1 sender address.
+    pub fn l1_handler(
+        contract_address: ContractAddress,
+        entry_point_selector: EntryPointSelector,
+        calldata: Calldata,
+    ) -> Self {
+        Self {
+            entry_point_type: EntryPointType::L1Handler,
+            ..Self::external(contract_address, entry_point_selector, calldata)
+        }
+    }
+
+    pub fn with_caller(mut self, caller_address: ContractAddress) -> Self {
+        self.caller_address = caller_address;
+        self
+    }
+
+    pub fn with_initial_gas(mut self, initial_gas: u64) -> Self {
+        self.initial_gas = initial_gas;
+        self
+    }
+
+    /// Executes the call; a failed call is an `ExecutionFailed` error.
     pub fn execute(
+        self,
+        state: &mut dyn State,
+        resources: &mut ExecutionResources,
+        context: &mut EntryPointExecutionContext,
+    ) -> EntryPointExecutionResult<CallInfo> {
+        self.execute_call(state, resources, context, false)
+    }
+
+    /// Like `execute`, but returns a failed call as is, for its caller to revert (see
+    /// `VersionedConstants::enable_reverts`).
+    pub fn execute_revertible(
+        self,
+        state: &mut dyn State,
+        resources: &mut ExecutionResources,
+        context: &mut EntryPointExecutionContext,
+    ) -> EntryPointExecutionResult<CallInfo> {
+        self.execute_call(state, resources, context, true)
+    }
+
+    fn execute_call(
         mut self,
         state: &mut dyn State,
         resources: &mut ExecutionResources,
         context: &mut EntryPointExecutionContext,
+        return_failed_call: bool,
     ) -> EntryPointExecutionResult<CallInfo> {
+        if context.execution_aborted() {
+            return Err(EntryPointExecutionError::ExecutionAborted);
+        }
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "entry_point_call",
+            class_hash = tracing::field::Empty,
+            selector = %self.entry_point_selector.0,
+            storage_address = %self.storage_address.0.key(),
+            call_type = ?self.call_type,
+            depth = *context.current_recursion_depth.borrow(),
+            n_steps = tracing::field::Empty,
+        )
+        .entered();
         let tx_context = &context.tx_context;
         let mut decrement_when_dropped = RecursionDepthGuard::new(
             context.current_recursion_depth.clone(),
@@ -96,9 +194,67 @@ impl CallEntryPoint {
         }
         // Add class hash to the call, that will appear in the output (call info).
         self.class_hash = Some(class_hash);
+        #[cfg(feature = "tracing")]
+        span.record("class_hash", tracing::field::display(class_hash));
         let contract_class = state.get_compiled_contract_class(class_hash)?;
 
-        execute_entry_point_call(self, contract_class, state, resources, context)
+        let is_context_switch = self.call_type == CallType::Call;
+        let reentrant =
+            is_context_switch && context.active_call_stack.contains(&self.storage_address);
+        let reentrancy_policy = tx_context.block_context.reentrancy_policy;
+        if reentrant && reentrancy_policy == ReentrancyPolicy::Reject {
+            return Err(PreExecutionError::ReentrantCall(self.storage_address).into());
+        }
+
+        let depth = *context.current_recursion_depth.borrow();
+        let (storage_address, entry_point_selector) =
+            (self.storage_address, self.entry_point_selector);
+        tx_context.block_context.record_event(|| ExecutionEvent::CallEntered {
+            storage_address,
+            class_hash,
+            entry_point_selector,
+            entry_point_type: self.entry_point_type,
+            call_type: self.call_type,
+            depth,
+        });
+        if is_context_switch {
+            context.active_call_stack.push(storage_address);
+        }
+        let execution_result =
+            execute_entry_point_call(self, contract_class, state, resources, context).and_then(
+                |call_info| {
+                    if call_info.execution.failed && !return_failed_call {
+                        return Err(EntryPointExecutionError::ExecutionFailed {
+                            error_data: call_info.execution.retdata.into_vec(),
+                        });
+                    }
+                    Ok(call_info)
+                },
+            );
+        if is_context_switch {
+            context.active_call_stack.pop();
+        }
+        let execution_result = execution_result.map_err(|error| {
+            // The run of an aborted execution fails with the error at which it was stopped.
+            if context.execution_aborted() {
+                EntryPointExecutionError::ExecutionAborted
+            } else {
+                error
+            }
+        });
+        context.tx_context.block_context.record_event(|| ExecutionEvent::CallExited {
+            storage_address,
+            entry_point_selector,
+            depth,
+            n_steps: execution_result.as_ref().ok().map(|call_info| call_info.resources.n_steps),
+            error: execution_result.as_ref().err().map(ToString::to_string),
+        });
+        let mut call_info = execution_result?;
+        call_info.reentrant = reentrant && reentrancy_policy == ReentrancyPolicy::Flag;
+        #[cfg(feature = "tracing")]
+        span.record("n_steps", call_info.resources.n_steps);
+
+        Ok(call_info)
     }
 }
 
@@ -123,9 +279,15 @@ pub struct EntryPointExecutionContext {
     pub n_sent_messages_to_l1: usize,
     // Managed by dedicated guard object.
     current_recursion_depth: Arc<RefCell<usize>>,
+    // The storage addresses of the executing calls, outermost first; library calls are omitted.
+    active_call_stack: Vec<ContractAddress>,
 
     // The execution mode affects the behavior of the hint processor.
     pub execution_mode: ExecutionMode,
+    /// Called around each syscall of Cairo 1 entry points, if set.
+    pub syscall_hook: Option<Arc<dyn SyscallHook>>,
+    /// Testing overrides of the execution environment of Cairo 1 entry points, if set.
+    pub cheatcodes: Option<Arc<Cheatcodes>>,
 }
 
 impl EntryPointExecutionContext {
@@ -141,7 +303,10 @@ impl EntryPointExecutionContext {
             n_sent_messages_to_l1: 0,
             tx_context: tx_context.clone(),
             current_recursion_depth: Default::default(),
+            active_call_stack: Vec::new(),
             execution_mode: mode,
+            syscall_hook: None,
+            cheatcodes: None,
         })
     }
 
@@ -283,6 +448,32 @@ impl EntryPointExecutionContext {
         &self.tx_context.block_context.versioned_constants
     }
 
+    /// Returns the profiler of the block context, if it samples PCs.
+    pub(crate) fn pc_sampling_profiler(&self) -> Option<&ExecutionProfiler> {
+        self.tx_context.block_context.profiler.as_ref().filter(|profiler| profiler.samples_pcs())
+    }
+
+    /// Returns whether the execution was aborted, via the cancellation token of the block context.
+    pub fn execution_aborted(&self) -> bool {
+        self.tx_context.block_context.cancellation_token.is_cancelled()
+    }
+
+    /// Returns whether the VM run should stop: the steps limit was reached, or the execution was
+    /// aborted (checked every `ABORT_CHECK_INTERVAL_N_STEPS` steps).
+    pub fn vm_run_consumed(&self) -> bool {
+        self.vm_run_resources.consumed()
+            || (self
+                .vm_run_resources
+                .get_n_steps()
+                .is_some_and(|n_steps| n_steps % ABORT_CHECK_INTERVAL_N_STEPS == 0)
+                && self.execution_aborted())
+    }
+
+    /// The storage addresses of the executing calls, outermost first; library calls are omitted.
+    pub fn active_call_stack(&self) -> &[ContractAddress] {
+        &self.active_call_stack
+    }
+
     pub fn gas_costs(&self) -> &GasCosts {
         &self.versioned_constants().os_constants.gas_costs
     }
@@ -96,6 +96,9 @@ pub fn execute_entry_point_call(
         program_segment_size,
         bytecode_length,
     )?;
+    if let Some(profiler) = syscall_handler.context.pc_sampling_profiler() {
+        profiler.record_pc_samples(class_hash, &contract_class.program, vm.get_relocated_trace()?);
+    }
 
     let call_info = finalize_execution(
         vm,
@@ -105,11 +108,6 @@ pub fn execute_entry_point_call(
         n_total_args,
         program_extra_data_length,
     )?;
-    if call_info.execution.failed {
-        return Err(EntryPointExecutionError::ExecutionFailed {
-            error_data: call_info.execution.retdata.0,
-        });
-    }
 
     Ok(call_info)
 }
@@ -297,7 +295,7 @@ pub fn run_entry_point(
         hint_processor,
     );
 
-    Ok(result?)
+    result.map_err(EntryPointExecutionError::from_run_error)
 }
 
 pub fn finalize_execution(
@@ -334,8 +332,16 @@ pub fn finalize_execution(
     *syscall_handler.resources += &vm_resources_without_inner_calls;
     let versioned_constants = syscall_handler.context.versioned_constants();
     // Take into account the syscall resources of the current call.
-    *syscall_handler.resources += &versioned_constants
+    let syscall_resources = versioned_constants
         .get_additional_os_syscall_resources(&syscall_handler.syscall_counter)?;
+    *syscall_handler.resources += &syscall_resources;
+    if let Some(profiler) = &syscall_handler.context.tx_context.block_context.profiler {
+        profiler.record_call(
+            &syscall_handler.call,
+            &(&vm_resources_without_inner_calls + &syscall_resources),
+            &syscall_handler.syscall_counter,
+        );
+    }
 
     let full_call_resources = &*syscall_handler.resources - &previous_resources;
     Ok(CallInfo {
@@ -351,6 +357,10 @@ pub fn finalize_execution(
         inner_calls: syscall_handler.inner_calls,
         storage_read_values: syscall_handler.read_values,
         accessed_storage_keys: syscall_handler.accessed_keys,
+        storage_accesses: syscall_handler.storage_accesses,
+        syscall_counter: syscall_handler.syscall_counter,
+        debug_logs: syscall_handler.debug_logs,
+        reentrant: false,
     })
 }
 
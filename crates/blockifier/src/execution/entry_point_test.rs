@@ -1,6 +1,9 @@
 use std::collections::HashSet;
+use std::sync::Arc;
 
+use assert_matches::assert_matches;
 use cairo_vm::serde::deserialize_program::BuiltinName;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use num_bigint::BigInt;
 use pretty_assertions::assert_eq;
 use regex::Regex;
@@ -12,13 +15,16 @@ use starknet_api::transaction::{Calldata, TransactionVersion};
 use starknet_api::{calldata, stark_felt};
 
 use crate::abi::abi_utils::{get_storage_var_address, selector_from_name};
-use crate::context::{BlockContext, ChainInfo};
-use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
+use crate::blockifier::block::BlockInfo;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
+use crate::execution::call_info::{CallExecution, CallInfo, Retdata, StorageAccess};
 use crate::execution::contract_class::ContractClass;
-use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext, ReentrancyPolicy};
+use crate::execution::errors::EntryPointExecutionError;
 use crate::state::cached_state::CachedState;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::fixtures::{reentrant_call, CAIRO1_TEST_CONTRACT};
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{
     create_calldata, trivial_external_entry_point_new, CairoVersion, NonceManager, BALANCE,
@@ -28,6 +34,7 @@ use crate::transaction::constants::{
     EXECUTE_ENTRY_POINT_NAME, VALIDATE_DECLARE_ENTRY_POINT_NAME, VALIDATE_DEPLOY_ENTRY_POINT_NAME,
     VALIDATE_ENTRY_POINT_NAME,
 };
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
 use crate::transaction::test_utils::{
     block_context, create_account_tx_for_validate_test, run_invoke_tx, FaultyAccountTxCreatorArgs,
     INVALID,
@@ -508,7 +515,8 @@ fn test_post_run_validation_security_failure() {
     );
 }
 
-// Tests correct update of the fields: `storage_read_values` and `accessed_storage_keys`.
+// Tests correct update of the fields: `storage_read_values`, `accessed_storage_keys` and
+// `storage_accesses`.
 #[test]
 fn test_storage_related_members() {
     let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
@@ -520,10 +528,15 @@ fn test_storage_related_members() {
         ..trivial_external_entry_point_new(test_contract)
     };
     let actual_call_info = entry_point_call.execute_directly(&mut state).unwrap();
+    let storage_var_key = get_storage_var_address("number_map", &[stark_felt!(1_u8)]);
     assert_eq!(actual_call_info.storage_read_values, vec![stark_felt!(39_u8)]);
+    assert_eq!(actual_call_info.accessed_storage_keys, HashSet::from([storage_var_key]));
     assert_eq!(
-        actual_call_info.accessed_storage_keys,
-        HashSet::from([get_storage_var_address("number_map", &[stark_felt!(1_u8)])])
+        actual_call_info.storage_accesses,
+        vec![
+            StorageAccess::write(storage_var_key, stark_felt!(39_u8)),
+            StorageAccess::read(storage_var_key, stark_felt!(39_u8))
+        ]
     );
 
     // Test raw storage read and write.
@@ -538,6 +551,13 @@ fn test_storage_related_members() {
     let actual_call_info = entry_point_call.execute_directly(&mut state).unwrap();
     assert_eq!(actual_call_info.storage_read_values, vec![value]);
     assert_eq!(actual_call_info.accessed_storage_keys, HashSet::from([storage_key!(key)]));
+    assert_eq!(
+        actual_call_info.storage_accesses,
+        vec![
+            StorageAccess::write(storage_key!(key), value),
+            StorageAccess::read(storage_key!(key), value)
+        ]
+    );
 }
 
 #[test]
@@ -1094,3 +1114,96 @@ Execution failed. Failure reason: 0x496e76616c6964207363656e6172696f ('Invalid s
     // Compare actual trace to the expected trace (sans pc locations).
     assert_eq!(cleaned_actual_error.to_string(), cleaned_expected_error.to_string());
 }
+
+#[test]
+fn test_steps_exhausted() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = ChainInfo::create_for_testing();
+    let mut state = test_state(&chain_info, BALANCE, &[(test_contract, 1)]);
+    let block_context = BlockContext::builder(BlockInfo::create_for_testing())
+        .chain_info(chain_info)
+        .invoke_tx_max_n_steps(100)
+        .build();
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("recurse"),
+        calldata: calldata![stark_felt!(1000_u16)],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let error = entry_point_call
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap_err();
+    assert_matches!(error, EntryPointExecutionError::StepsExhausted { .. });
+    assert!(error.to_string().contains("RunResources has no remaining steps."));
+}
+
+#[rstest]
+fn test_reentrancy_policy(
+    #[values(ReentrancyPolicy::Allow, ReentrancyPolicy::Flag, ReentrancyPolicy::Reject)]
+    reentrancy_policy: ReentrancyPolicy,
+) {
+    let chain_info = ChainInfo::create_for_testing();
+    let mut state = test_state(&chain_info, BALANCE, &[(CAIRO1_TEST_CONTRACT, 1)]);
+    let block_context = BlockContext::builder(BlockInfo::create_for_testing())
+        .chain_info(chain_info)
+        .reentrancy_policy(reentrancy_policy)
+        .build();
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+
+    // The test contract calls itself.
+    let result = reentrant_call("recurse", &[stark_felt!(1_u8)]).execute(
+        &mut state,
+        &mut ExecutionResources::default(),
+        &mut context,
+    );
+    assert!(context.active_call_stack().is_empty());
+    if reentrancy_policy == ReentrancyPolicy::Reject {
+        assert!(format!("{:?}", result.unwrap_err()).contains("ReentrantCall"));
+        return;
+    }
+
+    let call_info = result.unwrap();
+    assert!(!call_info.reentrant);
+    assert_eq!(call_info.inner_calls[0].reentrant, reentrancy_policy == ReentrancyPolicy::Flag);
+}
+
+#[test]
+fn test_call_entry_point_constructors() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let address = test_contract.get_instance_address(0);
+
+    let (key, value) = (stark_felt!(1234_u16), stark_felt!(18_u8));
+    let call = CallEntryPoint::external(
+        address,
+        selector_from_name("test_storage_read_write"),
+        calldata![key, value],
+    );
+    assert_eq!(call.entry_point_type, EntryPointType::External);
+    assert_eq!(call.initial_gas, VersionedConstants::latest_constants().tx_initial_gas());
+    assert_eq!(call.execute_directly(&mut state).unwrap().execution.retdata, retdata![value]);
+
+    let caller_address = FeatureContract::Empty(CairoVersion::Cairo1).get_instance_address(0);
+    let l1_handler_call = CallEntryPoint::l1_handler(
+        address,
+        selector_from_name("l1_handler_set_value"),
+        calldata![],
+    )
+    .with_caller(caller_address)
+    .with_initial_gas(100);
+    assert_eq!(
+        (l1_handler_call.entry_point_type, l1_handler_call.caller_address),
+        (EntryPointType::L1Handler, caller_address)
+    );
+    assert_eq!(l1_handler_call.initial_gas, 100);
+}
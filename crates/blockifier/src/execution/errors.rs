@@ -1,6 +1,6 @@
 use cairo_vm::types::errors::math_errors::MathError;
+use cairo_vm::types::errors::program_errors::ProgramError;
 use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
-use cairo_vm::vm::errors::hint_errors::HintError;
 use cairo_vm::vm::errors::memory_errors::MemoryError;
 use cairo_vm::vm::errors::runner_errors::RunnerError;
 use cairo_vm::vm::errors::trace_errors::TraceError;
@@ -12,12 +12,11 @@ use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
 use thiserror::Error;
 
-use super::deprecated_syscalls::hint_processor::DeprecatedSyscallExecutionError;
-use super::syscalls::hint_processor::SyscallExecutionError;
 use crate::execution::entry_point::ConstructorContext;
+use crate::execution::execution_policy::ContractAction;
 use crate::execution::execution_utils::format_panic_data;
+use crate::execution::stack_trace::{gen_entry_point_execution_error_stack, ErrorStack};
 use crate::state::errors::StateError;
-use crate::transaction::errors::TransactionExecutionError;
 
 // TODO(AlonH, 21/12/2022): Implement Display for all types that appear in errors.
 
@@ -41,6 +40,17 @@ pub enum PreExecutionError {
     NoEntryPointOfTypeFound(EntryPointType),
     #[error(transparent)]
     ProgramError(#[from] cairo_vm::types::errors::program_errors::ProgramError),
+    #[error(
+        "The execution policy prohibits the action {action:?} on contract {contract_address:?} of \
+         class {class_hash}."
+    )]
+    ProhibitedByExecutionPolicy {
+        action: ContractAction,
+        class_hash: ClassHash,
+        contract_address: ContractAddress,
+    },
+    #[error("Reentrant call to contract {0:?} is prohibited.")]
+    ReentrantCall(ContractAddress),
     #[error(transparent)]
     RunnerError(Box<RunnerError>),
     #[error(transparent)]
@@ -81,6 +91,8 @@ impl From<RunnerError> for PostExecutionError {
 pub enum EntryPointExecutionError {
     #[error(transparent)]
     CairoRunError(#[from] CairoRunError),
+    #[error("Execution was aborted.")]
+    ExecutionAborted,
     #[error("Execution failed. Failure reason: {}.", format_panic_data(.error_data))]
     ExecutionFailed { error_data: Vec<StarkFelt> },
     #[error("Internal error: {0}")]
@@ -95,10 +107,34 @@ pub enum EntryPointExecutionError {
     RecursionDepthExceeded,
     #[error(transparent)]
     StateError(#[from] StateError),
+    // The steps limit is shared by all the calls of the transaction.
+    #[error("Execution ran out of steps: {error}")]
+    StepsExhausted {
+        #[source]
+        error: CairoRunError,
+    },
     #[error(transparent)]
     TraceError(#[from] TraceError),
 }
 
+impl EntryPointExecutionError {
+    /// Converts the error of a VM run, distinguishing runs stopped due to the steps limit.
+    pub fn from_run_error(error: CairoRunError) -> Self {
+        match error {
+            CairoRunError::VmException(VmException {
+                inner_exc: VirtualMachineError::UnfinishedExecution,
+                ..
+            }) => Self::StepsExhausted { error },
+            _ => Self::CairoRunError(error),
+        }
+    }
+
+    /// Returns the structured trace of the error, starting at the failed entry point.
+    pub fn error_stack(&self) -> ErrorStack {
+        gen_entry_point_execution_error_stack(self, 0)
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ConstructorEntryPointExecutionError {
     #[error(
@@ -139,257 +175,8 @@ pub enum ContractClassError {
         contract_class_version: u8,
         sierra_program_length: usize,
     },
-}
-
-// A set of functions used to extract error trace from a recursive error object.
-
-pub const TRACE_LENGTH_CAP: usize = 15000;
-pub const TRACE_EXTRA_CHARS_SLACK: usize = 100;
-
-/// Extracts the error trace from a `TransactionExecutionError`. This is a top level function.
-pub fn gen_transaction_execution_error_trace(error: &TransactionExecutionError) -> String {
-    let mut error_stack: Vec<String> = Vec::new();
-
-    match error {
-        TransactionExecutionError::ExecutionError {
-            error,
-            class_hash,
-            storage_address,
-            selector,
-        }
-        | TransactionExecutionError::ValidateTransactionError {
-            error,
-            class_hash,
-            storage_address,
-            selector,
-        }
-        | TransactionExecutionError::ContractConstructorExecutionFailed(
-            ConstructorEntryPointExecutionError::ExecutionError {
-                error,
-                class_hash,
-                contract_address: storage_address,
-                // TODO(Dori, 5/5/2024): Also handle the no-selector case.
-                constructor_selector: Some(selector),
-            },
-        ) => {
-            let depth: usize = 0;
-            error_stack.push(format!(
-                "{}: Error in the called contract (contract address: {}, class hash: {}, \
-                 selector: {}):",
-                depth,
-                *storage_address.0.key(),
-                class_hash,
-                selector.0
-            ));
-            extract_entry_point_execution_error_into_stack_trace(
-                &mut error_stack,
-                depth + 1,
-                error,
-            );
-        }
-        _ => {
-            error_stack.push(error.to_string());
-        }
-    }
-
-    let error_stack_str = error_stack.join("\n");
-
-    // When the trace string is too long, trim it in a way that keeps both the beginning and end.
-    if error_stack_str.len() > TRACE_LENGTH_CAP + TRACE_EXTRA_CHARS_SLACK {
-        error_stack_str[..(TRACE_LENGTH_CAP / 2)].to_string()
-            + "\n\n...\n\n"
-            + &error_stack_str[(error_stack_str.len() - TRACE_LENGTH_CAP / 2)..]
-    } else {
-        error_stack_str
-    }
-}
-
-fn extract_cairo_run_error_into_stack_trace(
-    error_stack: &mut Vec<String>,
-    depth: usize,
-    error: &CairoRunError,
-) {
-    if let CairoRunError::VmException(vm_exception) = error {
-        return extract_vm_exception_into_stack_trace(error_stack, depth, vm_exception);
-    }
-    error_stack.push(error.to_string());
-}
-
-fn extract_vm_exception_into_stack_trace(
-    error_stack: &mut Vec<String>,
-    depth: usize,
-    vm_exception: &VmException,
-) {
-    let vm_exception_preamble = format!("Error at pc=0:{}:", vm_exception.pc);
-    error_stack.push(vm_exception_preamble);
-
-    if let Some(traceback) = &vm_exception.traceback {
-        error_stack.push(traceback.to_string());
-    }
-    extract_virtual_machine_error_into_stack_trace(error_stack, depth, &vm_exception.inner_exc)
-}
-
-fn extract_virtual_machine_error_into_stack_trace(
-    error_stack: &mut Vec<String>,
-    depth: usize,
-    vm_error: &VirtualMachineError,
-) {
-    match vm_error {
-        VirtualMachineError::Hint(ref boxed_hint_error) => {
-            if let HintError::Internal(internal_vm_error) = &boxed_hint_error.1 {
-                return extract_virtual_machine_error_into_stack_trace(
-                    error_stack,
-                    depth,
-                    internal_vm_error,
-                );
-            }
-            error_stack.push(boxed_hint_error.1.to_string());
-        }
-        VirtualMachineError::Other(anyhow_error) => {
-            let syscall_exec_err = anyhow_error.downcast_ref::<SyscallExecutionError>();
-            if let Some(downcast_anyhow) = syscall_exec_err {
-                extract_syscall_execution_error_into_stack_trace(
-                    error_stack,
-                    depth,
-                    downcast_anyhow,
-                )
-            } else {
-                let deprecated_syscall_exec_err =
-                    anyhow_error.downcast_ref::<DeprecatedSyscallExecutionError>();
-                if let Some(downcast_anyhow) = deprecated_syscall_exec_err {
-                    extract_deprecated_syscall_execution_error_into_stack_trace(
-                        error_stack,
-                        depth,
-                        downcast_anyhow,
-                    )
-                }
-            }
-        }
-        _ => {
-            error_stack.push(format!("{}\n", vm_error));
-        }
-    }
-}
-
-fn extract_syscall_execution_error_into_stack_trace(
-    error_stack: &mut Vec<String>,
-    depth: usize,
-    syscall_error: &SyscallExecutionError,
-) {
-    match syscall_error {
-        SyscallExecutionError::CallContractExecutionError {
-            class_hash,
-            storage_address,
-            selector,
-            error,
-        } => {
-            let call_contract_preamble = format!(
-                "{}: Error in the called contract (contract address: {}, class hash: {}, \
-                 selector: {}):",
-                depth,
-                storage_address.0.key(),
-                class_hash,
-                selector.0
-            );
-            error_stack.push(call_contract_preamble);
-            extract_syscall_execution_error_into_stack_trace(error_stack, depth + 1, error)
-        }
-        SyscallExecutionError::LibraryCallExecutionError {
-            class_hash,
-            storage_address,
-            selector,
-            error,
-        } => {
-            let libcall_preamble = format!(
-                "{}: Error in a library call (contract address: {}, class hash: {}, selector: {}):",
-                depth,
-                storage_address.0.key(),
-                class_hash,
-                selector.0
-            );
-            error_stack.push(libcall_preamble);
-            extract_syscall_execution_error_into_stack_trace(error_stack, depth + 1, error);
-        }
-        SyscallExecutionError::EntryPointExecutionError(entry_point_error) => {
-            extract_entry_point_execution_error_into_stack_trace(
-                error_stack,
-                depth,
-                entry_point_error,
-            )
-        }
-        _ => {
-            error_stack.push(syscall_error.to_string());
-        }
-    }
-}
-
-fn extract_deprecated_syscall_execution_error_into_stack_trace(
-    error_stack: &mut Vec<String>,
-    depth: usize,
-    syscall_error: &DeprecatedSyscallExecutionError,
-) {
-    match syscall_error {
-        DeprecatedSyscallExecutionError::CallContractExecutionError {
-            class_hash,
-            storage_address,
-            selector,
-            error,
-        } => {
-            let call_contract_preamble = format!(
-                "{}: Error in the called contract (contract address: {}, class hash: {}, \
-                 selector: {}):",
-                depth,
-                storage_address.0.key(),
-                class_hash,
-                selector.0
-            );
-            error_stack.push(call_contract_preamble);
-            extract_deprecated_syscall_execution_error_into_stack_trace(
-                error_stack,
-                depth + 1,
-                error,
-            )
-        }
-        DeprecatedSyscallExecutionError::LibraryCallExecutionError {
-            class_hash,
-            storage_address,
-            selector,
-            error,
-        } => {
-            let libcall_preamble = format!(
-                "{}: Error in a library call (contract address: {}, class hash: {}, selector: {}):",
-                depth,
-                storage_address.0.key(),
-                class_hash,
-                selector.0
-            );
-            error_stack.push(libcall_preamble);
-            extract_deprecated_syscall_execution_error_into_stack_trace(
-                error_stack,
-                depth + 1,
-                error,
-            )
-        }
-        DeprecatedSyscallExecutionError::EntryPointExecutionError(entry_point_error) => {
-            extract_entry_point_execution_error_into_stack_trace(
-                error_stack,
-                depth,
-                entry_point_error,
-            )
-        }
-        _ => error_stack.push(syscall_error.to_string()),
-    }
-}
-
-fn extract_entry_point_execution_error_into_stack_trace(
-    error_stack: &mut Vec<String>,
-    depth: usize,
-    entry_point_error: &EntryPointExecutionError,
-) {
-    match entry_point_error {
-        EntryPointExecutionError::CairoRunError(cairo_run_error) => {
-            extract_cairo_run_error_into_stack_trace(error_stack, depth, cairo_run_error)
-        }
-        _ => error_stack.push(format!("{}\n", entry_point_error)),
-    }
+    #[error("Contract class exceeds the maximal allowed size of {max_size_in_bytes} bytes.")]
+    ContractClassTooLarge { max_size_in_bytes: usize },
+    #[error(transparent)]
+    ProgramError(#[from] ProgramError),
 }
@@ -0,0 +1,60 @@
+use std::fmt::Debug;
+
+use starknet_api::core::{ClassHash, ContractAddress};
+
+use crate::context::ChainInfo;
+use crate::execution::entry_point::EntryPointExecutionResult;
+use crate::execution::errors::PreExecutionError;
+
+#[cfg(test)]
+#[path = "execution_policy_test.rs"]
+pub mod test;
+
+/// An action of a contract on another contract, subject to the execution policy of the chain.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ContractAction {
+    /// A `call_contract` syscall.
+    Call,
+    /// A `deploy` syscall.
+    Deploy,
+    /// A `library_call` (or `library_call_l1_handler`) syscall; the contract is the caller, in
+    /// whose context the class runs.
+    LibraryCall,
+}
+
+/// Restricts which classes may be called, deployed or run by library calls; e.g., on permissioned
+/// appchains, which can provide an implementation in their chain info. A prohibited syscall fails
+/// the call, and thus reverts the transaction.
+pub trait ExecutionPolicy: Debug + Send + Sync {
+    /// Returns whether the action may be taken with the given class on the contract at the given
+    /// address (see `ContractAction`).
+    fn is_allowed(
+        &self,
+        action: ContractAction,
+        class_hash: ClassHash,
+        contract_address: ContractAddress,
+    ) -> bool;
+}
+
+/// Fails if the execution policy of the chain, if set, prohibits the action.
+pub(crate) fn check_execution_policy(
+    chain_info: &ChainInfo,
+    action: ContractAction,
+    class_hash: ClassHash,
+    contract_address: ContractAddress,
+) -> EntryPointExecutionResult<()> {
+    let is_allowed = chain_info
+        .execution_policy
+        .as_ref()
+        .map_or(true, |policy| policy.is_allowed(action, class_hash, contract_address));
+    if !is_allowed {
+        return Err(PreExecutionError::ProhibitedByExecutionPolicy {
+            action,
+            class_hash,
+            contract_address,
+        }
+        .into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use rstest::rstest;
+use starknet_api::core::{ClassHash, ContractAddress};
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::blockifier::block::BlockInfo;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
+};
+use crate::execution::execution_policy::{ContractAction, ExecutionPolicy};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::fixtures::{deploy_call, reentrant_call, CAIRO1_TEST_CONTRACT};
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
+
+const CAIRO0_TEST_CONTRACT: FeatureContract = FeatureContract::TestContract(CairoVersion::Cairo0);
+const EMPTY_CONTRACT: FeatureContract = FeatureContract::Empty(CairoVersion::Cairo1);
+
+/// Prohibits a single action on the instances of a single class.
+#[derive(Clone, Copy, Debug)]
+struct ProhibitedAction(ContractAction, ClassHash);
+
+impl ExecutionPolicy for ProhibitedAction {
+    fn is_allowed(
+        &self,
+        action: ContractAction,
+        class_hash: ClassHash,
+        _contract_address: ContractAddress,
+    ) -> bool {
+        (action, class_hash) != (self.0, self.1)
+    }
+}
+
+fn execute_with_policy(
+    call: CallEntryPoint,
+    policy: ProhibitedAction,
+) -> EntryPointExecutionResult<CallInfo> {
+    let chain_info =
+        ChainInfo { execution_policy: Some(Arc::new(policy)), ..ChainInfo::create_for_testing() };
+    let contracts = [(CAIRO1_TEST_CONTRACT, 1), (CAIRO0_TEST_CONTRACT, 1), (EMPTY_CONTRACT, 0)];
+    let mut state = test_state(&chain_info, BALANCE, &contracts);
+    let block_context =
+        BlockContext::builder(BlockInfo::create_for_testing()).chain_info(chain_info).build();
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+    call.execute(&mut state, &mut ExecutionResources::default(), &mut context)
+}
+
+#[test]
+fn test_execution_policy() {
+    // The test contract calls itself, or deploys an empty contract.
+    let call = || reentrant_call("recurse", &[stark_felt!(1_u8)]);
+    let deploy = || deploy_call(EMPTY_CONTRACT, &[]);
+    let prohibit_calls =
+        ProhibitedAction(ContractAction::Call, CAIRO1_TEST_CONTRACT.get_class_hash());
+    let prohibit_deploys =
+        ProhibitedAction(ContractAction::Deploy, EMPTY_CONTRACT.get_class_hash());
+
+    // Allowed actions are executed.
+    execute_with_policy(call(), prohibit_deploys).unwrap();
+    execute_with_policy(deploy(), prohibit_calls).unwrap();
+
+    // Prohibited actions fail the call.
+    for (call, policy) in [(call(), prohibit_calls), (deploy(), prohibit_deploys)] {
+        let error = execute_with_policy(call, policy).unwrap_err();
+        assert!(error.to_string().contains("The execution policy prohibits"));
+    }
+}
+
+#[rstest]
+fn test_execution_policy_library_call(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    // The test contract runs its own class, with `library_call`.
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let class_hash = test_contract.get_class_hash();
+    let library_call = || CallEntryPoint {
+        entry_point_selector: selector_from_name("test_library_call"),
+        calldata: calldata![
+            class_hash.0,
+            selector_from_name("test_storage_read_write").0,
+            stark_felt!(2_u8),
+            stark_felt!(1234_u16),
+            stark_felt!(91_u8)
+        ],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+
+    // Library calls are checked by the class they run, not as calls.
+    execute_with_policy(library_call(), ProhibitedAction(ContractAction::Call, class_hash))
+        .unwrap();
+    let error = execute_with_policy(
+        library_call(),
+        ProhibitedAction(ContractAction::LibraryCall, class_hash),
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("The execution policy prohibits the action LibraryCall"));
+}
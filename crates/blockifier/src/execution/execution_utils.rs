@@ -89,7 +89,7 @@ pub fn read_execution_retdata(
         }
     };
 
-    Ok(Retdata(felt_range_from_ptr(vm, Relocatable::try_from(retdata_ptr)?, retdata_size)?))
+    Ok(felt_range_from_ptr(vm, Relocatable::try_from(retdata_ptr)?, retdata_size)?)
 }
 
 pub fn stark_felt_from_ptr(
@@ -117,11 +117,12 @@ pub fn write_u256(
     write_felt(vm, ptr, Felt252::from(value >> 128))
 }
 
-pub fn felt_range_from_ptr(
+/// Reads `size` felts starting at `ptr` into any collection of `StarkFelt`s (e.g., `Retdata`).
+pub fn felt_range_from_ptr<C: FromIterator<StarkFelt>>(
     vm: &VirtualMachine,
     ptr: Relocatable,
     size: usize,
-) -> Result<Vec<StarkFelt>, VirtualMachineError> {
+) -> Result<C, VirtualMachineError> {
     let values = vm.get_integer_range(ptr, size)?;
     // Extract values as `StarkFelt`.
     let values = values.into_iter().map(|felt| felt_to_stark_felt(felt.as_ref())).collect();
@@ -287,6 +288,8 @@ pub fn max_fee_for_execution_info(tx_info: &TransactionInfo) -> Felt252 {
     .into()
 }
 
+/// Decodes Cairo 1 panic data into a readable message; short strings (e.g., error codes such as
+/// 'ENTRYPOINT_NOT_FOUND') and `ByteArray`s are decoded, other felts are kept in hex.
 pub fn format_panic_data(felts: &[StarkFelt]) -> String {
     let mut felts = felts.iter().map(|felt| stark_felt_to_felt(*felt));
     let mut items = Vec::new();
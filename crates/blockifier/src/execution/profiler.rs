@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use cairo_vm::types::program::Program;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use cairo_vm::vm::trace::trace_entry::RelocatedTraceEntry;
+use starknet_api::core::{ClassHash, EntryPointSelector};
+
+use crate::execution::entry_point::CallEntryPoint;
+use crate::execution::syscalls::hint_processor::SyscallCounter;
+
+#[cfg(test)]
+#[path = "profiler_test.rs"]
+pub mod test;
+
+pub type EntryPointKey = (ClassHash, EntryPointSelector);
+
+/// The aggregated cost of the calls to an entry point, excluding their inner calls.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct EntryPointProfile {
+    pub n_calls: usize,
+    /// VM resources, including the OS resources of the syscalls.
+    pub resources: ExecutionResources,
+    pub syscall_counter: SyscallCounter,
+}
+
+/// The sampled VM steps of a class.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PcSamples {
+    /// The number of samples per PC (an offset in the class bytecode).
+    pub pc_histogram: HashMap<usize, usize>,
+    /// The number of samples per Cairo call stack: function names, outermost first, separated by
+    /// `;`. Functions without debug info (e.g., in CASM) are named by their PC: `pc_<PC>`.
+    pub stack_histogram: HashMap<String, usize>,
+}
+
+/// Aggregates the cost of the executed calls per entry point, across the executions using the
+/// block context it is set on (e.g., a block); clones share the aggregate.
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionProfiler {
+    profiles: Arc<Mutex<HashMap<EntryPointKey, EntryPointProfile>>>,
+    pc_sampling_interval: Option<NonZeroUsize>,
+    pc_samples: Arc<Mutex<HashMap<ClassHash, PcSamples>>>,
+}
+
+impl ExecutionProfiler {
+    /// Creates a profiler that also samples the PC every `sampling_interval` VM steps of each call.
+    /// Sampling requires tracing the VM runs, which slows down the execution.
+    pub fn with_pc_sampling(sampling_interval: NonZeroUsize) -> Self {
+        Self { pc_sampling_interval: Some(sampling_interval), ..Default::default() }
+    }
+
+    pub fn samples_pcs(&self) -> bool {
+        self.pc_sampling_interval.is_some()
+    }
+
+    /// Records a finished call, given its resources and syscalls, excluding inner calls.
+    pub(crate) fn record_call(
+        &self,
+        call: &CallEntryPoint,
+        resources: &ExecutionResources,
+        syscall_counter: &SyscallCounter,
+    ) {
+        let class_hash = call.class_hash.expect("Class hash must be set after execution.");
+        let mut profiles = lock(&self.profiles);
+        let profile = profiles.entry((class_hash, call.entry_point_selector)).or_default();
+        profile.n_calls += 1;
+        profile.resources = (&profile.resources + resources).filter_unused_builtins();
+        for (selector, count) in syscall_counter {
+            *profile.syscall_counter.entry(*selector).or_default() += count;
+        }
+    }
+
+    /// Samples the trace of a call to the given program, relocated with the program segment at
+    /// address 1. Call stacks are reconstructed from the frame pointers in the trace.
+    pub(crate) fn record_pc_samples(
+        &self,
+        class_hash: ClassHash,
+        program: &Program,
+        trace: &[RelocatedTraceEntry],
+    ) {
+        let Some(sampling_interval) = self.pc_sampling_interval else {
+            return;
+        };
+        let function_names: BTreeMap<usize, &str> = program
+            .iter_identifiers()
+            .filter(|(_, identifier)| identifier.type_.as_deref() == Some("function"))
+            .filter_map(|(name, identifier)| Some((identifier.pc?, name)))
+            .collect();
+        let function_name = |pc: usize| match function_names.range(..=pc).next_back() {
+            Some((_, name)) => name.to_string(),
+            None => format!("pc_{pc}"),
+        };
+
+        let mut pc_samples = lock(&self.pc_samples);
+        let class_samples = pc_samples.entry(class_hash).or_default();
+        // The active frames, outermost first, by frame pointer.
+        let mut frames: Vec<(usize, String)> = vec![];
+        for (step, trace_entry) in trace.iter().enumerate() {
+            let pc = trace_entry.pc.saturating_sub(1);
+            match frames.iter().rposition(|(fp, _)| *fp == trace_entry.fp) {
+                // Same frame, or a return to an outer frame.
+                Some(frame_index) => frames.truncate(frame_index + 1),
+                // A new frame; the first step of a frame is at its function entry.
+                None => frames.push((trace_entry.fp, function_name(pc))),
+            }
+
+            if step % sampling_interval.get() != 0 {
+                continue;
+            }
+            *class_samples.pc_histogram.entry(pc).or_default() += 1;
+            let stack = frames.iter().map(|(_, name)| name.as_str()).collect::<Vec<_>>().join(";");
+            *class_samples.stack_histogram.entry(stack).or_default() += 1;
+        }
+    }
+
+    /// Returns the profiles, sorted by cost (VM steps), highest first.
+    pub fn report(&self) -> Vec<(EntryPointKey, EntryPointProfile)> {
+        let mut report: Vec<_> =
+            lock(&self.profiles).iter().map(|(key, profile)| (*key, profile.clone())).collect();
+        report.sort_by(|(key, profile), (other_key, other_profile)| {
+            other_profile.resources.n_steps.cmp(&profile.resources.n_steps).then(key.cmp(other_key))
+        });
+        report
+    }
+
+    pub fn pc_samples(&self) -> HashMap<ClassHash, PcSamples> {
+        lock(&self.pc_samples).clone()
+    }
+
+    /// Writes the sampled call stacks in the collapsed-stack format of flamegraph tools: a
+    /// `<class hash>;<function>;...;<function> <n_samples>` line per stack, sorted.
+    pub fn write_collapsed_stacks(&self, writer: &mut impl Write) -> io::Result<()> {
+        let mut lines: Vec<_> = lock(&self.pc_samples)
+            .iter()
+            .flat_map(|(class_hash, class_samples)| {
+                class_samples
+                    .stack_histogram
+                    .iter()
+                    .map(move |(stack, n_samples)| format!("{};{stack} {n_samples}", class_hash.0))
+            })
+            .collect();
+        lines.sort();
+        lines.iter().try_for_each(|line| writeln!(writer, "{line}"))
+    }
+
+    pub fn clear(&self) {
+        lock(&self.profiles).clear();
+        lock(&self.pc_samples).clear();
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().expect("Execution profiler is poisoned.")
+}
@@ -0,0 +1,113 @@
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::hash::StarkFelt;
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, stark_felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
+use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
+use crate::execution::profiler::{EntryPointProfile, ExecutionProfiler};
+use crate::execution::syscalls::SyscallSelector;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
+
+#[rstest]
+fn test_execution_profiler(
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+
+    let profiler = ExecutionProfiler::default();
+    let block_context =
+        BlockContext { profiler: Some(profiler.clone()), ..BlockContext::create_for_testing() };
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+
+    // Execute the same entry point twice; the profile aggregates both calls.
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let call_info = entry_point_call
+        .clone()
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap();
+    entry_point_call
+        .clone()
+        .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+        .unwrap();
+
+    let expected_profile = EntryPointProfile {
+        n_calls: 2,
+        resources: &call_info.resources + &call_info.resources,
+        syscall_counter: [(SyscallSelector::StorageRead, 2), (SyscallSelector::StorageWrite, 2)]
+            .into(),
+    };
+    assert_eq!(
+        profiler.report(),
+        vec![(
+            (test_contract.get_class_hash(), entry_point_call.entry_point_selector),
+            expected_profile
+        )]
+    );
+
+    profiler.clear();
+    assert_eq!(profiler.report(), vec![]);
+}
+
+#[test]
+fn test_pc_sampling() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+
+    let profiler = ExecutionProfiler::with_pc_sampling(NonZeroUsize::new(1).unwrap());
+    let block_context =
+        BlockContext { profiler: Some(profiler.clone()), ..BlockContext::create_for_testing() };
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    entry_point_call.execute(&mut state, &mut ExecutionResources::default(), &mut context).unwrap();
+
+    // Every step is sampled, within the external function wrapper.
+    let class_hash = test_contract.get_class_hash();
+    let pc_samples = profiler.pc_samples().remove(&class_hash).unwrap();
+    let n_samples: usize = pc_samples.pc_histogram.values().sum();
+    assert_eq!(pc_samples.stack_histogram.values().sum::<usize>(), n_samples);
+    assert!(
+        pc_samples
+            .stack_histogram
+            .keys()
+            .all(|stack| stack.starts_with("__wrappers__.test_storage_read_write"))
+    );
+    assert!(pc_samples.stack_histogram.keys().any(|stack| {
+        stack.starts_with("__wrappers__.test_storage_read_write;__main__.test_storage_read_write")
+    }));
+
+    let mut collapsed_stacks = vec![];
+    profiler.write_collapsed_stacks(&mut collapsed_stacks).unwrap();
+    let collapsed_stacks = String::from_utf8(collapsed_stacks).unwrap();
+    assert_eq!(collapsed_stacks.lines().count(), pc_samples.stack_histogram.len());
+    assert!(collapsed_stacks.lines().all(|line| line.starts_with(&format!("{};", class_hash.0))));
+}
@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use parity_scale_codec::{Compact, Decode, Encode, EncodeAsRef, Error, Input, Output};
+use scale_info::build::Fields;
+use scale_info::{Path, Type, TypeInfo};
+use serde::{Deserialize, Serialize};
+
+use crate::execution::call_info::ExecutionResourcesDef;
+
+#[cfg(test)]
+#[path = "scale_codec_test.rs"]
+pub mod test;
+
+type BuiltinCounters = Vec<(String, Compact<u64>)>;
+
+/// SCALE codec for `ExecutionResources` (e.g., of `CallInfo::resources`), which is defined in
+/// cairo-vm. Counters are encoded as compact integers, and the builtin counters as a list of
+/// pairs sorted by builtin name, so that the encoding is deterministic.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EncodableExecutionResources(
+    #[serde(with = "ExecutionResourcesDef")] pub ExecutionResources,
+);
+
+impl From<ExecutionResources> for EncodableExecutionResources {
+    fn from(resources: ExecutionResources) -> Self {
+        Self(resources)
+    }
+}
+
+impl From<EncodableExecutionResources> for ExecutionResources {
+    fn from(resources: EncodableExecutionResources) -> Self {
+        resources.0
+    }
+}
+
+fn encodable_counter(counter: usize) -> Compact<u64> {
+    Compact(u64::try_from(counter).expect("Counters fit in 64 bits."))
+}
+
+fn decoded_counter(counter: Compact<u64>) -> Result<usize, Error> {
+    usize::try_from(counter.0).map_err(|_| "Counter does not fit in usize.".into())
+}
+
+fn encode_execution_resources<T: Output + ?Sized>(resources: &ExecutionResources, dest: &mut T) {
+    let ExecutionResources { n_steps, n_memory_holes, builtin_instance_counter } = resources;
+    encodable_counter(*n_steps).encode_to(dest);
+    encodable_counter(*n_memory_holes).encode_to(dest);
+    let mut builtin_counters: BuiltinCounters = builtin_instance_counter
+        .iter()
+        .map(|(builtin_name, &counter)| (builtin_name.clone(), encodable_counter(counter)))
+        .collect();
+    builtin_counters.sort_unstable_by(|(name, _), (other_name, _)| name.cmp(other_name));
+    builtin_counters.encode_to(dest);
+}
+
+impl Encode for EncodableExecutionResources {
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        encode_execution_resources(&self.0, dest);
+    }
+}
+
+/// Encodes a borrowed `ExecutionResources` as `EncodableExecutionResources`, without cloning it.
+pub struct ExecutionResourcesRef<'a>(&'a ExecutionResources);
+
+impl<'a> From<&'a ExecutionResources> for ExecutionResourcesRef<'a> {
+    fn from(resources: &'a ExecutionResources) -> Self {
+        Self(resources)
+    }
+}
+
+impl Encode for ExecutionResourcesRef<'_> {
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        encode_execution_resources(self.0, dest);
+    }
+}
+
+// Allows `#[codec(encoded_as = "EncodableExecutionResources")]` on `ExecutionResources` fields
+// (e.g., a copy of `CallInfo::resources`) of types deriving `Encode` and `Decode`.
+impl<'a> EncodeAsRef<'a, ExecutionResources> for EncodableExecutionResources {
+    type RefType = ExecutionResourcesRef<'a>;
+}
+
+impl Decode for EncodableExecutionResources {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+        let n_steps = decoded_counter(Compact::<u64>::decode(input)?)?;
+        let n_memory_holes = decoded_counter(Compact::<u64>::decode(input)?)?;
+        let builtin_instance_counter = BuiltinCounters::decode(input)?
+            .into_iter()
+            .map(|(builtin_name, counter)| Ok((builtin_name, decoded_counter(counter)?)))
+            .collect::<Result<HashMap<_, _>, Error>>()?;
+
+        Ok(Self(ExecutionResources { n_steps, n_memory_holes, builtin_instance_counter }))
+    }
+}
+
+impl TypeInfo for EncodableExecutionResources {
+    type Identity = Self;
+
+    fn type_info() -> Type {
+        Type::builder().path(Path::new("ExecutionResources", module_path!())).composite(
+            Fields::named()
+                .field(|field| field.compact::<u64>().name("n_steps").type_name("usize"))
+                .field(|field| field.compact::<u64>().name("n_memory_holes").type_name("usize"))
+                .field(|field| {
+                    field
+                        .ty::<BuiltinCounters>()
+                        .name("builtin_instance_counter")
+                        .type_name("HashMap<String, usize>")
+                }),
+        )
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use cairo_vm::vm::runners::builtin_runner::{HASH_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::{TypeDef, TypeInfo};
+
+use crate::execution::call_info::CallInfo;
+use crate::execution::scale_codec::EncodableExecutionResources;
+use crate::test_utils::golden::assert_scale_golden;
+
+fn resources(builtin_instance_counter: HashMap<String, usize>) -> EncodableExecutionResources {
+    ExecutionResources { n_steps: 1000, n_memory_holes: 2, builtin_instance_counter }.into()
+}
+
+#[test]
+fn test_encode_decode_execution_resources() {
+    let resources = resources(HashMap::from([
+        (RANGE_CHECK_BUILTIN_NAME.to_string(), 3),
+        (HASH_BUILTIN_NAME.to_string(), 4),
+    ]));
+    let encoded = resources.encode();
+    assert_eq!(EncodableExecutionResources::decode(&mut encoded.as_slice()).unwrap(), resources);
+
+    // The encoding does not depend on the iteration order of the builtin counters.
+    let mut reordered_counters = HashMap::with_capacity(16);
+    reordered_counters.insert(HASH_BUILTIN_NAME.to_string(), 4);
+    reordered_counters.insert(RANGE_CHECK_BUILTIN_NAME.to_string(), 3);
+    assert_eq!(resources(reordered_counters).encode(), encoded);
+}
+
+/// A type holding a copy of `CallInfo::resources`, with a derived codec.
+#[derive(Debug, Decode, Encode, Eq, PartialEq)]
+struct CallResources {
+    #[codec(encoded_as = "EncodableExecutionResources")]
+    resources: ExecutionResources,
+}
+
+#[test]
+fn test_call_info_resources_encoded_as() {
+    let call_info = CallInfo {
+        resources: resources(HashMap::from([(HASH_BUILTIN_NAME.to_string(), 4)])).into(),
+        ..Default::default()
+    };
+    let call_resources = CallResources { resources: call_info.resources.clone() };
+    let encoded = call_resources.encode();
+    assert_eq!(encoded, call_info.encodable_resources().encode());
+    assert_eq!(CallResources::decode(&mut encoded.as_slice()).unwrap(), call_resources);
+}
+
+#[test]
+fn test_execution_resources_type_info() {
+    let TypeDef::Composite(composite) = EncodableExecutionResources::type_info().type_def else {
+        panic!("Expected a composite type.");
+    };
+    let field_names: Vec<_> = composite.fields.iter().map(|field| field.name.unwrap()).collect();
+    assert_eq!(field_names, ["n_steps", "n_memory_holes", "builtin_instance_counter"]);
+}
+
+#[test]
+fn test_execution_resources_scale_golden() {
+    let resources = resources(HashMap::from([
+        (RANGE_CHECK_BUILTIN_NAME.to_string(), 3),
+        (HASH_BUILTIN_NAME.to_string(), 4),
+    ]));
+    assert_scale_golden("execution_resources", &resources);
+}
@@ -0,0 +1,113 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use cached::{Cached, SizedCache};
+use cairo_lang_starknet_classes::casm_contract_class::{
+    CasmContractClass, StarknetSierraCompilationError,
+};
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
+use cairo_lang_starknet_classes::felt252_serde::{sierra_from_felt252s, Felt252SerdeError};
+use cairo_vm::types::errors::program_errors::ProgramError;
+use starknet_api::core::ClassHash;
+use thiserror::Error;
+
+use crate::execution::contract_class::{ClassInfo, ContractClass, ContractClassV1, SierraVersion};
+use crate::execution::errors::ContractClassError;
+
+#[cfg(test)]
+#[path = "sierra_compilation_test.rs"]
+pub mod test;
+
+/// The bytecode size limit used by the Starknet Sierra compiler.
+pub const DEFAULT_MAX_BYTECODE_SIZE: usize = 180000;
+
+#[derive(Debug, Error)]
+pub enum SierraCompilationError {
+    #[error(transparent)]
+    CompilationError(#[from] StarknetSierraCompilationError),
+    #[error(transparent)]
+    ContractClassError(#[from] ContractClassError),
+    #[error(transparent)]
+    ProgramError(#[from] ProgramError),
+    #[error(transparent)]
+    SierraProgramError(#[from] Felt252SerdeError),
+    #[error(transparent)]
+    TryFromIntError(#[from] std::num::TryFromIntError),
+}
+
+pub type SierraCompilationResult<T> = Result<T, SierraCompilationError>;
+
+type CompiledClassLRUCache = SizedCache<ClassHash, ContractClassV1>;
+
+/// Compiles Sierra classes into runnable Cairo 1 classes in-process.
+/// Compiled classes are kept in a thread-safe LRU cache, keyed by the Sierra class hash.
+#[derive(Clone, Debug)]
+pub struct SierraCompiler {
+    cache: Arc<Mutex<CompiledClassLRUCache>>,
+    max_bytecode_size: usize,
+}
+
+impl SierraCompiler {
+    pub fn new(cache_size: usize, max_bytecode_size: usize) -> Self {
+        Self {
+            cache: Arc::new(Mutex::new(CompiledClassLRUCache::with_size(cache_size))),
+            max_bytecode_size,
+        }
+    }
+
+    /// Returns the compiled class of the given Sierra class; compiles it on a cache miss.
+    /// The caller is responsible for the class hash matching the Sierra class.
+    pub fn compile(
+        &self,
+        class_hash: ClassHash,
+        sierra_contract_class: SierraContractClass,
+    ) -> SierraCompilationResult<ContractClass> {
+        if let Some(contract_class) = self.get(&class_hash) {
+            return Ok(contract_class.into());
+        }
+
+        // Compile without holding the lock; compilation may be slow.
+        let casm_contract_class = CasmContractClass::from_contract_class(
+            sierra_contract_class,
+            false,
+            self.max_bytecode_size,
+        )?;
+        let contract_class = ContractClassV1::try_from(casm_contract_class)?;
+        self.lock().cache_set(class_hash, contract_class.clone());
+
+        Ok(contract_class.into())
+    }
+
+    /// Returns the class info of a Declare transaction of the given Sierra class; compiles the
+    /// class on a cache miss. The ABI length is that of the raw ABI string.
+    pub fn compile_class_info(
+        &self,
+        class_hash: ClassHash,
+        sierra_contract_class: SierraContractClass,
+        abi_length: usize,
+    ) -> SierraCompilationResult<ClassInfo> {
+        let sierra_program_length = sierra_contract_class.sierra_program.len();
+        let (sierra_version_id, _compiler_version_id, _program) =
+            sierra_from_felt252s(&sierra_contract_class.sierra_program)?;
+        let sierra_version = SierraVersion(
+            u64::try_from(sierra_version_id.major)?,
+            u64::try_from(sierra_version_id.minor)?,
+            u64::try_from(sierra_version_id.patch)?,
+        );
+
+        let contract_class = self.compile(class_hash, sierra_contract_class)?;
+        let class_info = ClassInfo::new(&contract_class, sierra_program_length, abi_length)?;
+        Ok(class_info.with_sierra_version(sierra_version))
+    }
+
+    pub fn get(&self, class_hash: &ClassHash) -> Option<ContractClassV1> {
+        self.lock().cache_get(class_hash).cloned()
+    }
+
+    pub fn cache_size(&self) -> usize {
+        self.lock().cache_size()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CompiledClassLRUCache> {
+        self.cache.lock().expect("Sierra compilation cache is poisoned.")
+    }
+}
@@ -0,0 +1,99 @@
+use assert_matches::assert_matches;
+use cairo_lang_sierra::program::Program;
+use cairo_lang_starknet_classes::compiler_version::VersionId;
+use cairo_lang_starknet_classes::contract_class::{
+    ContractClass as SierraContractClass, ContractEntryPoints,
+};
+use cairo_lang_starknet_classes::felt252_serde::sierra_to_felt252s;
+use starknet_api::class_hash;
+use starknet_api::hash::StarkHash;
+use starknet_api::transaction::{DeclareTransactionV2, Fee, TransactionHash};
+
+use crate::context::BlockContext;
+use crate::execution::contract_class::SierraVersion;
+use crate::execution::sierra_compilation::{
+    SierraCompilationError, SierraCompiler, DEFAULT_MAX_BYTECODE_SIZE,
+};
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{CairoVersion, BALANCE, MAX_FEE};
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::transactions::{DeclareTransaction, ExecutableTransaction};
+
+/// The Sierra class of the empty Cairo 1 feature contract: a contract without entry points.
+fn empty_sierra_contract_class() -> SierraContractClass {
+    let program = Program {
+        type_declarations: vec![],
+        libfunc_declarations: vec![],
+        statements: vec![],
+        funcs: vec![],
+    };
+    let sierra_version = VersionId { major: 1, minor: 5, patch: 0 };
+    let compiler_version = VersionId { major: 2, minor: 6, patch: 0 };
+
+    SierraContractClass {
+        sierra_program: sierra_to_felt252s(sierra_version, compiler_version, &program).unwrap(),
+        sierra_program_debug_info: None,
+        contract_class_version: "0.1.0".to_string(),
+        entry_points_by_type: ContractEntryPoints::default(),
+        abi: None,
+    }
+}
+
+#[test]
+fn test_invalid_sierra_class_is_not_cached() {
+    let compiler = SierraCompiler::new(1, DEFAULT_MAX_BYTECODE_SIZE);
+    let sierra_contract_class = SierraContractClass {
+        sierra_program: vec![],
+        sierra_program_debug_info: None,
+        contract_class_version: "0.1.0".to_string(),
+        entry_points_by_type: ContractEntryPoints::default(),
+        abi: None,
+    };
+
+    let class_hash = class_hash!("0x1");
+    assert_matches!(
+        compiler.compile(class_hash, sierra_contract_class),
+        Err(SierraCompilationError::CompilationError(_))
+    );
+    assert!(compiler.get(&class_hash).is_none());
+    assert_eq!(compiler.cache_size(), 0);
+}
+
+#[test]
+fn test_declare_sierra_class() {
+    let block_context = BlockContext::create_for_account_testing();
+    let empty_contract = FeatureContract::Empty(CairoVersion::Cairo1);
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let state = &mut test_state(&block_context.chain_info, BALANCE, &[(account, 1)]);
+    let class_hash = empty_contract.get_class_hash();
+    let compiler = SierraCompiler::new(1, DEFAULT_MAX_BYTECODE_SIZE);
+
+    let declare_tx = DeclareTransaction::new_from_sierra(
+        starknet_api::transaction::DeclareTransaction::V2(DeclareTransactionV2 {
+            max_fee: Fee(MAX_FEE),
+            sender_address: account.get_instance_address(0),
+            class_hash,
+            compiled_class_hash: empty_contract.get_compiled_class_hash(),
+            ..Default::default()
+        }),
+        TransactionHash::default(),
+        empty_sierra_contract_class(),
+        0,
+        &compiler,
+        false,
+    )
+    .unwrap();
+    assert_eq!(declare_tx.class_info.sierra_version(), Some(SierraVersion(1, 5, 0)));
+    assert_eq!(compiler.cache_size(), 1);
+
+    let tx_execution_info =
+        AccountTransaction::Declare(declare_tx).execute(state, &block_context, true, true).unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    assert_eq!(state.get_compiled_contract_class(class_hash).unwrap(), empty_contract.get_class());
+    assert_eq!(
+        state.get_compiled_class_hash(class_hash).unwrap(),
+        empty_contract.get_compiled_class_hash()
+    );
+}
@@ -0,0 +1,19 @@
+use std::fmt::Debug;
+
+use starknet_api::hash::StarkFelt;
+
+/// Verifies signatures natively, outside the Cairo VM.
+/// Appchains whose accounts use signature schemes other than the STARK-curve ECDSA (e.g., ed25519
+/// or secp256k1) can provide an implementation in their chain info; contracts then invoke it
+/// through the `verify_signature` syscall, instead of verifying the signature in Cairo.
+pub trait SignatureVerifier: Debug + Send + Sync {
+    /// Returns whether the signature is a valid signature of the message hash by the given public
+    /// key. The scheme-specific encoding of the public key and signature into felts is defined by
+    /// the implementation.
+    fn verify_signature(
+        &self,
+        message_hash: StarkFelt,
+        public_key: &[StarkFelt],
+        signature: &[StarkFelt],
+    ) -> bool;
+}
@@ -0,0 +1,394 @@
+use std::fmt::{Display, Formatter};
+
+use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
+use cairo_vm::vm::errors::hint_errors::HintError;
+use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
+use cairo_vm::vm::errors::vm_exception::VmException;
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
+
+use crate::execution::deprecated_syscalls::hint_processor::DeprecatedSyscallExecutionError;
+use crate::execution::errors::{ConstructorEntryPointExecutionError, EntryPointExecutionError};
+use crate::execution::syscalls::hint_processor::SyscallExecutionError;
+use crate::transaction::errors::TransactionExecutionError;
+
+#[cfg(test)]
+#[path = "stack_trace_test.rs"]
+pub mod test;
+
+pub const TRACE_LENGTH_CAP: usize = 15000;
+pub const TRACE_EXTRA_CHARS_SLACK: usize = 100;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PreambleType {
+    CallContract,
+    LibraryCall,
+}
+
+impl PreambleType {
+    pub fn text(&self) -> &str {
+        match self {
+            Self::CallContract => "Error in the called contract",
+            Self::LibraryCall => "Error in a library call",
+        }
+    }
+}
+
+/// A call in the chain of calls that led to the error.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EntryPointErrorFrame {
+    pub depth: usize,
+    pub preamble_type: PreambleType,
+    pub storage_address: ContractAddress,
+    pub class_hash: ClassHash,
+    pub selector: EntryPointSelector,
+}
+
+impl Display for EntryPointErrorFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} (contract address: {}, class hash: {}, selector: {}):",
+            self.depth,
+            self.preamble_type.text(),
+            self.storage_address.0.key(),
+            self.class_hash,
+            self.selector.0
+        )
+    }
+}
+
+/// The VM location at which an error was raised.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VmExceptionFrame {
+    pub pc: usize,
+    pub traceback: Option<String>,
+}
+
+impl Display for VmExceptionFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error at pc=0:{}:", self.pc)?;
+        if let Some(traceback) = &self.traceback {
+            write!(f, "\n{traceback}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Frame {
+    EntryPoint(EntryPointErrorFrame),
+    Vm(VmExceptionFrame),
+    /// The error itself, or a message without call context.
+    StringFrame(String),
+}
+
+impl Display for Frame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EntryPoint(frame) => frame.fmt(f),
+            Self::Vm(frame) => frame.fmt(f),
+            Self::StringFrame(error) => error.fmt(f),
+        }
+    }
+}
+
+impl From<EntryPointErrorFrame> for Frame {
+    fn from(frame: EntryPointErrorFrame) -> Self {
+        Self::EntryPoint(frame)
+    }
+}
+
+impl From<VmExceptionFrame> for Frame {
+    fn from(frame: VmExceptionFrame) -> Self {
+        Self::Vm(frame)
+    }
+}
+
+impl From<String> for Frame {
+    fn from(error: String) -> Self {
+        Self::StringFrame(error)
+    }
+}
+
+/// The chain of calls and VM locations that led to an execution error, outermost first.
+/// Formats as the familiar error trace; long traces are trimmed, keeping both ends.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ErrorStack {
+    pub stack: Vec<Frame>,
+}
+
+impl ErrorStack {
+    pub fn push(&mut self, frame: impl Into<Frame>) {
+        self.stack.push(frame.into());
+    }
+}
+
+impl Display for ErrorStack {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let error_stack_str =
+            self.stack.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n");
+
+        // When the trace string is too long, trim it in a way that keeps both the beginning and
+        // end.
+        if error_stack_str.len() > TRACE_LENGTH_CAP + TRACE_EXTRA_CHARS_SLACK {
+            write!(
+                f,
+                "{}\n\n...\n\n{}",
+                &error_stack_str[..(TRACE_LENGTH_CAP / 2)],
+                &error_stack_str[(error_stack_str.len() - TRACE_LENGTH_CAP / 2)..]
+            )
+        } else {
+            write!(f, "{error_stack_str}")
+        }
+    }
+}
+
+// A set of functions used to extract error trace from a recursive error object.
+
+/// Extracts the error trace from a `TransactionExecutionError`. This is a top level function.
+pub fn gen_transaction_execution_error_trace(error: &TransactionExecutionError) -> String {
+    gen_transaction_execution_error_stack(error).to_string()
+}
+
+/// Extracts the error stack from a `TransactionExecutionError`.
+pub fn gen_transaction_execution_error_stack(error: &TransactionExecutionError) -> ErrorStack {
+    let mut error_stack = ErrorStack::default();
+
+    match error {
+        TransactionExecutionError::ExecutionError {
+            error,
+            class_hash,
+            storage_address,
+            selector,
+        }
+        | TransactionExecutionError::ValidateTransactionError {
+            error,
+            class_hash,
+            storage_address,
+            selector,
+        }
+        | TransactionExecutionError::ContractConstructorExecutionFailed(
+            ConstructorEntryPointExecutionError::ExecutionError {
+                error,
+                class_hash,
+                contract_address: storage_address,
+                // TODO(Dori, 5/5/2024): Also handle the no-selector case.
+                constructor_selector: Some(selector),
+            },
+        ) => {
+            let depth: usize = 0;
+            error_stack.push(EntryPointErrorFrame {
+                depth,
+                preamble_type: PreambleType::CallContract,
+                storage_address: *storage_address,
+                class_hash: *class_hash,
+                selector: *selector,
+            });
+            extract_entry_point_execution_error_into_stack_trace(
+                &mut error_stack,
+                depth + 1,
+                error,
+            );
+        }
+        _ => {
+            error_stack.push(error.to_string());
+        }
+    }
+
+    error_stack
+}
+
+/// Extracts the error stack from an `EntryPointExecutionError`, with the called entry point at
+/// the given depth.
+pub fn gen_entry_point_execution_error_stack(
+    error: &EntryPointExecutionError,
+    depth: usize,
+) -> ErrorStack {
+    let mut error_stack = ErrorStack::default();
+    extract_entry_point_execution_error_into_stack_trace(&mut error_stack, depth, error);
+    error_stack
+}
+
+fn extract_cairo_run_error_into_stack_trace(
+    error_stack: &mut ErrorStack,
+    depth: usize,
+    error: &CairoRunError,
+) {
+    if let CairoRunError::VmException(vm_exception) = error {
+        return extract_vm_exception_into_stack_trace(error_stack, depth, vm_exception);
+    }
+    error_stack.push(error.to_string());
+}
+
+fn extract_vm_exception_into_stack_trace(
+    error_stack: &mut ErrorStack,
+    depth: usize,
+    vm_exception: &VmException,
+) {
+    error_stack
+        .push(VmExceptionFrame { pc: vm_exception.pc, traceback: vm_exception.traceback.clone() });
+    extract_virtual_machine_error_into_stack_trace(error_stack, depth, &vm_exception.inner_exc)
+}
+
+fn extract_virtual_machine_error_into_stack_trace(
+    error_stack: &mut ErrorStack,
+    depth: usize,
+    vm_error: &VirtualMachineError,
+) {
+    match vm_error {
+        VirtualMachineError::Hint(ref boxed_hint_error) => {
+            if let HintError::Internal(internal_vm_error) = &boxed_hint_error.1 {
+                return extract_virtual_machine_error_into_stack_trace(
+                    error_stack,
+                    depth,
+                    internal_vm_error,
+                );
+            }
+            error_stack.push(boxed_hint_error.1.to_string());
+        }
+        VirtualMachineError::Other(anyhow_error) => {
+            let syscall_exec_err = anyhow_error.downcast_ref::<SyscallExecutionError>();
+            if let Some(downcast_anyhow) = syscall_exec_err {
+                extract_syscall_execution_error_into_stack_trace(
+                    error_stack,
+                    depth,
+                    downcast_anyhow,
+                )
+            } else {
+                let deprecated_syscall_exec_err =
+                    anyhow_error.downcast_ref::<DeprecatedSyscallExecutionError>();
+                if let Some(downcast_anyhow) = deprecated_syscall_exec_err {
+                    extract_deprecated_syscall_execution_error_into_stack_trace(
+                        error_stack,
+                        depth,
+                        downcast_anyhow,
+                    )
+                }
+            }
+        }
+        _ => {
+            error_stack.push(format!("{}\n", vm_error));
+        }
+    }
+}
+
+fn extract_syscall_execution_error_into_stack_trace(
+    error_stack: &mut ErrorStack,
+    depth: usize,
+    syscall_error: &SyscallExecutionError,
+) {
+    match syscall_error {
+        SyscallExecutionError::CallContractExecutionError {
+            class_hash,
+            storage_address,
+            selector,
+            error,
+        } => {
+            error_stack.push(EntryPointErrorFrame {
+                depth,
+                preamble_type: PreambleType::CallContract,
+                storage_address: *storage_address,
+                class_hash: *class_hash,
+                selector: *selector,
+            });
+            extract_syscall_execution_error_into_stack_trace(error_stack, depth + 1, error)
+        }
+        SyscallExecutionError::LibraryCallExecutionError {
+            class_hash,
+            storage_address,
+            selector,
+            error,
+        } => {
+            error_stack.push(EntryPointErrorFrame {
+                depth,
+                preamble_type: PreambleType::LibraryCall,
+                storage_address: *storage_address,
+                class_hash: *class_hash,
+                selector: *selector,
+            });
+            extract_syscall_execution_error_into_stack_trace(error_stack, depth + 1, error);
+        }
+        SyscallExecutionError::EntryPointExecutionError(entry_point_error) => {
+            extract_entry_point_execution_error_into_stack_trace(
+                error_stack,
+                depth,
+                entry_point_error,
+            )
+        }
+        _ => {
+            error_stack.push(syscall_error.to_string());
+        }
+    }
+}
+
+fn extract_deprecated_syscall_execution_error_into_stack_trace(
+    error_stack: &mut ErrorStack,
+    depth: usize,
+    syscall_error: &DeprecatedSyscallExecutionError,
+) {
+    match syscall_error {
+        DeprecatedSyscallExecutionError::CallContractExecutionError {
+            class_hash,
+            storage_address,
+            selector,
+            error,
+        } => {
+            error_stack.push(EntryPointErrorFrame {
+                depth,
+                preamble_type: PreambleType::CallContract,
+                storage_address: *storage_address,
+                class_hash: *class_hash,
+                selector: *selector,
+            });
+            extract_deprecated_syscall_execution_error_into_stack_trace(
+                error_stack,
+                depth + 1,
+                error,
+            )
+        }
+        DeprecatedSyscallExecutionError::LibraryCallExecutionError {
+            class_hash,
+            storage_address,
+            selector,
+            error,
+        } => {
+            error_stack.push(EntryPointErrorFrame {
+                depth,
+                preamble_type: PreambleType::LibraryCall,
+                storage_address: *storage_address,
+                class_hash: *class_hash,
+                selector: *selector,
+            });
+            extract_deprecated_syscall_execution_error_into_stack_trace(
+                error_stack,
+                depth + 1,
+                error,
+            )
+        }
+        DeprecatedSyscallExecutionError::EntryPointExecutionError(entry_point_error) => {
+            extract_entry_point_execution_error_into_stack_trace(
+                error_stack,
+                depth,
+                entry_point_error,
+            )
+        }
+        _ => error_stack.push(syscall_error.to_string()),
+    }
+}
+
+fn extract_entry_point_execution_error_into_stack_trace(
+    error_stack: &mut ErrorStack,
+    depth: usize,
+    entry_point_error: &EntryPointExecutionError,
+) {
+    match entry_point_error {
+        EntryPointExecutionError::CairoRunError(cairo_run_error) => {
+            extract_cairo_run_error_into_stack_trace(error_stack, depth, cairo_run_error)
+        }
+        EntryPointExecutionError::StepsExhausted { error } => {
+            error_stack.push("Execution ran out of steps:".to_string());
+            extract_cairo_run_error_into_stack_trace(error_stack, depth, error)
+        }
+        _ => error_stack.push(format!("{}\n", entry_point_error)),
+    }
+}
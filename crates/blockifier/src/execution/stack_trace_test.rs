@@ -0,0 +1,68 @@
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::{class_hash, contract_address, patricia_key};
+
+use crate::execution::errors::EntryPointExecutionError;
+use crate::execution::stack_trace::{
+    EntryPointErrorFrame, ErrorStack, Frame, PreambleType, VmExceptionFrame, TRACE_LENGTH_CAP,
+};
+
+#[test]
+fn test_error_stack_format() {
+    let storage_address = contract_address!("0x1");
+    let class_hash = class_hash!("0x2");
+    let library_class_hash = class_hash!("0x3");
+    let selector = EntryPointSelector(StarkFelt::from(4_u8));
+    let library_selector = EntryPointSelector(StarkFelt::from(5_u8));
+
+    let mut error_stack = ErrorStack::default();
+    error_stack.push(EntryPointErrorFrame {
+        depth: 0,
+        preamble_type: PreambleType::CallContract,
+        storage_address,
+        class_hash,
+        selector,
+    });
+    error_stack.push(EntryPointErrorFrame {
+        depth: 1,
+        preamble_type: PreambleType::LibraryCall,
+        storage_address,
+        class_hash: library_class_hash,
+        selector: library_selector,
+    });
+    error_stack.push(VmExceptionFrame { pc: 7, traceback: Some("Cairo traceback.".to_string()) });
+    error_stack.push("Error message.".to_string());
+
+    let storage_address_felt = *storage_address.0.key();
+    let expected_trace = format!(
+        "0: Error in the called contract (contract address: {storage_address_felt}, class hash: \
+         {class_hash}, selector: {}):
+1: Error in a library call (contract address: {storage_address_felt}, class hash: \
+         {library_class_hash}, selector: {}):
+Error at pc=0:7:
+Cairo traceback.
+Error message.",
+        selector.0, library_selector.0
+    );
+    assert_eq!(error_stack.to_string(), expected_trace);
+}
+
+#[test]
+fn test_error_stack_trimming() {
+    let mut error_stack = ErrorStack::default();
+    error_stack.push("a".repeat(TRACE_LENGTH_CAP));
+    error_stack.push("b".repeat(TRACE_LENGTH_CAP));
+
+    let trace = error_stack.to_string();
+    let half_cap = TRACE_LENGTH_CAP / 2;
+    assert_eq!(trace, format!("{}\n\n...\n\n{}", "a".repeat(half_cap), "b".repeat(half_cap)));
+}
+
+#[test]
+fn test_entry_point_execution_error_stack() {
+    let error = EntryPointExecutionError::RecursionDepthExceeded;
+    assert_eq!(
+        error.error_stack().stack,
+        vec![Frame::StringFrame("Execution failed due to recursion depth exceeded.\n".to_string())]
+    );
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use starknet_api::block::{BlockNumber, BlockTimestamp};
+use starknet_api::core::{ContractAddress, EntryPointSelector};
+use starknet_api::hash::StarkFelt;
+
+/// Overrides of the execution environment, per contract address, applied by the syscall handler
+/// of Cairo 1 contracts. Meant for contract testing frameworks (similar to Foundry cheatcodes);
+/// must not be used when executing actual blocks.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Cheatcodes {
+    warped_block_timestamps: HashMap<ContractAddress, BlockTimestamp>,
+    rolled_block_numbers: HashMap<ContractAddress, BlockNumber>,
+    pranked_caller_addresses: HashMap<ContractAddress, ContractAddress>,
+    mocked_calls: HashMap<(ContractAddress, EntryPointSelector), Vec<StarkFelt>>,
+}
+
+impl Cheatcodes {
+    /// Sets the block timestamp seen by the given contract.
+    pub fn warp(&mut self, contract_address: ContractAddress, block_timestamp: BlockTimestamp) {
+        self.warped_block_timestamps.insert(contract_address, block_timestamp);
+    }
+
+    pub fn stop_warp(&mut self, contract_address: ContractAddress) {
+        self.warped_block_timestamps.remove(&contract_address);
+    }
+
+    /// Sets the block number seen by the given contract.
+    pub fn roll(&mut self, contract_address: ContractAddress, block_number: BlockNumber) {
+        self.rolled_block_numbers.insert(contract_address, block_number);
+    }
+
+    pub fn stop_roll(&mut self, contract_address: ContractAddress) {
+        self.rolled_block_numbers.remove(&contract_address);
+    }
+
+    /// Sets the caller address seen by the given contract.
+    pub fn prank(&mut self, contract_address: ContractAddress, caller_address: ContractAddress) {
+        self.pranked_caller_addresses.insert(contract_address, caller_address);
+    }
+
+    pub fn stop_prank(&mut self, contract_address: ContractAddress) {
+        self.pranked_caller_addresses.remove(&contract_address);
+    }
+
+    /// Makes `call_contract` syscalls to the given contract and selector return the given retdata,
+    /// without executing the call.
+    pub fn mock_call(
+        &mut self,
+        contract_address: ContractAddress,
+        entry_point_selector: EntryPointSelector,
+        retdata: Vec<StarkFelt>,
+    ) {
+        self.mocked_calls.insert((contract_address, entry_point_selector), retdata);
+    }
+
+    pub fn clear_mock_call(
+        &mut self,
+        contract_address: ContractAddress,
+        entry_point_selector: EntryPointSelector,
+    ) {
+        self.mocked_calls.remove(&(contract_address, entry_point_selector));
+    }
+
+    pub fn block_timestamp(&self, contract_address: ContractAddress) -> Option<BlockTimestamp> {
+        self.warped_block_timestamps.get(&contract_address).copied()
+    }
+
+    pub fn block_number(&self, contract_address: ContractAddress) -> Option<BlockNumber> {
+        self.rolled_block_numbers.get(&contract_address).copied()
+    }
+
+    pub fn caller_address(&self, contract_address: ContractAddress) -> Option<ContractAddress> {
+        self.pranked_caller_addresses.get(&contract_address).copied()
+    }
+
+    pub fn mocked_retdata(
+        &self,
+        contract_address: ContractAddress,
+        entry_point_selector: EntryPointSelector,
+    ) -> Option<&[StarkFelt]> {
+        self.mocked_calls.get(&(contract_address, entry_point_selector)).map(Vec::as_slice)
+    }
+}
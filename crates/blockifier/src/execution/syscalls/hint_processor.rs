@@ -1,8 +1,9 @@
 use std::any::Any;
 use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
 
 use cairo_felt::Felt252;
-use cairo_lang_casm::hints::{Hint, StarknetHint};
+use cairo_lang_casm::hints::{CoreHint, CoreHintBase, Hint, StarknetHint};
 use cairo_lang_casm::operand::{BinOpOperand, DerefOrImmediate, Operation, Register, ResOperand};
 use cairo_lang_runner::casm_run::execute_core_hint_base;
 use cairo_vm::hint_processor::hint_processor_definition::{HintProcessorLogic, HintReference};
@@ -25,13 +26,14 @@ use starknet_api::StarknetApiError;
 use thiserror::Error;
 
 use crate::abi::sierra_types::SierraTypeError;
-use crate::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message};
+use crate::event_log::ExecutionEvent;
+use crate::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message, StorageAccess};
 use crate::execution::common_hints::{ExecutionMode, HintExecutionResult};
 use crate::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
 use crate::execution::errors::{ConstructorEntryPointExecutionError, EntryPointExecutionError};
 use crate::execution::execution_utils::{
-    felt_range_from_ptr, max_fee_for_execution_info, stark_felt_from_ptr, stark_felt_to_felt,
-    write_maybe_relocatable, ReadOnlySegment, ReadOnlySegments,
+    felt_range_from_ptr, format_panic_data, max_fee_for_execution_info, stark_felt_from_ptr,
+    stark_felt_to_felt, write_maybe_relocatable, ReadOnlySegment, ReadOnlySegments,
 };
 use crate::execution::syscalls::secp::{
     secp256k1_add, secp256k1_get_point_from_x, secp256k1_get_xy, secp256k1_mul, secp256k1_new,
@@ -40,10 +42,11 @@ use crate::execution::syscalls::secp::{
 };
 use crate::execution::syscalls::{
     call_contract, deploy, emit_event, get_block_hash, get_execution_info, keccak, library_call,
-    library_call_l1_handler, replace_class, send_message_to_l1, storage_read, storage_write,
-    StorageReadResponse, StorageWriteResponse, SyscallRequest, SyscallRequestWrapper,
-    SyscallResponse, SyscallResponseWrapper, SyscallResult, SyscallSelector,
+    library_call_l1_handler, replace_class, send_message_to_l1, sha256_process_block, storage_read,
+    storage_write, verify_signature, StorageReadResponse, StorageWriteResponse, SyscallRequest,
+    SyscallRequestWrapper, SyscallResponse, SyscallResponseWrapper, SyscallResult, SyscallSelector,
 };
+use crate::state::cached_state::{CachedState, MutRefState};
 use crate::state::errors::StateError;
 use crate::state::state_api::State;
 use crate::transaction::objects::{CurrentTransactionInfo, TransactionInfo};
@@ -97,8 +100,10 @@ pub enum SyscallExecutionError {
     StateError(#[from] StateError),
     #[error(transparent)]
     VirtualMachineError(#[from] VirtualMachineError),
-    #[error("Syscall error.")]
+    #[error("Syscall error: {}.", format_panic_data(.error_data))]
     SyscallError { error_data: Vec<StarkFelt> },
+    #[error("Syscall {syscall_name} is not supported by this chain or Starknet version.")]
+    UnsupportedSyscall { syscall_name: String },
 }
 
 #[derive(Debug, Error)]
@@ -200,11 +205,17 @@ pub struct SyscallHintProcessor<'a> {
     // Additional information gathered during execution.
     pub read_values: Vec<StarkFelt>,
     pub accessed_keys: HashSet<StorageKey>,
+    pub storage_accesses: Vec<StorageAccess>,
+    /// Debug prints of the contract; only captured in development mode.
+    pub debug_logs: Vec<Vec<StarkFelt>>,
 
     // Secp hint processors.
     pub secp256k1_hint_processor: SecpHintProcessor<ark_secp256k1::Config>,
     pub secp256r1_hint_processor: SecpHintProcessor<ark_secp256r1::Config>,
 
+    // Sha256 states are written to a single segment, allocated on the first call.
+    pub sha256_segment_end_ptr: Option<Relocatable>,
+
     // Additional fields.
     hints: &'a HashMap<String, Hint>,
     // Transaction info. and signature segments; allocated on-demand.
@@ -234,10 +245,13 @@ impl<'a> SyscallHintProcessor<'a> {
             syscall_ptr: initial_syscall_ptr,
             read_values: vec![],
             accessed_keys: HashSet::new(),
+            storage_accesses: vec![],
+            debug_logs: vec![],
             hints,
             execution_info_ptr: None,
             secp256k1_hint_processor: SecpHintProcessor::default(),
             secp256r1_hint_processor: SecpHintProcessor::default(),
+            sha256_segment_end_ptr: None,
         }
     }
 
@@ -261,6 +275,19 @@ impl<'a> SyscallHintProcessor<'a> {
         self.execution_mode() == ExecutionMode::Validate
     }
 
+    /// Returns an error if the syscall is forbidden in the current execution mode.
+    pub fn verify_syscall_allowed(&self, selector: SyscallSelector) -> SyscallResult<()> {
+        let forbidden_syscalls = &self.context.versioned_constants().validate_forbidden_syscalls;
+        if self.is_validate_mode() && forbidden_syscalls.contains(&selector) {
+            return Err(SyscallExecutionError::InvalidSyscallInExecutionMode {
+                syscall_name: selector.name(),
+                execution_mode: self.execution_mode(),
+            });
+        }
+
+        Ok(())
+    }
+
     pub fn verify_syscall_ptr(&self, actual_ptr: Relocatable) -> SyscallResult<()> {
         if actual_ptr != self.syscall_ptr {
             return Err(SyscallExecutionError::BadSyscallPointer {
@@ -272,6 +299,24 @@ impl<'a> SyscallHintProcessor<'a> {
         Ok(())
     }
 
+    /// Captures a debug print of the contract (the felts between the given pointers) into its
+    /// debug logs, instead of writing it to stdout.
+    fn capture_debug_log(
+        &mut self,
+        vm: &mut VirtualMachine,
+        start: &ResOperand,
+        end: &ResOperand,
+    ) -> HintExecutionResult {
+        let start = get_ptr_from_res_operand_unchecked(vm, start);
+        let end = get_ptr_from_res_operand_unchecked(vm, end);
+        let length = (end - start).map_err(SyscallExecutionError::from)?;
+        let debug_log =
+            felt_range_from_ptr(vm, start, length).map_err(SyscallExecutionError::from)?;
+        self.debug_logs.push(debug_log);
+
+        Ok(())
+    }
+
     /// Infers and executes the next syscall.
     /// Must comply with the API of a hint function, as defined by the `HintProcessor`.
     pub fn execute_next_syscall(
@@ -279,6 +324,11 @@ impl<'a> SyscallHintProcessor<'a> {
         vm: &mut VirtualMachine,
         hint: &StarknetHint,
     ) -> HintExecutionResult {
+        if self.context.execution_aborted() {
+            return Err(
+                SyscallExecutionError::from(EntryPointExecutionError::ExecutionAborted).into()
+            );
+        }
         let StarknetHint::SystemCall { system: syscall } = hint else {
             return Err(HintError::Internal(VirtualMachineError::Other(anyhow::anyhow!(
                 "Test functions are unsupported on starknet."
@@ -288,6 +338,14 @@ impl<'a> SyscallHintProcessor<'a> {
         self.verify_syscall_ptr(initial_syscall_ptr)?;
 
         let selector = SyscallSelector::try_from(self.read_next_syscall_selector(vm)?)?;
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("syscall", ?selector).entered();
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_syscall(&selector);
+        self.context.tx_context.block_context.record_event(|| ExecutionEvent::SyscallExecuted {
+            storage_address: self.storage_address(),
+            selector: format!("{selector:?}"),
+        });
 
         // Keccak resource usage depends on the input length, so we increment the syscall count
         // in the syscall execution callback.
@@ -297,109 +355,149 @@ impl<'a> SyscallHintProcessor<'a> {
 
         match selector {
             SyscallSelector::CallContract => self.execute_syscall(
+                selector,
                 vm,
                 call_contract,
                 self.context.gas_costs().call_contract_gas_cost,
             ),
             SyscallSelector::Deploy => {
-                self.execute_syscall(vm, deploy, self.context.gas_costs().deploy_gas_cost)
-            }
-            SyscallSelector::EmitEvent => {
-                self.execute_syscall(vm, emit_event, self.context.gas_costs().emit_event_gas_cost)
+                self.execute_syscall(selector, vm, deploy, self.context.gas_costs().deploy_gas_cost)
             }
+            SyscallSelector::EmitEvent => self.execute_syscall(
+                selector,
+                vm,
+                emit_event,
+                self.context.gas_costs().emit_event_gas_cost,
+            ),
             SyscallSelector::GetBlockHash => self.execute_syscall(
+                selector,
                 vm,
                 get_block_hash,
                 self.context.gas_costs().get_block_hash_gas_cost,
             ),
             SyscallSelector::GetExecutionInfo => self.execute_syscall(
+                selector,
                 vm,
                 get_execution_info,
                 self.context.gas_costs().get_execution_info_gas_cost,
             ),
             SyscallSelector::Keccak => {
-                self.execute_syscall(vm, keccak, self.context.gas_costs().keccak_gas_cost)
+                self.execute_syscall(selector, vm, keccak, self.context.gas_costs().keccak_gas_cost)
             }
             SyscallSelector::LibraryCall => self.execute_syscall(
+                selector,
                 vm,
                 library_call,
                 self.context.gas_costs().library_call_gas_cost,
             ),
             SyscallSelector::LibraryCallL1Handler => self.execute_syscall(
+                selector,
                 vm,
                 library_call_l1_handler,
                 self.context.gas_costs().library_call_gas_cost,
             ),
             SyscallSelector::ReplaceClass => self.execute_syscall(
+                selector,
                 vm,
                 replace_class,
                 self.context.gas_costs().replace_class_gas_cost,
             ),
             SyscallSelector::Secp256k1Add => self.execute_syscall(
+                selector,
                 vm,
                 secp256k1_add,
                 self.context.gas_costs().secp256k1_add_gas_cost,
             ),
             SyscallSelector::Secp256k1GetPointFromX => self.execute_syscall(
+                selector,
                 vm,
                 secp256k1_get_point_from_x,
                 self.context.gas_costs().secp256k1_get_point_from_x_gas_cost,
             ),
             SyscallSelector::Secp256k1GetXy => self.execute_syscall(
+                selector,
                 vm,
                 secp256k1_get_xy,
                 self.context.gas_costs().secp256k1_get_xy_gas_cost,
             ),
             SyscallSelector::Secp256k1Mul => self.execute_syscall(
+                selector,
                 vm,
                 secp256k1_mul,
                 self.context.gas_costs().secp256k1_mul_gas_cost,
             ),
             SyscallSelector::Secp256k1New => self.execute_syscall(
+                selector,
                 vm,
                 secp256k1_new,
                 self.context.gas_costs().secp256k1_new_gas_cost,
             ),
             SyscallSelector::Secp256r1Add => self.execute_syscall(
+                selector,
                 vm,
                 secp256r1_add,
                 self.context.gas_costs().secp256r1_add_gas_cost,
             ),
             SyscallSelector::Secp256r1GetPointFromX => self.execute_syscall(
+                selector,
                 vm,
                 secp256r1_get_point_from_x,
                 self.context.gas_costs().secp256r1_get_point_from_x_gas_cost,
             ),
             SyscallSelector::Secp256r1GetXy => self.execute_syscall(
+                selector,
                 vm,
                 secp256r1_get_xy,
                 self.context.gas_costs().secp256r1_get_xy_gas_cost,
             ),
             SyscallSelector::Secp256r1Mul => self.execute_syscall(
+                selector,
                 vm,
                 secp256r1_mul,
                 self.context.gas_costs().secp256r1_mul_gas_cost,
             ),
             SyscallSelector::Secp256r1New => self.execute_syscall(
+                selector,
                 vm,
                 secp256r1_new,
                 self.context.gas_costs().secp256r1_new_gas_cost,
             ),
             SyscallSelector::SendMessageToL1 => self.execute_syscall(
+                selector,
                 vm,
                 send_message_to_l1,
                 self.context.gas_costs().send_message_to_l1_gas_cost,
             ),
+            SyscallSelector::Sha256ProcessBlock => self.execute_syscall(
+                selector,
+                vm,
+                sha256_process_block,
+                supported_syscall_gas_cost(
+                    self.context.gas_costs().sha256_process_block_gas_cost,
+                    "sha256_process_block",
+                )?,
+            ),
             SyscallSelector::StorageRead => self.execute_syscall(
+                selector,
                 vm,
                 storage_read,
                 self.context.gas_costs().storage_read_gas_cost,
             ),
             SyscallSelector::StorageWrite => self.execute_syscall(
+                selector,
                 vm,
                 storage_write,
                 self.context.gas_costs().storage_write_gas_cost,
             ),
+            SyscallSelector::VerifySignature => self.execute_syscall(
+                selector,
+                vm,
+                verify_signature,
+                supported_syscall_gas_cost(
+                    self.context.gas_costs().verify_signature_gas_cost,
+                    "verify_signature",
+                )?,
+            ),
             _ => Err(HintError::UnknownHint(
                 format!("Unsupported syscall selector {selector:?}.").into(),
             )),
@@ -450,6 +548,7 @@ impl<'a> SyscallHintProcessor<'a> {
 
     fn execute_syscall<Request, Response, ExecuteCallback>(
         &mut self,
+        selector: SyscallSelector,
         vm: &mut VirtualMachine,
         execute_callback: ExecuteCallback,
         syscall_gas_cost: u64,
@@ -470,6 +569,11 @@ impl<'a> SyscallHintProcessor<'a> {
         let SyscallRequestWrapper { gas_counter, request } =
             SyscallRequestWrapper::<Request>::read(vm, &mut self.syscall_ptr)?;
 
+        let syscall_hook = self.context.syscall_hook.clone();
+        if let Some(hook) = &syscall_hook {
+            hook.before_syscall(selector, &request, gas_counter);
+        }
+
         if gas_counter < required_gas {
             //  Out of gas failure.
             let out_of_gas_error =
@@ -483,7 +587,13 @@ impl<'a> SyscallHintProcessor<'a> {
 
         // Execute.
         let mut remaining_gas = gas_counter - required_gas;
-        let original_response = execute_callback(request, vm, self, &mut remaining_gas);
+        let original_response = self
+            .verify_syscall_allowed(selector)
+            .and_then(|()| execute_callback(request, vm, self, &mut remaining_gas));
+        if let Some(hook) = &syscall_hook {
+            let result = original_response.as_ref().map(|response| response as &dyn Debug);
+            hook.after_syscall(selector, result, remaining_gas);
+        }
         let response = match original_response {
             Ok(response) => {
                 SyscallResponseWrapper::Success { gas_counter: remaining_gas, response }
@@ -520,11 +630,17 @@ impl<'a> SyscallHintProcessor<'a> {
     ) -> SyscallResult<Relocatable> {
         let block_info_ptr = self.allocate_block_info_segment(vm)?;
         let tx_info_ptr = self.allocate_tx_info_segment(vm)?;
+        let caller_address = self
+            .context
+            .cheatcodes
+            .as_ref()
+            .and_then(|cheatcodes| cheatcodes.caller_address(self.storage_address()))
+            .unwrap_or(self.caller_address());
 
         let additional_info: Vec<MaybeRelocatable> = vec![
             block_info_ptr.into(),
             tx_info_ptr.into(),
-            stark_felt_to_felt(*self.caller_address().0.key()).into(),
+            stark_felt_to_felt(*caller_address.0.key()).into(),
             stark_felt_to_felt(*self.storage_address().0.key()).into(),
             stark_felt_to_felt(self.entry_point_selector().0).into(),
         ];
@@ -539,8 +655,15 @@ impl<'a> SyscallHintProcessor<'a> {
         vm: &mut VirtualMachine,
     ) -> SyscallResult<Relocatable> {
         let block_info = &self.context.tx_context.block_context.block_info;
-        let block_timestamp = block_info.block_timestamp.0;
-        let block_number = block_info.block_number.0;
+        let cheatcodes = self.context.cheatcodes.as_deref();
+        let block_timestamp = cheatcodes
+            .and_then(|cheatcodes| cheatcodes.block_timestamp(self.storage_address()))
+            .unwrap_or(block_info.block_timestamp)
+            .0;
+        let block_number = cheatcodes
+            .and_then(|cheatcodes| cheatcodes.block_number(self.storage_address()))
+            .unwrap_or(block_info.block_number)
+            .0;
         let versioned_constants = self.context.versioned_constants();
         let block_data: Vec<StarkFelt> = if self.is_validate_mode() {
             // Round down to the nearest multiple of validate_block_number_rounding.
@@ -650,6 +773,7 @@ impl<'a> SyscallHintProcessor<'a> {
         self.accessed_keys.insert(key);
         let value = self.state.get_storage_at(self.storage_address(), key)?;
         self.read_values.push(value);
+        self.storage_accesses.push(StorageAccess::read(key, value));
 
         Ok(StorageReadResponse { value })
     }
@@ -661,11 +785,25 @@ impl<'a> SyscallHintProcessor<'a> {
     ) -> SyscallResult<StorageWriteResponse> {
         self.accessed_keys.insert(key);
         self.state.set_storage_at(self.storage_address(), key, value)?;
+        self.storage_accesses.push(StorageAccess::write(key, value));
+        self.context.tx_context.block_context.record_event(|| ExecutionEvent::StorageWritten {
+            contract_address: self.storage_address(),
+            key,
+            value,
+        });
 
         Ok(StorageWriteResponse {})
     }
 }
 
+/// Returns the gas cost of a syscall that is not defined in all Starknet versions; fails if it is
+/// not defined in the current one.
+fn supported_syscall_gas_cost(gas_cost: Option<u64>, syscall_name: &str) -> SyscallResult<u64> {
+    gas_cost.ok_or_else(|| SyscallExecutionError::UnsupportedSyscall {
+        syscall_name: syscall_name.to_string(),
+    })
+}
+
 /// Retrieves a [Relocatable] from the VM given a [ResOperand].
 /// A [ResOperand] represents a CASM result expression, and is deserialized with the hint.
 fn get_ptr_from_res_operand_unchecked(vm: &mut VirtualMachine, res: &ResOperand) -> Relocatable {
@@ -688,7 +826,7 @@ fn get_ptr_from_res_operand_unchecked(vm: &mut VirtualMachine, res: &ResOperand)
 
 impl ResourceTracker for SyscallHintProcessor<'_> {
     fn consumed(&self) -> bool {
-        self.context.vm_run_resources.consumed()
+        self.context.vm_run_consumed()
     }
 
     fn consume_step(&mut self) {
@@ -714,6 +852,11 @@ impl HintProcessorLogic for SyscallHintProcessor<'_> {
     ) -> HintExecutionResult {
         let hint = hint_data.downcast_ref::<Hint>().ok_or(HintError::WrongHintData)?;
         match hint {
+            Hint::Core(CoreHintBase::Core(CoreHint::DebugPrint { start, end }))
+                if self.context.tx_context.block_context.dev_mode =>
+            {
+                self.capture_debug_log(vm, start, end)
+            }
             Hint::Core(hint) => execute_core_hint_base(vm, exec_scopes, hint),
             Hint::Starknet(hint) => self.execute_next_syscall(vm, hint),
         }
@@ -761,24 +904,59 @@ pub fn execute_inner_call(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     remaining_gas: &mut u64,
 ) -> SyscallResult<ReadOnlySegment> {
-    let call_info =
-        call.execute(syscall_handler.state, syscall_handler.resources, syscall_handler.context)?;
-    let raw_retdata = &call_info.execution.retdata.0;
+    let mut call_info = if syscall_handler.context.versioned_constants().enable_reverts {
+        execute_revertible_inner_call(call, syscall_handler)?
+    } else {
+        call.execute(syscall_handler.state, syscall_handler.resources, syscall_handler.context)?
+    };
+    // The gas consumed by the inner call is charged even if it failed.
+    update_remaining_gas(remaining_gas, &call_info);
 
     if call_info.execution.failed {
         // TODO(spapini): Append an error word according to starknet spec if needed.
         // Something like "EXECUTION_ERROR".
-        return Err(SyscallExecutionError::SyscallError { error_data: raw_retdata.clone() });
+        let error_data = call_info.execution.retdata.0.clone();
+        // The reverted call is kept in the call tree, without the events and messages it emitted.
+        clear_reverted_call_effects(&mut call_info);
+        syscall_handler.inner_calls.push(call_info);
+        return Err(SyscallExecutionError::SyscallError { error_data });
     }
 
+    let raw_retdata = &call_info.execution.retdata.0;
     let retdata_segment = create_retdata_segment(vm, syscall_handler, raw_retdata)?;
-    update_remaining_gas(remaining_gas, &call_info);
 
     syscall_handler.inner_calls.push(call_info);
 
     Ok(retdata_segment)
 }
 
+/// Executes an inner call on top of its caller's state, and reverts its state changes if it fails.
+fn execute_revertible_inner_call(
+    call: CallEntryPoint,
+    syscall_handler: &mut SyscallHintProcessor<'_>,
+) -> SyscallResult<CallInfo> {
+    let mut call_state = CachedState::new(MutRefState::new(&mut *syscall_handler.state));
+    let call_info = call.execute_revertible(
+        &mut call_state,
+        syscall_handler.resources,
+        syscall_handler.context,
+    )?;
+    if call_info.execution.failed {
+        call_state.revert();
+    } else {
+        call_state.commit()?;
+    }
+
+    Ok(call_info)
+}
+
+/// Drops the events and L2-to-L1 messages of a reverted call and of its inner calls.
+fn clear_reverted_call_effects(call_info: &mut CallInfo) {
+    call_info.execution.events.clear();
+    call_info.execution.l2_to_l1_messages.clear();
+    call_info.inner_calls.iter_mut().for_each(clear_reverted_call_effects);
+}
+
 pub fn create_retdata_segment(
     vm: &mut VirtualMachine,
     syscall_handler: &mut SyscallHintProcessor<'_>,
@@ -0,0 +1,22 @@
+use std::fmt::Debug;
+
+use crate::execution::syscalls::hint_processor::SyscallExecutionError;
+use crate::execution::syscalls::SyscallSelector;
+
+/// Callbacks invoked around each syscall of a Cairo 1 entry point execution; attached to the
+/// execution context, e.g., by debuggers, cheatcode frameworks and syscall usage analytics.
+/// Both callbacks do nothing by default.
+pub trait SyscallHook: Debug + Send + Sync {
+    /// Called after the syscall request is read, before the syscall gas is charged.
+    fn before_syscall(&self, _selector: SyscallSelector, _request: &dyn Debug, _gas_counter: u64) {}
+
+    /// Called after the syscall is executed, with its response or error.
+    /// Not called if the syscall did not run due to insufficient gas.
+    fn after_syscall(
+        &self,
+        _selector: SyscallSelector,
+        _result: Result<&dyn Debug, &SyscallExecutionError>,
+        _remaining_gas: u64,
+    ) {
+    }
+}
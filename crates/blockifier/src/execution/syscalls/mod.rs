@@ -1,11 +1,10 @@
 use cairo_felt::Felt252;
-use cairo_vm::types::relocatable::Relocatable;
+use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
 use cairo_vm::vm::vm_core::VirtualMachine;
 use num_traits::ToPrimitive;
+use sha2::digest::generic_array::GenericArray;
 use starknet_api::block::{BlockHash, BlockNumber};
-use starknet_api::core::{
-    calculate_contract_address, ClassHash, ContractAddress, EntryPointSelector, EthAddress,
-};
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, EthAddress};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
@@ -20,9 +19,11 @@ use self::hint_processor::{
 };
 use crate::abi::constants;
 use crate::execution::call_info::{MessageToL1, OrderedEvent, OrderedL2ToL1Message};
+use crate::execution::contract_address::calculate_deployed_contract_address;
 use crate::execution::contract_class::ContractClass;
 use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
 use crate::execution::entry_point::{CallEntryPoint, CallType, ConstructorContext};
+use crate::execution::execution_policy::{check_execution_policy, ContractAction};
 use crate::execution::execution_utils::{
     execute_deployment, felt_from_ptr, felt_to_stark_felt, stark_felt_from_ptr, stark_felt_to_felt,
     write_felt, write_maybe_relocatable, write_stark_felt, ReadOnlySegment,
@@ -31,7 +32,9 @@ use crate::execution::syscalls::hint_processor::{INVALID_INPUT_LENGTH_ERROR, OUT
 use crate::transaction::transaction_utils::update_remaining_gas;
 use crate::versioned_constants::{EventLimits, VersionedConstants};
 
+pub mod cheatcodes;
 pub mod hint_processor;
+pub mod hook;
 mod secp;
 
 #[cfg(test)]
@@ -168,6 +171,23 @@ pub fn call_contract(
             execution_mode: syscall_handler.execution_mode(),
         });
     }
+    check_execution_policy(
+        &syscall_handler.context.tx_context.block_context.chain_info,
+        ContractAction::Call,
+        class_hash,
+        storage_address,
+    )?;
+    if let Some(retdata) = syscall_handler
+        .context
+        .cheatcodes
+        .as_ref()
+        .and_then(|cheatcodes| cheatcodes.mocked_retdata(storage_address, selector))
+    {
+        let retdata = retdata.to_vec();
+        let retdata_segment = create_retdata_segment(vm, syscall_handler, &retdata)?;
+        return Ok(CallContractResponse { segment: retdata_segment });
+    }
+
     let entry_point = CallEntryPoint {
         class_hash: None,
         code_address: Some(storage_address),
@@ -236,15 +256,18 @@ pub fn deploy(
     remaining_gas: &mut u64,
 ) -> SyscallResult<DeployResponse> {
     let deployer_address = syscall_handler.storage_address();
-    let deployer_address_for_calculation = match request.deploy_from_zero {
-        true => ContractAddress::default(),
-        false => deployer_address,
-    };
-    let deployed_contract_address = calculate_contract_address(
+    let deployed_contract_address = calculate_deployed_contract_address(
         request.contract_address_salt,
         request.class_hash,
         &request.constructor_calldata,
-        deployer_address_for_calculation,
+        deployer_address,
+        request.deploy_from_zero,
+    )?;
+    check_execution_policy(
+        &syscall_handler.context.tx_context.block_context.chain_info,
+        ContractAction::Deploy,
+        request.class_hash,
+        deployed_contract_address,
     )?;
 
     let ctor_context = ConstructorContext {
@@ -379,13 +402,6 @@ pub fn get_block_hash(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<GetBlockHashResponse> {
-    if syscall_handler.is_validate_mode() {
-        return Err(SyscallExecutionError::InvalidSyscallInExecutionMode {
-            syscall_name: "get_block_hash".to_string(),
-            execution_mode: syscall_handler.execution_mode(),
-        });
-    }
-
     let requested_block_number = request.block_number.0;
     let current_block_number =
         syscall_handler.context.tx_context.block_context.block_info.block_number.0;
@@ -458,6 +474,12 @@ pub fn library_call(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     remaining_gas: &mut u64,
 ) -> SyscallResult<LibraryCallResponse> {
+    check_execution_policy(
+        &syscall_handler.context.tx_context.block_context.chain_info,
+        ContractAction::LibraryCall,
+        request.class_hash,
+        syscall_handler.storage_address(),
+    )?;
     let call_to_external = true;
     let retdata_segment = execute_library_call(
         syscall_handler,
@@ -480,6 +502,12 @@ pub fn library_call_l1_handler(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     remaining_gas: &mut u64,
 ) -> SyscallResult<LibraryCallResponse> {
+    check_execution_policy(
+        &syscall_handler.context.tx_context.block_context.chain_info,
+        ContractAction::LibraryCall,
+        request.class_hash,
+        syscall_handler.storage_address(),
+    )?;
     let call_to_external = false;
     let retdata_segment = execute_library_call(
         syscall_handler,
@@ -570,6 +598,82 @@ pub fn send_message_to_l1(
     Ok(SendMessageToL1Response {})
 }
 
+// Sha256ProcessBlock syscall.
+
+const SHA256_BLOCK_SIZE_IN_WORDS: usize = 16;
+const SHA256_STATE_SIZE_IN_WORDS: usize = 8;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Sha256ProcessBlockRequest {
+    pub state_ptr: Relocatable,
+    pub input_start: Relocatable,
+}
+
+impl SyscallRequest for Sha256ProcessBlockRequest {
+    fn read(
+        vm: &VirtualMachine,
+        ptr: &mut Relocatable,
+    ) -> SyscallResult<Sha256ProcessBlockRequest> {
+        let state_ptr = vm.get_relocatable(*ptr)?;
+        *ptr = (*ptr + 1)?;
+        let input_start = vm.get_relocatable(*ptr)?;
+        *ptr = (*ptr + 1)?;
+        Ok(Sha256ProcessBlockRequest { state_ptr, input_start })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Sha256ProcessBlockResponse {
+    pub state_ptr: Relocatable,
+}
+
+impl SyscallResponse for Sha256ProcessBlockResponse {
+    fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
+        write_maybe_relocatable(vm, ptr, self.state_ptr)?;
+        Ok(())
+    }
+}
+
+/// Applies the SHA-256 compression function to the given state and 16-word input block, and
+/// returns a pointer to the new state.
+pub fn sha256_process_block(
+    request: Sha256ProcessBlockRequest,
+    vm: &mut VirtualMachine,
+    syscall_handler: &mut SyscallHintProcessor<'_>,
+    _remaining_gas: &mut u64,
+) -> SyscallResult<Sha256ProcessBlockResponse> {
+    let felt_to_u32 = |felt: &Felt252| {
+        felt.to_u32().ok_or_else(|| SyscallExecutionError::InvalidSyscallInput {
+            input: felt_to_stark_felt(felt),
+            info: String::from("Invalid input for the sha256_process_block syscall."),
+        })
+    };
+
+    let input = vm.get_integer_range(request.input_start, SHA256_BLOCK_SIZE_IN_WORDS)?;
+    let mut input_as_bytes = [0_u8; 4 * SHA256_BLOCK_SIZE_IN_WORDS];
+    for (bytes, word) in input_as_bytes.chunks_exact_mut(4).zip(input.iter()) {
+        bytes.copy_from_slice(&felt_to_u32(word)?.to_be_bytes());
+    }
+
+    let prev_state = vm.get_integer_range(request.state_ptr, SHA256_STATE_SIZE_IN_WORDS)?;
+    let mut state = [0_u32; SHA256_STATE_SIZE_IN_WORDS];
+    for (word, felt) in state.iter_mut().zip(prev_state.iter()) {
+        *word = felt_to_u32(felt)?;
+    }
+
+    sha2::compress256(&mut state, &[GenericArray::clone_from_slice(&input_as_bytes)]);
+
+    let state_ptr = match syscall_handler.sha256_segment_end_ptr {
+        Some(segment_end_ptr) => segment_end_ptr,
+        None => vm.add_memory_segment(),
+    };
+    let new_state: Vec<MaybeRelocatable> =
+        state.iter().map(|&word| MaybeRelocatable::from(Felt252::from(word))).collect();
+    syscall_handler.sha256_segment_end_ptr = Some(vm.load_data(state_ptr, &new_state)?);
+
+    Ok(Sha256ProcessBlockResponse { state_ptr })
+}
+
 // TODO(spapini): Do something with address domain in read and write.
 // StorageRead syscall.
 
@@ -643,6 +747,60 @@ pub fn storage_write(
     syscall_handler.set_contract_storage_at(request.address, request.value)
 }
 
+// VerifySignature syscall.
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct VerifySignatureRequest {
+    pub message_hash: StarkFelt,
+    pub public_key: Vec<StarkFelt>,
+    pub signature: Vec<StarkFelt>,
+}
+
+impl SyscallRequest for VerifySignatureRequest {
+    fn read(vm: &VirtualMachine, ptr: &mut Relocatable) -> SyscallResult<VerifySignatureRequest> {
+        let message_hash = stark_felt_from_ptr(vm, ptr)?;
+        let public_key = read_felt_array::<SyscallExecutionError>(vm, ptr)?;
+        let signature = read_felt_array::<SyscallExecutionError>(vm, ptr)?;
+
+        Ok(VerifySignatureRequest { message_hash, public_key, signature })
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct VerifySignatureResponse {
+    pub is_valid: bool,
+}
+
+impl SyscallResponse for VerifySignatureResponse {
+    fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
+        write_stark_felt(vm, ptr, StarkFelt::from(u8::from(self.is_valid)))?;
+        Ok(())
+    }
+}
+
+/// Verifies the signature using the signature verifier of the chain; fails on chains that do not
+/// define one.
+pub fn verify_signature(
+    request: VerifySignatureRequest,
+    _vm: &mut VirtualMachine,
+    syscall_handler: &mut SyscallHintProcessor<'_>,
+    _remaining_gas: &mut u64,
+) -> SyscallResult<VerifySignatureResponse> {
+    let chain_info = &syscall_handler.context.tx_context.block_context.chain_info;
+    let Some(signature_verifier) = &chain_info.signature_verifier else {
+        return Err(SyscallExecutionError::UnsupportedSyscall {
+            syscall_name: "verify_signature".to_string(),
+        });
+    };
+
+    let is_valid = signature_verifier.verify_signature(
+        request.message_hash,
+        &request.public_key,
+        &request.signature,
+    );
+    Ok(VerifySignatureResponse { is_valid })
+}
+
 // Keccak syscall.
 
 #[derive(Debug, Eq, PartialEq)]
@@ -1,15 +1,24 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
 
 use assert_matches::assert_matches;
 use cairo_felt::Felt252;
+use cairo_lang_casm::hints::StarknetHint;
+use cairo_lang_casm::operand::{CellRef, Register, ResOperand};
 use cairo_lang_utils::byte_array::BYTE_ARRAY_MAGIC;
+use cairo_vm::types::relocatable::{MaybeRelocatable, Relocatable};
+use cairo_vm::vm::errors::hint_errors::HintError;
 use cairo_vm::vm::runners::builtin_runner::RANGE_CHECK_BUILTIN_NAME;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
-use num_traits::Pow;
+use cairo_vm::vm::vm_core::VirtualMachine;
+use num_traits::{Pow, ToPrimitive, Zero};
 use pretty_assertions::assert_eq;
 use rstest::rstest;
+use starknet_api::block::{BlockNumber, BlockTimestamp};
 use starknet_api::core::{
-    calculate_contract_address, ChainId, ContractAddress, EthAddress, PatriciaKey,
+    calculate_contract_address, ChainId, ClassHash, ContractAddress, EntryPointSelector,
+    EthAddress, PatriciaKey,
 };
 use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::hash::{StarkFelt, StarkHash};
@@ -19,25 +28,34 @@ use starknet_api::transaction::{
     L2ToL1Payload, PaymasterData, Resource, ResourceBounds, ResourceBoundsMapping, Tip,
     TransactionHash, TransactionVersion,
 };
-use starknet_api::{calldata, stark_felt};
+use starknet_api::{calldata, contract_address, patricia_key, stark_felt};
 use test_case::test_case;
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants;
-use crate::context::ChainInfo;
+use crate::blockifier::block::BlockInfo;
+use crate::context::{BlockContext, CancellationToken, ChainInfo, TransactionContext};
 use crate::execution::call_info::{
     CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
+    StorageAccess,
 };
 use crate::execution::common_hints::ExecutionMode;
-use crate::execution::entry_point::{CallEntryPoint, CallType};
+use crate::execution::contract_class::{ContractClass, ContractClassV1};
+use crate::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
 use crate::execution::errors::EntryPointExecutionError;
-use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt, ReadOnlySegments};
+use crate::execution::signature_verifier::SignatureVerifier;
+use crate::execution::syscalls::cheatcodes::Cheatcodes;
 use crate::execution::syscalls::hint_processor::{
-    EmitEventError, BLOCK_NUMBER_OUT_OF_RANGE_ERROR, L1_GAS, L2_GAS, OUT_OF_GAS_ERROR,
+    EmitEventError, SyscallExecutionError, SyscallHintProcessor, BLOCK_NUMBER_OUT_OF_RANGE_ERROR,
+    L1_GAS, L2_GAS, OUT_OF_GAS_ERROR,
 };
+use crate::execution::syscalls::hook::SyscallHook;
 use crate::execution::syscalls::SyscallSelector;
+use crate::state::cached_state::CachedState;
 use crate::state::state_api::{State, StateReader};
 use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{
     calldata_for_deploy_test, create_calldata, get_syscall_resources,
@@ -49,7 +67,7 @@ use crate::transaction::constants::QUERY_VERSION_BASE_BIT;
 use crate::transaction::objects::{
     CommonAccountFields, CurrentTransactionInfo, DeprecatedTransactionInfo, TransactionInfo,
 };
-use crate::versioned_constants::VersionedConstants;
+use crate::versioned_constants::{StarknetVersion, VersionedConstants};
 use crate::{check_entry_point_execution_error_for_custom_hint, nonce, retdata, storage_key};
 pub const REQUIRED_GAS_STORAGE_READ_WRITE_TEST: u64 = 27150;
 pub const REQUIRED_GAS_CALL_CONTRACT_TEST: u64 = 105680;
@@ -84,6 +102,112 @@ fn test_storage_read_write() {
     assert_eq!(value_from_state, value);
 }
 
+#[derive(Debug, Default)]
+struct RecordingSyscallHook {
+    // Tuples of (selector, is after syscall, gas).
+    records: Mutex<Vec<(SyscallSelector, bool, u64)>>,
+}
+
+impl SyscallHook for RecordingSyscallHook {
+    fn before_syscall(&self, selector: SyscallSelector, _request: &dyn Debug, gas_counter: u64) {
+        self.records.lock().unwrap().push((selector, false, gas_counter));
+    }
+
+    fn after_syscall(
+        &self,
+        selector: SyscallSelector,
+        result: Result<&dyn Debug, &SyscallExecutionError>,
+        remaining_gas: u64,
+    ) {
+        assert!(result.is_ok());
+        self.records.lock().unwrap().push((selector, true, remaining_gas));
+    }
+}
+
+#[test]
+fn test_syscall_hook() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let tx_context = TransactionContext {
+        block_context: BlockContext::create_for_testing(),
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+    let hook = Arc::new(RecordingSyscallHook::default());
+    context.syscall_hook = Some(hook.clone());
+    entry_point_call.execute(&mut state, &mut ExecutionResources::default(), &mut context).unwrap();
+
+    let records = hook.records.lock().unwrap();
+    let selectors_and_stages: Vec<_> =
+        records.iter().map(|(selector, is_after, _)| (*selector, *is_after)).collect();
+    assert_eq!(
+        selectors_and_stages,
+        vec![
+            (SyscallSelector::StorageWrite, false),
+            (SyscallSelector::StorageWrite, true),
+            (SyscallSelector::StorageRead, false),
+            (SyscallSelector::StorageRead, true),
+        ]
+    );
+    // Each syscall charges its gas cost between the two callbacks; the base cost is pre-charged.
+    let gas_costs = &VersionedConstants::create_for_testing().os_constants.gas_costs;
+    let syscall_base_gas_cost = gas_costs.syscall_base_gas_cost;
+    assert_eq!(
+        records[0].2 - records[1].2,
+        gas_costs.storage_write_gas_cost - syscall_base_gas_cost
+    );
+    assert_eq!(
+        records[2].2 - records[3].2,
+        gas_costs.storage_read_gas_cost - syscall_base_gas_cost
+    );
+}
+
+/// Aborts the execution at its first syscall.
+#[derive(Debug)]
+struct AbortingSyscallHook(CancellationToken);
+
+impl SyscallHook for AbortingSyscallHook {
+    fn before_syscall(&self, _selector: SyscallSelector, _request: &dyn Debug, _gas_counter: u64) {
+        self.0.cancel();
+    }
+}
+
+#[test]
+fn test_execution_aborted() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![stark_felt!(1234_u16), stark_felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let block_context = BlockContext::create_for_testing();
+    let cancellation_token = block_context.cancellation_token().clone();
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+    context.syscall_hook = Some(Arc::new(AbortingSyscallHook(cancellation_token)));
+
+    // The storage write runs, and the execution is aborted at the following storage read.
+    assert_matches!(
+        entry_point_call
+            .execute(&mut state, &mut ExecutionResources::default(), &mut context)
+            .unwrap_err(),
+        EntryPointExecutionError::ExecutionAborted
+    );
+}
+
 #[test]
 fn test_call_contract() {
     let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
@@ -495,6 +619,94 @@ fn test_get_execution_info(
     assert!(!result.unwrap().execution.failed);
 }
 
+#[test]
+fn test_cheatcodes() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let test_contract_address = test_contract.get_instance_address(0);
+    let pranked_caller_address = contract_address!("0x1234");
+    let inner_entry_point_selector = selector_from_name("test_storage_read_write");
+
+    let mut cheatcodes = Cheatcodes::default();
+    cheatcodes.warp(test_contract_address, BlockTimestamp(CURRENT_BLOCK_TIMESTAMP + 100));
+    cheatcodes.roll(test_contract_address, BlockNumber(CURRENT_BLOCK_NUMBER + 10));
+    cheatcodes.prank(test_contract_address, pranked_caller_address);
+    cheatcodes.mock_call(
+        test_contract_address,
+        inner_entry_point_selector,
+        vec![stark_felt!(7_u8)],
+    );
+    let cheatcodes = Arc::new(cheatcodes);
+    let tx_context = Arc::new(TransactionContext {
+        block_context: BlockContext::create_for_testing(),
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    });
+    let mut execute_with_cheatcodes = |entry_point_call: CallEntryPoint| {
+        let mut context = EntryPointExecutionContext::new_invoke(tx_context.clone(), true).unwrap();
+        context.cheatcodes = Some(cheatcodes.clone());
+        entry_point_call.execute(&mut state, &mut ExecutionResources::default(), &mut context)
+    };
+
+    // Warp, roll and prank.
+    let entry_point_selector = selector_from_name("test_get_execution_info");
+    let expected_block_info = vec![
+        stark_felt!(CURRENT_BLOCK_NUMBER + 10),
+        stark_felt!(CURRENT_BLOCK_TIMESTAMP + 100),
+        StarkFelt::try_from(TEST_SEQUENCER_ADDRESS).unwrap(),
+    ];
+    let expected_tx_info = vec![
+        StarkFelt::ZERO,                                            // Transaction version.
+        StarkFelt::ZERO,                                            // Account address.
+        StarkFelt::ZERO,                                            // Max fee.
+        StarkFelt::ZERO,                                            // Signature.
+        StarkFelt::ZERO,                                            // Transaction hash.
+        stark_felt!(&*ChainId(CHAIN_ID_NAME.to_string()).as_hex()), // Chain ID.
+        StarkFelt::ZERO,                                            // Nonce.
+        StarkFelt::ZERO,                                            // Length of resource bounds.
+        StarkFelt::ZERO,                                            // Tip.
+        StarkFelt::ZERO,                                            // Paymaster data.
+        StarkFelt::ZERO,                                            // Nonce DA.
+        StarkFelt::ZERO,                                            // Fee DA.
+        StarkFelt::ZERO,                                            // Account data.
+    ];
+    let expected_call_info = vec![
+        *pranked_caller_address.0.key(),     // Caller address.
+        *test_contract_address.0.key(),      // Storage address.
+        stark_felt!(entry_point_selector.0), // Entry point selector.
+    ];
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector,
+        calldata: Calldata(
+            [expected_block_info, expected_tx_info, expected_call_info].concat().into(),
+        ),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    assert!(!execute_with_cheatcodes(entry_point_call).unwrap().execution.failed);
+
+    // Mock call.
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_call_contract"),
+        calldata: create_calldata(
+            test_contract_address,
+            "test_storage_read_write",
+            &[
+                stark_felt!(405_u16), // Calldata: address.
+                stark_felt!(48_u8),   // Calldata: value.
+            ],
+        ),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let call_info = execute_with_cheatcodes(entry_point_call).unwrap();
+    assert_eq!(call_info.execution.retdata, retdata![stark_felt!(7_u8)]);
+    // The mocked call is not executed.
+    assert!(call_info.inner_calls.is_empty());
+    assert_eq!(
+        state.get_storage_at(test_contract_address, storage_key!(405_u16)).unwrap(),
+        StarkFelt::ZERO
+    );
+}
+
 #[test]
 fn test_library_call() {
     let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
@@ -622,6 +834,14 @@ fn test_nested_library_call() {
         resources: storage_entry_point_resources.clone(),
         storage_read_values: vec![stark_felt!(value + 1)],
         accessed_storage_keys: HashSet::from([storage_key!(key + 1)]),
+        storage_accesses: vec![
+            StorageAccess::write(storage_key!(key + 1), stark_felt!(value + 1)),
+            StorageAccess::read(storage_key!(key + 1), stark_felt!(value + 1)),
+        ],
+        syscall_counter: HashMap::from([
+            (SyscallSelector::StorageWrite, 1),
+            (SyscallSelector::StorageRead, 1),
+        ]),
         ..Default::default()
     };
     let library_call_resources = &get_syscall_resources(SyscallSelector::LibraryCall)
@@ -639,6 +859,7 @@ fn test_nested_library_call() {
         },
         resources: library_call_resources,
         inner_calls: vec![nested_storage_call_info],
+        syscall_counter: HashMap::from([(SyscallSelector::LibraryCall, 1)]),
         ..Default::default()
     };
     let storage_call_info = CallInfo {
@@ -651,6 +872,14 @@ fn test_nested_library_call() {
         resources: storage_entry_point_resources,
         storage_read_values: vec![stark_felt!(value)],
         accessed_storage_keys: HashSet::from([storage_key!(key)]),
+        storage_accesses: vec![
+            StorageAccess::write(storage_key!(key), stark_felt!(value)),
+            StorageAccess::read(storage_key!(key), stark_felt!(value)),
+        ],
+        syscall_counter: HashMap::from([
+            (SyscallSelector::StorageWrite, 1),
+            (SyscallSelector::StorageRead, 1),
+        ]),
         ..Default::default()
     };
 
@@ -669,6 +898,7 @@ fn test_nested_library_call() {
         },
         resources: main_call_resources,
         inner_calls: vec![library_call_info, storage_call_info],
+        syscall_counter: HashMap::from([(SyscallSelector::LibraryCall, 2)]),
         ..Default::default()
     };
 
@@ -914,7 +1144,7 @@ fn test_out_of_gas() {
 
 #[test]
 fn test_syscall_failure_format() {
-    let error_data = vec![
+    let error_data: Vec<StarkFelt> = vec![
         // Magic to indicate that this is a byte array.
         BYTE_ARRAY_MAGIC,
         // the number of full words in the byte array.
@@ -927,6 +1157,323 @@ fn test_syscall_failure_format() {
     .into_iter()
     .map(|x| StarkFelt::try_from(x).unwrap())
     .collect();
-    let error = EntryPointExecutionError::ExecutionFailed { error_data };
+    let error = EntryPointExecutionError::ExecutionFailed { error_data: error_data.clone() };
     assert_eq!(error.to_string(), "Execution failed. Failure reason: \"Execution failure\".");
+    let error = SyscallExecutionError::SyscallError { error_data };
+    assert_eq!(error.to_string(), "Syscall error: \"Execution failure\".");
+
+    // Short strings, e.g., error codes, are decoded as well.
+    let error_data =
+        vec![StarkFelt::from(1_u8), stark_felt!("0x454e545259504f494e545f4e4f545f464f554e44")];
+    let error = EntryPointExecutionError::ExecutionFailed { error_data };
+    assert_eq!(
+        error.to_string(),
+        "Execution failed. Failure reason: (0x1, 0x454e545259504f494e545f4e4f545f464f554e44 \
+         ('ENTRYPOINT_NOT_FOUND'))."
+    );
+}
+
+/// Executes a single syscall through the syscall handler, outside of any contract, on a state with
+/// the given contracts; used for syscalls (or failures) that the feature contracts cannot invoke.
+/// `write_request` writes any data the request points to, and returns the request;
+/// `read_response` reads the response, following its gas counter and failure flag, given the calls
+/// made by the syscall. Also returns whether the syscall succeeded.
+fn execute_raw_syscall<R>(
+    block_context: BlockContext,
+    contracts: &[(FeatureContract, u16)],
+    selector: SyscallSelector,
+    write_request: impl FnOnce(&mut VirtualMachine) -> Vec<MaybeRelocatable>,
+    read_response: impl FnOnce(&VirtualMachine, Relocatable, &[CallInfo]) -> R,
+) -> Result<(bool, R), HintError> {
+    let mut state = test_state(&block_context.chain_info, BALANCE, contracts);
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+    let mut resources = ExecutionResources::default();
+    let hints = HashMap::new();
+
+    // The frame pointer points to the start of the execution segment, holding the syscall pointer.
+    let mut vm = VirtualMachine::new(false);
+    vm.add_memory_segment();
+    let execution_segment = vm.add_memory_segment();
+    let syscall_ptr = vm.add_memory_segment();
+    vm.insert_value(execution_segment, syscall_ptr).unwrap();
+
+    let selector_name = format!("{selector:?}");
+    let mut syscall = vec![
+        MaybeRelocatable::from(Felt252::from_bytes_be(selector_name.as_bytes())),
+        MaybeRelocatable::from(Felt252::from(u64::from(u32::MAX))),
+    ];
+    syscall.extend(write_request(&mut vm));
+    let response_ptr = vm.load_data(syscall_ptr, &syscall).unwrap();
+
+    let mut syscall_handler = SyscallHintProcessor::new(
+        &mut state,
+        &mut resources,
+        &mut context,
+        syscall_ptr,
+        CallEntryPoint::default(),
+        &hints,
+        ReadOnlySegments::default(),
+    );
+    let hint = StarknetHint::SystemCall {
+        system: ResOperand::Deref(CellRef { register: Register::FP, offset: 0 }),
+    };
+    syscall_handler.execute_next_syscall(&mut vm, &hint)?;
+
+    let success = vm.get_integer((response_ptr + 1).unwrap()).unwrap().is_zero();
+    let response = read_response(&vm, (response_ptr + 2).unwrap(), &syscall_handler.inner_calls);
+    Ok((success, response))
+}
+
+/// Accepts signatures equal to the public key.
+#[derive(Debug)]
+struct PublicKeySignatureVerifier;
+
+impl SignatureVerifier for PublicKeySignatureVerifier {
+    fn verify_signature(
+        &self,
+        _message_hash: StarkFelt,
+        public_key: &[StarkFelt],
+        signature: &[StarkFelt],
+    ) -> bool {
+        public_key == signature
+    }
+}
+
+#[rstest]
+fn test_verify_signature(
+    #[values(StarknetVersion::Latest, StarknetVersion::V0_13_1_1)] version: StarknetVersion,
+    #[values(true, false)] has_verifier: bool,
+    #[values(true, false)] is_valid: bool,
+) {
+    let chain_info = ChainInfo {
+        signature_verifier: has_verifier
+            .then(|| Arc::new(PublicKeySignatureVerifier) as Arc<dyn SignatureVerifier>),
+        ..ChainInfo::create_for_testing()
+    };
+    let block_context = BlockContext::builder(BlockInfo::create_for_testing())
+        .chain_info(chain_info)
+        .versioned_constants(VersionedConstants::get(version).clone())
+        .build();
+
+    let public_key = vec![Felt252::from(1_u8), Felt252::from(2_u8)];
+    let signature = if is_valid { public_key.clone() } else { vec![Felt252::from(3_u8)] };
+    let write_request = |vm: &mut VirtualMachine| {
+        let mut request = vec![MaybeRelocatable::from(Felt252::from(4_u8))];
+        for array in [public_key, signature] {
+            let start = vm.add_memory_segment();
+            let data: Vec<MaybeRelocatable> = array.into_iter().map(Into::into).collect();
+            let end = vm.load_data(start, &data).unwrap();
+            request.extend([MaybeRelocatable::from(start), MaybeRelocatable::from(end)]);
+        }
+        request
+    };
+    let read_response = |vm: &VirtualMachine, response_ptr, _: &[CallInfo]| {
+        vm.get_integer(response_ptr).unwrap().into_owned()
+    };
+    let result = execute_raw_syscall(
+        block_context,
+        &[],
+        SyscallSelector::VerifySignature,
+        write_request,
+        read_response,
+    );
+
+    if has_verifier && version == StarknetVersion::Latest {
+        let (success, response) = result.unwrap();
+        assert!(success);
+        assert_eq!(response, Felt252::from(u8::from(is_valid)));
+    } else {
+        // Not supported without a verifier, nor in versions preceding the syscall.
+        let error = result.unwrap_err();
+        assert!(format!("{error:?}").contains("UnsupportedSyscall"));
+    }
+}
+
+#[rstest]
+fn test_sha256_process_block(
+    #[values(StarknetVersion::Latest, StarknetVersion::V0_13_1_1)] version: StarknetVersion,
+) {
+    let block_context = BlockContext::builder(BlockInfo::create_for_testing())
+        .versioned_constants(VersionedConstants::get(version).clone())
+        .build();
+
+    // The padded single block of "abc", on the initial state of SHA-256.
+    let initial_state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+    let mut input = [0_u32; 16];
+    input[0] = 0x61626380;
+    input[15] = 0x18;
+    let write_request = |vm: &mut VirtualMachine| {
+        let mut request = vec![];
+        for words in [&initial_state[..], &input[..]] {
+            let start = vm.add_memory_segment();
+            let data: Vec<MaybeRelocatable> =
+                words.iter().map(|&word| Felt252::from(word).into()).collect();
+            vm.load_data(start, &data).unwrap();
+            request.push(MaybeRelocatable::from(start));
+        }
+        request
+    };
+    let read_response = |vm: &VirtualMachine, response_ptr, _: &[CallInfo]| {
+        let state_ptr = vm.get_relocatable(response_ptr).unwrap();
+        vm.get_integer_range(state_ptr, 8)
+            .unwrap()
+            .into_iter()
+            .map(|word| word.into_owned())
+            .collect::<Vec<_>>()
+    };
+    let result = execute_raw_syscall(
+        block_context,
+        &[],
+        SyscallSelector::Sha256ProcessBlock,
+        write_request,
+        read_response,
+    );
+
+    if version == StarknetVersion::Latest {
+        // SHA-256("abc").
+        let expected_state: Vec<Felt252> = [
+            0xba7816bf_u32,
+            0x8f01cfea,
+            0x414140de,
+            0x5dae2223,
+            0xb00361a3,
+            0x96177a9c,
+            0xb410ff61,
+            0xf20015ad,
+        ]
+        .into_iter()
+        .map(Felt252::from)
+        .collect();
+        assert_eq!(result.unwrap(), (true, expected_state));
+    } else {
+        let error = result.unwrap_err();
+        assert!(format!("{error:?}").contains("UnsupportedSyscall"));
+    }
+}
+
+#[test]
+fn test_call_contract_failure_gas() {
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.versioned_constants.enable_reverts = true;
+    let gas_costs = &block_context.versioned_constants.os_constants.gas_costs;
+    let syscall_gas = gas_costs.call_contract_gas_cost - gas_costs.syscall_base_gas_cost;
+    let initial_gas = u64::from(u32::MAX);
+
+    // Calls the `fail` entry point of the test contract, without calldata.
+    let write_request = |vm: &mut VirtualMachine| {
+        let calldata_ptr = vm.add_memory_segment();
+        let contract_address = *test_contract.get_instance_address(0).0.key();
+        vec![
+            MaybeRelocatable::from(stark_felt_to_felt(contract_address)),
+            MaybeRelocatable::from(stark_felt_to_felt(selector_from_name("fail").0)),
+            MaybeRelocatable::from(calldata_ptr),
+            MaybeRelocatable::from(calldata_ptr),
+        ]
+    };
+    let read_response = |vm: &VirtualMachine, response_ptr: Relocatable, calls: &[CallInfo]| {
+        let gas_counter = vm.get_integer((response_ptr - 2).unwrap()).unwrap().to_u64().unwrap();
+        let error_data_start = vm.get_relocatable(response_ptr).unwrap();
+        let error_data_end = vm.get_relocatable((response_ptr + 1).unwrap()).unwrap();
+        let error_data: Vec<StarkFelt> = vm
+            .get_integer_range(error_data_start, (error_data_end - error_data_start).unwrap())
+            .unwrap()
+            .into_iter()
+            .map(|felt| felt_to_stark_felt(&felt))
+            .collect();
+        let [call_info] = calls else { panic!("Expected a single call, got {calls:?}.") };
+        (gas_counter, error_data, call_info.execution.clone())
+    };
+    let (success, (remaining_gas, error_data, inner_execution)) = execute_raw_syscall(
+        block_context,
+        &[(test_contract, 1)],
+        SyscallSelector::CallContract,
+        write_request,
+        read_response,
+    )
+    .unwrap();
+
+    // The failure is returned to the caller, which is charged for the gas the failed call consumed.
+    assert!(!success);
+    assert!(inner_execution.failed);
+    assert_eq!(error_data, inner_execution.retdata.0);
+    assert_eq!(error_data, vec![stark_felt!("0x6661696c")]); // 'fail'.
+    assert!(inner_execution.gas_consumed > 0);
+    let gas_consumed = initial_gas - remaining_gas;
+    assert_eq!(gas_consumed, syscall_gas + inner_execution.gas_consumed);
+}
+
+// A class with a single external entry point (selector 1), which prints its calldata (as
+// `core::debug::print` does) and returns nothing. Hand-assembled, since none of the feature
+// contracts prints.
+const DEBUG_PRINT_CLASS: &str = r#"{
+    "prime": "0x800000000000011000000000000000000000000000000000000000000000001",
+    "compiler_version": "2.6.0",
+    "bytecode": [
+        "0x480a7ffa7fff8000",
+        "0x480a7ffb7fff8000",
+        "0x480680017fff8000",
+        "0x0",
+        "0x480a7ffc7fff8000",
+        "0x480a7ffc7fff8000",
+        "0x208b7fff7fff7ffe"
+    ],
+    "hints": [
+        [
+            0,
+            [
+                {
+                    "DebugPrint": {
+                        "start": {"Deref": {"register": "FP", "offset": -4}},
+                        "end": {"Deref": {"register": "FP", "offset": -3}}
+                    }
+                }
+            ]
+        ]
+    ],
+    "entry_points_by_type": {
+        "EXTERNAL": [{"selector": "0x1", "offset": 0, "builtins": []}],
+        "L1_HANDLER": [],
+        "CONSTRUCTOR": []
+    }
+}"#;
+
+#[rstest]
+fn test_debug_print(#[values(true, false)] dev_mode: bool) {
+    let class_hash = ClassHash(stark_felt!(1_u8));
+    let contract_address = contract_address!("0x2");
+    let contract_class = ContractClassV1::try_from_json_string(DEBUG_PRINT_CLASS).unwrap();
+    let mut state = CachedState::from(DictStateReader {
+        address_to_class_hash: HashMap::from([(contract_address, class_hash)]),
+        class_hash_to_class: HashMap::from([(class_hash, ContractClass::V1(contract_class))]),
+        ..Default::default()
+    });
+    let block_context =
+        BlockContext::builder(BlockInfo::create_for_testing()).dev_mode(dev_mode).build();
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+
+    let printed = vec![stark_felt!(3_u8), stark_felt!(4_u8)];
+    let call = CallEntryPoint::external(
+        contract_address,
+        EntryPointSelector(stark_felt!(1_u8)),
+        Calldata(printed.clone().into()),
+    );
+    let call_info =
+        call.execute(&mut state, &mut ExecutionResources::default(), &mut context).unwrap();
+
+    // The print is captured in development mode only (otherwise, it is written to stdout).
+    let expected_debug_logs = if dev_mode { vec![printed] } else { vec![] };
+    assert_eq!(call_info.debug_logs, expected_debug_logs);
+    assert_eq!(call_info.execution.retdata, Retdata::default());
 }
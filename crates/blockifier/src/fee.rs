@@ -1,5 +1,7 @@
 pub mod actual_cost;
 pub mod eth_gas_constants;
+pub mod fee_breakdown;
 pub mod fee_checks;
+pub mod fee_estimation;
 pub mod fee_utils;
 pub mod gas_usage;
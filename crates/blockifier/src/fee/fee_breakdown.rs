@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use starknet_api::transaction::Fee;
+
+use crate::context::BlockContext;
+use crate::fee::fee_utils::{calculate_l1_gas_by_vm_resource, get_fee_by_gas_vector};
+use crate::transaction::objects::{
+    FeeType, GasVector, TransactionExecutionInfo, TransactionFeeResult, TransactionResources,
+};
+
+#[cfg(test)]
+#[path = "fee_breakdown_test.rs"]
+pub mod test;
+
+/// Details how the fee of a transaction decomposes into the costs of the resources it consumed.
+/// Note that only the heaviest Cairo resource is charged, rather than the sum of all of them.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct FeeBreakdown {
+    /// The L1 gas weight of each Cairo resource in use: steps (including reverted steps) and
+    /// builtins.
+    pub vm_resources: HashMap<String, u128>,
+    /// The charged gas of the Cairo resources; the heaviest entry in `vm_resources`.
+    pub vm_gas: GasVector,
+    pub calldata_and_signature_gas: GasVector,
+    pub code_gas: GasVector,
+    pub state_diff_gas: GasVector,
+    pub messages_gas: GasVector,
+    pub events_gas: GasVector,
+    pub total_gas: GasVector,
+    pub fee: Fee,
+}
+
+impl FeeBreakdown {
+    pub fn new(
+        tx_resources: &TransactionResources,
+        block_context: &BlockContext,
+        fee_type: &FeeType,
+    ) -> TransactionFeeResult<Self> {
+        let versioned_constants = &block_context.versioned_constants;
+        let starknet_resources = &tx_resources.starknet_resources;
+
+        let vm_resources = calculate_l1_gas_by_vm_resource(
+            versioned_constants,
+            &tx_resources.vm_resources,
+            tx_resources.n_reverted_steps,
+        )?;
+        let vm_gas = GasVector::from_l1_gas(vm_resources.values().copied().max().unwrap_or(0));
+        let calldata_and_signature_gas =
+            starknet_resources.get_calldata_and_signature_cost(versioned_constants);
+        let code_gas = starknet_resources.get_code_cost(versioned_constants);
+        let state_diff_gas =
+            starknet_resources.get_state_changes_cost(block_context.block_info.use_kzg_da);
+        let messages_gas = starknet_resources.get_messages_cost();
+        let events_gas = starknet_resources.get_events_cost(versioned_constants);
+
+        let total_gas = vm_gas
+            + calldata_and_signature_gas
+            + code_gas
+            + state_diff_gas
+            + messages_gas
+            + events_gas;
+        let fee = get_fee_by_gas_vector(&block_context.block_info, total_gas, fee_type);
+
+        Ok(Self {
+            vm_resources,
+            vm_gas,
+            calldata_and_signature_gas,
+            code_gas,
+            state_diff_gas,
+            messages_gas,
+            events_gas,
+            total_gas,
+            fee,
+        })
+    }
+
+    /// Breaks down the fee of an executed transaction, whether or not the fee was charged.
+    pub fn from_execution_info(
+        tx_execution_info: &TransactionExecutionInfo,
+        block_context: &BlockContext,
+        fee_type: &FeeType,
+    ) -> TransactionFeeResult<Self> {
+        Self::new(&tx_execution_info.actual_resources, block_context, fee_type)
+    }
+}
@@ -0,0 +1,45 @@
+use rstest::rstest;
+use starknet_api::transaction::Fee;
+
+use crate::context::BlockContext;
+use crate::fee::fee_breakdown::FeeBreakdown;
+use crate::invoke_tx_args;
+use crate::test_utils::{create_trivial_calldata, CairoVersion, MAX_FEE};
+use crate::transaction::objects::FeeType;
+use crate::transaction::test_utils::{
+    account_invoke_tx, block_context, create_test_init_data, TestInitData,
+};
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[rstest]
+fn test_fee_breakdown(
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let TestInitData { mut state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, cairo_version);
+    let tx = account_invoke_tx(invoke_tx_args! {
+        max_fee: Fee(MAX_FEE),
+        sender_address: account_address,
+        calldata: create_trivial_calldata(contract_address),
+    });
+    let tx_execution_info = tx.execute(&mut state, &block_context, true, true).unwrap();
+
+    let fee_breakdown =
+        FeeBreakdown::from_execution_info(&tx_execution_info, &block_context, &FeeType::Eth)
+            .unwrap();
+    assert_eq!(fee_breakdown.fee, tx_execution_info.actual_fee);
+    assert_eq!(
+        fee_breakdown.vm_gas.l1_gas,
+        fee_breakdown.vm_resources.values().copied().max().unwrap()
+    );
+    assert_eq!(
+        fee_breakdown.total_gas,
+        fee_breakdown.vm_gas
+            + fee_breakdown.calldata_and_signature_gas
+            + fee_breakdown.code_gas
+            + fee_breakdown.state_diff_gas
+            + fee_breakdown.messages_gas
+            + fee_breakdown.events_gas
+    );
+}
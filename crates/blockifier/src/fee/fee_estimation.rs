@@ -0,0 +1,113 @@
+use num_traits::CheckedMul;
+use serde::Serialize;
+use starknet_api::transaction::Fee;
+
+use crate::context::BlockContext;
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::StateReader;
+use crate::transaction::objects::{
+    FeeType, GasVector, HasRelatedFeeType, TransactionExecutionResult, TransactionInfoCreator,
+};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutableTransaction;
+use crate::versioned_constants::ResourceCost;
+
+#[cfg(test)]
+#[path = "fee_estimation_test.rs"]
+pub mod test;
+
+/// The unit in which a fee is denominated.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum PriceUnit {
+    Wei,
+    Fri,
+}
+
+impl From<FeeType> for PriceUnit {
+    fn from(fee_type: FeeType) -> Self {
+        match fee_type {
+            FeeType::Eth => Self::Wei,
+            FeeType::Strk => Self::Fri,
+        }
+    }
+}
+
+/// Fee estimation of a single transaction, broken down by resource.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct FeeEstimate {
+    pub gas_consumed: u128,
+    pub gas_price: u128,
+    pub data_gas_consumed: u128,
+    pub data_gas_price: u128,
+    pub overall_fee: Fee,
+    pub unit: PriceUnit,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct FeeEstimationFlags {
+    /// Whether to run the account's `__validate__` entry point during the estimation.
+    pub validate: bool,
+    /// Multiplies the consumed gas amounts (rounding up), to account for state changes between
+    /// the estimation and the actual execution; e.g., `3/2` adds a 50% margin.
+    pub safety_margin: Option<ResourceCost>,
+}
+
+impl Default for FeeEstimationFlags {
+    fn default() -> Self {
+        Self { validate: true, safety_margin: None }
+    }
+}
+
+/// Estimates the fee of the given transaction by executing it on top of the given state, without
+/// charging fee. The state is left untouched.
+/// Note: reverted transactions are estimated as well, based on the resources consumed until the
+/// revert.
+pub fn estimate_fee<S: StateReader>(
+    tx: &Transaction,
+    state: &mut CachedState<S>,
+    block_context: &BlockContext,
+    flags: FeeEstimationFlags,
+) -> TransactionExecutionResult<FeeEstimate> {
+    let mut transactional_state = CachedState::create_transactional(state);
+    let charge_fee = false;
+    let execution_result =
+        tx.execute_raw(&mut transactional_state, block_context, charge_fee, flags.validate);
+    transactional_state.abort();
+    let tx_execution_info = execution_result?;
+
+    let gas_vector = tx_execution_info
+        .actual_resources
+        .to_gas_vector(&block_context.versioned_constants, block_context.block_info.use_kzg_da)?;
+    let gas_vector = match flags.safety_margin {
+        Some(margin) => apply_safety_margin(gas_vector, margin),
+        None => gas_vector,
+    };
+
+    let fee_type = tx.create_tx_info().fee_type();
+    let gas_prices = &block_context.block_info.gas_prices;
+    let gas_price = u128::from(gas_prices.get_gas_price_by_fee_type(&fee_type));
+    let data_gas_price = u128::from(gas_prices.get_data_gas_price_by_fee_type(&fee_type));
+
+    Ok(FeeEstimate {
+        gas_consumed: gas_vector.l1_gas,
+        gas_price,
+        data_gas_consumed: gas_vector.l1_data_gas,
+        data_gas_price,
+        overall_fee: gas_vector.saturated_cost(gas_price, data_gas_price),
+        unit: fee_type.into(),
+    })
+}
+
+/// Scales the gas amounts by the margin, saturating on overflow.
+fn apply_safety_margin(gas_vector: GasVector, margin: ResourceCost) -> GasVector {
+    let scale = |gas: u128| {
+        margin
+            .checked_mul(&ResourceCost::from_integer(gas))
+            .map_or(u128::MAX, |scaled_gas| scaled_gas.ceil().to_integer())
+    };
+    GasVector {
+        l1_gas: scale(gas_vector.l1_gas),
+        l1_data_gas: scale(gas_vector.l1_data_gas),
+        l2_gas: scale(gas_vector.l2_gas),
+    }
+}
@@ -0,0 +1,77 @@
+use rstest::rstest;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::{Fee, TransactionVersion};
+
+use crate::context::BlockContext;
+use crate::fee::fee_estimation::{
+    apply_safety_margin, estimate_fee, FeeEstimationFlags, PriceUnit,
+};
+use crate::invoke_tx_args;
+use crate::state::state_api::StateReader;
+use crate::test_utils::{create_calldata, CairoVersion, MAX_FEE};
+use crate::transaction::objects::GasVector;
+use crate::transaction::test_utils::{
+    account_invoke_tx, block_context, create_test_init_data, max_resource_bounds, TestInitData,
+};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transactions::ExecutableTransaction;
+use crate::versioned_constants::ResourceCost;
+
+#[rstest]
+#[case::deprecated_tx(TransactionVersion::ONE, PriceUnit::Wei)]
+#[case::current_tx(TransactionVersion::THREE, PriceUnit::Fri)]
+fn test_estimate_fee(
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+    #[case] version: TransactionVersion,
+    #[case] expected_unit: PriceUnit,
+) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, cairo_version);
+    let tx_args = invoke_tx_args! {
+        sender_address: account_address,
+        calldata: create_calldata(contract_address, "return_result", &[stark_felt!(2_u8)]),
+        max_fee: Fee(MAX_FEE),
+        resource_bounds: max_resource_bounds(),
+        version,
+        nonce: nonce_manager.next(account_address),
+    };
+    let tx = Transaction::AccountTransaction(account_invoke_tx(tx_args.clone()));
+
+    let estimate =
+        estimate_fee(&tx, &mut state, &block_context, FeeEstimationFlags::default()).unwrap();
+    assert_eq!(estimate.unit, expected_unit);
+    assert_eq!(
+        estimate.overall_fee.0,
+        estimate.gas_consumed * estimate.gas_price
+            + estimate.data_gas_consumed * estimate.data_gas_price
+    );
+    // The estimation does not modify the state.
+    assert_eq!(state.get_nonce_at(account_address).unwrap(), tx_args.nonce);
+
+    // A safety margin scales the consumed gas.
+    let flags = FeeEstimationFlags {
+        safety_margin: Some(ResourceCost::new(3, 2)),
+        ..FeeEstimationFlags::default()
+    };
+    let estimate_with_margin = estimate_fee(&tx, &mut state, &block_context, flags).unwrap();
+    assert_eq!(
+        estimate_with_margin.gas_consumed,
+        (ResourceCost::new(3, 2) * estimate.gas_consumed).ceil().to_integer()
+    );
+
+    // The estimation matches the fee charged upon execution.
+    let tx_execution_info =
+        account_invoke_tx(tx_args).execute(&mut state, &block_context, true, true).unwrap();
+    assert_eq!(tx_execution_info.actual_fee, estimate.overall_fee);
+}
+
+#[test]
+fn test_safety_margin_saturates() {
+    let gas_vector = GasVector { l1_gas: u128::MAX, l1_data_gas: 3, l2_gas: 0 };
+    assert_eq!(
+        apply_safety_margin(gas_vector, ResourceCost::new(3, 2)),
+        GasVector { l1_gas: u128::MAX, l1_data_gas: 5, l2_gas: 0 }
+    );
+}
@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use starknet_api::hash::StarkFelt;
@@ -28,6 +28,20 @@ pub fn calculate_l1_gas_by_vm_usage(
     vm_resource_usage: &ExecutionResources,
     n_reverted_steps: usize,
 ) -> TransactionFeeResult<GasVector> {
+    let vm_l1_gas_usage =
+        calculate_l1_gas_by_vm_resource(versioned_constants, vm_resource_usage, n_reverted_steps)?
+            .into_values()
+            .fold(0, u128::max);
+
+    Ok(GasVector::from_l1_gas(vm_l1_gas_usage))
+}
+
+/// Returns the L1 gas weight of each Cairo resource in use (steps and prover builtins).
+pub fn calculate_l1_gas_by_vm_resource(
+    versioned_constants: &VersionedConstants,
+    vm_resource_usage: &ExecutionResources,
+    n_reverted_steps: usize,
+) -> TransactionFeeResult<HashMap<String, u128>> {
     // TODO(Yoni, 1/7/2024): rename vm -> cairo.
     let vm_resource_fee_costs = versioned_constants.vm_resource_fee_cost();
     let mut vm_resource_usage_for_fee = vm_resource_usage.prover_builtins();
@@ -47,17 +61,13 @@ pub fn calculate_l1_gas_by_vm_usage(
     );
 
     // Convert Cairo usage to L1 gas usage.
-    let vm_l1_gas_usage = vm_resource_fee_costs
-        .iter()
-        .map(|(key, resource_val)| {
-            ((*resource_val)
-                * u128_from_usize(vm_resource_usage_for_fee.get(key).cloned().unwrap_or_default()))
-            .ceil()
-            .to_integer()
+    Ok(vm_resource_usage_for_fee
+        .into_iter()
+        .map(|(key, usage)| {
+            let l1_gas = (vm_resource_fee_costs[&key] * u128_from_usize(usage)).ceil().to_integer();
+            (key, l1_gas)
         })
-        .fold(0, u128::max);
-
-    Ok(GasVector::from_l1_gas(vm_l1_gas_usage))
+        .collect())
 }
 
 /// Converts the gas vector to a fee.
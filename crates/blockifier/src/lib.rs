@@ -9,17 +9,30 @@
     target_pointer_width = "128"
 ))]
 
+#[cfg(all(feature = "concurrency", target_arch = "wasm32", target_os = "unknown"))]
+compile_error!("The `concurrency` feature requires threads, which `wasm32-unknown-unknown` lacks.");
+
 pub mod abi;
 pub mod blockifier;
 pub mod bouncer;
+#[cfg(feature = "commitment")]
+pub mod commitment;
 #[cfg(feature = "concurrency")]
 pub mod concurrency;
 pub mod context;
+pub mod event_log;
 pub mod execution;
 pub mod fee;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod prelude;
+#[cfg(feature = "proto")]
+pub mod proto;
 pub mod state;
 #[cfg(any(feature = "testing", test))]
+#[doc(hidden)]
 pub mod test_utils;
 pub mod transaction;
+#[doc(hidden)]
 pub mod utils;
 pub mod versioned_constants;
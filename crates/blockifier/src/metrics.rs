@@ -0,0 +1,54 @@
+//! Execution metrics, recorded through the `metrics` facade. Integrators install a recorder (e.g.,
+//! a Prometheus exporter) to collect them; without one, recording is a no-op.
+
+use std::fmt::Debug;
+use std::time::Duration;
+
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionExecutionResult};
+
+#[cfg(test)]
+#[path = "metrics_test.rs"]
+pub mod test;
+
+/// Counter of the executed transactions, labeled by `result`: `success`, `reverted` or `failed`.
+pub const TXS_EXECUTED: &str = "blockifier_txs_executed_total";
+/// Histogram of the VM steps of each executed transaction, including reverted steps.
+pub const TX_STEPS: &str = "blockifier_tx_steps";
+/// Counter of the executed syscalls, labeled by `selector`.
+pub const SYSCALLS: &str = "blockifier_syscalls_total";
+/// Counter of the global contract class cache lookups, labeled by `result`: `hit` or `miss`.
+pub const CLASS_CACHE_LOOKUPS: &str = "blockifier_class_cache_lookups_total";
+/// Histogram of the time, in seconds, from the creation of a transaction executor until the
+/// block is finalized.
+pub const BLOCK_BUILD_SECONDS: &str = "blockifier_block_build_seconds";
+
+pub(crate) fn record_tx_execution(
+    tx_execution_result: &TransactionExecutionResult<TransactionExecutionInfo>,
+) {
+    let result = match tx_execution_result {
+        Ok(tx_execution_info) => {
+            let actual_resources = &tx_execution_info.actual_resources;
+            // Conversion to f64 is lossy only for unrealistic step counts.
+            #[allow(clippy::as_conversions)]
+            let n_steps =
+                (actual_resources.vm_resources.n_steps + actual_resources.n_reverted_steps) as f64;
+            metrics::histogram!(TX_STEPS).record(n_steps);
+            if tx_execution_info.is_reverted() { "reverted" } else { "success" }
+        }
+        Err(_) => "failed",
+    };
+    metrics::counter!(TXS_EXECUTED, "result" => result).increment(1);
+}
+
+pub(crate) fn record_syscall(selector: &impl Debug) {
+    metrics::counter!(SYSCALLS, "selector" => format!("{selector:?}")).increment(1);
+}
+
+pub(crate) fn record_class_cache_lookup(hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    metrics::counter!(CLASS_CACHE_LOOKUPS, "result" => result).increment(1);
+}
+
+pub(crate) fn record_block_build_time(duration: Duration) {
+    metrics::histogram!(BLOCK_BUILD_SECONDS).record(duration.as_secs_f64());
+}
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use metrics::{Key, Label, SharedString, Unit};
+use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshotter};
+use metrics_util::{CompositeKey, MetricKind};
+
+use crate::metrics::{
+    record_class_cache_lookup, record_tx_execution, CLASS_CACHE_LOOKUPS, TXS_EXECUTED, TX_STEPS,
+};
+use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::{TransactionExecutionInfo, TransactionResources};
+
+type Snapshot = HashMap<CompositeKey, (Option<Unit>, Option<SharedString>, DebugValue)>;
+
+fn record(recording: impl FnOnce()) -> Snapshot {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter: Snapshotter = recorder.snapshotter();
+    metrics::with_local_recorder(&recorder, recording);
+    snapshotter.snapshot().into_hashmap()
+}
+
+fn metric(snapshot: &Snapshot, kind: MetricKind, name: &'static str, result: &str) -> DebugValue {
+    let labels =
+        if result.is_empty() { vec![] } else { vec![Label::new("result", result.to_string())] };
+    let key = CompositeKey::new(kind, Key::from_parts(name, labels));
+    snapshot[&key].2.clone()
+}
+
+#[test]
+fn test_record_tx_execution() {
+    let snapshot = record(|| {
+        record_tx_execution(&Ok(TransactionExecutionInfo {
+            actual_resources: TransactionResources {
+                vm_resources: ExecutionResources { n_steps: 10, ..Default::default() },
+                n_reverted_steps: 5,
+                ..Default::default()
+            },
+            ..Default::default()
+        }));
+        record_tx_execution(&Ok(TransactionExecutionInfo {
+            revert_error: Some("Revert reason.".to_string()),
+            ..Default::default()
+        }));
+        record_tx_execution(&Err(TransactionExecutionError::ExecutionAborted));
+    });
+
+    for result in ["success", "reverted", "failed"] {
+        assert_eq!(
+            metric(&snapshot, MetricKind::Counter, TXS_EXECUTED, result),
+            DebugValue::Counter(1)
+        );
+    }
+    // Failed transactions have no resources.
+    let DebugValue::Histogram(tx_steps) = metric(&snapshot, MetricKind::Histogram, TX_STEPS, "")
+    else {
+        panic!("Expected a histogram.");
+    };
+    assert_eq!(tx_steps.into_iter().map(|n_steps| n_steps.0).collect::<Vec<_>>(), [15.0, 0.0]);
+}
+
+#[test]
+fn test_record_class_cache_lookup() {
+    let snapshot = record(|| {
+        record_class_cache_lookup(true);
+        record_class_cache_lookup(true);
+        record_class_cache_lookup(false);
+    });
+
+    assert_eq!(
+        metric(&snapshot, MetricKind::Counter, CLASS_CACHE_LOOKUPS, "hit"),
+        DebugValue::Counter(2)
+    );
+    assert_eq!(
+        metric(&snapshot, MetricKind::Counter, CLASS_CACHE_LOOKUPS, "miss"),
+        DebugValue::Counter(1)
+    );
+}
@@ -0,0 +1,48 @@
+//! The supported public API of the Blockifier.
+//!
+//! Downstream code should import from this module; items reachable only through other paths are
+//! considered internal and may change between releases without notice. Changes to the items
+//! re-exported here follow semantic versioning.
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub use crate::blockifier::block::SystemClock;
+pub use crate::blockifier::block::{
+    pre_process_block, BlockInfo, BlockNumberHashPair, Clock, DeterministicClock, GasPrices,
+};
+pub use crate::blockifier::config::{ConcurrencyConfig, TransactionExecutorConfig};
+pub use crate::blockifier::stateful_validator::{
+    StatefulValidator, StatefulValidatorError, StatefulValidatorResult,
+};
+pub use crate::blockifier::transaction_executor::{
+    RejectionReport, RejectionStage, TransactionExecutor, TransactionExecutorError,
+    TransactionExecutorResult, VisitedSegmentsMapping,
+};
+pub use crate::bouncer::{BouncerConfig, BouncerWeights};
+pub use crate::context::{
+    BlockContext, BlockContextBuilder, ChainInfo, FeeTokenAddresses, TransactionContext,
+};
+pub use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
+pub use crate::execution::contract_class::{
+    ClassInfo, ContractClass, ContractClassV0, ContractClassV1,
+};
+pub use crate::execution::errors::{ContractClassError, EntryPointExecutionError};
+pub use crate::execution::execution_policy::{ContractAction, ExecutionPolicy};
+pub use crate::execution::signature_verifier::SignatureVerifier;
+pub use crate::fee::fee_estimation::{estimate_fee, FeeEstimate, FeeEstimationFlags, PriceUnit};
+pub use crate::state::cached_state::{CachedState, CommitmentStateDiff, TransactionalState};
+pub use crate::state::errors::StateError;
+pub use crate::state::global_cache::GlobalContractCache;
+pub use crate::state::state_api::{State, StateReader, StateResult};
+pub use crate::transaction::account_transaction::AccountTransaction;
+pub use crate::transaction::errors::{
+    TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
+};
+pub use crate::transaction::objects::{
+    FeeType, GasVector, TransactionExecutionInfo, TransactionExecutionResult, TransactionResources,
+};
+pub use crate::transaction::transaction_execution::Transaction;
+pub use crate::transaction::transactions::{
+    DeclareTransaction, DeployAccountTransaction, ExecutableTransaction, InvokeTransaction,
+    L1HandlerTransaction,
+};
+pub use crate::versioned_constants::{StarknetVersion, VersionedConstants};
@@ -0,0 +1,535 @@
+use std::sync::Arc;
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use indexmap::IndexMap;
+use starknet_api::core::{
+    ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, EthAddress, Nonce,
+    PatriciaKey,
+};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{Calldata, EventContent, EventData, EventKey, Fee, L2ToL1Payload};
+use starknet_api::StarknetApiError;
+use thiserror::Error;
+
+use crate::execution::call_info::{
+    CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
+};
+use crate::execution::entry_point::{CallEntryPoint, CallType};
+use crate::state::cached_state::CommitmentStateDiff;
+use crate::transaction::objects::{GasVector, TransactionExecutionInfo, TransactionResources};
+
+#[cfg(test)]
+#[path = "proto_test.rs"]
+pub mod test;
+
+/// Types generated from `proto/execution.proto`.
+#[allow(clippy::all)]
+pub mod execution {
+    include!(concat!(env!("OUT_DIR"), "/blockifier.execution.rs"));
+}
+
+#[derive(Debug, Error)]
+pub enum ProtoConversionError {
+    #[error("Invalid value {value} of enum {enum_name}.")]
+    InvalidEnumValue { enum_name: &'static str, value: i32 },
+    #[error("Invalid length of an Ethereum address: expected 20 bytes, got {0}.")]
+    InvalidEthAddressLength(usize),
+    #[error("Invalid length of a field element: expected 32 bytes, got {0}.")]
+    InvalidFeltLength(usize),
+    #[error("Missing field {0}.")]
+    MissingField(&'static str),
+    #[error(transparent)]
+    StarknetApiError(#[from] StarknetApiError),
+    #[error(transparent)]
+    TryFromIntError(#[from] std::num::TryFromIntError),
+}
+
+pub type ProtoConversionResult<T> = Result<T, ProtoConversionError>;
+
+fn felt_bytes(felt: &StarkFelt) -> Vec<u8> {
+    felt.bytes().to_vec()
+}
+
+fn address_bytes(address: &ContractAddress) -> Vec<u8> {
+    felt_bytes(address.0.key())
+}
+
+fn proto_u64(value: usize) -> u64 {
+    u64::try_from(value).expect("Conversion of usize to u64 should not fail.")
+}
+
+fn felt_from_bytes(bytes: Vec<u8>) -> ProtoConversionResult<StarkFelt> {
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| ProtoConversionError::InvalidFeltLength(bytes.len()))?;
+    Ok(StarkFelt::new(bytes)?)
+}
+
+fn felts_from_bytes(felts: Vec<Vec<u8>>) -> ProtoConversionResult<Vec<StarkFelt>> {
+    felts.into_iter().map(felt_from_bytes).collect()
+}
+
+fn patricia_key_from_bytes(bytes: Vec<u8>) -> ProtoConversionResult<PatriciaKey> {
+    Ok(PatriciaKey::try_from(felt_from_bytes(bytes)?)?)
+}
+
+fn address_from_bytes(bytes: Vec<u8>) -> ProtoConversionResult<ContractAddress> {
+    Ok(ContractAddress(patricia_key_from_bytes(bytes)?))
+}
+
+fn eth_address_from_bytes(bytes: Vec<u8>) -> ProtoConversionResult<EthAddress> {
+    let n_bytes = bytes.len();
+    if n_bytes != 20 {
+        return Err(ProtoConversionError::InvalidEthAddressLength(n_bytes));
+    }
+    let mut felt_bytes = [0; 32];
+    felt_bytes[12..].copy_from_slice(&bytes);
+    Ok(EthAddress::try_from(StarkFelt::new(felt_bytes)?)?)
+}
+
+fn required<T>(field: Option<T>, field_name: &'static str) -> ProtoConversionResult<T> {
+    field.ok_or(ProtoConversionError::MissingField(field_name))
+}
+
+impl From<u128> for execution::Uint128 {
+    fn from(value: u128) -> Self {
+        let [high, low] = [value >> 64, value & u128::from(u64::MAX)]
+            .map(|half| u64::try_from(half).expect("Each half of a u128 fits in a u64."));
+        Self { high, low }
+    }
+}
+
+impl From<execution::Uint128> for u128 {
+    fn from(value: execution::Uint128) -> Self {
+        (u128::from(value.high) << 64) | u128::from(value.low)
+    }
+}
+
+impl From<&ExecutionResources> for execution::ExecutionResources {
+    fn from(resources: &ExecutionResources) -> Self {
+        Self {
+            n_steps: proto_u64(resources.n_steps),
+            n_memory_holes: proto_u64(resources.n_memory_holes),
+            builtin_instance_counter: resources
+                .builtin_instance_counter
+                .iter()
+                .map(|(builtin_name, &counter)| (builtin_name.clone(), proto_u64(counter)))
+                .collect(),
+        }
+    }
+}
+
+impl From<EntryPointType> for execution::EntryPointType {
+    fn from(entry_point_type: EntryPointType) -> Self {
+        match entry_point_type {
+            EntryPointType::External => Self::External,
+            EntryPointType::Constructor => Self::Constructor,
+            EntryPointType::L1Handler => Self::L1Handler,
+        }
+    }
+}
+
+impl From<CallType> for execution::CallType {
+    fn from(call_type: CallType) -> Self {
+        match call_type {
+            CallType::Call => Self::Call,
+            CallType::Delegate => Self::Delegate,
+        }
+    }
+}
+
+impl From<&CallEntryPoint> for execution::CallEntryPoint {
+    fn from(call: &CallEntryPoint) -> Self {
+        Self {
+            class_hash: call.class_hash.map(|class_hash| felt_bytes(&class_hash.0)),
+            code_address: call.code_address.as_ref().map(address_bytes),
+            entry_point_type: execution::EntryPointType::from(call.entry_point_type).into(),
+            entry_point_selector: felt_bytes(&call.entry_point_selector.0),
+            calldata: call.calldata.0.iter().map(felt_bytes).collect(),
+            storage_address: address_bytes(&call.storage_address),
+            caller_address: address_bytes(&call.caller_address),
+            call_type: execution::CallType::from(call.call_type).into(),
+            initial_gas: call.initial_gas,
+        }
+    }
+}
+
+impl From<&OrderedEvent> for execution::OrderedEvent {
+    fn from(ordered_event: &OrderedEvent) -> Self {
+        let event = &ordered_event.event;
+        Self {
+            order: proto_u64(ordered_event.order),
+            keys: event.keys.iter().map(|key| felt_bytes(&key.0)).collect(),
+            data: event.data.0.iter().map(felt_bytes).collect(),
+        }
+    }
+}
+
+impl From<&OrderedL2ToL1Message> for execution::OrderedL2ToL1Message {
+    fn from(ordered_message: &OrderedL2ToL1Message) -> Self {
+        let message = &ordered_message.message;
+        Self {
+            order: proto_u64(ordered_message.order),
+            to_address: message.to_address.0.as_bytes().to_vec(),
+            payload: message.payload.0.iter().map(felt_bytes).collect(),
+        }
+    }
+}
+
+impl From<&CallExecution> for execution::CallExecution {
+    fn from(call_execution: &CallExecution) -> Self {
+        Self {
+            retdata: call_execution.retdata.0.iter().map(felt_bytes).collect(),
+            events: call_execution.events.iter().map(Into::into).collect(),
+            l2_to_l1_messages: call_execution.l2_to_l1_messages.iter().map(Into::into).collect(),
+            failed: call_execution.failed,
+            gas_consumed: call_execution.gas_consumed,
+        }
+    }
+}
+
+impl From<&CallInfo> for execution::CallInfo {
+    fn from(call_info: &CallInfo) -> Self {
+        let mut accessed_storage_keys: Vec<_> =
+            call_info.accessed_storage_keys.iter().map(|key| felt_bytes(key.0.key())).collect();
+        accessed_storage_keys.sort_unstable();
+        Self {
+            call: Some((&call_info.call).into()),
+            execution: Some((&call_info.execution).into()),
+            resources: Some((&call_info.resources).into()),
+            inner_calls: call_info.inner_calls.iter().map(Into::into).collect(),
+            storage_read_values: call_info.storage_read_values.iter().map(felt_bytes).collect(),
+            accessed_storage_keys,
+        }
+    }
+}
+
+impl From<&GasVector> for execution::GasVector {
+    fn from(gas_vector: &GasVector) -> Self {
+        Self {
+            l1_gas: Some(gas_vector.l1_gas.into()),
+            l1_data_gas: Some(gas_vector.l1_data_gas.into()),
+            l2_gas: Some(gas_vector.l2_gas.into()),
+        }
+    }
+}
+
+impl From<&TransactionResources> for execution::TransactionResources {
+    fn from(resources: &TransactionResources) -> Self {
+        Self {
+            vm_resources: Some((&resources.vm_resources).into()),
+            n_reverted_steps: proto_u64(resources.n_reverted_steps),
+        }
+    }
+}
+
+impl From<&TransactionExecutionInfo> for execution::TransactionExecutionInfo {
+    fn from(tx_execution_info: &TransactionExecutionInfo) -> Self {
+        Self {
+            validate_call_info: tx_execution_info.validate_call_info.as_ref().map(Into::into),
+            execute_call_info: tx_execution_info.execute_call_info.as_ref().map(Into::into),
+            fee_transfer_call_info: tx_execution_info
+                .fee_transfer_call_info
+                .as_ref()
+                .map(Into::into),
+            actual_fee: Some(tx_execution_info.actual_fee.0.into()),
+            da_gas: Some((&tx_execution_info.da_gas).into()),
+            actual_resources: Some((&tx_execution_info.actual_resources).into()),
+            revert_error: tx_execution_info.revert_error.clone(),
+        }
+    }
+}
+
+impl From<&CommitmentStateDiff> for execution::StateDiff {
+    fn from(state_diff: &CommitmentStateDiff) -> Self {
+        let contract_felt =
+            |address: &ContractAddress, value: &StarkFelt| execution::ContractFelt {
+                contract_address: address_bytes(address),
+                value: felt_bytes(value),
+            };
+        Self {
+            address_to_class_hash: state_diff
+                .address_to_class_hash
+                .iter()
+                .map(|(address, class_hash)| contract_felt(address, &class_hash.0))
+                .collect(),
+            address_to_nonce: state_diff
+                .address_to_nonce
+                .iter()
+                .map(|(address, nonce)| contract_felt(address, &nonce.0))
+                .collect(),
+            storage_updates: state_diff
+                .storage_updates
+                .iter()
+                .flat_map(|(address, storage)| {
+                    storage.iter().map(move |(key, value)| execution::StorageUpdate {
+                        contract_address: address_bytes(address),
+                        key: felt_bytes(key.0.key()),
+                        value: felt_bytes(value),
+                    })
+                })
+                .collect(),
+            class_hash_to_compiled_class_hash: state_diff
+                .class_hash_to_compiled_class_hash
+                .iter()
+                .map(|(class_hash, compiled_class_hash)| execution::DeclaredClass {
+                    class_hash: felt_bytes(&class_hash.0),
+                    compiled_class_hash: felt_bytes(&compiled_class_hash.0),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<execution::ExecutionResources> for ExecutionResources {
+    type Error = ProtoConversionError;
+
+    fn try_from(resources: execution::ExecutionResources) -> ProtoConversionResult<Self> {
+        Ok(Self {
+            n_steps: usize::try_from(resources.n_steps)?,
+            n_memory_holes: usize::try_from(resources.n_memory_holes)?,
+            builtin_instance_counter: resources
+                .builtin_instance_counter
+                .into_iter()
+                .map(|(builtin_name, counter)| Ok((builtin_name, usize::try_from(counter)?)))
+                .collect::<ProtoConversionResult<_>>()?,
+        })
+    }
+}
+
+impl From<execution::EntryPointType> for EntryPointType {
+    fn from(entry_point_type: execution::EntryPointType) -> Self {
+        match entry_point_type {
+            execution::EntryPointType::External => Self::External,
+            execution::EntryPointType::Constructor => Self::Constructor,
+            execution::EntryPointType::L1Handler => Self::L1Handler,
+        }
+    }
+}
+
+impl From<execution::CallType> for CallType {
+    fn from(call_type: execution::CallType) -> Self {
+        match call_type {
+            execution::CallType::Call => Self::Call,
+            execution::CallType::Delegate => Self::Delegate,
+        }
+    }
+}
+
+impl TryFrom<execution::CallEntryPoint> for CallEntryPoint {
+    type Error = ProtoConversionError;
+
+    fn try_from(call: execution::CallEntryPoint) -> ProtoConversionResult<Self> {
+        let entry_point_type =
+            execution::EntryPointType::try_from(call.entry_point_type).map_err(|_| {
+                ProtoConversionError::InvalidEnumValue {
+                    enum_name: "EntryPointType",
+                    value: call.entry_point_type,
+                }
+            })?;
+        let call_type = execution::CallType::try_from(call.call_type).map_err(|_| {
+            ProtoConversionError::InvalidEnumValue { enum_name: "CallType", value: call.call_type }
+        })?;
+        Ok(Self {
+            class_hash: call.class_hash.map(felt_from_bytes).transpose()?.map(ClassHash),
+            code_address: call.code_address.map(address_from_bytes).transpose()?,
+            entry_point_type: entry_point_type.into(),
+            entry_point_selector: EntryPointSelector(felt_from_bytes(call.entry_point_selector)?),
+            calldata: Calldata(Arc::new(felts_from_bytes(call.calldata)?)),
+            storage_address: address_from_bytes(call.storage_address)?,
+            caller_address: address_from_bytes(call.caller_address)?,
+            call_type: call_type.into(),
+            initial_gas: call.initial_gas,
+        })
+    }
+}
+
+impl TryFrom<execution::OrderedEvent> for OrderedEvent {
+    type Error = ProtoConversionError;
+
+    fn try_from(ordered_event: execution::OrderedEvent) -> ProtoConversionResult<Self> {
+        Ok(Self {
+            order: usize::try_from(ordered_event.order)?,
+            event: EventContent {
+                keys: felts_from_bytes(ordered_event.keys)?.into_iter().map(EventKey).collect(),
+                data: EventData(felts_from_bytes(ordered_event.data)?),
+            },
+        })
+    }
+}
+
+impl TryFrom<execution::OrderedL2ToL1Message> for OrderedL2ToL1Message {
+    type Error = ProtoConversionError;
+
+    fn try_from(ordered_message: execution::OrderedL2ToL1Message) -> ProtoConversionResult<Self> {
+        Ok(Self {
+            order: usize::try_from(ordered_message.order)?,
+            message: MessageToL1 {
+                to_address: eth_address_from_bytes(ordered_message.to_address)?,
+                payload: L2ToL1Payload(felts_from_bytes(ordered_message.payload)?),
+            },
+        })
+    }
+}
+
+impl TryFrom<execution::CallExecution> for CallExecution {
+    type Error = ProtoConversionError;
+
+    fn try_from(call_execution: execution::CallExecution) -> ProtoConversionResult<Self> {
+        Ok(Self {
+            retdata: Retdata::from(felts_from_bytes(call_execution.retdata)?),
+            events: call_execution
+                .events
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<ProtoConversionResult<_>>()?,
+            l2_to_l1_messages: call_execution
+                .l2_to_l1_messages
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<ProtoConversionResult<_>>()?,
+            failed: call_execution.failed,
+            gas_consumed: call_execution.gas_consumed,
+        })
+    }
+}
+
+/// Note: the fields of the call info that are not part of the protobuf message (e.g., the storage
+/// accesses and the syscall counter) are left empty.
+impl TryFrom<execution::CallInfo> for CallInfo {
+    type Error = ProtoConversionError;
+
+    fn try_from(call_info: execution::CallInfo) -> ProtoConversionResult<Self> {
+        Ok(Self {
+            call: required(call_info.call, "CallInfo.call")?.try_into()?,
+            execution: required(call_info.execution, "CallInfo.execution")?.try_into()?,
+            resources: required(call_info.resources, "CallInfo.resources")?.try_into()?,
+            inner_calls: call_info
+                .inner_calls
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<ProtoConversionResult<_>>()?,
+            storage_read_values: felts_from_bytes(call_info.storage_read_values)?,
+            accessed_storage_keys: call_info
+                .accessed_storage_keys
+                .into_iter()
+                .map(|key| Ok(StorageKey(patricia_key_from_bytes(key)?)))
+                .collect::<ProtoConversionResult<_>>()?,
+            ..Default::default()
+        })
+    }
+}
+
+impl From<execution::GasVector> for GasVector {
+    fn from(gas_vector: execution::GasVector) -> Self {
+        let gas = |gas: Option<execution::Uint128>| gas.map_or(0, u128::from);
+        Self {
+            l1_gas: gas(gas_vector.l1_gas),
+            l1_data_gas: gas(gas_vector.l1_data_gas),
+            l2_gas: gas(gas_vector.l2_gas),
+        }
+    }
+}
+
+/// Note: the Starknet resources are not part of the protobuf message, and are left empty.
+impl TryFrom<execution::TransactionResources> for TransactionResources {
+    type Error = ProtoConversionError;
+
+    fn try_from(resources: execution::TransactionResources) -> ProtoConversionResult<Self> {
+        Ok(Self {
+            vm_resources: required(resources.vm_resources, "TransactionResources.vm_resources")?
+                .try_into()?,
+            n_reverted_steps: usize::try_from(resources.n_reverted_steps)?,
+            ..Default::default()
+        })
+    }
+}
+
+impl TryFrom<execution::TransactionExecutionInfo> for TransactionExecutionInfo {
+    type Error = ProtoConversionError;
+
+    fn try_from(
+        tx_execution_info: execution::TransactionExecutionInfo,
+    ) -> ProtoConversionResult<Self> {
+        Ok(Self {
+            validate_call_info: tx_execution_info
+                .validate_call_info
+                .map(TryInto::try_into)
+                .transpose()?,
+            execute_call_info: tx_execution_info
+                .execute_call_info
+                .map(TryInto::try_into)
+                .transpose()?,
+            fee_transfer_call_info: tx_execution_info
+                .fee_transfer_call_info
+                .map(TryInto::try_into)
+                .transpose()?,
+            actual_fee: Fee(required(
+                tx_execution_info.actual_fee,
+                "TransactionExecutionInfo.actual_fee",
+            )?
+            .into()),
+            da_gas: required(tx_execution_info.da_gas, "TransactionExecutionInfo.da_gas")?.into(),
+            actual_resources: required(
+                tx_execution_info.actual_resources,
+                "TransactionExecutionInfo.actual_resources",
+            )?
+            .try_into()?,
+            revert_error: tx_execution_info.revert_error,
+        })
+    }
+}
+
+impl TryFrom<execution::StateDiff> for CommitmentStateDiff {
+    type Error = ProtoConversionError;
+
+    fn try_from(state_diff: execution::StateDiff) -> ProtoConversionResult<Self> {
+        let mut storage_updates: IndexMap<ContractAddress, IndexMap<StorageKey, StarkFelt>> =
+            IndexMap::new();
+        for update in state_diff.storage_updates {
+            storage_updates
+                .entry(address_from_bytes(update.contract_address)?)
+                .or_default()
+                .insert(
+                    StorageKey(patricia_key_from_bytes(update.key)?),
+                    felt_from_bytes(update.value)?,
+                );
+        }
+
+        Ok(Self {
+            address_to_class_hash: state_diff
+                .address_to_class_hash
+                .into_iter()
+                .map(|entry| {
+                    Ok((
+                        address_from_bytes(entry.contract_address)?,
+                        ClassHash(felt_from_bytes(entry.value)?),
+                    ))
+                })
+                .collect::<ProtoConversionResult<_>>()?,
+            address_to_nonce: state_diff
+                .address_to_nonce
+                .into_iter()
+                .map(|entry| {
+                    Ok((
+                        address_from_bytes(entry.contract_address)?,
+                        Nonce(felt_from_bytes(entry.value)?),
+                    ))
+                })
+                .collect::<ProtoConversionResult<_>>()?,
+            storage_updates,
+            class_hash_to_compiled_class_hash: state_diff
+                .class_hash_to_compiled_class_hash
+                .into_iter()
+                .map(|declared_class| {
+                    Ok((
+                        ClassHash(felt_from_bytes(declared_class.class_hash)?),
+                        CompiledClassHash(felt_from_bytes(declared_class.compiled_class_hash)?),
+                    ))
+                })
+                .collect::<ProtoConversionResult<_>>()?,
+        })
+    }
+}
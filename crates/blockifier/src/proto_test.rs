@@ -0,0 +1,232 @@
+use std::collections::{HashMap, HashSet};
+
+use assert_matches::assert_matches;
+use cairo_vm::vm::runners::builtin_runner::RANGE_CHECK_BUILTIN_NAME;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use indexmap::IndexMap;
+use pretty_assertions::assert_eq;
+use prost::Message;
+use starknet_api::core::{
+    ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, EthAddress, Nonce,
+    PatriciaKey,
+};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{EventContent, EventData, EventKey, Fee, L2ToL1Payload};
+use starknet_api::{calldata, class_hash, contract_address, patricia_key, stark_felt};
+
+use crate::execution::call_info::{
+    CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
+};
+use crate::execution::entry_point::{CallEntryPoint, CallType};
+use crate::proto::{execution, ProtoConversionError};
+use crate::retdata;
+use crate::state::cached_state::CommitmentStateDiff;
+use crate::transaction::objects::{GasVector, TransactionExecutionInfo, TransactionResources};
+
+#[test]
+fn test_uint128() {
+    let value = u128::MAX - 1;
+    let proto_value = execution::Uint128::from(value);
+    assert_eq!(proto_value, execution::Uint128 { high: u64::MAX, low: u64::MAX - 1 });
+    assert_eq!(u128::from(proto_value), value);
+}
+
+#[test]
+fn test_transaction_execution_info() {
+    let inner_call = CallInfo {
+        call: CallEntryPoint { call_type: CallType::Delegate, ..Default::default() },
+        accessed_storage_keys: HashSet::from([
+            StorageKey(patricia_key!("0x2")),
+            StorageKey(patricia_key!("0x1")),
+        ]),
+        ..Default::default()
+    };
+    let tx_execution_info = TransactionExecutionInfo {
+        execute_call_info: Some(CallInfo {
+            call: CallEntryPoint {
+                class_hash: Some(class_hash!("0x3")),
+                storage_address: contract_address!("0x4"),
+                ..Default::default()
+            },
+            execution: CallExecution { retdata: retdata![stark_felt!(5_u8)], ..Default::default() },
+            inner_calls: vec![inner_call],
+            ..Default::default()
+        }),
+        revert_error: Some("Revert reason.".to_string()),
+        ..Default::default()
+    };
+
+    let encoded = execution::TransactionExecutionInfo::from(&tx_execution_info).encode_to_vec();
+    let decoded = execution::TransactionExecutionInfo::decode(encoded.as_slice()).unwrap();
+    assert!(decoded.validate_call_info.is_none());
+    assert_eq!(decoded.revert_error.as_deref(), Some("Revert reason."));
+
+    let execute_call_info = decoded.execute_call_info.unwrap();
+    let call = execute_call_info.call.unwrap();
+    assert_eq!(call.class_hash, Some(stark_felt!(3_u8).bytes().to_vec()));
+    assert_eq!(call.storage_address, stark_felt!(4_u8).bytes().to_vec());
+    assert_eq!(execute_call_info.execution.unwrap().retdata, [stark_felt!(5_u8).bytes().to_vec()]);
+
+    let inner_call = &execute_call_info.inner_calls[0];
+    assert_eq!(inner_call.call.as_ref().unwrap().call_type(), execution::CallType::Delegate);
+    assert_eq!(
+        inner_call.accessed_storage_keys,
+        [stark_felt!(1_u8).bytes().to_vec(), stark_felt!(2_u8).bytes().to_vec()]
+    );
+}
+
+#[test]
+fn test_state_diff() {
+    let address = contract_address!("0x1");
+    let state_diff = CommitmentStateDiff {
+        address_to_class_hash: IndexMap::new(),
+        address_to_nonce: IndexMap::from([(address, Nonce(stark_felt!(2_u8)))]),
+        storage_updates: IndexMap::from([(
+            address,
+            IndexMap::from([
+                (StorageKey(patricia_key!("0x4")), stark_felt!(5_u8)),
+                (StorageKey(patricia_key!("0x3")), stark_felt!(6_u8)),
+            ]),
+        )]),
+        class_hash_to_compiled_class_hash: IndexMap::new(),
+    };
+
+    let proto_state_diff = execution::StateDiff::from(&state_diff);
+    assert_eq!(
+        proto_state_diff.address_to_nonce,
+        [execution::ContractFelt {
+            contract_address: stark_felt!(1_u8).bytes().to_vec(),
+            value: stark_felt!(2_u8).bytes().to_vec(),
+        }]
+    );
+    // Storage updates keep the order of the state diff.
+    let storage_keys: Vec<_> =
+        proto_state_diff.storage_updates.iter().map(|update| update.key.clone()).collect();
+    assert_eq!(
+        storage_keys,
+        [stark_felt!(4_u8).bytes().to_vec(), stark_felt!(3_u8).bytes().to_vec()]
+    );
+}
+
+#[test]
+fn test_transaction_execution_info_round_trip() {
+    let inner_call = CallInfo {
+        call: CallEntryPoint {
+            code_address: Some(contract_address!("0x5")),
+            entry_point_type: EntryPointType::L1Handler,
+            call_type: CallType::Delegate,
+            ..Default::default()
+        },
+        execution: CallExecution {
+            events: vec![OrderedEvent {
+                order: 1,
+                event: EventContent {
+                    keys: vec![EventKey(stark_felt!(6_u8))],
+                    data: EventData(vec![stark_felt!(7_u8), stark_felt!(8_u8)]),
+                },
+            }],
+            l2_to_l1_messages: vec![OrderedL2ToL1Message {
+                order: 2,
+                message: MessageToL1 {
+                    to_address: EthAddress::try_from(stark_felt!(9_u8)).unwrap(),
+                    payload: L2ToL1Payload(vec![stark_felt!(10_u8)]),
+                },
+            }],
+            failed: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let tx_execution_info = TransactionExecutionInfo {
+        execute_call_info: Some(CallInfo {
+            call: CallEntryPoint {
+                class_hash: Some(class_hash!("0x3")),
+                entry_point_selector: EntryPointSelector(stark_felt!(11_u8)),
+                calldata: calldata![stark_felt!(12_u8)],
+                storage_address: contract_address!("0x4"),
+                caller_address: contract_address!("0x13"),
+                initial_gas: 14,
+                ..Default::default()
+            },
+            execution: CallExecution {
+                retdata: retdata![stark_felt!(15_u8)],
+                gas_consumed: 16,
+                ..Default::default()
+            },
+            resources: ExecutionResources {
+                n_steps: 17,
+                n_memory_holes: 18,
+                builtin_instance_counter: HashMap::from([(
+                    RANGE_CHECK_BUILTIN_NAME.to_string(),
+                    19,
+                )]),
+            },
+            inner_calls: vec![inner_call],
+            storage_read_values: vec![stark_felt!(20_u8)],
+            accessed_storage_keys: HashSet::from([StorageKey(patricia_key!("0x21"))]),
+            ..Default::default()
+        }),
+        actual_fee: Fee(u128::MAX),
+        da_gas: GasVector { l1_gas: 22, l1_data_gas: 23, l2_gas: 24 },
+        actual_resources: TransactionResources { n_reverted_steps: 25, ..Default::default() },
+        revert_error: Some("Revert reason.".to_string()),
+        ..Default::default()
+    };
+
+    let encoded = execution::TransactionExecutionInfo::from(&tx_execution_info).encode_to_vec();
+    let decoded = execution::TransactionExecutionInfo::decode(encoded.as_slice()).unwrap();
+    assert_eq!(TransactionExecutionInfo::try_from(decoded).unwrap(), tx_execution_info);
+}
+
+#[test]
+fn test_state_diff_round_trip() {
+    let address = contract_address!("0x1");
+    let other_address = contract_address!("0x2");
+    let state_diff = CommitmentStateDiff {
+        address_to_class_hash: IndexMap::from([(other_address, class_hash!("0x3"))]),
+        address_to_nonce: IndexMap::from([(address, Nonce(stark_felt!(4_u8)))]),
+        storage_updates: IndexMap::from([
+            (
+                other_address,
+                IndexMap::from([
+                    (StorageKey(patricia_key!("0x6")), stark_felt!(5_u8)),
+                    (StorageKey(patricia_key!("0x5")), stark_felt!(6_u8)),
+                ]),
+            ),
+            (address, IndexMap::from([(StorageKey(patricia_key!("0x7")), stark_felt!(8_u8))])),
+        ]),
+        class_hash_to_compiled_class_hash: IndexMap::from([(
+            class_hash!("0x3"),
+            CompiledClassHash(stark_felt!(9_u8)),
+        )]),
+    };
+
+    let encoded = execution::StateDiff::from(&state_diff).encode_to_vec();
+    let decoded = execution::StateDiff::decode(encoded.as_slice()).unwrap();
+    let round_tripped = CommitmentStateDiff::try_from(decoded).unwrap();
+    assert_eq!(round_tripped, state_diff);
+    // The order of the entries is kept as well.
+    assert!(round_tripped.storage_updates.iter().eq(state_diff.storage_updates.iter()));
+}
+
+#[test]
+fn test_invalid_proto_values() {
+    let call = execution::CallEntryPoint::from(&CallEntryPoint::default());
+    assert_matches!(
+        CallEntryPoint::try_from(execution::CallEntryPoint {
+            storage_address: vec![0; 31],
+            ..call.clone()
+        }),
+        Err(ProtoConversionError::InvalidFeltLength(31))
+    );
+    assert_matches!(
+        CallEntryPoint::try_from(execution::CallEntryPoint { call_type: 2, ..call }),
+        Err(ProtoConversionError::InvalidEnumValue { enum_name: "CallType", value: 2 })
+    );
+    assert_matches!(
+        CallInfo::try_from(execution::CallInfo::default()),
+        Err(ProtoConversionError::MissingField("CallInfo.call"))
+    );
+}
@@ -1,4 +1,6 @@
 pub mod cached_state;
+pub mod da_encoding;
 pub mod errors;
 pub mod global_cache;
 pub mod state_api;
+pub mod stateful_compression;
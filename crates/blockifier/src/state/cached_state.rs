@@ -1,8 +1,11 @@
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 
 use derive_more::IntoIterator;
-use indexmap::IndexMap;
+use indexmap::{IndexMap, IndexSet};
+use serde::Serialize;
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::hash::StarkFelt;
 use starknet_api::state::StorageKey;
@@ -18,6 +21,7 @@ use crate::utils::{strict_subtract_mappings, subtract_mappings};
 mod test;
 
 pub type ContractClassMapping = HashMap<ClassHash, ContractClass>;
+type StorageDiff = IndexMap<ContractAddress, IndexMap<StorageKey, StarkFelt>>;
 
 /// Caches read and write requests.
 ///
@@ -130,8 +134,24 @@ impl<S: StateReader> CachedState<S> {
     }
 
     pub fn to_state_diff(&mut self) -> CommitmentStateDiff {
-        type StorageDiff = IndexMap<ContractAddress, IndexMap<StorageKey, StarkFelt>>;
+        self.to_state_diff_with(|storage_diffs| StorageDiff::from(StorageView(storage_diffs)))
+    }
 
+    /// Same as [`Self::to_state_diff`], but groups the storage updates, which dominate the diff
+    /// size in large blocks, on `n_workers` threads.
+    /// The write set is sharded by contract address and the shards are merged in address order,
+    /// so the output does not depend on the number of workers.
+    pub fn to_state_diff_in_parallel(&mut self, n_workers: usize) -> CommitmentStateDiff {
+        self.to_state_diff_with(|storage_diffs| {
+            group_storage_updates_in_parallel(storage_diffs, n_workers)
+        })
+    }
+
+    /// Computes the state diff, grouping the storage updates by contract with the given function.
+    fn to_state_diff_with(
+        &mut self,
+        group_storage_updates: impl FnOnce(HashMap<StorageEntry, StarkFelt>) -> StorageDiff,
+    ) -> CommitmentStateDiff {
         // TODO(Gilad): Consider returning an error here, would require changing the API though.
         self.update_initial_values_of_write_only_access()
             .unwrap_or_else(|_| panic!("Cannot convert stateDiff to CommitmentStateDiff."));
@@ -144,11 +164,127 @@ impl<S: StateReader> CachedState<S> {
 
         CommitmentStateDiff {
             address_to_class_hash: IndexMap::from_iter(class_hash_updates),
-            storage_updates: StorageDiff::from(StorageView(storage_diffs)),
+            storage_updates: group_storage_updates(storage_diffs),
             class_hash_to_compiled_class_hash: IndexMap::from_iter(declared_classes),
             address_to_nonce: IndexMap::from_iter(nonces),
         }
     }
+
+    /// Returns the writes done through this state so far; see [`Self::to_state_diff_since`].
+    pub fn writes(&self) -> StateMaps {
+        self.cache.borrow().writes.clone()
+    }
+
+    /// Returns the values of the accessed cells in the underlying state; i.e., prior to any write.
+    pub fn initial_reads(&self) -> StateMaps {
+        self.cache.borrow().initial_reads.clone()
+    }
+
+    /// Returns the state diff between the state at the time `previous_writes` were taken (by
+    /// [`Self::writes`]) and the current state.
+    /// Unlike in [`Self::to_state_diff`], a cell written back to its initial value is included if
+    /// it had changed by then, so applying the successive diffs yields the current state.
+    pub fn to_state_diff_since(
+        &mut self,
+        previous_writes: &StateMaps,
+    ) -> StateResult<CommitmentStateDiff> {
+        self.update_initial_values_of_write_only_access()?;
+        let state_cache = self.cache.borrow();
+        let (writes, initial_reads) = (&state_cache.writes, &state_cache.initial_reads);
+        let storage_diffs =
+            updates_since(&writes.storage, &previous_writes.storage, &initial_reads.storage);
+        let class_hash_updates = updates_since(
+            &writes.class_hashes,
+            &previous_writes.class_hashes,
+            &initial_reads.class_hashes,
+        );
+        let nonces = updates_since(&writes.nonces, &previous_writes.nonces, &initial_reads.nonces);
+        // As in `to_state_diff`, declared classes are not compared against the initial reads.
+        let declared_classes = updates_since(
+            &writes.compiled_class_hashes,
+            &previous_writes.compiled_class_hashes,
+            &HashMap::new(),
+        );
+
+        Ok(CommitmentStateDiff {
+            address_to_class_hash: IndexMap::from_iter(class_hash_updates),
+            storage_updates: StorageDiff::from(StorageView(storage_diffs)),
+            class_hash_to_compiled_class_hash: IndexMap::from_iter(declared_classes),
+            address_to_nonce: IndexMap::from_iter(nonces),
+        })
+    }
+}
+
+/// Returns the entries of `writes` whose value differs from the previously written one, or, for
+/// cells not written before, from the initial one.
+fn updates_since<K, V>(
+    writes: &HashMap<K, V>,
+    previous_writes: &HashMap<K, V>,
+    initial_values: &HashMap<K, V>,
+) -> HashMap<K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone + PartialEq,
+{
+    writes
+        .iter()
+        .filter(|(key, value)| {
+            previous_writes.get(key).or_else(|| initial_values.get(key)) != Some(value)
+        })
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// Groups the given storage updates by contract address, using `n_workers` threads; with a single
+/// worker, or on targets without threads, the updates are grouped on the calling thread.
+/// Addresses and keys are sorted in the output.
+fn group_storage_updates_in_parallel(
+    storage_updates: HashMap<StorageEntry, StarkFelt>,
+    n_workers: usize,
+) -> StorageDiff {
+    type GroupedStorage = BTreeMap<ContractAddress, BTreeMap<StorageKey, StarkFelt>>;
+    let group_shard = |shard: Vec<(StorageEntry, StarkFelt)>| {
+        let mut grouped_shard = GroupedStorage::new();
+        for ((address, key), value) in shard {
+            grouped_shard.entry(address).or_default().insert(key, value);
+        }
+        grouped_shard
+    };
+    let into_index_map = |grouped_storage: GroupedStorage| {
+        grouped_storage
+            .into_iter()
+            .map(|(address, storage)| (address, IndexMap::from_iter(storage)))
+            .collect::<IndexMap<_, _>>()
+    };
+
+    if n_workers <= 1 || cfg!(all(target_arch = "wasm32", target_os = "unknown")) {
+        return into_index_map(group_shard(storage_updates.into_iter().collect()));
+    }
+
+    let mut shards: Vec<Vec<(StorageEntry, StarkFelt)>> = vec![Vec::new(); n_workers];
+    for (storage_entry, value) in storage_updates {
+        let mut hasher = DefaultHasher::new();
+        storage_entry.0.hash(&mut hasher);
+        let shard_index = usize::try_from(hasher.finish() % u64::try_from(n_workers).unwrap())
+            .expect("Shard index is smaller than the number of workers.");
+        shards[shard_index].push((storage_entry, value));
+    }
+
+    let grouped_shards: Vec<GroupedStorage> = std::thread::scope(|scope| {
+        let handles: Vec<_> =
+            shards.into_iter().map(|shard| scope.spawn(move || group_shard(shard))).collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("State diff worker thread panicked."))
+            .collect()
+    });
+
+    // Shards are disjoint by contract address, hence merging them loses no entry.
+    let mut merged_storage = GroupedStorage::new();
+    for grouped_shard in grouped_shards {
+        merged_storage.extend(grouped_shard);
+    }
+    into_index_map(merged_storage)
 }
 
 #[cfg(any(feature = "testing", test))]
@@ -159,6 +295,7 @@ impl<S: StateReader> From<S> for CachedState<S> {
 }
 
 impl<S: StateReader> StateReader for CachedState<S> {
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
     fn get_storage_at(
         &self,
         contract_address: ContractAddress,
@@ -177,6 +314,7 @@ impl<S: StateReader> StateReader for CachedState<S> {
         Ok(*value)
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
     fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
         let mut cache = self.cache.borrow_mut();
 
@@ -192,6 +330,7 @@ impl<S: StateReader> StateReader for CachedState<S> {
         Ok(*nonce)
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
     fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
         let mut cache = self.cache.borrow_mut();
 
@@ -206,6 +345,68 @@ impl<S: StateReader> StateReader for CachedState<S> {
         Ok(*class_hash)
     }
 
+    /// Reads all uncached nonces from the underlying state in a single batch.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
+    fn get_nonces_at(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        let mut cache = self.cache.borrow_mut();
+
+        let uncached_addresses: Vec<ContractAddress> = contract_addresses
+            .iter()
+            .copied()
+            .filter(|&address| cache.get_nonce_at(address).is_none())
+            .collect::<IndexSet<_>>()
+            .into_iter()
+            .collect();
+        if !uncached_addresses.is_empty() {
+            let nonces = self.state.get_nonces_at(&uncached_addresses)?;
+            for (address, nonce) in uncached_addresses.into_iter().zip(nonces) {
+                cache.set_nonce_initial_value(address, nonce);
+            }
+        }
+
+        Ok(contract_addresses
+            .iter()
+            .map(|address| {
+                *cache
+                    .get_nonce_at(*address)
+                    .unwrap_or_else(|| panic!("Cannot retrieve '{address:?}' from the cache."))
+            })
+            .collect())
+    }
+
+    /// Reads all uncached class hashes from the underlying state in a single batch.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
+    fn get_class_hashes_at(
+        &self,
+        contract_addresses: &[ContractAddress],
+    ) -> StateResult<Vec<ClassHash>> {
+        let mut cache = self.cache.borrow_mut();
+
+        let uncached_addresses: Vec<ContractAddress> = contract_addresses
+            .iter()
+            .copied()
+            .filter(|&address| cache.get_class_hash_at(address).is_none())
+            .collect::<IndexSet<_>>()
+            .into_iter()
+            .collect();
+        if !uncached_addresses.is_empty() {
+            let class_hashes = self.state.get_class_hashes_at(&uncached_addresses)?;
+            for (address, class_hash) in uncached_addresses.into_iter().zip(class_hashes) {
+                cache.set_class_hash_initial_value(address, class_hash);
+            }
+        }
+
+        Ok(contract_addresses
+            .iter()
+            .map(|address| {
+                *cache
+                    .get_class_hash_at(*address)
+                    .unwrap_or_else(|| panic!("Cannot retrieve '{address:?}' from the cache."))
+            })
+            .collect())
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
     fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
         let mut cache = self.cache.borrow_mut();
         let class_hash_to_class = &mut *self.class_hash_to_class.borrow_mut();
@@ -234,6 +435,7 @@ impl<S: StateReader> StateReader for CachedState<S> {
         Ok(contract_class)
     }
 
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
     fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
         let mut cache = self.cache.borrow_mut();
 
@@ -346,13 +548,13 @@ impl From<StorageView> for IndexMap<ContractAddress, IndexMap<StorageKey, StarkF
     }
 }
 
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct StateMaps {
-    pub(crate) nonces: HashMap<ContractAddress, Nonce>,
-    pub(crate) class_hashes: HashMap<ContractAddress, ClassHash>,
-    pub(crate) storage: HashMap<StorageEntry, StarkFelt>,
-    pub(crate) compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
-    pub(crate) declared_contracts: HashMap<ClassHash, bool>,
+    pub nonces: HashMap<ContractAddress, Nonce>,
+    pub class_hashes: HashMap<ContractAddress, ClassHash>,
+    pub storage: HashMap<StorageEntry, StarkFelt>,
+    pub compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
+    pub declared_contracts: HashMap<ClassHash, bool>,
 }
 
 impl StateMaps {
@@ -369,7 +571,7 @@ impl StateMaps {
 
 // Invariant: keys cannot be deleted from fields (only used internally by the cached state).
 #[derive(Debug, Default, PartialEq, Eq)]
-pub struct StateCache {
+pub(crate) struct StateCache {
     // Reader's cached information; initial values, read before any write operation (per cell).
     pub(crate) initial_reads: StateMaps,
 
@@ -528,6 +730,17 @@ impl<'a, S: State + ?Sized> StateReader for MutRefState<'a, S> {
         self.0.get_class_hash_at(contract_address)
     }
 
+    fn get_nonces_at(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        self.0.get_nonces_at(contract_addresses)
+    }
+
+    fn get_class_hashes_at(
+        &self,
+        contract_addresses: &[ContractAddress],
+    ) -> StateResult<Vec<ClassHash>> {
+        self.0.get_class_hashes_at(contract_addresses)
+    }
+
     fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
         self.0.get_compiled_contract_class(class_hash)
     }
@@ -554,8 +767,38 @@ impl<'a, S: StateReader> TransactionalState<'a, S> {
     pub fn abort(self) {}
 }
 
+/// Buffers the changes of an inner call on top of its caller's state, so that they can be reverted
+/// if the call fails.
+impl<'a, 'b> CachedState<MutRefState<'a, dyn State + 'b>> {
+    /// Commits the changes of the call to its caller's state. Calls only write storage and class
+    /// hashes (by deploying contracts and replacing classes).
+    pub fn commit(self) -> StateResult<()> {
+        let state = self.state.0;
+        let writes = self.cache.into_inner().writes;
+        for ((contract_address, key), value) in writes.storage {
+            state.set_storage_at(contract_address, key, value)?;
+        }
+        for (contract_address, class_hash) in writes.class_hashes {
+            state.set_class_hash_at(contract_address, class_hash)?;
+        }
+        for (class_hash, class_visited_pcs) in &self.visited_pcs {
+            state.add_visited_pcs(*class_hash, class_visited_pcs);
+        }
+
+        Ok(())
+    }
+
+    /// Drops the changes of the call; the PCs it visited are kept, as its code still ran.
+    pub fn revert(self) {
+        let state = self.state.0;
+        for (class_hash, class_visited_pcs) in &self.visited_pcs {
+            state.add_visited_pcs(*class_hash, class_visited_pcs);
+        }
+    }
+}
+
 /// Holds uncommitted changes induced on Starknet contracts.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
 pub struct CommitmentStateDiff {
     // Contract instance attributes (per address).
     pub address_to_class_hash: IndexMap<ContractAddress, ClassHash>,
@@ -566,6 +809,26 @@ pub struct CommitmentStateDiff {
     pub class_hash_to_compiled_class_hash: IndexMap<ClassHash, CompiledClassHash>,
 }
 
+impl CommitmentStateDiff {
+    /// Squashes the diffs of two consecutive blocks (or block ranges) into the diff of both; the
+    /// values of the newer diff supersede those of the older one (storage values, nonces, replaced
+    /// classes and recompiled class hashes). For `StateChanges`, see `StateChanges::merge`.
+    ///
+    /// Note: writes that restore an older value (0 -> 1 -> 0) are kept, as the base state is
+    /// unknown.
+    pub fn squash(older: Self, newer: Self) -> Self {
+        let mut squashed = older;
+        squashed.address_to_class_hash.extend(newer.address_to_class_hash);
+        squashed.address_to_nonce.extend(newer.address_to_nonce);
+        for (contract_address, storage_updates) in newer.storage_updates {
+            squashed.storage_updates.entry(contract_address).or_default().extend(storage_updates);
+        }
+        squashed.class_hash_to_compiled_class_hash.extend(newer.class_hash_to_compiled_class_hash);
+
+        squashed
+    }
+}
+
 /// Used to track the state diff size, which is determined by the number of new keys.
 /// Also, can be used to accuratly measure the contribution of a single (say, transactional)
 /// state to a cumulative state diff - provides set-like functionallities for this porpuse.
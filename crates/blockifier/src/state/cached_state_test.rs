@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use assert_matches::assert_matches;
 use indexmap::indexmap;
 use pretty_assertions::assert_eq;
+use proptest::prelude::*;
 use rstest::rstest;
 use starknet_api::core::PatriciaKey;
 use starknet_api::hash::StarkHash;
@@ -12,7 +13,9 @@ use crate::context::{BlockContext, ChainInfo};
 use crate::state::cached_state::*;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::golden::assert_json_golden;
 use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::state_model::{check_cached_state_operations, StateOperation};
 use crate::test_utils::CairoVersion;
 use crate::{compiled_class_hash, nonce, storage_key};
 const CONTRACT_ADDRESS: &str = "0x100";
@@ -162,6 +165,42 @@ fn get_and_increment_nonce() {
     assert_eq!(state.get_nonce_at(contract_address2).unwrap(), nonce2_plus_one);
 }
 
+#[test]
+fn get_nonces_and_class_hashes_in_batch() {
+    let contract_address1 = contract_address!("0x100");
+    let contract_address2 = contract_address!("0x200");
+    let uninitialized_address = contract_address!("0x300");
+    let class_hash = class_hash!("0x10");
+
+    let mut state = CachedState::from(DictStateReader {
+        address_to_nonce: HashMap::from([
+            (contract_address1, nonce!("0x1")),
+            (contract_address2, nonce!("0x2")),
+        ]),
+        address_to_class_hash: HashMap::from([(contract_address1, class_hash)]),
+        ..Default::default()
+    });
+    state.increment_nonce(contract_address2).unwrap();
+
+    // Written values take precedence; duplicate addresses are answered in place.
+    let addresses =
+        [contract_address2, contract_address1, uninitialized_address, contract_address1];
+    assert_eq!(
+        state.get_nonces_at(&addresses).unwrap(),
+        vec![nonce!("0x3"), nonce!("0x1"), Nonce::default(), nonce!("0x1")]
+    );
+    assert_eq!(
+        state.get_class_hashes_at(&addresses).unwrap(),
+        vec![ClassHash::default(), class_hash, ClassHash::default(), class_hash]
+    );
+
+    // The uncached values were read into the cache.
+    let initial_reads = &state.cache.borrow().initial_reads;
+    assert_eq!(initial_reads.nonces[&uninitialized_address], Nonce::default());
+    assert_eq!(initial_reads.nonces[&contract_address2], nonce!("0x2"));
+    assert_eq!(initial_reads.class_hashes.len(), 3);
+}
+
 #[test]
 fn get_contract_class() {
     // Positive flow.
@@ -285,6 +324,64 @@ fn cached_state_state_diff_conversion() {
     assert_eq!(expected_state_diff, state.to_state_diff());
 }
 
+#[rstest]
+fn test_state_diff_in_parallel(#[values(1, 3, 8)] n_workers: usize) {
+    let mut state: CachedState<DictStateReader> = CachedState::default();
+    for address in 1_u128..20 {
+        let contract_address = contract_address!(address);
+        for key in 1_u128..10 {
+            state
+                .set_storage_at(contract_address, storage_key!(key), stark_felt!(address * key))
+                .unwrap();
+        }
+        state.increment_nonce(contract_address).unwrap();
+    }
+
+    let state_diff = state.to_state_diff_in_parallel(n_workers);
+    assert_eq!(state_diff, state.to_state_diff());
+    // Storage updates are ordered by address, and then by key.
+    let addresses: Vec<_> = state_diff.storage_updates.keys().copied().collect();
+    assert!(addresses.windows(2).all(|pair| pair[0] < pair[1]));
+    for storage in state_diff.storage_updates.values() {
+        let keys: Vec<_> = storage.keys().copied().collect();
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
+
+#[test]
+fn test_state_diff_since() {
+    let contract_address = contract_address!("0x100");
+    let key = storage_key!(0x10_u16);
+    let initial_value = stark_felt!(1_u8);
+    let mut state = CachedState::from(DictStateReader {
+        storage_view: HashMap::from([((contract_address, key), initial_value)]),
+        ..Default::default()
+    });
+
+    // First chunk: change the cell.
+    state.set_storage_at(contract_address, key, stark_felt!(2_u8)).unwrap();
+    let first_diff = state.to_state_diff_since(&StateMaps::default()).unwrap();
+    assert_eq!(first_diff, state.to_state_diff());
+    let writes = state.writes();
+
+    // Second chunk: restore the cell, and bump a nonce.
+    state.set_storage_at(contract_address, key, initial_value).unwrap();
+    state.increment_nonce(contract_address).unwrap();
+    let second_diff = state.to_state_diff_since(&writes).unwrap();
+    assert_eq!(
+        second_diff.storage_updates,
+        indexmap! { contract_address => indexmap! { key => initial_value } }
+    );
+    assert_eq!(second_diff.address_to_nonce, indexmap! { contract_address => nonce!(1_u8) });
+    // The full diff omits the restored cell.
+    assert!(state.to_state_diff().storage_updates.is_empty());
+
+    // Nothing changed since.
+    let writes = state.writes();
+    let empty_diff = state.to_state_diff_since(&writes).unwrap();
+    assert!(empty_diff.storage_updates.is_empty() && empty_diff.address_to_nonce.is_empty());
+}
+
 fn create_state_changes_for_test<S: StateReader>(
     state: &mut CachedState<S>,
     sender_address: Option<ContractAddress>,
@@ -594,3 +691,132 @@ fn test_state_maps() {
 
     assert_eq!(maps, empty);
 }
+
+#[test]
+fn test_state_diff_json_golden() {
+    let state_diff = CommitmentStateDiff {
+        address_to_class_hash: indexmap! { contract_address!("0x1") => class_hash!("0x11") },
+        address_to_nonce: indexmap! { contract_address!("0x1") => nonce!(2_u8) },
+        storage_updates: indexmap! {
+            contract_address!("0x1") => indexmap! {
+                storage_key!(5_u8) => stark_felt!(6_u8),
+                storage_key!(7_u8) => stark_felt!(0_u8),
+            },
+        },
+        class_hash_to_compiled_class_hash: indexmap! {
+            class_hash!("0x11") => compiled_class_hash!("0x12"),
+        },
+    };
+    assert_json_golden("state_diff", &state_diff);
+}
+
+#[test]
+fn test_state_diff_squash() {
+    let (address0, address1) = (contract_address!("0x100"), contract_address!("0x200"));
+    let older = CommitmentStateDiff {
+        address_to_class_hash: indexmap! { address0 => class_hash!("0x10") },
+        address_to_nonce: indexmap! { address0 => nonce!(1_u8), address1 => nonce!(5_u8) },
+        storage_updates: indexmap! {
+            address0 => indexmap! {
+                storage_key!(1_u8) => stark_felt!(1_u8),
+                storage_key!(2_u8) => stark_felt!(2_u8),
+            },
+        },
+        class_hash_to_compiled_class_hash: indexmap! {
+            class_hash!("0x10") => compiled_class_hash!("0x11"),
+        },
+    };
+    let newer = CommitmentStateDiff {
+        // The class of the contract is replaced.
+        address_to_class_hash: indexmap! { address0 => class_hash!("0x20") },
+        address_to_nonce: indexmap! { address0 => nonce!(2_u8) },
+        storage_updates: indexmap! {
+            address0 => indexmap! { storage_key!(2_u8) => stark_felt!(0_u8) },
+            address1 => indexmap! { storage_key!(3_u8) => stark_felt!(3_u8) },
+        },
+        class_hash_to_compiled_class_hash: indexmap! {
+            class_hash!("0x20") => compiled_class_hash!("0x21"),
+        },
+    };
+
+    let expected_squashed_diff = CommitmentStateDiff {
+        address_to_class_hash: indexmap! { address0 => class_hash!("0x20") },
+        address_to_nonce: indexmap! { address0 => nonce!(2_u8), address1 => nonce!(5_u8) },
+        storage_updates: indexmap! {
+            address0 => indexmap! {
+                storage_key!(1_u8) => stark_felt!(1_u8),
+                storage_key!(2_u8) => stark_felt!(0_u8),
+            },
+            address1 => indexmap! { storage_key!(3_u8) => stark_felt!(3_u8) },
+        },
+        class_hash_to_compiled_class_hash: indexmap! {
+            class_hash!("0x10") => compiled_class_hash!("0x11"),
+            class_hash!("0x20") => compiled_class_hash!("0x21"),
+        },
+    };
+    assert_eq!(CommitmentStateDiff::squash(older, newer), expected_squashed_diff);
+}
+
+#[rstest]
+fn test_call_state_commit_and_revert(#[values(false, true)] failed: bool) {
+    let mut state: CachedState<DictStateReader> = CachedState::default();
+    let (address, key, class_hash) =
+        (contract_address!("0x100"), storage_key!(1_u8), class_hash!("0x10"));
+    let visited_pcs = HashSet::from([0, 1]);
+
+    let parent_state: &mut dyn State = &mut state;
+    let mut call_state = CachedState::new(MutRefState::new(parent_state));
+    call_state.set_storage_at(address, key, stark_felt!(1_u8)).unwrap();
+    call_state.set_class_hash_at(address, class_hash).unwrap();
+    call_state.add_visited_pcs(class_hash, &visited_pcs);
+    if failed {
+        call_state.revert();
+    } else {
+        call_state.commit().unwrap();
+    }
+
+    // Only the changes of a successful call are committed; the PCs visited by a failed call are
+    // kept.
+    let (expected_value, expected_class_hash) = if failed {
+        (StarkFelt::ZERO, ClassHash::default())
+    } else {
+        (stark_felt!(1_u8), class_hash)
+    };
+    assert_eq!(state.get_storage_at(address, key).unwrap(), expected_value);
+    assert_eq!(state.get_class_hash_at(address).unwrap(), expected_class_hash);
+    assert_eq!(state.visited_pcs, HashMap::from([(class_hash, visited_pcs)]));
+}
+
+fn state_operation() -> impl Strategy<Value = StateOperation> {
+    prop_oneof![
+        (any::<u8>(), any::<u8>())
+            .prop_map(|(address, key)| StateOperation::GetStorage { address, key }),
+        (any::<u8>(), any::<u8>(), any::<u8>())
+            .prop_map(|(address, key, value)| StateOperation::SetStorage { address, key, value }),
+        any::<u8>().prop_map(|address| StateOperation::GetNonce { address }),
+        any::<u8>().prop_map(|address| StateOperation::IncrementNonce { address }),
+        any::<u8>().prop_map(|address| StateOperation::GetClassHash { address }),
+        (any::<u8>(), any::<u8>()).prop_map(|(address, class_hash)| {
+            StateOperation::DeployContract { address, class_hash }
+        }),
+        (any::<u8>(), any::<u8>()).prop_map(|(class_hash, compiled_class_hash)| {
+            StateOperation::SetCompiledClassHash { class_hash, compiled_class_hash }
+        }),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn test_cached_state_against_model(
+        operations in prop::collection::vec(state_operation(), 0..64)
+    ) {
+        check_cached_state_operations(&operations);
+    }
+
+    #[test]
+    fn test_cached_state_against_model_raw_bytes(
+        data in prop::collection::vec(any::<u8>(), 0..256)
+    ) {
+        check_cached_state_operations(&StateOperation::decode_all(&data));
+    }
+}
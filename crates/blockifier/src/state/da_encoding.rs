@@ -0,0 +1,189 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use indexmap::IndexMap;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+use starknet_api::StarknetApiError;
+use thiserror::Error;
+
+use crate::state::cached_state::CommitmentStateDiff;
+use crate::state::errors::StateError;
+use crate::state::state_api::StateReader;
+use crate::utils::u128_from_usize;
+
+#[cfg(test)]
+#[path = "da_encoding_test.rs"]
+pub mod da_encoding_test;
+
+#[derive(Debug, Error)]
+pub enum DaEncodingError {
+    #[error("Invalid contract header {0:?}.")]
+    InvalidContractHeader(StarkFelt),
+    #[error("Invalid length {0:?}.")]
+    InvalidLength(StarkFelt),
+    #[error("Nonce {nonce:?} of contract {contract_address:?} does not fit in 64 bits.")]
+    NonceOutOfRange { contract_address: ContractAddress, nonce: Nonce },
+    #[error(transparent)]
+    StarknetApiError(#[from] StarknetApiError),
+    #[error(transparent)]
+    StateError(#[from] StateError),
+    #[error("Encoded state diff has {0} trailing felts.")]
+    TrailingData(usize),
+    #[error("Encoded state diff ended unexpectedly.")]
+    UnexpectedEnd,
+}
+
+pub type DaEncodingResult<T> = Result<T, DaEncodingError>;
+
+/// Encodes a state diff into the felt array posted to L1 as data availability:
+/// 1. The number of modified contracts.
+/// 2. For each contract, in ascending order of address: its address; a header word, `class_flag *
+///    2^128 + new_nonce * 2^64 + n_storage_updates`; the new class hash, if the class flag is set
+///    (i.e., the contract was deployed or its class replaced); and the pairs of storage key and
+///    value, in ascending order of key.
+/// 3. The number of declared classes, followed by the pairs of class hash and compiled class hash,
+///    in ascending order of class hash.
+///
+/// The new nonce is encoded for every contract; the nonces of contracts whose nonce was not
+/// updated are read from the state (after the state diff).
+pub fn encode_state_diff(
+    state_diff: &CommitmentStateDiff,
+    state: &impl StateReader,
+) -> DaEncodingResult<Vec<StarkFelt>> {
+    let contract_addresses: BTreeSet<ContractAddress> = state_diff
+        .address_to_class_hash
+        .keys()
+        .chain(state_diff.address_to_nonce.keys())
+        .chain(state_diff.storage_updates.keys())
+        .copied()
+        .collect();
+
+    let mut data = vec![StarkFelt::from(u128_from_usize(contract_addresses.len()))];
+    for contract_address in contract_addresses {
+        let storage_updates: BTreeMap<StorageKey, StarkFelt> = state_diff
+            .storage_updates
+            .get(&contract_address)
+            .map(|storage_updates| storage_updates.clone().into_iter().collect())
+            .unwrap_or_default();
+        let nonce = match state_diff.address_to_nonce.get(&contract_address) {
+            Some(nonce) => *nonce,
+            None => state.get_nonce_at(contract_address)?,
+        };
+        let class_hash = state_diff.address_to_class_hash.get(&contract_address);
+
+        let contract_header = ContractHeader {
+            was_class_updated: class_hash.is_some(),
+            nonce: felt_to_u64(nonce.0)
+                .ok_or(DaEncodingError::NonceOutOfRange { contract_address, nonce })?,
+            n_storage_updates: u64::try_from(storage_updates.len())
+                .expect("The number of storage updates fits in 64 bits."),
+        };
+        data.extend([*contract_address.0.key(), contract_header.encode()]);
+        data.extend(class_hash.map(|class_hash| class_hash.0));
+        data.extend(storage_updates.into_iter().flat_map(|(key, value)| [*key.0.key(), value]));
+    }
+
+    let declared_classes: BTreeMap<ClassHash, CompiledClassHash> =
+        state_diff.class_hash_to_compiled_class_hash.clone().into_iter().collect();
+    data.push(StarkFelt::from(u128_from_usize(declared_classes.len())));
+    data.extend(
+        declared_classes
+            .into_iter()
+            .flat_map(|(class_hash, compiled_class_hash)| [class_hash.0, compiled_class_hash.0]),
+    );
+
+    Ok(data)
+}
+
+/// Decodes a state diff encoded by `encode_state_diff`. Note that the nonces of all the encoded
+/// contracts are decoded, including those that were not updated.
+pub fn decode_state_diff(data: &[StarkFelt]) -> DaEncodingResult<CommitmentStateDiff> {
+    let mut data = data.iter().copied();
+    let mut next = || data.next().ok_or(DaEncodingError::UnexpectedEnd);
+
+    let mut state_diff = CommitmentStateDiff {
+        address_to_class_hash: IndexMap::new(),
+        address_to_nonce: IndexMap::new(),
+        storage_updates: IndexMap::new(),
+        class_hash_to_compiled_class_hash: IndexMap::new(),
+    };
+    let n_contracts = decode_length(next()?)?;
+    for _ in 0..n_contracts {
+        let contract_address = ContractAddress(PatriciaKey::try_from(next()?)?);
+        let contract_header = ContractHeader::decode(next()?)?;
+        if contract_header.was_class_updated {
+            state_diff.address_to_class_hash.insert(contract_address, ClassHash(next()?));
+        }
+        state_diff
+            .address_to_nonce
+            .insert(contract_address, Nonce(StarkFelt::from(contract_header.nonce)));
+
+        let mut storage_updates = IndexMap::new();
+        for _ in 0..contract_header.n_storage_updates {
+            storage_updates.insert(StorageKey(PatriciaKey::try_from(next()?)?), next()?);
+        }
+        if !storage_updates.is_empty() {
+            state_diff.storage_updates.insert(contract_address, storage_updates);
+        }
+    }
+
+    let n_declared_classes = decode_length(next()?)?;
+    for _ in 0..n_declared_classes {
+        state_diff
+            .class_hash_to_compiled_class_hash
+            .insert(ClassHash(next()?), CompiledClassHash(next()?));
+    }
+
+    let n_trailing_felts = data.count();
+    if n_trailing_felts > 0 {
+        return Err(DaEncodingError::TrailingData(n_trailing_felts));
+    }
+
+    Ok(state_diff)
+}
+
+/// The word following the address of a contract in the encoding.
+struct ContractHeader {
+    was_class_updated: bool,
+    nonce: u64,
+    n_storage_updates: u64,
+}
+
+impl ContractHeader {
+    fn encode(&self) -> StarkFelt {
+        let mut bytes = [0; 32];
+        bytes[15] = u8::from(self.was_class_updated);
+        bytes[16..24].copy_from_slice(&self.nonce.to_be_bytes());
+        bytes[24..].copy_from_slice(&self.n_storage_updates.to_be_bytes());
+        StarkFelt::new(bytes).expect("A contract header is smaller than 2^129.")
+    }
+
+    fn decode(header: StarkFelt) -> DaEncodingResult<Self> {
+        let bytes = header.bytes();
+        let (high_bytes, class_flag) = (&bytes[..15], bytes[15]);
+        if high_bytes.iter().any(|byte| *byte != 0) || class_flag > 1 {
+            return Err(DaEncodingError::InvalidContractHeader(header));
+        }
+
+        Ok(Self {
+            was_class_updated: class_flag == 1,
+            nonce: u64::from_be_bytes(bytes[16..24].try_into().expect("Slice of 8 bytes.")),
+            n_storage_updates: u64::from_be_bytes(
+                bytes[24..].try_into().expect("Slice of 8 bytes."),
+            ),
+        })
+    }
+}
+
+fn felt_to_u64(felt: StarkFelt) -> Option<u64> {
+    let bytes = felt.bytes();
+    if bytes[..24].iter().any(|byte| *byte != 0) {
+        return None;
+    }
+    Some(u64::from_be_bytes(bytes[24..].try_into().expect("Slice of 8 bytes.")))
+}
+
+fn decode_length(length: StarkFelt) -> DaEncodingResult<u64> {
+    felt_to_u64(length).ok_or(DaEncodingError::InvalidLength(length))
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use indexmap::indexmap;
+use pretty_assertions::assert_eq;
+use starknet_api::core::{ClassHash, ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::{class_hash, contract_address, patricia_key, stark_felt};
+
+use crate::state::cached_state::CommitmentStateDiff;
+use crate::state::da_encoding::{decode_state_diff, encode_state_diff, DaEncodingError};
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::{compiled_class_hash, nonce, storage_key};
+
+#[test]
+fn test_da_encoding() {
+    let (address0, address1) = (contract_address!("0x100"), contract_address!("0x200"));
+    // The second contract is deployed; its nonce is read from the state.
+    let state_diff = CommitmentStateDiff {
+        address_to_class_hash: indexmap! { address1 => class_hash!("0x20") },
+        address_to_nonce: indexmap! { address0 => nonce!(1_u8) },
+        storage_updates: indexmap! {
+            address1 => indexmap! {
+                storage_key!(5_u8) => stark_felt!(7_u8),
+                storage_key!(1_u8) => stark_felt!(9_u8),
+            },
+        },
+        class_hash_to_compiled_class_hash: indexmap! {
+            class_hash!("0x30") => compiled_class_hash!("0x31"),
+            class_hash!("0x10") => compiled_class_hash!("0x11"),
+        },
+    };
+    let state = DictStateReader {
+        address_to_nonce: HashMap::from([(address1, nonce!(3_u8))]),
+        ..Default::default()
+    };
+
+    // Contracts, storage keys and classes are sorted.
+    let encoded_state_diff = encode_state_diff(&state_diff, &state).unwrap();
+    let expected_encoded_state_diff = vec![
+        stark_felt!(2_u8),
+        *address0.0.key(),
+        // Nonce 1, no storage updates.
+        stark_felt!("0x10000000000000000"),
+        *address1.0.key(),
+        // Class flag, nonce 3, 2 storage updates.
+        stark_felt!("0x100000000000000030000000000000002"),
+        stark_felt!("0x20"),
+        stark_felt!(1_u8),
+        stark_felt!(9_u8),
+        stark_felt!(5_u8),
+        stark_felt!(7_u8),
+        stark_felt!(2_u8),
+        stark_felt!("0x10"),
+        stark_felt!("0x11"),
+        stark_felt!("0x30"),
+        stark_felt!("0x31"),
+    ];
+    assert_eq!(encoded_state_diff, expected_encoded_state_diff);
+
+    // The nonces of all the contracts are decoded.
+    let mut expected_decoded_state_diff = state_diff;
+    expected_decoded_state_diff.address_to_nonce.insert(address1, nonce!(3_u8));
+    assert_eq!(decode_state_diff(&encoded_state_diff).unwrap(), expected_decoded_state_diff);
+
+    // Truncated or extended data is rejected.
+    assert_matches!(
+        decode_state_diff(&encoded_state_diff[..encoded_state_diff.len() - 1]),
+        Err(DaEncodingError::UnexpectedEnd)
+    );
+    let extended_encoded_state_diff = [encoded_state_diff, vec![StarkFelt::ZERO]].concat();
+    assert_matches!(
+        decode_state_diff(&extended_encoded_state_diff),
+        Err(DaEncodingError::TrailingData(1))
+    );
+}
@@ -1,5 +1,6 @@
 use cairo_vm::types::errors::program_errors::ProgramError;
 use starknet_api::core::{ClassHash, ContractAddress};
+use starknet_api::state::StorageKey;
 use starknet_api::StarknetApiError;
 use thiserror::Error;
 
@@ -12,6 +13,8 @@ pub enum StateError {
         constants::STORED_BLOCK_HASH_BUFFER
     )]
     OldBlockHashNotProvided,
+    #[error("No alias is allocated for {0:?}.")]
+    MissingAlias(StorageKey),
     #[error("Cannot deploy contract at address 0.")]
     OutOfRangeContractAddress,
     #[error(transparent)]
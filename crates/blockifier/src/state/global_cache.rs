@@ -24,7 +24,10 @@ impl GlobalContractCache {
     }
 
     pub fn get(&self, class_hash: &ClassHash) -> Option<ContractClass> {
-        self.lock().cache_get(class_hash).cloned()
+        let contract_class = self.lock().cache_get(class_hash).cloned();
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_class_cache_lookup(contract_class.is_some());
+        contract_class
     }
 
     pub fn set(&self, class_hash: ClassHash, contract_class: ContractClass) {
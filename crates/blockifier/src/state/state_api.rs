@@ -39,6 +39,22 @@ pub trait StateReader {
     /// Default: 0 (uninitialized class hash) for an uninitialized contract address.
     fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash>;
 
+    /// Returns the nonces of the given contract instances, in order.
+    /// Default: one `get_nonce_at` per address; readers backed by a database may override it to
+    /// fetch all nonces in a single query.
+    fn get_nonces_at(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        contract_addresses.iter().map(|&address| self.get_nonce_at(address)).collect()
+    }
+
+    /// Returns the class hashes at the given contract instances, in order.
+    /// Default: one `get_class_hash_at` per address; see `get_nonces_at`.
+    fn get_class_hashes_at(
+        &self,
+        contract_addresses: &[ContractAddress],
+    ) -> StateResult<Vec<ClassHash>> {
+        contract_addresses.iter().map(|&address| self.get_class_hash_at(address)).collect()
+    }
+
     /// Returns the contract class of the given class hash.
     fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass>;
 
@@ -0,0 +1,142 @@
+use std::collections::BTreeSet;
+
+use cairo_felt::Felt252;
+use indexmap::IndexMap;
+use starknet_api::core::ContractAddress;
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use crate::abi::constants;
+use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
+use crate::state::cached_state::{CachedState, CommitmentStateDiff, StateMaps};
+use crate::state::errors::StateError;
+use crate::state::state_api::{State, StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "stateful_compression_test.rs"]
+pub mod stateful_compression_test;
+
+// The alias contract stores the next available alias under this key.
+const ALIAS_COUNTER_STORAGE_KEY: u64 = 0;
+// Smaller values are short enough as they are, and are not aliased; this is also the first alias.
+const MIN_VALUE_FOR_ALIAS_ALLOC: u64 = 128;
+// The storage keys of contracts up to this address (i.e., system contracts) are not aliased.
+const MAX_NON_COMPRESSED_CONTRACT_ADDRESS: u64 = 15;
+
+pub(crate) fn alias_contract_address() -> ContractAddress {
+    ContractAddress::from(constants::ALIAS_CONTRACT_ADDRESS)
+}
+
+fn is_aliased(key: StorageKey) -> bool {
+    *key.0.key() >= StarkFelt::from(MIN_VALUE_FOR_ALIAS_ALLOC)
+}
+
+fn has_compressed_storage(contract_address: ContractAddress) -> bool {
+    *contract_address.0.key() > StarkFelt::from(MAX_NON_COMPRESSED_CONTRACT_ADDRESS)
+}
+
+/// Allocates aliases, in the alias contract, for the contract addresses and storage keys modified
+/// in the state that have none yet; to be called at the end of the block, before taking its state
+/// diff. Addresses are aliased first, each group in ascending order. Returns the writes made to
+/// the alias contract.
+pub fn allocate_aliases<S: StateReader>(state: &mut CachedState<S>) -> StateResult<StateMaps> {
+    let state_diff = state.to_state_diff();
+    let contract_addresses: BTreeSet<ContractAddress> = state_diff
+        .address_to_class_hash
+        .keys()
+        .chain(state_diff.address_to_nonce.keys())
+        .chain(state_diff.storage_updates.keys())
+        .copied()
+        .collect();
+    let storage_keys: BTreeSet<StorageKey> = state_diff
+        .storage_updates
+        .iter()
+        .filter(|(contract_address, _)| has_compressed_storage(**contract_address))
+        .flat_map(|(_, storage_updates)| storage_updates.keys().copied())
+        .collect();
+
+    let alias_contract_address = alias_contract_address();
+    let counter_key = StorageKey::from(ALIAS_COUNTER_STORAGE_KEY);
+    let counter = state.get_storage_at(alias_contract_address, counter_key)?;
+    let mut next_alias = if counter == StarkFelt::ZERO {
+        Felt252::from(MIN_VALUE_FOR_ALIAS_ALLOC)
+    } else {
+        stark_felt_to_felt(counter)
+    };
+
+    let mut writes = StateMaps::default();
+    let keys =
+        contract_addresses.into_iter().map(|address| StorageKey(address.0)).chain(storage_keys);
+    for key in keys.filter(|key| is_aliased(*key)) {
+        // Also skips keys aliased earlier in this loop.
+        if state.get_storage_at(alias_contract_address, key)? == StarkFelt::ZERO {
+            let alias = felt_to_stark_felt(&next_alias);
+            state.set_storage_at(alias_contract_address, key, alias)?;
+            writes.storage.insert((alias_contract_address, key), alias);
+            next_alias += Felt252::from(1_u8);
+        }
+    }
+
+    // The counter is initialized by the first allocation, even if no alias is allocated.
+    let next_alias = felt_to_stark_felt(&next_alias);
+    if next_alias != counter {
+        state.set_storage_at(alias_contract_address, counter_key, next_alias)?;
+        writes.storage.insert((alias_contract_address, counter_key), next_alias);
+    }
+
+    Ok(writes)
+}
+
+/// Compresses a state diff by replacing its contract addresses and storage keys with their
+/// aliases; values and class hashes are kept as is. The aliases must have been allocated (see
+/// `allocate_aliases`) in the given state.
+pub fn compress(
+    state_diff: &CommitmentStateDiff,
+    state: &impl StateReader,
+) -> StateResult<CommitmentStateDiff> {
+    let compress_address = |contract_address: &ContractAddress| -> StateResult<ContractAddress> {
+        Ok(ContractAddress(get_alias(state, StorageKey(contract_address.0))?.0))
+    };
+
+    let mut storage_updates = IndexMap::new();
+    for (contract_address, contract_storage_updates) in &state_diff.storage_updates {
+        let compressed_storage_updates = if has_compressed_storage(*contract_address) {
+            contract_storage_updates
+                .iter()
+                .map(|(key, value)| Ok((get_alias(state, *key)?, *value)))
+                .collect::<StateResult<_>>()?
+        } else {
+            contract_storage_updates.clone()
+        };
+        storage_updates.insert(compress_address(contract_address)?, compressed_storage_updates);
+    }
+
+    Ok(CommitmentStateDiff {
+        address_to_class_hash: state_diff
+            .address_to_class_hash
+            .iter()
+            .map(|(contract_address, class_hash)| {
+                Ok((compress_address(contract_address)?, *class_hash))
+            })
+            .collect::<StateResult<_>>()?,
+        address_to_nonce: state_diff
+            .address_to_nonce
+            .iter()
+            .map(|(contract_address, nonce)| Ok((compress_address(contract_address)?, *nonce)))
+            .collect::<StateResult<_>>()?,
+        storage_updates,
+        class_hash_to_compiled_class_hash: state_diff.class_hash_to_compiled_class_hash.clone(),
+    })
+}
+
+fn get_alias(state: &impl StateReader, key: StorageKey) -> StateResult<StorageKey> {
+    if !is_aliased(key) {
+        return Ok(key);
+    }
+
+    let alias = state.get_storage_at(alias_contract_address(), key)?;
+    if alias == StarkFelt::ZERO {
+        return Err(StateError::MissingAlias(key));
+    }
+    Ok(StorageKey::try_from(alias)?)
+}
@@ -0,0 +1,65 @@
+use indexmap::indexmap;
+use pretty_assertions::assert_eq;
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::{contract_address, patricia_key, stark_felt};
+
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::{State, StateReader};
+use crate::state::stateful_compression::{alias_contract_address, allocate_aliases, compress};
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::{nonce, storage_key};
+
+#[test]
+fn test_stateful_compression() {
+    let mut state = CachedState::from(DictStateReader::default());
+    let (contract_address, system_contract_address) =
+        (contract_address!("0x200"), contract_address!("0x1"));
+    let value = stark_felt!(7_u8);
+    // Small keys are not aliased, nor are the storage keys of system contracts.
+    state.set_storage_at(contract_address, storage_key!(5_u8), value).unwrap();
+    state.set_storage_at(contract_address, storage_key!("0x300"), value).unwrap();
+    state.set_storage_at(system_contract_address, storage_key!("0x400"), value).unwrap();
+    state.increment_nonce(contract_address!("0x500")).unwrap();
+
+    let alias_writes = allocate_aliases(&mut state).unwrap();
+    let alias_contract_address = alias_contract_address();
+    let get_alias = |key| state.get_storage_at(alias_contract_address, key).unwrap();
+    // Addresses are aliased first, starting at 128; the counter holds the next alias.
+    assert_eq!(get_alias(storage_key!("0x200")), stark_felt!(128_u8));
+    assert_eq!(get_alias(storage_key!("0x500")), stark_felt!(129_u8));
+    assert_eq!(get_alias(storage_key!("0x300")), stark_felt!(130_u8));
+    assert_eq!(get_alias(storage_key!("0x400")), StarkFelt::ZERO);
+    assert_eq!(get_alias(storage_key!(0_u8)), stark_felt!(131_u8));
+    // The returned writes are those made to the alias contract.
+    assert_eq!(alias_writes.storage.len(), 4);
+    assert!(alias_writes.storage.iter().all(|((address, key), alias)| {
+        *address == alias_contract_address && *alias == get_alias(*key)
+    }));
+
+    let state_diff = state.to_state_diff();
+    let compressed_state_diff = compress(&state_diff, &state).unwrap();
+    assert_eq!(
+        compressed_state_diff.storage_updates,
+        indexmap! {
+            contract_address!("0x80") => indexmap! {
+                storage_key!(5_u8) => value,
+                storage_key!("0x82") => value,
+            },
+            system_contract_address => indexmap! { storage_key!("0x400") => value },
+            alias_contract_address => state_diff.storage_updates[&alias_contract_address].clone(),
+        }
+    );
+    assert_eq!(
+        compressed_state_diff.address_to_nonce,
+        indexmap! { contract_address!("0x81") => nonce!(1_u8) }
+    );
+
+    // Existing aliases are reused.
+    state.set_storage_at(contract_address, storage_key!("0x300"), stark_felt!(8_u8)).unwrap();
+    assert!(allocate_aliases(&mut state).unwrap().storage.is_empty());
+    assert_eq!(
+        state.get_storage_at(alias_contract_address, storage_key!(0_u8)).unwrap(),
+        stark_felt!(131_u8)
+    );
+}
@@ -2,9 +2,14 @@ pub mod contracts;
 pub mod declare;
 pub mod deploy_account;
 pub mod dict_state_reader;
+pub mod fixtures;
+pub mod golden;
 pub mod initial_test_state;
 pub mod invoke;
 pub mod prices;
+pub mod resources;
+pub mod state_builder;
+pub mod state_model;
 pub mod struct_impls;
 use std::collections::HashMap;
 use std::fs;
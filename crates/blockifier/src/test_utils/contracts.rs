@@ -1,4 +1,4 @@
-use starknet_api::core::{ClassHash, ContractAddress, PatriciaKey};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, PatriciaKey};
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
 use starknet_api::hash::StarkHash;
 use starknet_api::{class_hash, contract_address, patricia_key};
@@ -158,6 +158,14 @@ impl FeatureContract {
         }
     }
 
+    /// Returns the compiled class hash of the class; Cairo 0 classes have none, so the default.
+    pub fn get_compiled_class_hash(&self) -> CompiledClassHash {
+        match self.get_class() {
+            ContractClass::V0(_) => CompiledClassHash::default(),
+            ContractClass::V1(contract_class) => contract_class.compiled_class_hash(),
+        }
+    }
+
     // TODO(Arni, 1/1/2025): Remove this function, and use the get_class function instead.
     pub fn get_deprecated_contract_class(&self) -> DeprecatedContractClass {
         let mut raw_contract_class: serde_json::Value =
@@ -0,0 +1,112 @@
+use starknet_api::core::ContractAddress;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+use starknet_api::transaction::Calldata;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::execution::entry_point::CallEntryPoint;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::{
+    calldata_for_deploy_test, trivial_external_entry_point_new,
+    trivial_external_entry_point_with_address, CairoVersion,
+};
+use crate::transaction::constants::TRANSFER_ENTRY_POINT_NAME;
+use crate::utils::u128_from_usize;
+
+#[cfg(test)]
+#[path = "fixtures_test.rs"]
+pub mod test;
+
+// This file contains ready-made calls for common execution scenarios: many events, secp signature
+// verification, deep recursion, reentrancy, panics, deployments and ERC20 transfers.
+//
+// Unless stated otherwise, the calls target the first instance of `CAIRO1_TEST_CONTRACT`; deploy it
+// with `test_state` (see initial_test_state.rs), and run the calls with `execute_directly`.
+
+pub const CAIRO1_TEST_CONTRACT: FeatureContract =
+    FeatureContract::TestContract(CairoVersion::Cairo1);
+
+fn test_contract_call(entry_point_name: &str, calldata: Vec<StarkFelt>) -> CallEntryPoint {
+    CallEntryPoint {
+        entry_point_selector: selector_from_name(entry_point_name),
+        calldata: Calldata(calldata.into()),
+        ..trivial_external_entry_point_new(CAIRO1_TEST_CONTRACT)
+    }
+}
+
+fn felt_array(elements: &[StarkFelt]) -> Vec<StarkFelt> {
+    [vec![stark_felt!(u128_from_usize(elements.len()))], elements.to_vec()].concat()
+}
+
+/// Emits `n_events` events, each with the given keys and data.
+pub fn emit_events_call(n_events: u64, keys: &[StarkFelt], data: &[StarkFelt]) -> CallEntryPoint {
+    let calldata = [vec![stark_felt!(n_events)], felt_array(keys), felt_array(data)].concat();
+    test_contract_call("test_emit_events", calldata)
+}
+
+/// Verifies a secp256k1 signature.
+pub fn secp256k1_call() -> CallEntryPoint {
+    test_contract_call("test_secp256k1", vec![])
+}
+
+/// Verifies a secp256r1 signature.
+pub fn secp256r1_call() -> CallEntryPoint {
+    test_contract_call("test_secp256r1", vec![])
+}
+
+/// Recurses `depth` times, within a single call.
+pub fn recursion_call(depth: u64) -> CallEntryPoint {
+    test_contract_call("recurse", vec![stark_felt!(depth)])
+}
+
+/// Calls itself (with `call_contract`) `depth` times, nesting the calls.
+pub fn nested_calls_call(depth: u64) -> CallEntryPoint {
+    let contract_address = *CAIRO1_TEST_CONTRACT.get_instance_address(0).0.key();
+    let selector = selector_from_name("recursive_syscall").0;
+    test_contract_call("recursive_syscall", vec![contract_address, selector, stark_felt!(depth)])
+}
+
+/// Reenters the test contract: calls the given entry point of it, with `call_contract`.
+pub fn reentrant_call(entry_point_name: &str, args: &[StarkFelt]) -> CallEntryPoint {
+    let contract_address = *CAIRO1_TEST_CONTRACT.get_instance_address(0).0.key();
+    let selector = selector_from_name(entry_point_name).0;
+    let calldata = [vec![contract_address, selector], felt_array(args)].concat();
+    test_contract_call("test_call_contract", calldata)
+}
+
+/// Panics with 'fail'.
+pub fn panic_call() -> CallEntryPoint {
+    test_contract_call("fail", vec![])
+}
+
+/// Recurses `depth` times, then panics with 'recursive_fail'.
+pub fn recursive_panic_call(depth: u64) -> CallEntryPoint {
+    test_contract_call("recursive_fail", vec![stark_felt!(depth)])
+}
+
+/// Deploys an instance of `contract`, with the given constructor calldata; the class of the
+/// contract must be declared (e.g., by passing it with zero instances to `test_state`).
+pub fn deploy_call(
+    contract: FeatureContract,
+    constructor_calldata: &[StarkFelt],
+) -> CallEntryPoint {
+    let calldata = calldata_for_deploy_test(contract.get_class_hash(), constructor_calldata, true);
+    test_contract_call("test_deploy", calldata.0.to_vec())
+}
+
+/// Transfers `amount` of the ERC20 token at `token_address` from `sender` to `recipient`; e.g.,
+/// from an account funded by `test_state`, on a fee token.
+pub fn erc20_transfer_call(
+    token_address: ContractAddress,
+    sender: ContractAddress,
+    recipient: ContractAddress,
+    amount: u128,
+) -> CallEntryPoint {
+    CallEntryPoint {
+        entry_point_selector: selector_from_name(TRANSFER_ENTRY_POINT_NAME),
+        // The amount is a `Uint256`: low and high words.
+        calldata: Calldata(vec![*recipient.0.key(), stark_felt!(amount), stark_felt!(0_u8)].into()),
+        caller_address: sender,
+        ..trivial_external_entry_point_with_address(token_address)
+    }
+}
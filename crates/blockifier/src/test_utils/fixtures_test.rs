@@ -0,0 +1,70 @@
+use assert_matches::assert_matches;
+use starknet_api::hash::StarkFelt;
+use starknet_api::stark_felt;
+
+use crate::abi::abi_utils::get_fee_token_var_address;
+use crate::context::ChainInfo;
+use crate::execution::errors::EntryPointExecutionError;
+use crate::state::cached_state::CachedState;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::fixtures::*;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{CairoVersion, BALANCE};
+use crate::transaction::objects::FeeType;
+
+const ACCOUNT: FeatureContract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+const EMPTY_CONTRACT: FeatureContract = FeatureContract::Empty(CairoVersion::Cairo1);
+
+fn fixtures_state(chain_info: &ChainInfo) -> CachedState<DictStateReader> {
+    test_state(chain_info, BALANCE, &[(CAIRO1_TEST_CONTRACT, 1), (ACCOUNT, 1), (EMPTY_CONTRACT, 0)])
+}
+
+#[test]
+fn test_fixtures() {
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = fixtures_state(chain_info);
+
+    let call_info = emit_events_call(3, &[stark_felt!(1_u8)], &[stark_felt!(2_u8)])
+        .execute_directly(&mut state)
+        .unwrap();
+    assert_eq!(call_info.execution.events.len(), 3);
+
+    for call in [secp256k1_call(), secp256r1_call(), recursion_call(50)] {
+        assert!(!call.execute_directly(&mut state).unwrap().execution.failed);
+    }
+
+    let call_info = nested_calls_call(3).execute_directly(&mut state).unwrap();
+    assert_eq!(call_info.inner_calls[0].inner_calls[0].inner_calls.len(), 1);
+
+    let call_info = reentrant_call("recurse", &[stark_felt!(1_u8)]).execute_directly(&mut state);
+    let reentered_call = &call_info.unwrap().inner_calls[0];
+    assert_eq!(reentered_call.call.storage_address, CAIRO1_TEST_CONTRACT.get_instance_address(0));
+
+    for call in [panic_call(), recursive_panic_call(10)] {
+        assert_matches!(
+            call.execute_directly(&mut state).unwrap_err(),
+            EntryPointExecutionError::ExecutionFailed { .. }
+        );
+    }
+
+    let call_info = deploy_call(EMPTY_CONTRACT, &[]).execute_directly(&mut state).unwrap();
+    let deployed_address = call_info.inner_calls[0].call.storage_address;
+    assert_eq!(state.get_class_hash_at(deployed_address).unwrap(), EMPTY_CONTRACT.get_class_hash());
+}
+
+#[test]
+fn test_erc20_transfer_fixture() {
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = fixtures_state(chain_info);
+    let token_address = chain_info.fee_token_address(&FeeType::Eth);
+    let recipient = CAIRO1_TEST_CONTRACT.get_instance_address(0);
+
+    let call = erc20_transfer_call(token_address, ACCOUNT.get_instance_address(0), recipient, 7);
+    assert!(!call.execute_directly(&mut state).unwrap().execution.failed);
+
+    let recipient_balance =
+        state.get_storage_at(token_address, get_fee_token_var_address(recipient)).unwrap();
+    assert_eq!(recipient_balance, StarkFelt::from(7_u8));
+}
@@ -0,0 +1,59 @@
+//! Golden-file (snapshot) assertions: encodings of representative values are compared to the ones
+//! committed under `tests/golden`, to catch accidental encoding changes, which break the consumers
+//! of stored encodings. After an intended change, regenerate the golden files by running the tests
+//! with `UPDATE_GOLDEN_FILES=1`, and commit them.
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+const UPDATE_GOLDEN_FILES_ENV_VAR: &str = "UPDATE_GOLDEN_FILES";
+
+fn golden_file_path(file_name: &str) -> PathBuf {
+    [env!("CARGO_MANIFEST_DIR"), "tests", "golden", file_name].iter().collect()
+}
+
+/// Writes the golden file if requested; otherwise, returns its content.
+fn update_or_read_golden_file(file_name: &str, content: &str) -> Option<String> {
+    let path = golden_file_path(file_name);
+    if std::env::var_os(UPDATE_GOLDEN_FILES_ENV_VAR).is_some() {
+        fs::create_dir_all(path.parent().expect("Golden files are in a directory.")).unwrap();
+        fs::write(&path, content).unwrap();
+        return None;
+    }
+
+    Some(fs::read_to_string(&path).unwrap_or_else(|error| {
+        panic!(
+            "Failed to read golden file {path:?}: {error}. Run with \
+             {UPDATE_GOLDEN_FILES_ENV_VAR}=1 to create it."
+        )
+    }))
+}
+
+/// Asserts that the JSON encoding of the value matches the golden file `<name>.json`, regardless
+/// of formatting.
+pub fn assert_json_golden(name: &str, value: &impl Serialize) {
+    let actual = serde_json::to_value(value).unwrap();
+    let content = serde_json::to_string_pretty(&actual).unwrap() + "\n";
+    let Some(golden) = update_or_read_golden_file(&format!("{name}.json"), &content) else {
+        return;
+    };
+
+    let expected: serde_json::Value = serde_json::from_str(&golden).unwrap();
+    assert_eq!(actual, expected, "The JSON encoding of {name} differs from its golden file.");
+}
+
+/// Asserts that the SCALE encoding of the value matches the golden file `<name>.scale.hex`, which
+/// holds it as a hex string.
+#[cfg(feature = "scale-codec")]
+pub fn assert_scale_golden(name: &str, value: &impl parity_scale_codec::Encode) {
+    let actual: String = value.encode().iter().map(|byte| format!("{byte:02x}")).collect();
+    let Some(golden) =
+        update_or_read_golden_file(&format!("{name}.scale.hex"), &(actual.clone() + "\n"))
+    else {
+        return;
+    };
+
+    assert_eq!(actual, golden.trim(), "The SCALE encoding of {name} differs from its golden file.");
+}
@@ -0,0 +1,147 @@
+//! Comparison of execution resources with a tolerance, for tests that pin resource usage without
+//! breaking on every small change in the VM or in the compiled contracts.
+
+use std::collections::BTreeSet;
+use std::fmt::{self, Display};
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+
+use crate::execution::call_info::CallInfo;
+
+#[cfg(test)]
+#[path = "resources_test.rs"]
+pub mod test;
+
+/// The usage of a single resource (steps, memory holes or a builtin), expected vs. actual.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResourceDiff {
+    pub name: String,
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl ResourceDiff {
+    /// Whether the actual usage is within `tolerance_percent` percent of the expected one.
+    pub fn is_within(&self, tolerance_percent: usize) -> bool {
+        self.actual.abs_diff(self.expected) * 100 <= self.expected * tolerance_percent
+    }
+}
+
+impl Display for ResourceDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.actual >= self.expected { '+' } else { '-' };
+        let (name, expected, actual) = (&self.name, self.expected, self.actual);
+        write!(f, "{name}: {expected} -> {actual} ({sign}{})", self.actual.abs_diff(expected))
+    }
+}
+
+/// The resources that differ between two `ExecutionResources`, ordered by name (steps and memory
+/// holes first).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ResourcesDiff(pub Vec<ResourceDiff>);
+
+impl ResourcesDiff {
+    pub fn new(actual: &ExecutionResources, expected: &ExecutionResources) -> Self {
+        let builtin_names: BTreeSet<&String> = actual
+            .builtin_instance_counter
+            .keys()
+            .chain(expected.builtin_instance_counter.keys())
+            .collect();
+        let builtin_usages = builtin_names.into_iter().map(|name| {
+            let usage = |resources: &ExecutionResources| {
+                resources.builtin_instance_counter.get(name).copied().unwrap_or_default()
+            };
+            (name.as_str(), usage(expected), usage(actual))
+        });
+
+        let usages = [
+            ("n_steps", expected.n_steps, actual.n_steps),
+            ("n_memory_holes", expected.n_memory_holes, actual.n_memory_holes),
+        ];
+        Self(
+            usages
+                .into_iter()
+                .chain(builtin_usages)
+                .filter(|(_, expected, actual)| expected != actual)
+                .map(|(name, expected, actual)| ResourceDiff {
+                    name: name.to_string(),
+                    expected,
+                    actual,
+                })
+                .collect(),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether every resource is within `tolerance_percent` percent of its expected usage.
+    pub fn is_within(&self, tolerance_percent: usize) -> bool {
+        self.0.iter().all(|diff| diff.is_within(tolerance_percent))
+    }
+}
+
+impl Display for ResourcesDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let diffs: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "{}", diffs.join(", "))
+    }
+}
+
+/// Asserts that every resource is within `tolerance_percent` percent of its expected usage; on
+/// failure, reports all the differing resources.
+pub fn assert_resources_close(
+    actual: &ExecutionResources,
+    expected: &ExecutionResources,
+    tolerance_percent: usize,
+) {
+    let diff = ResourcesDiff::new(actual, expected);
+    assert!(
+        diff.is_within(tolerance_percent),
+        "Resources differ by more than {tolerance_percent}%: {diff}."
+    );
+}
+
+/// Per-call resource differences between two call trees, in pre-order; calls whose resources are
+/// equal are omitted. Returns a report line per differing call, e.g.,
+/// `call #2 (depth 1): n_steps: 100 -> 120 (+20)`.
+pub fn call_resources_diff_report(actual: &CallInfo, expected: &CallInfo) -> Vec<String> {
+    let (n_actual_calls, n_expected_calls) = (actual.iter().count(), expected.iter().count());
+    let mut report: Vec<String> = actual
+        .iter_with_depth()
+        .zip(expected.iter())
+        .filter_map(|(actual_node, expected_call)| {
+            let diff =
+                ResourcesDiff::new(&actual_node.call_info.resources, &expected_call.resources);
+            (!diff.is_empty()).then(|| {
+                format!("call #{} (depth {}): {diff}", actual_node.index, actual_node.depth)
+            })
+        })
+        .collect();
+    if n_actual_calls != n_expected_calls {
+        report.push(format!("number of calls: {n_expected_calls} -> {n_actual_calls}"));
+    }
+
+    report
+}
+
+/// Asserts that the call trees have the same shape and that, in every call, every resource is
+/// within `tolerance_percent` percent of its expected usage; on failure, reports the differences
+/// of all calls.
+pub fn assert_call_resources_close(
+    actual: &CallInfo,
+    expected: &CallInfo,
+    tolerance_percent: usize,
+) {
+    let same_shape = actual.iter().count() == expected.iter().count();
+    let all_within = actual.iter().zip(expected.iter()).all(|(actual_call, expected_call)| {
+        ResourcesDiff::new(&actual_call.resources, &expected_call.resources)
+            .is_within(tolerance_percent)
+    });
+    assert!(
+        same_shape && all_within,
+        "Call resources differ by more than {tolerance_percent}%:\n{}",
+        call_resources_diff_report(actual, expected).join("\n")
+    );
+}
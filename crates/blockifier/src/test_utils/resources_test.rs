@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use cairo_vm::vm::runners::builtin_runner::{HASH_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+
+use crate::execution::call_info::CallInfo;
+use crate::test_utils::resources::{
+    assert_call_resources_close, assert_resources_close, call_resources_diff_report, ResourceDiff,
+    ResourcesDiff,
+};
+
+fn resources(n_steps: usize, range_checks: usize) -> ExecutionResources {
+    ExecutionResources {
+        n_steps,
+        n_memory_holes: 0,
+        builtin_instance_counter: HashMap::from([(
+            RANGE_CHECK_BUILTIN_NAME.to_string(),
+            range_checks,
+        )]),
+    }
+}
+
+#[test]
+fn test_resources_diff() {
+    let mut actual = resources(110, 10);
+    actual.builtin_instance_counter.insert(HASH_BUILTIN_NAME.to_string(), 1);
+    let diff = ResourcesDiff::new(&actual, &resources(100, 10));
+
+    let expected_diff = ResourcesDiff(vec![
+        ResourceDiff { name: "n_steps".to_string(), expected: 100, actual: 110 },
+        ResourceDiff { name: HASH_BUILTIN_NAME.to_string(), expected: 0, actual: 1 },
+    ]);
+    assert_eq!(diff, expected_diff);
+    assert_eq!(diff.to_string(), "n_steps: 100 -> 110 (+10), pedersen_builtin: 0 -> 1 (+1)");
+    assert!(!diff.is_within(10));
+    assert!(ResourcesDiff::new(&resources(100, 10), &resources(100, 10)).is_empty());
+}
+
+#[test]
+fn test_assert_resources_close() {
+    assert_resources_close(&resources(105, 9), &resources(100, 10), 10);
+}
+
+#[test]
+#[should_panic(expected = "Resources differ by more than 10%: n_steps: 100 -> 111 (+11).")]
+fn test_assert_resources_close_out_of_tolerance() {
+    assert_resources_close(&resources(111, 10), &resources(100, 10), 10);
+}
+
+#[test]
+fn test_call_resources_diff_report() {
+    let call_info = |n_steps, inner_calls| CallInfo {
+        resources: resources(n_steps, 0),
+        inner_calls,
+        ..Default::default()
+    };
+    let expected = call_info(100, vec![call_info(50, vec![])]);
+    let actual = call_info(100, vec![call_info(60, vec![]), call_info(10, vec![])]);
+
+    assert_eq!(
+        call_resources_diff_report(&actual, &expected),
+        vec![
+            "call #1 (depth 1): n_steps: 50 -> 60 (+10)".to_string(),
+            "number of calls: 2 -> 3".to_string(),
+        ]
+    );
+    assert_call_resources_close(&expected, &expected, 0);
+}
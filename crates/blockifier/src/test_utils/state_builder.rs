@@ -0,0 +1,95 @@
+use starknet_api::core::{ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use crate::context::ChainInfo;
+use crate::state::cached_state::CachedState;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::{fund_account, test_state_reader};
+
+#[cfg(test)]
+#[path = "state_builder_test.rs"]
+pub mod test;
+
+/// Builds a state for testing, starting from one with the ERC20 fee tokens deployed. E.g.,
+/// `StateBuilder::new().with_account(account, BALANCE).deploy(address, contract).build()`.
+#[derive(Debug)]
+pub struct StateBuilder {
+    chain_info: ChainInfo,
+    state_reader: DictStateReader,
+}
+
+impl StateBuilder {
+    /// Uses the fee tokens of `ChainInfo::create_for_testing()`.
+    pub fn new() -> Self {
+        Self::for_chain(ChainInfo::create_for_testing())
+    }
+
+    pub fn for_chain(chain_info: ChainInfo) -> Self {
+        let state_reader = test_state_reader(&chain_info, 0, &[]);
+        Self { chain_info, state_reader }
+    }
+
+    /// Declares the class of the contract.
+    pub fn declare(mut self, contract: FeatureContract) -> Self {
+        self.state_reader
+            .class_hash_to_class
+            .insert(contract.get_class_hash(), contract.get_class());
+        self
+    }
+
+    /// Declares the class of the contract, and deploys an instance of it at the given address.
+    pub fn deploy(mut self, address: ContractAddress, contract: FeatureContract) -> Self {
+        self.state_reader.address_to_class_hash.insert(address, contract.get_class_hash());
+        self.declare(contract)
+    }
+
+    /// Deploys instances of the contract at its mock addresses (see `get_instance_address`).
+    pub fn deploy_instances(self, contract: FeatureContract, n_instances: u16) -> Self {
+        (0..n_instances).fold(self, |builder, instance| {
+            builder.deploy(contract.get_instance_address(instance), contract)
+        })
+    }
+
+    /// Deploys the first instance of the account contract, and funds it in both fee tokens.
+    pub fn with_account(self, account: FeatureContract, balance: u128) -> Self {
+        let address = account.get_instance_address(0);
+        self.deploy(address, account).fund(address, balance)
+    }
+
+    pub fn with_storage(
+        mut self,
+        address: ContractAddress,
+        key: StorageKey,
+        value: StarkFelt,
+    ) -> Self {
+        self.state_reader.storage_view.insert((address, key), value);
+        self
+    }
+
+    pub fn with_nonce(mut self, address: ContractAddress, nonce: Nonce) -> Self {
+        self.state_reader.address_to_nonce.insert(address, nonce);
+        self
+    }
+
+    /// Sets the balance of the address in both fee tokens.
+    pub fn fund(mut self, address: ContractAddress, amount: u128) -> Self {
+        fund_account(&self.chain_info, address, amount, &mut self.state_reader);
+        self
+    }
+
+    pub fn build_reader(self) -> DictStateReader {
+        self.state_reader
+    }
+
+    pub fn build(self) -> CachedState<DictStateReader> {
+        CachedState::from(self.build_reader())
+    }
+}
+
+impl Default for StateBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,38 @@
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::{contract_address, patricia_key, stark_felt};
+
+use crate::context::ChainInfo;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::state_builder::StateBuilder;
+use crate::test_utils::{CairoVersion, BALANCE};
+use crate::transaction::objects::FeeType;
+use crate::{nonce, storage_key};
+
+#[test]
+fn test_state_builder() {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let address = contract_address!("0x1234");
+    let mut state = StateBuilder::new()
+        .with_account(account, BALANCE)
+        .deploy(address, test_contract)
+        .with_storage(address, storage_key!(1_u8), stark_felt!(2_u8))
+        .with_nonce(address, nonce!(3_u8))
+        .build();
+
+    let account_address = account.get_instance_address(0);
+    assert_eq!(state.get_class_hash_at(account_address).unwrap(), account.get_class_hash());
+    assert_eq!(state.get_class_hash_at(address).unwrap(), test_contract.get_class_hash());
+    state.get_compiled_contract_class(test_contract.get_class_hash()).unwrap();
+    assert_eq!(state.get_storage_at(address, storage_key!(1_u8)).unwrap(), stark_felt!(2_u8));
+    assert_eq!(state.get_nonce_at(address).unwrap(), nonce!(3_u8));
+
+    let chain_info = ChainInfo::create_for_testing();
+    for fee_type in [FeeType::Eth, FeeType::Strk] {
+        let fee_token_address = chain_info.fee_token_address(&fee_type);
+        let (low, high) = state.get_fee_token_balance(account_address, fee_token_address).unwrap();
+        assert_eq!((low, high), (stark_felt!(BALANCE), StarkFelt::ZERO));
+    }
+}
@@ -0,0 +1,224 @@
+//! A naive model of the state, against which random sequences of operations on `CachedState` are
+//! checked. Used by the property tests of the cached state and by the fuzz target under `fuzz/`.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use indexmap::IndexMap;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::hash::StarkFelt;
+use starknet_api::state::StorageKey;
+
+use crate::state::cached_state::{CachedState, CommitmentStateDiff};
+use crate::state::state_api::{State, StateReader, StateResult};
+use crate::test_utils::dict_state_reader::DictStateReader;
+
+// The operations address small domains, so that random sequences keep hitting the same entries.
+const N_ADDRESSES: u8 = 4;
+const N_KEYS: u8 = 4;
+const N_VALUES: u8 = 4;
+const N_CLASS_HASHES: u8 = 4;
+const N_OPERATION_KINDS: u8 = 7;
+const OPERATION_SIZE: usize = 4;
+
+/// An operation on the state. Fields are indices, taken modulo the size of their domain.
+#[derive(Clone, Copy, Debug)]
+pub enum StateOperation {
+    GetStorage { address: u8, key: u8 },
+    SetStorage { address: u8, key: u8, value: u8 },
+    GetNonce { address: u8 },
+    IncrementNonce { address: u8 },
+    GetClassHash { address: u8 },
+    DeployContract { address: u8, class_hash: u8 },
+    SetCompiledClassHash { class_hash: u8, compiled_class_hash: u8 },
+}
+
+impl StateOperation {
+    /// Decodes a sequence of operations from raw (e.g., fuzzer-generated) bytes, four bytes per
+    /// operation; trailing bytes are ignored.
+    pub fn decode_all(data: &[u8]) -> Vec<Self> {
+        data.chunks_exact(OPERATION_SIZE)
+            .map(|chunk| Self::decode(chunk[0], chunk[1], chunk[2], chunk[3]))
+            .collect()
+    }
+
+    fn decode(kind: u8, a: u8, b: u8, c: u8) -> Self {
+        match kind % N_OPERATION_KINDS {
+            0 => Self::GetStorage { address: a, key: b },
+            1 => Self::SetStorage { address: a, key: b, value: c },
+            2 => Self::GetNonce { address: a },
+            3 => Self::IncrementNonce { address: a },
+            4 => Self::GetClassHash { address: a },
+            5 => Self::DeployContract { address: a, class_hash: b },
+            _ => Self::SetCompiledClassHash { class_hash: a, compiled_class_hash: b },
+        }
+    }
+}
+
+fn address(index: u8) -> ContractAddress {
+    // Address zero is reserved, and cannot be deployed to.
+    ContractAddress::from(u64::from(index % N_ADDRESSES) + 1)
+}
+
+fn key(index: u8) -> StorageKey {
+    StorageKey::from(u64::from(index % N_KEYS))
+}
+
+fn value(index: u8) -> StarkFelt {
+    StarkFelt::from(index % N_VALUES)
+}
+
+fn nonce(value: u64) -> Nonce {
+    Nonce(StarkFelt::from(value))
+}
+
+fn class_hash(index: u8) -> ClassHash {
+    ClassHash(StarkFelt::from(index % N_CLASS_HASHES))
+}
+
+fn compiled_class_hash(index: u8) -> CompiledClassHash {
+    CompiledClassHash(StarkFelt::from(index % N_CLASS_HASHES))
+}
+
+/// The expected state: plain maps holding the initial values and the latest ones.
+#[derive(Default)]
+struct NaiveState {
+    initial_storage: HashMap<(ContractAddress, StorageKey), StarkFelt>,
+    storage: HashMap<(ContractAddress, StorageKey), StarkFelt>,
+    initial_nonces: HashMap<ContractAddress, u64>,
+    nonces: HashMap<ContractAddress, u64>,
+    initial_class_hashes: HashMap<ContractAddress, ClassHash>,
+    class_hashes: HashMap<ContractAddress, ClassHash>,
+    compiled_class_hashes: IndexMap<ClassHash, CompiledClassHash>,
+}
+
+impl NaiveState {
+    /// The state the operations start from; some of the entries are set, and some are zero.
+    fn initial() -> Self {
+        let mut state = Self::default();
+        for address_index in 0..N_ADDRESSES {
+            let address = address(address_index);
+            for key_index in 0..N_KEYS {
+                state.storage.insert((address, key(key_index)), value(address_index + key_index));
+            }
+            state.nonces.insert(address, u64::from(address_index % 2));
+            state.class_hashes.insert(address, class_hash(address_index));
+        }
+
+        state.initial_storage = state.storage.clone();
+        state.initial_nonces = state.nonces.clone();
+        state.initial_class_hashes = state.class_hashes.clone();
+        state
+    }
+
+    fn to_state_reader(&self) -> DictStateReader {
+        DictStateReader {
+            storage_view: self.storage.clone(),
+            address_to_nonce: self
+                .nonces
+                .iter()
+                .map(|(&address, &value)| (address, nonce(value)))
+                .collect(),
+            address_to_class_hash: self.class_hashes.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn state_diff(&self) -> CommitmentStateDiff {
+        let mut storage_updates = IndexMap::<_, IndexMap<_, _>>::new();
+        for (&(address, key), &value) in &self.storage {
+            if self.initial_storage.get(&(address, key)).copied().unwrap_or_default() != value {
+                storage_updates.entry(address).or_default().insert(key, value);
+            }
+        }
+
+        CommitmentStateDiff {
+            address_to_class_hash: changed_entries(&self.initial_class_hashes, &self.class_hashes),
+            address_to_nonce: changed_entries(&self.initial_nonces, &self.nonces)
+                .into_iter()
+                .map(|(address, value)| (address, nonce(value)))
+                .collect(),
+            storage_updates,
+            class_hash_to_compiled_class_hash: self.compiled_class_hashes.clone(),
+        }
+    }
+}
+
+fn changed_entries<V: Copy + Default + PartialEq>(
+    initial: &HashMap<ContractAddress, V>,
+    current: &HashMap<ContractAddress, V>,
+) -> IndexMap<ContractAddress, V> {
+    current
+        .iter()
+        .filter(|(address, value)| initial.get(address).copied().unwrap_or_default() != **value)
+        .map(|(&address, &value)| (address, value))
+        .collect()
+}
+
+/// Reads twice, to check that reading does not change the read value.
+fn read_twice<T: Debug + PartialEq>(read: impl Fn() -> StateResult<T>) -> T {
+    let first_read = read().unwrap();
+    assert_eq!(read().unwrap(), first_read, "Non-idempotent read.");
+    first_read
+}
+
+/// Applies the operations to a `CachedState` and to a naive model of the state, and panics if
+/// they disagree: on any read, or on the resulting state diff.
+pub fn check_cached_state_operations(operations: &[StateOperation]) {
+    let mut expected = NaiveState::initial();
+    let mut state = CachedState::from(expected.to_state_reader());
+
+    for &operation in operations {
+        match operation {
+            StateOperation::GetStorage { address: a, key: k } => {
+                let (address, key) = (address(a), key(k));
+                let actual = read_twice(|| state.get_storage_at(address, key));
+                assert_eq!(actual, expected.storage[&(address, key)], "{operation:?}");
+            }
+            StateOperation::SetStorage { address: a, key: k, value: v } => {
+                let (address, key, value) = (address(a), key(k), value(v));
+                state.set_storage_at(address, key, value).unwrap();
+                expected.storage.insert((address, key), value);
+                assert_eq!(
+                    read_twice(|| state.get_storage_at(address, key)),
+                    value,
+                    "{operation:?}"
+                );
+            }
+            StateOperation::GetNonce { address: a } => {
+                let address = address(a);
+                let actual = read_twice(|| state.get_nonce_at(address));
+                assert_eq!(actual, nonce(expected.nonces[&address]), "{operation:?}");
+            }
+            StateOperation::IncrementNonce { address: a } => {
+                let address = address(a);
+                state.increment_nonce(address).unwrap();
+                let expected_nonce = expected.nonces.get_mut(&address).unwrap();
+                *expected_nonce += 1;
+                let expected_nonce = nonce(*expected_nonce);
+                assert_eq!(state.get_nonce_at(address).unwrap(), expected_nonce, "{operation:?}");
+            }
+            StateOperation::GetClassHash { address: a } => {
+                let address = address(a);
+                let actual = read_twice(|| state.get_class_hash_at(address));
+                assert_eq!(actual, expected.class_hashes[&address], "{operation:?}");
+            }
+            StateOperation::DeployContract { address: a, class_hash: c } => {
+                let (address, class_hash) = (address(a), class_hash(c));
+                state.set_class_hash_at(address, class_hash).unwrap();
+                expected.class_hashes.insert(address, class_hash);
+            }
+            StateOperation::SetCompiledClassHash { class_hash: c, compiled_class_hash: h } => {
+                let (class_hash, compiled_class_hash) = (class_hash(c), compiled_class_hash(h));
+                state.set_compiled_class_hash(class_hash, compiled_class_hash).unwrap();
+                expected.compiled_class_hashes.insert(class_hash, compiled_class_hash);
+            }
+        }
+    }
+
+    let expected_state_diff = expected.state_diff();
+    assert_eq!(state.to_state_diff(), expected_state_diff);
+    // Computing the diff must not affect the state, nor depend on the way it is computed.
+    assert_eq!(state.to_state_diff(), expected_state_diff);
+    assert_eq!(state.to_state_diff_in_parallel(2), expected_state_diff);
+}
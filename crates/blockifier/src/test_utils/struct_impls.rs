@@ -10,11 +10,13 @@ use starknet_api::{contract_address, patricia_key};
 use super::update_json_value;
 use crate::blockifier::block::{BlockInfo, GasPrices};
 use crate::bouncer::{BouncerConfig, BouncerWeights, BuiltinCount};
-use crate::context::{BlockContext, ChainInfo, FeeTokenAddresses, TransactionContext};
+use crate::context::{
+    BlockContext, CancellationToken, ChainInfo, FeeTokenAddresses, TransactionContext,
+};
 use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
 use crate::execution::contract_class::{ContractClassV0, ContractClassV1};
 use crate::execution::entry_point::{
-    CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
+    CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult, ReentrancyPolicy,
 };
 use crate::state::state_api::State;
 use crate::test_utils::{
@@ -111,6 +113,8 @@ impl ChainInfo {
                 eth_fee_token_address: contract_address!(TEST_ERC20_CONTRACT_ADDRESS),
                 strk_fee_token_address: contract_address!(TEST_ERC20_CONTRACT_ADDRESS2),
             },
+            signature_verifier: None,
+            execution_policy: None,
         }
     }
 }
@@ -143,6 +147,13 @@ impl BlockContext {
             chain_info: ChainInfo::create_for_testing(),
             versioned_constants: VersionedConstants::create_for_testing(),
             concurrency_mode: false,
+            disable_fee_charge: false,
+            cancellation_token: CancellationToken::default(),
+            profiler: None,
+            event_sink: None,
+            reentrancy_policy: ReentrancyPolicy::default(),
+            skip_compiled_class_hash_verification: false,
+            dev_mode: false,
         }
     }
 
@@ -152,6 +163,13 @@ impl BlockContext {
             chain_info: ChainInfo::create_for_testing(),
             versioned_constants: VersionedConstants::create_for_account_testing(),
             concurrency_mode: false,
+            disable_fee_charge: false,
+            cancellation_token: CancellationToken::default(),
+            profiler: None,
+            event_sink: None,
+            reentrancy_policy: ReentrancyPolicy::default(),
+            skip_compiled_class_hash_verification: false,
+            dev_mode: false,
         }
     }
 
@@ -1,11 +1,14 @@
 use std::sync::Arc;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use num_traits::ToPrimitive;
 use starknet_api::calldata;
-use starknet_api::core::{ContractAddress, EntryPointSelector};
+use starknet_api::core::{ContractAddress, EntryPointSelector, Nonce};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
-use starknet_api::transaction::{Calldata, Fee, ResourceBounds, TransactionVersion};
+use starknet_api::transaction::{
+    Calldata, Fee, ResourceBounds, TransactionHash, TransactionSignature, TransactionVersion,
+};
 
 use crate::abi::abi_utils::{get_fee_token_var_address, selector_from_name};
 use crate::abi::sierra_types::next_storage_key;
@@ -13,6 +16,7 @@ use crate::context::{BlockContext, TransactionContext};
 use crate::execution::call_info::{CallInfo, Retdata};
 use crate::execution::contract_class::ContractClass;
 use crate::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
+use crate::execution::execution_utils::stark_felt_to_felt;
 use crate::fee::actual_cost::TransactionReceipt;
 use crate::fee::fee_checks::{FeeCheckReportFields, PostExecutionReport};
 use crate::fee::fee_utils::{get_fee_by_gas_vector, verify_can_pay_committed_bounds};
@@ -30,7 +34,7 @@ use crate::transaction::objects::{
     TransactionPreValidationResult,
 };
 use crate::transaction::transaction_types::TransactionType;
-use crate::transaction::transaction_utils::update_remaining_gas;
+use crate::transaction::transaction_utils::{abort_if_cancelled, update_remaining_gas};
 use crate::transaction::transactions::{
     DeclareTransaction, DeployAccountTransaction, Executable, ExecutableTransaction,
     InvokeTransaction, ValidatableTransaction,
@@ -48,6 +52,34 @@ mod flavors_test;
 #[path = "post_execution_test.rs"]
 mod post_execution_test;
 
+/// How the nonce of an account transaction is checked against the nonce of its account.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum NonceCheckPolicy {
+    /// The nonces must be equal; used in block execution.
+    #[default]
+    Strict,
+    /// The transaction nonce may be ahead of the account nonce by at most the given amount; e.g.,
+    /// for mempool admission of transactions that follow pending ones.
+    AllowFutureUpTo(u64),
+    /// The nonce is not checked.
+    Skip,
+}
+
+impl NonceCheckPolicy {
+    pub fn accepts(&self, account_nonce: Nonce, incoming_tx_nonce: Nonce) -> bool {
+        match self {
+            Self::Strict => account_nonce == incoming_tx_nonce,
+            Self::AllowFutureUpTo(max_distance) => {
+                let distance =
+                    stark_felt_to_felt(incoming_tx_nonce.0) - stark_felt_to_felt(account_nonce.0);
+                account_nonce <= incoming_tx_nonce
+                    && distance.to_u64().is_some_and(|distance| distance <= *max_distance)
+            }
+            Self::Skip => true,
+        }
+    }
+}
+
 /// Represents a paid Starknet transaction.
 #[derive(Debug)]
 pub enum AccountTransaction {
@@ -84,6 +116,14 @@ impl AccountTransaction {
         }
     }
 
+    pub fn tx_hash(&self) -> TransactionHash {
+        match self {
+            Self::Declare(tx) => tx.tx_hash(),
+            Self::DeployAccount(tx) => tx.tx_hash,
+            Self::Invoke(tx) => tx.tx_hash,
+        }
+    }
+
     fn validate_entry_point_selector(&self) -> EntryPointSelector {
         let validate_entry_point_name = match self {
             Self::Declare(_) => constants::VALIDATE_DECLARE_ENTRY_POINT_NAME,
@@ -121,17 +161,28 @@ impl AccountTransaction {
         calldata.0.len()
     }
 
-    pub fn signature_length(&self) -> usize {
-        let signature = match self {
+    pub fn signature(&self) -> TransactionSignature {
+        match self {
             Self::Declare(tx) => tx.signature(),
             Self::DeployAccount(tx) => tx.signature(),
             Self::Invoke(tx) => tx.signature(),
-        };
+        }
+    }
 
-        signature.0.len()
+    pub fn signature_length(&self) -> usize {
+        self.signature().0.len()
+    }
+
+    /// Returns the initial gas of the transaction (see `VersionedConstants::initial_gas`).
+    pub fn initial_gas(&self, block_context: &BlockContext) -> u64 {
+        let entry_point_type = match self {
+            Self::DeployAccount(_) => EntryPointType::Constructor,
+            Self::Declare(_) | Self::Invoke(_) => EntryPointType::External,
+        };
+        block_context.versioned_constants.initial_gas(entry_point_type, self.version())
     }
 
-    fn verify_tx_version(&self, version: TransactionVersion) -> TransactionExecutionResult<()> {
+    fn verify_tx_version(&self, version: TransactionVersion) -> TransactionPreValidationResult<()> {
         let allowed_versions: Vec<TransactionVersion> = match self {
             // Support `Declare` of version 0 in order to allow bootstrapping of a new system.
             Self::Declare(_) => {
@@ -152,7 +203,7 @@ impl AccountTransaction {
         if allowed_versions.contains(&version) {
             Ok(())
         } else {
-            Err(TransactionExecutionError::InvalidVersion { version, allowed_versions })
+            Err(TransactionPreValidationError::InvalidVersion { version, allowed_versions })
         }
     }
 
@@ -163,11 +214,13 @@ impl AccountTransaction {
         state: &mut S,
         tx_context: &TransactionContext,
         charge_fee: bool,
-        strict_nonce_check: bool,
+        nonce_check: NonceCheckPolicy,
     ) -> TransactionPreValidationResult<()> {
         let tx_info = &tx_context.tx_info;
-        Self::handle_nonce(state, tx_info, strict_nonce_check)?;
+        self.verify_tx_version(tx_info.version())?;
+        Self::handle_nonce(state, tx_info, nonce_check)?;
 
+        let charge_fee = charge_fee && !tx_context.block_context.disable_fee_charge;
         if charge_fee && tx_info.enforce_fee()? {
             self.check_fee_bounds(tx_context)?;
 
@@ -177,6 +230,98 @@ impl AccountTransaction {
         Ok(())
     }
 
+    /// Runs the pre-validation checks and the validation entry point of the transaction, with the
+    /// validation step limit and syscall restrictions; e.g., for mempool admission. Returns the
+    /// validation call info ([None] for v0 transactions) and the VM resources used.
+    /// The given state is left untouched.
+    /// Note: for `DeployAccount` transactions, the account is assumed to be deployed already.
+    pub fn run_validate_only<S: StateReader>(
+        &self,
+        state: &mut CachedState<S>,
+        block_context: &BlockContext,
+        nonce_check: NonceCheckPolicy,
+    ) -> TransactionExecutionResult<(Option<CallInfo>, ExecutionResources)> {
+        let tx_context = Arc::new(block_context.to_tx_context(self));
+        let mut transactional_state = CachedState::create_transactional(state);
+        let result = self.run_validate_only_on(&mut transactional_state, tx_context, nonce_check);
+        transactional_state.abort();
+
+        result
+    }
+
+    fn run_validate_only_on<S: StateReader>(
+        &self,
+        state: &mut TransactionalState<'_, S>,
+        tx_context: Arc<TransactionContext>,
+        nonce_check: NonceCheckPolicy,
+    ) -> TransactionExecutionResult<(Option<CallInfo>, ExecutionResources)> {
+        let charge_fee = true;
+        self.perform_pre_validation_stage(state, &tx_context, charge_fee, nonce_check)?;
+
+        let mut resources = ExecutionResources::default();
+        let mut remaining_gas = self.initial_gas(&tx_context.block_context);
+        let limit_steps_by_resources = true;
+        let validate_call_info = self.validate_tx(
+            state,
+            &mut resources,
+            tx_context,
+            &mut remaining_gas,
+            limit_steps_by_resources,
+        )?;
+
+        Ok((validate_call_info, resources))
+    }
+
+    /// Same as `execute_raw`, with the given nonce check instead of a strict one; e.g., for
+    /// pre-executing transactions in the mempool.
+    pub fn execute_with_nonce_check<S: StateReader>(
+        &self,
+        state: &mut TransactionalState<'_, S>,
+        block_context: &BlockContext,
+        charge_fee: bool,
+        validate: bool,
+        nonce_check: NonceCheckPolicy,
+    ) -> TransactionExecutionResult<TransactionExecutionInfo> {
+        let tx_context = Arc::new(block_context.to_tx_context(self));
+        let charge_fee = charge_fee && !block_context.disable_fee_charge;
+
+        // Version, nonce and fee checks should be done before running user code.
+        self.perform_pre_validation_stage(state, &tx_context, charge_fee, nonce_check)?;
+
+        // Run validation and execution.
+        let mut remaining_gas = self.initial_gas(block_context);
+        let ValidateExecuteCallInfo {
+            validate_call_info,
+            execute_call_info,
+            revert_error,
+            final_cost:
+                TransactionReceipt {
+                    fee: final_fee,
+                    da_gas: final_da_gas,
+                    resources: final_resources,
+                    ..
+                },
+        } = abort_if_cancelled(
+            block_context,
+            self.run_or_revert(state, &mut remaining_gas, tx_context.clone(), validate, charge_fee),
+        )?;
+        let fee_transfer_call_info = abort_if_cancelled(
+            block_context,
+            self.handle_fee(state, tx_context, final_fee, charge_fee),
+        )?;
+
+        let tx_execution_info = TransactionExecutionInfo {
+            validate_call_info,
+            execute_call_info,
+            fee_transfer_call_info,
+            actual_fee: final_fee,
+            da_gas: final_da_gas,
+            actual_resources: final_resources,
+            revert_error,
+        };
+        Ok(tx_execution_info)
+    }
+
     fn check_fee_bounds(
         &self,
         tx_context: &TransactionContext,
@@ -232,7 +377,7 @@ impl AccountTransaction {
     fn handle_nonce(
         state: &mut dyn State,
         tx_info: &TransactionInfo,
-        strict: bool,
+        nonce_check: NonceCheckPolicy,
     ) -> TransactionPreValidationResult<()> {
         if tx_info.is_v0() {
             return Ok(());
@@ -241,12 +386,8 @@ impl AccountTransaction {
         let address = tx_info.sender_address();
         let account_nonce = state.get_nonce_at(address)?;
         let incoming_tx_nonce = tx_info.nonce();
-        let valid_nonce = if strict {
-            account_nonce == incoming_tx_nonce
-        } else {
-            account_nonce <= incoming_tx_nonce
-        };
-        if valid_nonce {
+        // Note that the account nonce is incremented even if the nonce check is skipped.
+        if nonce_check.accepts(account_nonce, incoming_tx_nonce) {
             return Ok(state.increment_nonce(address)?);
         }
         Err(TransactionPreValidationError::InvalidNonce {
@@ -341,22 +482,19 @@ impl AccountTransaction {
 
         let TransactionContext { block_context, tx_info } = tx_context.as_ref();
         let storage_address = block_context.chain_info.fee_token_address(&tx_info.fee_type());
-        let fee_transfer_call = CallEntryPoint {
-            class_hash: None,
-            code_address: None,
-            entry_point_type: EntryPointType::External,
-            entry_point_selector: selector_from_name(constants::TRANSFER_ENTRY_POINT_NAME),
-            calldata: calldata![
-                *block_context.block_info.sequencer_address.0.key(), // Recipient.
-                lsb_amount,
-                msb_amount
-            ],
+        let calldata = calldata![
+            *block_context.block_info.sequencer_address.0.key(), // Recipient.
+            lsb_amount,
+            msb_amount
+        ];
+        let fee_transfer_call = CallEntryPoint::external(
             storage_address,
-            caller_address: tx_info.sender_address(),
-            call_type: CallType::Call,
-            // The fee-token contract is a Cairo 0 contract, hence the initial gas is irrelevant.
-            initial_gas: block_context.versioned_constants.os_constants.gas_costs.initial_gas_cost,
-        };
+            selector_from_name(constants::TRANSFER_ENTRY_POINT_NAME),
+            calldata,
+        )
+        .with_caller(tx_info.sender_address())
+        // The fee-token contract is a Cairo 0 contract, hence the initial gas is irrelevant.
+        .with_initial_gas(block_context.versioned_constants.os_constants.gas_costs.initial_gas_cost);
 
         let mut context = EntryPointExecutionContext::new_invoke(tx_context, true)?;
 
@@ -635,6 +773,14 @@ impl AccountTransaction {
 }
 
 impl<S: StateReader> ExecutableTransaction<S> for AccountTransaction {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "execute_transaction",
+            skip_all,
+            fields(tx_hash = %self.tx_hash().0, tx_type = ?self.tx_type())
+        )
+    )]
     fn execute_raw(
         &self,
         state: &mut TransactionalState<'_, S>,
@@ -642,45 +788,13 @@ impl<S: StateReader> ExecutableTransaction<S> for AccountTransaction {
         charge_fee: bool,
         validate: bool,
     ) -> TransactionExecutionResult<TransactionExecutionInfo> {
-        let tx_context = Arc::new(block_context.to_tx_context(self));
-        self.verify_tx_version(tx_context.tx_info.version())?;
-
-        // Nonce and fee check should be done before running user code.
-        let strict_nonce_check = true;
-        self.perform_pre_validation_stage(state, &tx_context, charge_fee, strict_nonce_check)?;
-
-        // Run validation and execution.
-        let mut remaining_gas = block_context.versioned_constants.tx_initial_gas();
-        let ValidateExecuteCallInfo {
-            validate_call_info,
-            execute_call_info,
-            revert_error,
-            final_cost:
-                TransactionReceipt {
-                    fee: final_fee,
-                    da_gas: final_da_gas,
-                    resources: final_resources,
-                    ..
-                },
-        } = self.run_or_revert(
+        self.execute_with_nonce_check(
             state,
-            &mut remaining_gas,
-            tx_context.clone(),
-            validate,
+            block_context,
             charge_fee,
-        )?;
-        let fee_transfer_call_info = self.handle_fee(state, tx_context, final_fee, charge_fee)?;
-
-        let tx_execution_info = TransactionExecutionInfo {
-            validate_call_info,
-            execute_call_info,
-            fee_transfer_call_info,
-            actual_fee: final_fee,
-            da_gas: final_da_gas,
-            actual_resources: final_resources,
-            revert_error,
-        };
-        Ok(tx_execution_info)
+            validate,
+            NonceCheckPolicy::Strict,
+        )
     }
 }
 
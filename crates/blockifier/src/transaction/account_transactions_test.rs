@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use assert_matches::assert_matches;
 use cairo_felt::Felt252;
 use cairo_vm::vm::runners::cairo_runner::ResourceTracker;
 use pretty_assertions::assert_eq;
@@ -37,8 +38,9 @@ use crate::test_utils::{
     u64_from_usize, CairoVersion, NonceManager, BALANCE, DEFAULT_STRK_L1_GAS_PRICE, MAX_FEE,
     MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE,
 };
-use crate::transaction::account_transaction::AccountTransaction;
-use crate::transaction::constants::TRANSFER_ENTRY_POINT_NAME;
+use crate::transaction::account_transaction::{AccountTransaction, NonceCheckPolicy};
+use crate::transaction::constants::{TRANSFER_ENTRY_POINT_NAME, VALIDATE_ENTRY_POINT_NAME};
+use crate::transaction::errors::{TransactionExecutionError, TransactionPreValidationError};
 use crate::transaction::objects::{FeeType, HasRelatedFeeType, TransactionInfoCreator};
 use crate::transaction::test_utils::{
     account_invoke_tx, block_context, calculate_class_info_for_testing,
@@ -101,6 +103,85 @@ fn test_enforce_fee_false_works(block_context: BlockContext, #[case] version: Tr
     assert_eq!(tx_execution_info.actual_fee, Fee(0));
 }
 
+#[rstest]
+fn test_run_validate_only(block_context: BlockContext, max_fee: Fee) {
+    let TestInitData { mut state, account_address, contract_address, mut nonce_manager } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    let tx = account_invoke_tx(invoke_tx_args! {
+        max_fee,
+        sender_address: account_address,
+        calldata: create_trivial_calldata(contract_address),
+        nonce: nonce_manager.next(account_address),
+    });
+
+    let state_diff_before = state.to_state_diff();
+    let (validate_call_info, resources) =
+        tx.run_validate_only(&mut state, &block_context, NonceCheckPolicy::Strict).unwrap();
+    let validate_call_info = validate_call_info.unwrap();
+    assert_eq!(
+        validate_call_info.call.entry_point_selector,
+        selector_from_name(VALIDATE_ENTRY_POINT_NAME)
+    );
+    assert!(resources.n_steps > 0);
+    // The nonce increment of the pre-validation stage is not applied to the given state.
+    assert_eq!(state.get_nonce_at(account_address).unwrap(), nonce!(0_u8));
+    assert_eq!(state.to_state_diff(), state_diff_before);
+
+    // The pre-validation checks run before `__validate__`.
+    let tx = account_invoke_tx(invoke_tx_args! {
+        max_fee,
+        sender_address: account_address,
+        calldata: create_trivial_calldata(contract_address),
+        nonce: nonce!(1_u8),
+    });
+    assert_matches!(
+        tx.run_validate_only(&mut state, &block_context, NonceCheckPolicy::Strict).unwrap_err(),
+        TransactionExecutionError::TransactionPreValidationError(
+            TransactionPreValidationError::InvalidNonce { .. }
+        )
+    );
+    assert_eq!(state.to_state_diff(), state_diff_before);
+}
+
+#[rstest]
+fn test_disable_fee_charge(mut block_context: BlockContext, max_fee: Fee) {
+    block_context.disable_fee_charge = true;
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    // The account is not funded.
+    let mut state = test_state(&block_context.chain_info, 0, &[(account, 1), (test_contract, 1)]);
+
+    let tx = account_invoke_tx(invoke_tx_args! {
+        max_fee,
+        sender_address: account.get_instance_address(0),
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+    });
+    let tx_execution_info = tx.execute(&mut state, &block_context, true, true).unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    assert!(tx_execution_info.fee_transfer_call_info.is_none());
+    // The fee is still computed and reported.
+    assert!(tx_execution_info.actual_fee > Fee(0));
+}
+
+#[rstest]
+fn test_execution_aborted(block_context: BlockContext, max_fee: Fee) {
+    let TestInitData { mut state, account_address, contract_address, .. } =
+        create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    block_context.cancellation_token().cancel();
+
+    let tx = account_invoke_tx(invoke_tx_args! {
+        max_fee,
+        sender_address: account_address,
+        calldata: create_trivial_calldata(contract_address),
+    });
+    assert_matches!(
+        tx.execute(&mut state, &block_context, true, true).unwrap_err(),
+        TransactionExecutionError::ExecutionAborted
+    );
+    // Aborted transactions do not change the state.
+    assert_eq!(state.get_nonce_at(account_address).unwrap(), nonce!(0_u8));
+}
+
 // TODO(Dori, 15/9/2023): Convert version variance to attribute macro.
 // TODO(Dori, 10/10/2023): Add V3 case once `create_tx_info` is supported for V3.
 #[rstest]
@@ -1241,6 +1322,7 @@ fn test_concurrency_execute_fee_transfer(#[values(FeeType::Eth, FeeType::Strk)]
             version: TransactionVersion::THREE,
             resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE),
             class_hash,
+            compiled_class_hash: empty_contract.get_compiled_class_hash(),
         },
         class_info.clone(),
     );
@@ -1,14 +1,16 @@
-use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, Nonce};
+use starknet_api::core::{
+    ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, Nonce,
+};
 use starknet_api::hash::StarkFelt;
 use starknet_api::transaction::{Fee, TransactionVersion};
 use starknet_api::StarknetApiError;
 use thiserror::Error;
 
 use crate::execution::call_info::Retdata;
-use crate::execution::errors::{
-    gen_transaction_execution_error_trace, ConstructorEntryPointExecutionError,
-    EntryPointExecutionError,
-};
+use crate::execution::contract_class::SierraVersion;
+use crate::execution::errors::{ConstructorEntryPointExecutionError, EntryPointExecutionError};
+use crate::execution::sierra_compilation::SierraCompilationError;
+use crate::execution::stack_trace::gen_transaction_execution_error_trace;
 use crate::fee::fee_checks::FeeCheckError;
 use crate::state::errors::StateError;
 
@@ -22,6 +24,10 @@ pub enum TransactionFeeError {
     FeeTransferError { max_fee: Fee, actual_fee: Fee },
     #[error("Actual fee ({actual_fee:?}) exceeded paid fee on L1 ({paid_fee:?}).")]
     InsufficientL1Fee { paid_fee: Fee, actual_fee: Fee },
+    #[error(
+        "Sequencer balance (Uint256({balance_low:?}, {balance_high:?})) is not a valid Uint256."
+    )]
+    InvalidSequencerBalance { balance_low: StarkFelt, balance_high: StarkFelt },
     #[error(
         "L1 gas bounds (max amount: {max_amount:?}, max price: {max_price:?}) exceed balance \
          (Uint256({balance_low:?}, {balance_high:?}))."
@@ -48,12 +54,28 @@ pub enum TransactionFeeError {
     MaxL1GasAmountTooLow { max_l1_gas_amount: u64, minimal_l1_gas_amount: u64 },
     #[error("Missing L1 gas bounds in resource bounds.")]
     MissingL1GasBounds,
+    #[error("Fee transfer did not read the sequencer balance.")]
+    MissingSequencerBalanceRead,
+    #[error(
+        "Actual fee ({actual_fee:?}) overflows the sequencer balance (Uint256({balance_low:?}, \
+         {balance_high:?}))."
+    )]
+    SequencerBalanceOverflow { actual_fee: Fee, balance_low: StarkFelt, balance_high: StarkFelt },
     #[error(transparent)]
     StateError(#[from] StateError),
 }
 
 #[derive(Debug, Error)]
 pub enum TransactionExecutionError {
+    #[error(
+        "Compiled class hash of class {class_hash:?} does not match its class. Expected: \
+         {expected:?}; computed: {actual:?}."
+    )]
+    CompiledClassHashMismatch {
+        class_hash: ClassHash,
+        expected: CompiledClassHash,
+        actual: CompiledClassHash,
+    },
     #[error(
         "Declare transaction version {declare_version:?} must have a contract class of Cairo \
          version {cairo_version:?}."
@@ -66,6 +88,18 @@ pub enum TransactionExecutionError {
     ContractConstructorExecutionFailed(#[from] ConstructorEntryPointExecutionError),
     #[error("Class with hash {class_hash:?} is already declared.")]
     DeclareTransactionError { class_hash: ClassHash },
+    #[error(
+        "Declared class bytecode length ({bytecode_length}) exceeds the maximum \
+         ({max_bytecode_length})."
+    )]
+    DeclaredBytecodeTooLong { bytecode_length: usize, max_bytecode_length: usize },
+    #[error(
+        "Declared class Sierra program length ({sierra_program_length}) exceeds the maximum \
+         ({max_sierra_program_length})."
+    )]
+    DeclaredSierraProgramTooLong { sierra_program_length: usize, max_sierra_program_length: usize },
+    #[error("Execution was aborted.")]
+    ExecutionAborted,
     #[error("Transaction execution has failed:\n{}", gen_transaction_execution_error_trace(self))]
     ExecutionError {
         error: EntryPointExecutionError,
@@ -77,11 +111,8 @@ pub enum TransactionExecutionError {
     FeeCheckError(#[from] FeeCheckError),
     #[error("The `validate` entry point should return `VALID`. Got {actual:?}.")]
     InvalidValidateReturnData { actual: Retdata },
-    #[error(
-        "Transaction version {version:?} is not supported. Supported versions: \
-         {allowed_versions:?}."
-    )]
-    InvalidVersion { version: TransactionVersion, allowed_versions: Vec<TransactionVersion> },
+    #[error(transparent)]
+    SierraCompilationError(#[from] SierraCompilationError),
     #[error(transparent)]
     StarknetApiError(#[from] StarknetApiError),
     #[error(transparent)]
@@ -94,6 +125,15 @@ pub enum TransactionExecutionError {
     TryFromIntError(#[from] std::num::TryFromIntError),
     #[error("Transaction size exceeds the maximum block capacity.")]
     TransactionTooLarge,
+    #[error(
+        "Declared class Sierra version {sierra_version:?} is not supported. Supported versions: \
+         {min_sierra_version:?} to {max_sierra_version:?}."
+    )]
+    UnsupportedSierraVersion {
+        sierra_version: SierraVersion,
+        min_sierra_version: SierraVersion,
+        max_sierra_version: SierraVersion,
+    },
     #[error("Transaction validation has failed:\n{}", gen_transaction_execution_error_trace(self))]
     ValidateTransactionError {
         error: EntryPointExecutionError,
@@ -115,6 +155,11 @@ pub enum TransactionPreValidationError {
          {account_nonce:?}; got: {incoming_tx_nonce:?}."
     )]
     InvalidNonce { address: ContractAddress, account_nonce: Nonce, incoming_tx_nonce: Nonce },
+    #[error(
+        "Transaction version {version:?} is not supported. Supported versions: \
+         {allowed_versions:?}."
+    )]
+    InvalidVersion { version: TransactionVersion, allowed_versions: Vec<TransactionVersion> },
     #[error(transparent)]
     StateError(#[from] StateError),
     #[error(transparent)]
@@ -8,8 +8,8 @@ use serde::Serialize;
 use starknet_api::core::{ContractAddress, Nonce};
 use starknet_api::data_availability::DataAvailabilityMode;
 use starknet_api::transaction::{
-    AccountDeploymentData, Fee, PaymasterData, Resource, ResourceBounds, ResourceBoundsMapping,
-    Tip, TransactionHash, TransactionSignature, TransactionVersion,
+    AccountDeploymentData, EventContent, Fee, PaymasterData, Resource, ResourceBounds,
+    ResourceBoundsMapping, Tip, TransactionHash, TransactionSignature, TransactionVersion,
 };
 use strum_macros::EnumIter;
 
@@ -17,12 +17,8 @@ use crate::abi::constants as abi_constants;
 use crate::context::BlockContext;
 use crate::execution::call_info::{CallInfo, ExecutionSummary, MessageL1CostInfo, OrderedEvent};
 use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
-use crate::fee::eth_gas_constants;
 use crate::fee::fee_utils::{calculate_l1_gas_by_vm_usage, calculate_tx_fee};
-use crate::fee::gas_usage::{
-    get_consumed_message_to_l2_emissions_cost, get_da_gas_cost,
-    get_log_message_to_l1_emissions_cost, get_onchain_data_segment_length,
-};
+use crate::fee::gas_usage::{get_da_gas_cost, get_onchain_data_segment_length};
 use crate::state::cached_state::StateChangesCount;
 use crate::transaction::constants;
 use crate::transaction::errors::{
@@ -143,18 +139,60 @@ pub struct DeprecatedTransactionInfo {
 pub struct GasVector {
     pub l1_gas: u128,
     pub l1_data_gas: u128,
+    // Not priced in this Starknet version; kept out of the serialized form, which is consumed by
+    // Python.
+    #[serde(skip_serializing)]
+    pub l2_gas: u128,
 }
 
 impl GasVector {
     pub fn from_l1_gas(l1_gas: u128) -> Self {
-        Self { l1_gas, l1_data_gas: 0 }
+        Self { l1_gas, ..Default::default() }
     }
 
     pub fn from_l1_data_gas(l1_data_gas: u128) -> Self {
-        Self { l1_gas: 0, l1_data_gas }
+        Self { l1_data_gas, ..Default::default() }
+    }
+
+    pub fn from_l2_gas(l2_gas: u128) -> Self {
+        Self { l2_gas, ..Default::default() }
+    }
+
+    /// Returns the L1 gas consumed by the given Cairo resources (the heaviest resource, in terms
+    /// of L1 gas, determines the cost).
+    pub fn from_execution_resources(
+        versioned_constants: &VersionedConstants,
+        execution_resources: &ExecutionResources,
+    ) -> TransactionFeeResult<Self> {
+        calculate_l1_gas_by_vm_usage(versioned_constants, execution_resources, 0)
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(Self {
+            l1_gas: self.l1_gas.checked_add(rhs.l1_gas)?,
+            l1_data_gas: self.l1_data_gas.checked_add(rhs.l1_data_gas)?,
+            l2_gas: self.l2_gas.checked_add(rhs.l2_gas)?,
+        })
+    }
+
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Self {
+            l1_gas: self.l1_gas.saturating_add(rhs.l1_gas),
+            l1_data_gas: self.l1_data_gas.saturating_add(rhs.l1_data_gas),
+            l2_gas: self.l2_gas.saturating_add(rhs.l2_gas),
+        }
+    }
+
+    /// Computes the cost (in fee token units) of the gas vector; returns `None` on overflow.
+    /// L2 gas is not priced in this Starknet version.
+    pub fn checked_cost(&self, gas_price: u128, blob_gas_price: u128) -> Option<Fee> {
+        let l1_gas_cost = self.l1_gas.checked_mul(gas_price)?;
+        let l1_data_gas_cost = self.l1_data_gas.checked_mul(blob_gas_price)?;
+        Some(Fee(l1_gas_cost.checked_add(l1_data_gas_cost)?))
     }
 
     /// Computes the cost (in fee token units) of the gas vector (saturating on overflow).
+    /// L2 gas is not priced in this Starknet version.
     pub fn saturated_cost(&self, gas_price: u128, blob_gas_price: u128) -> Fee {
         let l1_gas_cost = self.l1_gas.checked_mul(gas_price).unwrap_or_else(|| {
             log::warn!(
@@ -224,6 +262,30 @@ impl TransactionExecutionInfo {
             .chain(self.fee_transfer_call_info.iter())
     }
 
+    /// Returns all the call infos of the transaction, including inner calls, in execution order.
+    pub fn all_call_infos(&self) -> impl Iterator<Item = &CallInfo> {
+        self.non_optional_call_infos().flat_map(|call_info| call_info.iter())
+    }
+
+    /// Returns the events of the transaction, in receipt order: phase by phase (see
+    /// `non_optional_call_infos`), each in emission order.
+    pub fn ordered_events(&self) -> Vec<&EventContent> {
+        self.non_optional_call_infos().flat_map(CallInfo::ordered_events).collect()
+    }
+
+    /// Returns the total number of keys and the total data length of the transaction's events.
+    pub fn total_event_keys_and_data_lengths(&self) -> (usize, usize) {
+        let events = self.all_call_infos().flat_map(|call_info| &call_info.execution.events);
+        events.fold((0, 0), |(n_keys, data_length), OrderedEvent { event, .. }| {
+            (n_keys + event.keys.len(), data_length + event.data.0.len())
+        })
+    }
+
+    /// Returns the L1<>L2 messages of the transaction, with their segment length and gas costs.
+    pub fn message_cost_info(&self) -> &MessageL1CostInfo {
+        &self.actual_resources.starknet_resources.message_cost_info
+    }
+
     pub fn is_reverted(&self) -> bool {
         self.revert_error.is_some()
     }
@@ -323,45 +385,17 @@ impl StarknetResources {
         GasVector::from_l1_gas(l1_gas)
     }
 
-    /// Returns an estimation of the gas usage for processing L1<>L2 messages on L1. Accounts for
-    /// Starknet contract only.
-    fn get_messages_gas_usage(&self) -> GasVector {
-        let n_l2_to_l1_messages = self.message_cost_info.l2_to_l1_payload_lengths.len();
-        let n_l1_to_l2_messages = usize::from(self.l1_handler_payload_size.is_some());
-
-        GasVector::from_l1_gas(
-            // Starknet's updateState gets the message segment as an argument.
-            u128_from_usize(
-                self.message_cost_info.message_segment_length * eth_gas_constants::GAS_PER_MEMORY_WORD
-                // Starknet's updateState increases a (storage) counter for each L2-to-L1 message.
-                + n_l2_to_l1_messages * eth_gas_constants::GAS_PER_ZERO_TO_NONZERO_STORAGE_SET
-                // Starknet's updateState decreases a (storage) counter for each L1-to-L2 consumed
-                // message (note that we will probably get a refund of 15,000 gas for each consumed
-                // message but we ignore it since refunded gas cannot be used for the current
-                // transaction execution).
-                + n_l1_to_l2_messages * eth_gas_constants::GAS_PER_COUNTER_DECREASE,
-            ),
-        ) + get_consumed_message_to_l2_emissions_cost(self.l1_handler_payload_size)
-            + get_log_message_to_l1_emissions_cost(&self.message_cost_info.l2_to_l1_payload_lengths)
-    }
-
     /// Returns an estimation of the gas usage for processing L1<>L2 messages on L1. Accounts for
     /// both Starknet and SHARP contracts.
     pub fn get_messages_cost(&self) -> GasVector {
-        let starknet_gas_usage = self.get_messages_gas_usage();
-        let sharp_gas_usage = GasVector::from_l1_gas(u128_from_usize(
-            self.message_cost_info.message_segment_length
-                * eth_gas_constants::SHARP_GAS_PER_MEMORY_WORD,
-        ));
-
-        starknet_gas_usage + sharp_gas_usage
+        self.message_cost_info.to_gas_vector()
     }
 
     /// Calculates the L1 resources used by L1<>L2 messages.
     /// Returns the total message segment length and the gas weight.
     pub fn calculate_message_l1_resources(&self) -> (usize, usize) {
         let message_segment_length = self.message_cost_info.message_segment_length;
-        let gas_usage = self.get_messages_gas_usage();
+        let gas_usage = self.message_cost_info.starknet_gas_usage();
         // TODO(Avi, 30/03/2024): Consider removing "l1_gas_usage" from actual resources.
         let gas_weight = usize_from_u128(gas_usage.l1_gas)
             .expect("This conversion should not fail as the value is a converted usize.");
@@ -453,7 +487,7 @@ impl TransactionResources {
         use_kzg_da: bool,
         with_reverted_steps: bool,
     ) -> ResourcesMapping {
-        let GasVector { l1_gas, l1_data_gas } =
+        let GasVector { l1_gas, l1_data_gas, .. } =
             self.starknet_resources.to_gas_vector(versioned_constants, use_kzg_da);
         let mut resources = self.vm_resources.to_resources_mapping();
         resources.0.extend(HashMap::from([
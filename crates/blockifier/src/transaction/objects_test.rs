@@ -1,13 +1,18 @@
+use std::collections::HashMap;
+
 use rstest::rstest;
-use starknet_api::class_hash;
 use starknet_api::core::ClassHash;
-use starknet_api::hash::StarkHash;
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{EventContent, EventData, EventKey, Fee};
+use starknet_api::{class_hash, stark_felt};
 
 use crate::execution::call_info::{
     CallExecution, CallInfo, ExecutionSummary, OrderedEvent, TestExecutionSummary,
 };
 use crate::execution::entry_point::CallEntryPoint;
-use crate::transaction::objects::TransactionExecutionInfo;
+use crate::execution::syscalls::SyscallSelector;
+use crate::fee::gas_usage::get_log_message_to_l1_emissions_cost;
+use crate::transaction::objects::{GasVector, TransactionExecutionInfo};
 
 fn shared_call_info() -> CallInfo {
     CallInfo {
@@ -155,6 +160,7 @@ fn test_summarize(
                 + execute_params.num_of_messages
                 + fee_transfer_params.num_of_messages
         ],
+        ..Default::default()
     };
 
     // Call the summarize method
@@ -165,4 +171,120 @@ fn test_summarize(
     assert_eq!(actual_summary.visited_storage_entries, expected_summary.visited_storage_entries);
     assert_eq!(actual_summary.n_events, expected_summary.n_events);
     assert_eq!(actual_summary.l2_to_l1_payload_lengths, expected_summary.l2_to_l1_payload_lengths);
+    assert_eq!(actual_summary.n_l2_to_l1_messages, expected_summary.l2_to_l1_payload_lengths.len());
+    assert_eq!(
+        actual_summary.l2_to_l1_payloads_gas,
+        get_log_message_to_l1_emissions_cost(&expected_summary.l2_to_l1_payload_lengths)
+    );
+}
+
+#[test]
+fn test_summarize_syscall_counter() {
+    let storage_reads = |count| HashMap::from([(SyscallSelector::StorageRead, count)]);
+    let execute_call_info = CallInfo {
+        inner_calls: vec![CallInfo { syscall_counter: storage_reads(2), ..shared_call_info() }],
+        syscall_counter: HashMap::from([
+            (SyscallSelector::CallContract, 1),
+            (SyscallSelector::StorageRead, 1),
+        ]),
+        ..shared_call_info()
+    };
+    let transaction_execution_info = TransactionExecutionInfo {
+        validate_call_info: Some(CallInfo {
+            syscall_counter: storage_reads(1),
+            ..shared_call_info()
+        }),
+        execute_call_info: Some(execute_call_info),
+        ..Default::default()
+    };
+
+    // The counts are summed over all the calls, including inner calls.
+    assert_eq!(
+        transaction_execution_info.summarize().syscall_counter,
+        HashMap::from([(SyscallSelector::CallContract, 1), (SyscallSelector::StorageRead, 4)])
+    );
+}
+
+#[test]
+fn test_all_call_infos() {
+    let transaction_execution_info = TransactionExecutionInfo {
+        validate_call_info: Some(call_info_with_x_events(0, 2)),
+        fee_transfer_call_info: Some(call_info_with_x_events(0, 1)),
+        ..Default::default()
+    };
+
+    let n_events_per_call: Vec<usize> = transaction_execution_info
+        .all_call_infos()
+        .map(|call_info| call_info.execution.events.len())
+        .collect();
+    // Each inner call emits one event.
+    assert_eq!(n_events_per_call, vec![0, 1, 1, 0, 1]);
+}
+
+fn call_info_with_events(events: Vec<(usize, u8)>, inner_calls: Vec<CallInfo>) -> CallInfo {
+    let ordered_event = |(order, key)| OrderedEvent {
+        order,
+        event: EventContent {
+            keys: vec![EventKey(stark_felt!(key))],
+            data: EventData(vec![stark_felt!(key); 2]),
+        },
+    };
+    CallInfo {
+        execution: CallExecution {
+            events: events.into_iter().map(ordered_event).collect(),
+            ..Default::default()
+        },
+        inner_calls,
+        ..shared_call_info()
+    }
+}
+
+#[test]
+fn test_ordered_events() {
+    // The order of an event is counted per execution context, i.e., per transaction phase.
+    let execute_inner_call = call_info_with_events(vec![(1, 3)], vec![]);
+    let execution_info = TransactionExecutionInfo {
+        validate_call_info: Some(call_info_with_events(vec![(0, 1)], vec![])),
+        execute_call_info: Some(call_info_with_events(
+            vec![(0, 2), (2, 4)],
+            vec![execute_inner_call],
+        )),
+        fee_transfer_call_info: Some(call_info_with_events(vec![(0, 5)], vec![])),
+        ..Default::default()
+    };
+
+    let event_keys: Vec<StarkFelt> =
+        execution_info.ordered_events().iter().map(|event| event.keys[0].0).collect();
+    let expected_event_keys: Vec<StarkFelt> = (1..=5_u8).map(StarkFelt::from).collect();
+    assert_eq!(event_keys, expected_event_keys);
+    assert_eq!(execution_info.total_event_keys_and_data_lengths(), (5, 10));
+}
+
+#[test]
+fn test_gas_vector_arithmetic() {
+    let gas_vector = GasVector { l1_gas: 1, l1_data_gas: 2, l2_gas: 3 };
+    assert_eq!(
+        gas_vector.checked_add(gas_vector),
+        Some(GasVector { l1_gas: 2, l1_data_gas: 4, l2_gas: 6 })
+    );
+
+    let max_l1_gas = GasVector::from_l1_gas(u128::MAX);
+    assert_eq!(max_l1_gas.checked_add(gas_vector), None);
+    assert_eq!(
+        max_l1_gas.saturating_add(gas_vector),
+        GasVector { l1_gas: u128::MAX, l1_data_gas: 2, l2_gas: 3 }
+    );
+
+    assert_eq!(gas_vector.checked_cost(10, 100), Some(Fee(210)));
+    assert_eq!(max_l1_gas.checked_cost(2, 1), None);
+    assert_eq!(max_l1_gas.saturated_cost(2, 1), Fee(u128::MAX));
+}
+
+#[test]
+fn test_gas_vector_serialization_omits_l2_gas() {
+    let gas_vector = GasVector { l1_gas: 1, l1_data_gas: 2, l2_gas: 3 };
+    assert_eq!(
+        serde_json::to_value(gas_vector).unwrap(),
+        serde_json::json!({"l1_gas": 1, "l1_data_gas": 2})
+    );
 }
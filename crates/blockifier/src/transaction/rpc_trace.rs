@@ -0,0 +1,299 @@
+use cairo_vm::vm::runners::builtin_runner::{
+    BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, KECCAK_BUILTIN_NAME,
+    POSEIDON_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME, SEGMENT_ARENA_BUILTIN_NAME,
+    SIGNATURE_BUILTIN_NAME,
+};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use serde::Serialize;
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector, EthAddress};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::hash::StarkFelt;
+
+use crate::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message};
+use crate::execution::entry_point::CallType;
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::transaction_types::TransactionType;
+
+#[cfg(test)]
+#[path = "rpc_trace_test.rs"]
+pub mod test;
+
+// Types of the Starknet JSON-RPC v0.7 transaction traces; state diffs are not included.
+
+/// A `TRANSACTION_TRACE`.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum TransactionTrace {
+    #[serde(rename = "DECLARE")]
+    Declare(DeclareTransactionTrace),
+    #[serde(rename = "DEPLOY_ACCOUNT")]
+    DeployAccount(DeployAccountTransactionTrace),
+    #[serde(rename = "INVOKE")]
+    Invoke(InvokeTransactionTrace),
+    #[serde(rename = "L1_HANDLER")]
+    L1Handler(L1HandlerTransactionTrace),
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct DeclareTransactionTrace {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<FunctionInvocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    pub execution_resources: TransactionExecutionResources,
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct DeployAccountTransactionTrace {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<FunctionInvocation>,
+    pub constructor_invocation: FunctionInvocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    pub execution_resources: TransactionExecutionResources,
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct InvokeTransactionTrace {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate_invocation: Option<FunctionInvocation>,
+    pub execute_invocation: ExecuteInvocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_transfer_invocation: Option<FunctionInvocation>,
+    pub execution_resources: TransactionExecutionResources,
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct L1HandlerTransactionTrace {
+    pub function_invocation: FunctionInvocation,
+    pub execution_resources: TransactionExecutionResources,
+}
+
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ExecuteInvocation {
+    Success(FunctionInvocation),
+    Reverted { revert_reason: String },
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum InvocationCallType {
+    #[serde(rename = "CALL")]
+    Call,
+    #[serde(rename = "LIBRARY_CALL")]
+    LibraryCall,
+}
+
+impl From<CallType> for InvocationCallType {
+    fn from(call_type: CallType) -> Self {
+        match call_type {
+            CallType::Call => Self::Call,
+            CallType::Delegate => Self::LibraryCall,
+        }
+    }
+}
+
+/// A `FUNCTION_INVOCATION`.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct FunctionInvocation {
+    pub contract_address: ContractAddress,
+    pub entry_point_selector: EntryPointSelector,
+    pub calldata: Vec<StarkFelt>,
+    pub caller_address: ContractAddress,
+    pub class_hash: ClassHash,
+    pub entry_point_type: EntryPointType,
+    pub call_type: InvocationCallType,
+    pub result: Vec<StarkFelt>,
+    pub calls: Vec<FunctionInvocation>,
+    pub events: Vec<InvocationEvent>,
+    pub messages: Vec<InvocationMessage>,
+    pub execution_resources: ComputationResources,
+}
+
+impl From<&CallInfo> for FunctionInvocation {
+    fn from(call_info: &CallInfo) -> Self {
+        let call = &call_info.call;
+        let execution = &call_info.execution;
+        Self {
+            contract_address: call.storage_address,
+            entry_point_selector: call.entry_point_selector,
+            calldata: call.calldata.0.to_vec(),
+            caller_address: call.caller_address,
+            // The class hash is set upon execution.
+            class_hash: call.class_hash.unwrap_or_default(),
+            entry_point_type: call.entry_point_type,
+            call_type: call.call_type.into(),
+            result: execution.retdata.0.to_vec(),
+            calls: call_info.inner_calls.iter().map(Self::from).collect(),
+            events: execution.events.iter().map(InvocationEvent::from).collect(),
+            messages: execution
+                .l2_to_l1_messages
+                .iter()
+                .map(|message| InvocationMessage::new(call.storage_address, message))
+                .collect(),
+            execution_resources: (&call_info.resources).into(),
+        }
+    }
+}
+
+/// An `ORDERED_EVENT`.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct InvocationEvent {
+    pub order: usize,
+    pub keys: Vec<StarkFelt>,
+    pub data: Vec<StarkFelt>,
+}
+
+impl From<&OrderedEvent> for InvocationEvent {
+    fn from(ordered_event: &OrderedEvent) -> Self {
+        let event = &ordered_event.event;
+        Self {
+            order: ordered_event.order,
+            keys: event.keys.iter().map(|key| key.0).collect(),
+            data: event.data.0.clone(),
+        }
+    }
+}
+
+/// An `ORDERED_MESSAGE`.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct InvocationMessage {
+    pub order: usize,
+    pub from_address: ContractAddress,
+    pub to_address: EthAddress,
+    pub payload: Vec<StarkFelt>,
+}
+
+impl InvocationMessage {
+    fn new(from_address: ContractAddress, ordered_message: &OrderedL2ToL1Message) -> Self {
+        Self {
+            order: ordered_message.order,
+            from_address,
+            to_address: ordered_message.message.to_address,
+            payload: ordered_message.message.payload.0.clone(),
+        }
+    }
+}
+
+/// `COMPUTATION_RESOURCES`; unused builtins are omitted.
+#[derive(Debug, Default, Eq, PartialEq, Serialize)]
+pub struct ComputationResources {
+    pub steps: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_holes: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range_check_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pedersen_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poseidon_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ec_op_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ecdsa_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bitwise_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keccak_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segment_arena_builtin: Option<usize>,
+}
+
+impl From<&ExecutionResources> for ComputationResources {
+    fn from(resources: &ExecutionResources) -> Self {
+        let builtin_usage = |builtin_name: &str| {
+            resources.builtin_instance_counter.get(builtin_name).copied().filter(|&n| n > 0)
+        };
+        Self {
+            steps: resources.n_steps,
+            memory_holes: Some(resources.n_memory_holes).filter(|&n| n > 0),
+            range_check_builtin_applications: builtin_usage(RANGE_CHECK_BUILTIN_NAME),
+            pedersen_builtin_applications: builtin_usage(HASH_BUILTIN_NAME),
+            poseidon_builtin_applications: builtin_usage(POSEIDON_BUILTIN_NAME),
+            ec_op_builtin_applications: builtin_usage(EC_OP_BUILTIN_NAME),
+            ecdsa_builtin_applications: builtin_usage(SIGNATURE_BUILTIN_NAME),
+            bitwise_builtin_applications: builtin_usage(BITWISE_BUILTIN_NAME),
+            keccak_builtin_applications: builtin_usage(KECCAK_BUILTIN_NAME),
+            segment_arena_builtin: builtin_usage(SEGMENT_ARENA_BUILTIN_NAME),
+        }
+    }
+}
+
+/// `DATA_AVAILABILITY` resources.
+#[derive(Debug, Default, Eq, PartialEq, Serialize)]
+pub struct DataAvailabilityResources {
+    pub l1_gas: u128,
+    pub l1_data_gas: u128,
+}
+
+/// `EXECUTION_RESOURCES` of a transaction, including the OS overhead and reverted steps.
+#[derive(Debug, Default, Eq, PartialEq, Serialize)]
+pub struct TransactionExecutionResources {
+    #[serde(flatten)]
+    pub computation_resources: ComputationResources,
+    pub data_availability: DataAvailabilityResources,
+}
+
+impl From<&TransactionExecutionInfo> for TransactionExecutionResources {
+    fn from(tx_execution_info: &TransactionExecutionInfo) -> Self {
+        let actual_resources = &tx_execution_info.actual_resources;
+        let mut computation_resources = ComputationResources::from(&actual_resources.vm_resources);
+        computation_resources.steps += actual_resources.n_reverted_steps;
+        Self {
+            computation_resources,
+            data_availability: DataAvailabilityResources {
+                l1_gas: tx_execution_info.da_gas.l1_gas,
+                l1_data_gas: tx_execution_info.da_gas.l1_data_gas,
+            },
+        }
+    }
+}
+
+impl TransactionTrace {
+    pub fn new(tx_type: TransactionType, tx_execution_info: &TransactionExecutionInfo) -> Self {
+        let validate_invocation =
+            tx_execution_info.validate_call_info.as_ref().map(FunctionInvocation::from);
+        let execute_invocation =
+            tx_execution_info.execute_call_info.as_ref().map(FunctionInvocation::from);
+        let fee_transfer_invocation =
+            tx_execution_info.fee_transfer_call_info.as_ref().map(FunctionInvocation::from);
+        let execution_resources = TransactionExecutionResources::from(tx_execution_info);
+
+        match tx_type {
+            TransactionType::Declare => Self::Declare(DeclareTransactionTrace {
+                validate_invocation,
+                fee_transfer_invocation,
+                execution_resources,
+            }),
+            TransactionType::DeployAccount => Self::DeployAccount(DeployAccountTransactionTrace {
+                validate_invocation,
+                constructor_invocation: execute_invocation
+                    .expect("Deploy account transactions must have a constructor call info."),
+                fee_transfer_invocation,
+                execution_resources,
+            }),
+            TransactionType::InvokeFunction => {
+                let execute_invocation =
+                    match &tx_execution_info.revert_error {
+                        Some(revert_reason) => {
+                            ExecuteInvocation::Reverted { revert_reason: revert_reason.clone() }
+                        }
+                        None => ExecuteInvocation::Success(execute_invocation.expect(
+                            "Successful invoke transactions must have an execute call info.",
+                        )),
+                    };
+                Self::Invoke(InvokeTransactionTrace {
+                    validate_invocation,
+                    execute_invocation,
+                    fee_transfer_invocation,
+                    execution_resources,
+                })
+            }
+            TransactionType::L1Handler => Self::L1Handler(L1HandlerTransactionTrace {
+                function_invocation: execute_invocation
+                    .expect("L1 handler transactions must have an execute call info."),
+                execution_resources,
+            }),
+        }
+    }
+}
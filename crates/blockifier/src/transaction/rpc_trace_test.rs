@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+
+use cairo_vm::vm::runners::builtin_runner::{HASH_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use serde_json::json;
+use starknet_api::core::{ClassHash, ContractAddress, EthAddress, PatriciaKey};
+use starknet_api::hash::{StarkFelt, StarkHash};
+use starknet_api::transaction::{EventContent, EventData, EventKey, L2ToL1Payload};
+use starknet_api::{class_hash, contract_address, patricia_key};
+
+use crate::execution::call_info::{
+    CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message,
+};
+use crate::execution::entry_point::{CallEntryPoint, CallType};
+use crate::transaction::objects::{GasVector, TransactionExecutionInfo, TransactionResources};
+use crate::transaction::rpc_trace::{ComputationResources, TransactionTrace};
+use crate::transaction::transaction_types::TransactionType;
+
+fn call_info_with_inner_library_call() -> CallInfo {
+    let storage_address = contract_address!("0x1");
+    let inner_call = CallInfo {
+        call: CallEntryPoint {
+            class_hash: Some(class_hash!("0x3")),
+            storage_address,
+            caller_address: storage_address,
+            call_type: CallType::Delegate,
+            ..Default::default()
+        },
+        execution: CallExecution {
+            events: vec![OrderedEvent {
+                order: 1,
+                event: EventContent {
+                    keys: vec![EventKey(StarkFelt::from(4_u8))],
+                    data: EventData(vec![StarkFelt::from(5_u8)]),
+                },
+            }],
+            l2_to_l1_messages: vec![OrderedL2ToL1Message {
+                order: 0,
+                message: MessageToL1 {
+                    to_address: EthAddress::default(),
+                    payload: L2ToL1Payload(vec![StarkFelt::from(6_u8)]),
+                },
+            }],
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    CallInfo {
+        call: CallEntryPoint {
+            class_hash: Some(class_hash!("0x2")),
+            storage_address,
+            ..Default::default()
+        },
+        resources: ExecutionResources {
+            n_steps: 100,
+            n_memory_holes: 0,
+            builtin_instance_counter: HashMap::from([
+                (RANGE_CHECK_BUILTIN_NAME.to_string(), 2),
+                (HASH_BUILTIN_NAME.to_string(), 0),
+            ]),
+        },
+        inner_calls: vec![inner_call],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_function_invocation() {
+    let trace = TransactionTrace::new(
+        TransactionType::L1Handler,
+        &TransactionExecutionInfo {
+            execute_call_info: Some(call_info_with_inner_library_call()),
+            ..Default::default()
+        },
+    );
+    let trace_json = serde_json::to_value(trace).unwrap();
+
+    assert_eq!(trace_json["type"], "L1_HANDLER");
+    let invocation = &trace_json["function_invocation"];
+    assert_eq!(invocation["call_type"], "CALL");
+    assert_eq!(
+        invocation["execution_resources"],
+        json!({"steps": 100, "range_check_builtin_applications": 2})
+    );
+
+    let inner_invocation = &invocation["calls"][0];
+    assert_eq!(inner_invocation["call_type"], "LIBRARY_CALL");
+    assert_eq!(inner_invocation["class_hash"], serde_json::to_value(class_hash!("0x3")).unwrap());
+    assert_eq!(
+        inner_invocation["events"],
+        json!([{
+            "order": 1,
+            "keys": [StarkFelt::from(4_u8)],
+            "data": [StarkFelt::from(5_u8)],
+        }])
+    );
+    assert_eq!(
+        inner_invocation["messages"],
+        json!([{
+            "order": 0,
+            "from_address": contract_address!("0x1"),
+            "to_address": EthAddress::default(),
+            "payload": [StarkFelt::from(6_u8)],
+        }])
+    );
+}
+
+#[test]
+fn test_reverted_invoke_trace() {
+    let tx_execution_info = TransactionExecutionInfo {
+        validate_call_info: Some(call_info_with_inner_library_call()),
+        da_gas: GasVector { l1_gas: 10, l1_data_gas: 20, ..Default::default() },
+        actual_resources: TransactionResources {
+            vm_resources: ExecutionResources { n_steps: 30, ..Default::default() },
+            n_reverted_steps: 40,
+            ..Default::default()
+        },
+        revert_error: Some("Revert reason.".to_string()),
+        ..Default::default()
+    };
+    let trace_json = serde_json::to_value(TransactionTrace::new(
+        TransactionType::InvokeFunction,
+        &tx_execution_info,
+    ))
+    .unwrap();
+
+    assert_eq!(trace_json["type"], "INVOKE");
+    assert!(trace_json["validate_invocation"].is_object());
+    assert!(trace_json.get("fee_transfer_invocation").is_none());
+    assert_eq!(trace_json["execute_invocation"], json!({"revert_reason": "Revert reason."}));
+    assert_eq!(
+        trace_json["execution_resources"],
+        json!({"steps": 70, "data_availability": {"l1_gas": 10, "l1_data_gas": 20}})
+    );
+}
+
+#[test]
+fn test_computation_resources_omit_unused_builtins() {
+    let resources = ExecutionResources {
+        n_steps: 1,
+        n_memory_holes: 2,
+        builtin_instance_counter: HashMap::from([(HASH_BUILTIN_NAME.to_string(), 0)]),
+    };
+    assert_eq!(
+        ComputationResources::from(&resources),
+        ComputationResources { steps: 1, memory_holes: Some(2), ..Default::default() }
+    );
+}
@@ -1,10 +1,15 @@
 use std::sync::Arc;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
-use starknet_api::core::{calculate_contract_address, ContractAddress};
-use starknet_api::transaction::{Fee, Transaction as StarknetApiTransaction, TransactionHash};
+use starknet_api::core::ContractAddress;
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::transaction::{
+    Fee, Transaction as StarknetApiTransaction, TransactionHash, TransactionSignature,
+};
 
 use crate::context::BlockContext;
+use crate::event_log::ExecutionEvent;
+use crate::execution::contract_address::calculate_contract_address_from_zero;
 use crate::execution::contract_class::ClassInfo;
 use crate::execution::entry_point::EntryPointExecutionContext;
 use crate::fee::actual_cost::TransactionReceipt;
@@ -15,6 +20,8 @@ use crate::transaction::errors::TransactionFeeError;
 use crate::transaction::objects::{
     TransactionExecutionInfo, TransactionExecutionResult, TransactionInfo, TransactionInfoCreator,
 };
+use crate::transaction::transaction_types::TransactionType;
+use crate::transaction::transaction_utils::abort_if_cancelled;
 use crate::transaction::transactions::{
     DeclareTransaction, DeployAccountTransaction, Executable, ExecutableTransaction,
     InvokeTransaction, L1HandlerTransaction,
@@ -59,11 +66,10 @@ impl Transaction {
             StarknetApiTransaction::DeployAccount(deploy_account) => {
                 let contract_address = match deployed_contract_address {
                     Some(address) => address,
-                    None => calculate_contract_address(
+                    None => calculate_contract_address_from_zero(
                         deploy_account.contract_address_salt(),
                         deploy_account.class_hash(),
                         &deploy_account.constructor_calldata(),
-                        ContractAddress::default(),
                     )?,
                 };
                 let deploy_account_tx = match only_query {
@@ -88,6 +94,21 @@ impl Transaction {
             _ => unimplemented!(),
         }
     }
+
+    pub fn tx_hash(&self) -> TransactionHash {
+        match self {
+            Self::AccountTransaction(account_tx) => account_tx.tx_hash(),
+            Self::L1HandlerTransaction(l1_handler_tx) => l1_handler_tx.tx_hash,
+        }
+    }
+
+    /// The signature of the transaction; L1 handler transactions are not signed.
+    pub fn signature(&self) -> TransactionSignature {
+        match self {
+            Self::AccountTransaction(account_tx) => account_tx.signature(),
+            Self::L1HandlerTransaction(_) => TransactionSignature::default(),
+        }
+    }
 }
 
 impl TransactionInfoCreator for Transaction {
@@ -100,6 +121,17 @@ impl TransactionInfoCreator for Transaction {
 }
 
 impl<S: StateReader> ExecutableTransaction<S> for L1HandlerTransaction {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "execute_transaction",
+            skip_all,
+            fields(
+                tx_hash = %self.tx_hash.0,
+                tx_type = ?TransactionType::L1Handler
+            )
+        )
+    )]
     fn execute_raw(
         &self,
         state: &mut TransactionalState<'_, S>,
@@ -111,9 +143,13 @@ impl<S: StateReader> ExecutableTransaction<S> for L1HandlerTransaction {
 
         let mut execution_resources = ExecutionResources::default();
         let mut context = EntryPointExecutionContext::new_invoke(tx_context.clone(), true)?;
-        let mut remaining_gas = block_context.versioned_constants.tx_initial_gas();
-        let execute_call_info =
-            self.run_execute(state, &mut execution_resources, &mut context, &mut remaining_gas)?;
+        let mut remaining_gas = block_context
+            .versioned_constants
+            .initial_gas(EntryPointType::L1Handler, self.tx.version);
+        let execute_call_info = abort_if_cancelled(
+            block_context,
+            self.run_execute(state, &mut execution_resources, &mut context, &mut remaining_gas),
+        )?;
         let l1_handler_payload_size = self.payload_size();
 
         let TransactionReceipt { fee: actual_fee, da_gas, resources: actual_resources, .. } =
@@ -128,7 +164,7 @@ impl<S: StateReader> ExecutableTransaction<S> for L1HandlerTransaction {
         let paid_fee = self.paid_fee_on_l1;
         // For now, assert only that any amount of fee was paid.
         // The error message still indicates the required fee.
-        if paid_fee == Fee(0) {
+        if paid_fee == Fee(0) && !block_context.disable_fee_charge {
             return Err(TransactionFeeError::InsufficientL1Fee { paid_fee, actual_fee })?;
         }
 
@@ -152,13 +188,24 @@ impl<S: StateReader> ExecutableTransaction<S> for Transaction {
         charge_fee: bool,
         validate: bool,
     ) -> TransactionExecutionResult<TransactionExecutionInfo> {
-        match self {
+        let tx_hash = self.tx_hash();
+        block_context.record_event(|| ExecutionEvent::TxStarted {
+            tx_hash,
+            tx_type: match self {
+                Self::AccountTransaction(account_tx) => account_tx.tx_type(),
+                Self::L1HandlerTransaction(_) => TransactionType::L1Handler,
+            },
+        });
+        let tx_execution_result = match self {
             Self::AccountTransaction(account_tx) => {
                 account_tx.execute_raw(state, block_context, charge_fee, validate)
             }
             Self::L1HandlerTransaction(tx) => {
                 tx.execute_raw(state, block_context, charge_fee, validate)
             }
-        }
+        };
+        block_context.record_event(|| ExecutionEvent::tx_finished(tx_hash, &tx_execution_result));
+
+        tx_execution_result
     }
 }
@@ -1,13 +1,27 @@
 use starknet_api::transaction::TransactionVersion;
 
+use crate::context::BlockContext;
 use crate::execution::call_info::CallInfo;
 use crate::execution::contract_class::ContractClass;
 use crate::transaction::errors::TransactionExecutionError;
+use crate::transaction::objects::TransactionExecutionResult;
 
 pub fn update_remaining_gas(remaining_gas: &mut u64, call_info: &CallInfo) {
     *remaining_gas -= call_info.execution.gas_consumed;
 }
 
+/// Replaces the result of an execution that was aborted (see `CancellationToken`) with an
+/// `ExecutionAborted` error, as the execution may have been stopped at any point.
+pub fn abort_if_cancelled<T>(
+    block_context: &BlockContext,
+    result: TransactionExecutionResult<T>,
+) -> TransactionExecutionResult<T> {
+    if block_context.cancellation_token.is_cancelled() {
+        return Err(TransactionExecutionError::ExecutionAborted);
+    }
+    result
+}
+
 pub fn verify_contract_class_version(
     contract_class: &ContractClass,
     declare_version: TransactionVersion,
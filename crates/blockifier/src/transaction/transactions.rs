@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use starknet_api::calldata;
-use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_api::hash::StarkFelt;
 use starknet_api::transaction::{
@@ -18,6 +19,7 @@ use crate::execution::entry_point::{
     CallEntryPoint, CallType, ConstructorContext, EntryPointExecutionContext,
 };
 use crate::execution::execution_utils::execute_deployment;
+use crate::execution::sierra_compilation::SierraCompiler;
 use crate::state::cached_state::{CachedState, TransactionalState};
 use crate::state::errors::StateError;
 use crate::state::state_api::{State, StateReader};
@@ -28,6 +30,7 @@ use crate::transaction::objects::{
     TransactionExecutionInfo, TransactionExecutionResult, TransactionInfo, TransactionInfoCreator,
 };
 use crate::transaction::transaction_utils::{update_remaining_gas, verify_contract_class_version};
+use crate::versioned_constants::DeclareLimits;
 
 #[cfg(test)]
 #[path = "transactions_test.rs"]
@@ -142,12 +145,51 @@ impl DeclareTransaction {
         Self::create(declare_tx, tx_hash, class_info, true)
     }
 
+    /// Creates a Declare transaction of the given Sierra class, which is compiled in-process
+    /// instead of being provided by the caller.
+    pub fn new_from_sierra(
+        declare_tx: starknet_api::transaction::DeclareTransaction,
+        tx_hash: TransactionHash,
+        sierra_contract_class: SierraContractClass,
+        abi_length: usize,
+        sierra_compiler: &SierraCompiler,
+        only_query: bool,
+    ) -> TransactionExecutionResult<Self> {
+        let class_info = sierra_compiler.compile_class_info(
+            declare_tx.class_hash(),
+            sierra_contract_class,
+            abi_length,
+        )?;
+        Self::create(declare_tx, tx_hash, class_info, only_query)
+    }
+
     implement_inner_tx_getter_calls!((class_hash, ClassHash), (signature, TransactionSignature));
 
     pub fn tx(&self) -> &starknet_api::transaction::DeclareTransaction {
         &self.tx
     }
 
+    /// Verifies that the given compiled class hash is the hash of the declared (Cairo 1) class;
+    /// otherwise, the class commitment would not match the class.
+    fn verify_compiled_class_hash(
+        &self,
+        compiled_class_hash: CompiledClassHash,
+    ) -> TransactionExecutionResult<()> {
+        let ContractClass::V1(contract_class) = self.contract_class() else {
+            unreachable!("Declare transactions of version 2 and up declare Cairo 1 classes.");
+        };
+        let actual_compiled_class_hash = contract_class.compiled_class_hash();
+        if actual_compiled_class_hash != compiled_class_hash {
+            return Err(TransactionExecutionError::CompiledClassHashMismatch {
+                class_hash: self.class_hash(),
+                expected: compiled_class_hash,
+                actual: actual_compiled_class_hash,
+            });
+        }
+
+        Ok(())
+    }
+
     pub fn tx_hash(&self) -> TransactionHash {
         self.tx_hash
     }
@@ -166,9 +208,10 @@ impl<S: State> Executable<S> for DeclareTransaction {
         &self,
         state: &mut S,
         _resources: &mut ExecutionResources,
-        _context: &mut EntryPointExecutionContext,
+        context: &mut EntryPointExecutionContext,
         _remaining_gas: &mut u64,
     ) -> TransactionExecutionResult<Option<CallInfo>> {
+        verify_declare_limits(&self.class_info, &context.versioned_constants().declare_limits)?;
         let class_hash = self.class_hash();
 
         match &self.tx {
@@ -188,6 +231,9 @@ impl<S: State> Executable<S> for DeclareTransaction {
             }) => {
                 match state.get_compiled_contract_class(class_hash) {
                     Err(StateError::UndeclaredClassHash(_)) => {
+                        if !context.tx_context.block_context.skip_compiled_class_hash_verification {
+                            self.verify_compiled_class_hash(*compiled_class_hash)?;
+                        }
                         // Class is undeclared; declare it.
                         state.set_contract_class(class_hash, self.contract_class())?;
                         state.set_compiled_class_hash(class_hash, *compiled_class_hash)?;
@@ -205,6 +251,41 @@ impl<S: State> Executable<S> for DeclareTransaction {
     }
 }
 
+/// Verifies that the declared class is within the limits of the current version.
+fn verify_declare_limits(
+    class_info: &ClassInfo,
+    declare_limits: &DeclareLimits,
+) -> TransactionExecutionResult<()> {
+    let bytecode_length = class_info.bytecode_length();
+    if bytecode_length > declare_limits.max_bytecode_length {
+        return Err(TransactionExecutionError::DeclaredBytecodeTooLong {
+            bytecode_length,
+            max_bytecode_length: declare_limits.max_bytecode_length,
+        });
+    }
+
+    let sierra_program_length = class_info.sierra_program_length();
+    if sierra_program_length > declare_limits.max_sierra_program_length {
+        return Err(TransactionExecutionError::DeclaredSierraProgramTooLong {
+            sierra_program_length,
+            max_sierra_program_length: declare_limits.max_sierra_program_length,
+        });
+    }
+
+    if let Some(sierra_version) = class_info.sierra_version() {
+        let DeclareLimits { min_sierra_version, max_sierra_version, .. } = *declare_limits;
+        if !(min_sierra_version..=max_sierra_version).contains(&sierra_version) {
+            return Err(TransactionExecutionError::UnsupportedSierraVersion {
+                sierra_version,
+                min_sierra_version,
+                max_sierra_version,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 impl TransactionInfoCreator for DeclareTransaction {
     fn create_tx_info(&self) -> TransactionInfo {
         // TODO(Nir, 01/11/2023): Consider to move this (from all get_tx_info methods).
@@ -511,17 +592,9 @@ impl<S: State> Executable<S> for L1HandlerTransaction {
         let storage_address = tx.contract_address;
         let class_hash = state.get_class_hash_at(storage_address)?;
         let selector = tx.entry_point_selector;
-        let execute_call = CallEntryPoint {
-            entry_point_type: EntryPointType::L1Handler,
-            entry_point_selector: selector,
-            calldata: Calldata(Arc::clone(&tx.calldata.0)),
-            class_hash: None,
-            code_address: None,
-            storage_address,
-            caller_address: ContractAddress::default(),
-            call_type: CallType::Call,
-            initial_gas: *remaining_gas,
-        };
+        let calldata = Calldata(Arc::clone(&tx.calldata.0));
+        let execute_call = CallEntryPoint::l1_handler(storage_address, selector, calldata)
+            .with_initial_gas(*remaining_gas);
 
         execute_call.execute(state, resources, context).map(Some).map_err(|error| {
             TransactionExecutionError::ExecutionError {
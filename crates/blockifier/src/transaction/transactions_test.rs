@@ -28,7 +28,9 @@ use crate::abi::sierra_types::next_storage_key;
 use crate::context::{BlockContext, ChainInfo, FeeTokenAddresses, TransactionContext};
 use crate::execution::call_info::{
     CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
+    StorageAccess,
 };
+use crate::execution::contract_class::SierraVersion;
 use crate::execution::entry_point::{CallEntryPoint, CallType};
 use crate::execution::errors::{ConstructorEntryPointExecutionError, EntryPointExecutionError};
 use crate::execution::execution_utils::{felt_to_stark_felt, stark_felt_to_felt};
@@ -55,7 +57,7 @@ use crate::test_utils::{
     CURRENT_BLOCK_TIMESTAMP, CURRENT_BLOCK_TIMESTAMP_FOR_VALIDATE, MAX_FEE, MAX_L1_GAS_AMOUNT,
     MAX_L1_GAS_PRICE, TEST_SEQUENCER_ADDRESS,
 };
-use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::account_transaction::{AccountTransaction, NonceCheckPolicy};
 use crate::transaction::constants;
 use crate::transaction::errors::{
     TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
@@ -72,10 +74,10 @@ use crate::transaction::test_utils::{
 };
 use crate::transaction::transaction_types::TransactionType;
 use crate::transaction::transactions::{ExecutableTransaction, L1HandlerTransaction};
-use crate::versioned_constants::VersionedConstants;
+use crate::versioned_constants::{DeclareLimits, VersionedConstants};
 use crate::{
     check_transaction_execution_error_for_custom_hint,
-    check_transaction_execution_error_for_invalid_scenario, declare_tx_args,
+    check_transaction_execution_error_for_invalid_scenario, compiled_class_hash, declare_tx_args,
     deploy_account_tx_args, invoke_tx_args, nonce, retdata,
 };
 
@@ -229,6 +231,23 @@ fn expected_fee_transfer_call_info(
             sequencer_balance_key_low,
             sequencer_balance_key_high,
         ]),
+        // Each balance is read, then written.
+        storage_accesses: vec![
+            StorageAccess::read(sender_balance_key_low, stark_felt!(BALANCE)),
+            StorageAccess::read(sender_balance_key_high, stark_felt!(0_u8)),
+            StorageAccess::write(sender_balance_key_low, stark_felt!(BALANCE - actual_fee.0)),
+            StorageAccess::write(sender_balance_key_high, stark_felt!(0_u8)),
+            StorageAccess::read(sequencer_balance_key_low, stark_felt!(0_u8)),
+            StorageAccess::read(sequencer_balance_key_high, stark_felt!(0_u8)),
+            StorageAccess::write(sequencer_balance_key_low, lsb_expected_amount),
+            StorageAccess::write(sequencer_balance_key_high, msb_expected_amount),
+        ],
+        syscall_counter: HashMap::from([
+            (SyscallSelector::GetCallerAddress, 1),
+            (SyscallSelector::StorageRead, 4),
+            (SyscallSelector::StorageWrite, 4),
+            (SyscallSelector::EmitEvent, 1),
+        ]),
         ..Default::default()
     })
 }
@@ -414,11 +433,19 @@ fn test_invoke_tx(
         initial_gas: tx_initial_gas() - expected_arguments.validate_gas_consumed,
         ..expected_validate_call_info.as_ref().unwrap().call.clone()
     };
-    let expected_return_result_retdata = Retdata(expected_return_result_calldata);
+    let expected_return_result_retdata = Retdata::from(expected_return_result_calldata);
+    // The Cairo 1 account also reads its caller address, via the execution info.
+    let expected_execute_syscall_counter = match account_cairo_version {
+        CairoVersion::Cairo0 => HashMap::from([(SyscallSelector::CallContract, 1)]),
+        CairoVersion::Cairo1 => HashMap::from([
+            (SyscallSelector::GetExecutionInfo, 1),
+            (SyscallSelector::CallContract, 1),
+        ]),
+    };
     let expected_execute_call_info = Some(CallInfo {
         call: expected_execute_call,
         execution: CallExecution {
-            retdata: Retdata(expected_return_result_retdata.0.clone()),
+            retdata: expected_return_result_retdata.clone(),
             gas_consumed: expected_arguments.execute_gas_consumed,
             ..Default::default()
         },
@@ -429,6 +456,7 @@ fn test_invoke_tx(
             resources: ExecutionResources { n_steps: 23, n_memory_holes: 0, ..Default::default() },
             ..Default::default()
         }],
+        syscall_counter: expected_execute_syscall_counter,
         ..Default::default()
     });
 
@@ -989,7 +1017,12 @@ fn test_invalid_nonce(
         account_invoke_tx(invoke_tx_args! { nonce: invalid_nonce, ..valid_invoke_tx_args.clone() });
     let invalid_tx_context = block_context.to_tx_context(&invalid_tx);
     let pre_validation_err = invalid_tx
-        .perform_pre_validation_stage(&mut transactional_state, &invalid_tx_context, false, true)
+        .perform_pre_validation_stage(
+            &mut transactional_state,
+            &invalid_tx_context,
+            false,
+            NonceCheckPolicy::Strict,
+        )
         .unwrap_err();
 
     // Test error.
@@ -1000,7 +1033,8 @@ fn test_invalid_nonce(
         (valid_invoke_tx_args.sender_address, Nonce::default(), invalid_nonce)
     );
 
-    // Non-strict.
+    // Future nonces allowed.
+    let nonce_check = NonceCheckPolicy::AllowFutureUpTo(1);
 
     // Positive flow: account nonce = 0, incoming tx nonce = 1.
     let valid_nonce = nonce!(1_u8);
@@ -1009,7 +1043,12 @@ fn test_invalid_nonce(
 
     let valid_tx_context = block_context.to_tx_context(&valid_tx);
     valid_tx
-        .perform_pre_validation_stage(&mut transactional_state, &valid_tx_context, false, false)
+        .perform_pre_validation_stage(
+            &mut transactional_state,
+            &valid_tx_context,
+            false,
+            nonce_check,
+        )
         .unwrap();
 
     // Negative flow: account nonce = 1, incoming tx nonce = 0.
@@ -1018,7 +1057,12 @@ fn test_invalid_nonce(
         account_invoke_tx(invoke_tx_args! { nonce: invalid_nonce, ..valid_invoke_tx_args.clone() });
     let invalid_tx_context = block_context.to_tx_context(&invalid_tx);
     let pre_validation_err = invalid_tx
-        .perform_pre_validation_stage(&mut transactional_state, &invalid_tx_context, false, false)
+        .perform_pre_validation_stage(
+            &mut transactional_state,
+            &invalid_tx_context,
+            false,
+            nonce_check,
+        )
         .unwrap_err();
 
     // Test error.
@@ -1028,6 +1072,35 @@ fn test_invalid_nonce(
         if (address, account_nonce, incoming_tx_nonce) ==
         (valid_invoke_tx_args.sender_address, nonce!(1_u8), invalid_nonce)
     );
+
+    // Negative flow: account nonce = 1, incoming tx nonce = 3 (too far ahead).
+    let invalid_nonce = nonce!(3_u8);
+    let invalid_tx =
+        account_invoke_tx(invoke_tx_args! { nonce: invalid_nonce, ..valid_invoke_tx_args.clone() });
+    let invalid_tx_context = block_context.to_tx_context(&invalid_tx);
+    let pre_validation_err = invalid_tx
+        .perform_pre_validation_stage(
+            &mut transactional_state,
+            &invalid_tx_context,
+            false,
+            nonce_check,
+        )
+        .unwrap_err();
+    assert_matches!(pre_validation_err, TransactionPreValidationError::InvalidNonce { .. });
+
+    // Skipped check: any nonce is accepted, and the account nonce is incremented.
+    invalid_tx
+        .perform_pre_validation_stage(
+            &mut transactional_state,
+            &invalid_tx_context,
+            false,
+            NonceCheckPolicy::Skip,
+        )
+        .unwrap();
+    assert_eq!(
+        transactional_state.get_nonce_at(valid_invoke_tx_args.sender_address).unwrap(),
+        nonce!(2_u8)
+    );
 }
 
 /// Expected CallInfo for `__validate__` call in a declare transaction.
@@ -1112,6 +1185,7 @@ fn test_declare_tx(
             version: tx_version,
             resource_bounds: l1_resource_bounds(MAX_L1_GAS_AMOUNT, MAX_L1_GAS_PRICE),
             class_hash,
+            compiled_class_hash: empty_contract.get_compiled_class_hash(),
         },
         class_info.clone(),
     );
@@ -1199,6 +1273,98 @@ fn test_declare_tx(
     assert_eq!(contract_class_from_state, class_info.contract_class());
 }
 
+#[test]
+fn test_declare_limits() {
+    let empty_contract = FeatureContract::Empty(CairoVersion::Cairo1);
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let sierra_version = SierraVersion(1, 4, 0);
+    let class_info = calculate_class_info_for_testing(empty_contract.get_class())
+        .with_sierra_version(sierra_version);
+    let bytecode_length = class_info.bytecode_length();
+    let sierra_program_length = class_info.sierra_program_length();
+    let declare = |declare_limits: DeclareLimits| {
+        let mut block_context = BlockContext::create_for_account_testing();
+        block_context.versioned_constants.declare_limits = declare_limits;
+        let state = &mut test_state(&block_context.chain_info, BALANCE, &[(account, 1)]);
+        let account_tx = declare_tx(
+            declare_tx_args! {
+                max_fee: Fee(MAX_FEE),
+                sender_address: account.get_instance_address(0),
+                version: TransactionVersion::TWO,
+                class_hash: empty_contract.get_class_hash(),
+                compiled_class_hash: empty_contract.get_compiled_class_hash(),
+            },
+            class_info.clone(),
+        );
+        account_tx.execute(state, &block_context, true, true).map(|_| ())
+    };
+
+    // A class exactly at the limits is declared.
+    let tight_limits = DeclareLimits {
+        max_bytecode_length: bytecode_length,
+        max_sierra_program_length: sierra_program_length,
+        min_sierra_version: sierra_version,
+        max_sierra_version: sierra_version,
+    };
+    declare(tight_limits).unwrap();
+
+    // Oversized classes and unsupported Sierra versions are rejected.
+    assert_matches!(
+        declare(DeclareLimits { max_bytecode_length: bytecode_length - 1, ..tight_limits })
+            .unwrap_err(),
+        TransactionExecutionError::DeclaredBytecodeTooLong { .. }
+    );
+    assert_matches!(
+        declare(DeclareLimits {
+            max_sierra_program_length: sierra_program_length - 1,
+            ..tight_limits
+        })
+        .unwrap_err(),
+        TransactionExecutionError::DeclaredSierraProgramTooLong { .. }
+    );
+    assert_matches!(
+        declare(DeclareLimits { min_sierra_version: SierraVersion(1, 5, 0), ..tight_limits })
+            .unwrap_err(),
+        TransactionExecutionError::UnsupportedSierraVersion { .. }
+    );
+}
+
+#[rstest]
+fn test_declare_compiled_class_hash_verification(
+    #[values(false, true)] skip_compiled_class_hash_verification: bool,
+) {
+    let empty_contract = FeatureContract::Empty(CairoVersion::Cairo1);
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let mut block_context = BlockContext::create_for_account_testing();
+    block_context.skip_compiled_class_hash_verification = skip_compiled_class_hash_verification;
+    let state = &mut test_state(&block_context.chain_info, BALANCE, &[(account, 1)]);
+
+    // Declare the class with a compiled class hash that does not match it.
+    let wrong_compiled_class_hash = compiled_class_hash!(1_u8);
+    let account_tx = declare_tx(
+        declare_tx_args! {
+            max_fee: Fee(MAX_FEE),
+            sender_address: account.get_instance_address(0),
+            version: TransactionVersion::TWO,
+            class_hash: empty_contract.get_class_hash(),
+            compiled_class_hash: wrong_compiled_class_hash,
+        },
+        calculate_class_info_for_testing(empty_contract.get_class()),
+    );
+    let result = account_tx.execute(state, &block_context, true, true);
+
+    if skip_compiled_class_hash_verification {
+        result.unwrap();
+    } else {
+        assert_matches!(
+            result.unwrap_err(),
+            TransactionExecutionError::CompiledClassHashMismatch { expected, actual, .. }
+            if expected == wrong_compiled_class_hash
+                && actual == empty_contract.get_compiled_class_hash()
+        );
+    }
+}
+
 #[rstest]
 fn test_deploy_account_tx(
     #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
@@ -1729,7 +1895,7 @@ fn test_l1_handler(#[values(false, true)] use_kzg_da: bool) {
             initial_gas: tx_initial_gas(),
         },
         execution: CallExecution {
-            retdata: Retdata(vec![value]),
+            retdata: retdata![value],
             gas_consumed: 11750,
             ..Default::default()
         },
@@ -1739,6 +1905,8 @@ fn test_l1_handler(#[values(false, true)] use_kzg_da: bool) {
             builtin_instance_counter: HashMap::from([(RANGE_CHECK_BUILTIN_NAME.to_string(), 6)]),
         },
         accessed_storage_keys: HashSet::from_iter(vec![accessed_storage_key]),
+        storage_accesses: vec![StorageAccess::write(accessed_storage_key, value)],
+        syscall_counter: HashMap::from([(SyscallSelector::StorageWrite, 1)]),
         ..Default::default()
     };
 
@@ -1746,7 +1914,7 @@ fn test_l1_handler(#[values(false, true)] use_kzg_da: bool) {
     // TODO(Nimrod, 1/5/2024): Change these hard coded values to match to the transaction resources
     // (currently matches only starknet resources).
     let expected_gas = match use_kzg_da {
-        true => GasVector { l1_gas: 16023, l1_data_gas: 128 },
+        true => GasVector { l1_gas: 16023, l1_data_gas: 128, ..Default::default() },
         false => GasVector::from_l1_gas(17675),
     };
     let expected_da_gas = match use_kzg_da {
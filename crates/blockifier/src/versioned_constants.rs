@@ -11,9 +11,13 @@ use once_cell::sync::Lazy;
 use serde::de::Error as DeserializationError;
 use serde::{Deserialize, Deserializer};
 use serde_json::{Map, Number, Value};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::transaction::TransactionVersion;
 use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
 use thiserror::Error;
 
+use crate::execution::contract_class::SierraVersion;
 use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
 use crate::execution::errors::PostExecutionError;
 use crate::execution::execution_utils::poseidon_hash_many_cost;
@@ -28,11 +32,31 @@ pub mod test;
 
 pub(crate) const DEFAULT_CONSTANTS_JSON: &str =
     include_str!("../resources/versioned_constants.json");
-static DEFAULT_CONSTANTS: Lazy<VersionedConstants> = Lazy::new(|| {
-    serde_json::from_str(DEFAULT_CONSTANTS_JSON)
-        .expect("Versioned constants JSON file is malformed")
+static DEFAULT_CONSTANTS: Lazy<VersionedConstants> =
+    Lazy::new(|| parse_embedded_constants(DEFAULT_CONSTANTS_JSON));
+static VERSIONED_CONSTANTS_13_0: Lazy<VersionedConstants> = Lazy::new(|| {
+    parse_embedded_constants(include_str!("../resources/versioned_constants_13_0.json"))
+});
+static VERSIONED_CONSTANTS_13_1: Lazy<VersionedConstants> = Lazy::new(|| {
+    parse_embedded_constants(include_str!("../resources/versioned_constants_13_1.json"))
+});
+static VERSIONED_CONSTANTS_13_1_1: Lazy<VersionedConstants> = Lazy::new(|| {
+    parse_embedded_constants(include_str!("../resources/versioned_constants_13_1_1.json"))
 });
 
+fn parse_embedded_constants(json: &str) -> VersionedConstants {
+    serde_json::from_str(json).expect("Versioned constants JSON file is malformed")
+}
+
+/// The Starknet protocol versions for which versioned constants are shipped with the Blockifier.
+#[derive(Clone, Copy, Debug, EnumIter, Eq, Hash, PartialEq)]
+pub enum StarknetVersion {
+    V0_13_0,
+    V0_13_1,
+    V0_13_1_1,
+    Latest,
+}
+
 pub type ResourceCost = Ratio<u128>;
 
 /// Contains constants for the Blockifier that may vary between versions.
@@ -41,6 +65,10 @@ pub type ResourceCost = Ratio<u128>;
 #[derive(Clone, Debug, Default, Deserialize)]
 pub struct VersionedConstants {
     // Limits.
+    #[serde(default)]
+    pub declare_limits: DeclareLimits,
+    #[serde(default)]
+    pub initial_gas_overrides: InitialGasOverrides,
     #[serde(default = "EventLimits::max")]
     pub tx_event_limits: EventLimits,
     pub invoke_tx_max_n_steps: u32,
@@ -48,6 +76,18 @@ pub struct VersionedConstants {
     pub l2_resource_gas_costs: L2ResourceGasCosts,
     pub max_recursion_depth: usize,
     pub validate_max_n_steps: u32,
+    // Syscalls that may not be invoked in validate mode. Calls to other contracts are forbidden in
+    // this mode regardless.
+    #[serde(default = "default_validate_forbidden_syscalls")]
+    pub validate_forbidden_syscalls: HashSet<SyscallSelector>,
+
+    // Whether to allocate aliases at the end of each block, and compress its state diff by them.
+    #[serde(default)]
+    pub enable_stateful_compression: bool,
+    // Whether a failed inner call is reverted and returned to its caller (which may handle the
+    // failure), rather than failing all of its callers.
+    #[serde(default)]
+    pub enable_reverts: bool,
 
     // Cairo OS constants.
     // Note: if loaded from a json file, there are some assumptions made on its structure.
@@ -70,12 +110,45 @@ impl VersionedConstants {
         &DEFAULT_CONSTANTS
     }
 
+    /// Get the constants of the given Starknet version; used to execute blocks of past versions.
+    pub fn get(version: StarknetVersion) -> &'static Self {
+        match version {
+            StarknetVersion::V0_13_0 => &VERSIONED_CONSTANTS_13_0,
+            StarknetVersion::V0_13_1 => &VERSIONED_CONSTANTS_13_1,
+            StarknetVersion::V0_13_1_1 => &VERSIONED_CONSTANTS_13_1_1,
+            StarknetVersion::Latest => Self::latest_constants(),
+        }
+    }
+
     /// Returns the initial gas of any transaction to run with.
     pub fn tx_initial_gas(&self) -> u64 {
         let os_consts = &self.os_constants;
         os_consts.gas_costs.initial_gas_cost - os_consts.gas_costs.transaction_gas_cost
     }
 
+    /// Returns the initial gas of a transaction of the given version, whose calls are of the given
+    /// entry point type (see `InitialGasOverrides`).
+    pub fn initial_gas(
+        &self,
+        entry_point_type: EntryPointType,
+        tx_version: TransactionVersion,
+    ) -> u64 {
+        let overrides = &self.initial_gas_overrides;
+        let by_tx_version = match entry_point_type {
+            // L1 handler transactions are unversioned.
+            EntryPointType::L1Handler => None,
+            _ if tx_version < TransactionVersion::THREE => overrides.legacy_tx,
+            _ => None,
+        };
+        let by_entry_point_type = match entry_point_type {
+            EntryPointType::Constructor => overrides.constructor,
+            EntryPointType::External => overrides.external,
+            EntryPointType::L1Handler => overrides.l1_handler,
+        };
+
+        by_tx_version.or(by_entry_point_type).unwrap_or_else(|| self.tx_initial_gas())
+    }
+
     pub fn vm_resource_fee_cost(&self) -> &HashMap<String, ResourceCost> {
         &self.vm_resource_fee_cost
     }
@@ -231,6 +304,44 @@ pub struct EventLimits {
     pub max_n_emitted_events: usize,
 }
 
+/// Limits on the classes declared by transactions; unlimited by default.
+/// Classes of an unknown Sierra version are not checked against the supported range.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+pub struct DeclareLimits {
+    pub max_bytecode_length: usize,
+    pub max_sierra_program_length: usize,
+    pub min_sierra_version: SierraVersion,
+    pub max_sierra_version: SierraVersion,
+}
+
+impl Default for DeclareLimits {
+    fn default() -> Self {
+        Self {
+            max_bytecode_length: usize::MAX,
+            max_sierra_program_length: usize::MAX,
+            min_sierra_version: SierraVersion::MIN,
+            max_sierra_version: SierraVersion::MAX,
+        }
+    }
+}
+
+/// Overrides of the initial gas of transactions; unset values default to `tx_initial_gas`.
+/// The calls of account transactions are external, except for the constructor call of
+/// deploy-account transactions.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+pub struct InitialGasOverrides {
+    pub constructor: Option<u64>,
+    pub external: Option<u64>,
+    pub l1_handler: Option<u64>,
+    /// Of account transactions of versions 0-2, which do not bound their L2 gas; takes precedence
+    /// over the entry point type.
+    pub legacy_tx: Option<u64>,
+}
+
+fn default_validate_forbidden_syscalls() -> HashSet<SyscallSelector> {
+    HashSet::from([SyscallSelector::GetBlockHash, SyscallSelector::GetSequencerAddress])
+}
+
 impl EventLimits {
     fn max() -> Self {
         Self {
@@ -241,6 +352,10 @@ impl EventLimits {
     }
 }
 
+/// Syscalls that the versioned constants of Starknet 0.13.1 and older do not define.
+const SYSCALLS_ADDED_AFTER_V0_13_1: [SyscallSelector; 2] =
+    [SyscallSelector::Sha256ProcessBlock, SyscallSelector::VerifySignature];
+
 #[derive(Clone, Debug, Default, Deserialize)]
 // Serde trick for adding validations via a customr deserializer, without forgoing the derive.
 // See: https://github.com/serde-rs/serde/issues/1220.
@@ -276,7 +391,9 @@ impl OsResources {
         }
 
         for syscall_handler in SyscallSelector::iter() {
-            if !self.execute_syscalls.contains_key(&syscall_handler) {
+            if !self.execute_syscalls.contains_key(&syscall_handler)
+                && !SYSCALLS_ADDED_AFTER_V0_13_1.contains(&syscall_handler)
+            {
                 return Err(DeserializationError::custom(format!(
                     "ValidationError: os_resources.execute_syscalls are missing syscall handler: \
                      {syscall_handler:?}"
@@ -435,6 +552,9 @@ pub struct GasCosts {
     pub secp256r1_new_gas_cost: u64,
     pub keccak_gas_cost: u64,
     pub keccak_round_cost_gas_cost: u64,
+    // Syscalls added after Starknet 0.13.1; unset, hence unsupported, in older versions.
+    pub sha256_process_block_gas_cost: Option<u64>,
+    pub verify_signature_gas_cost: Option<u64>,
 }
 
 // Below, serde first deserializes the json into a regular IndexMap wrapped by the newtype
@@ -98,6 +98,11 @@ fn test_default_values() {
 
     assert_eq!(versioned_constants.tx_event_limits, EventLimits::max());
     assert_eq!(versioned_constants.l2_resource_gas_costs, L2ResourceGasCosts::default());
+    assert_eq!(versioned_constants.initial_gas_overrides, InitialGasOverrides::default());
+    assert_eq!(
+        versioned_constants.validate_forbidden_syscalls,
+        HashSet::from([SyscallSelector::GetBlockHash, SyscallSelector::GetSequencerAddress])
+    );
 
     // Calldata factor was initialized as 0, and did not affect the expected result, even if
     // calldata length is nonzero.
@@ -116,6 +121,32 @@ fn test_default_values() {
     );
 }
 
+#[test]
+fn test_initial_gas() {
+    let mut json_value = get_json_value_without_dafaults();
+    json_value.as_object_mut().unwrap().insert(
+        "initial_gas_overrides".to_string(),
+        serde_json::json!({"constructor": 100, "l1_handler": 200, "legacy_tx": 300}),
+    );
+    let versioned_constants: VersionedConstants = serde_json::from_value(json_value).unwrap();
+    let tx_initial_gas = versioned_constants.tx_initial_gas();
+
+    for (entry_point_type, tx_version, expected_initial_gas) in [
+        (EntryPointType::Constructor, TransactionVersion::THREE, 100),
+        (EntryPointType::External, TransactionVersion::THREE, tx_initial_gas),
+        // Legacy transactions are overridden regardless of the entry point type.
+        (EntryPointType::Constructor, TransactionVersion::ONE, 300),
+        (EntryPointType::External, TransactionVersion::ZERO, 300),
+        // L1 handler transactions are unversioned.
+        (EntryPointType::L1Handler, TransactionVersion::ZERO, 200),
+    ] {
+        assert_eq!(
+            versioned_constants.initial_gas(entry_point_type, tx_version),
+            expected_initial_gas
+        );
+    }
+}
+
 #[test]
 fn test_string_inside_composed_field() {
     let json_data = r#"
@@ -201,3 +232,36 @@ fn test_old_json_parsing() {
             .unwrap_or_else(|_| panic!("Versioned constants JSON file {file:#?} is malformed"));
     }
 }
+
+#[test]
+fn test_constants_by_version() {
+    for version in StarknetVersion::iter() {
+        VersionedConstants::get(version);
+    }
+    assert!(std::ptr::eq(
+        VersionedConstants::get(StarknetVersion::Latest),
+        VersionedConstants::latest_constants()
+    ));
+    assert_eq!(VersionedConstants::get(StarknetVersion::V0_13_0).invoke_tx_max_n_steps, 3_000_000);
+    assert_eq!(VersionedConstants::get(StarknetVersion::V0_13_1).invoke_tx_max_n_steps, 4_000_000);
+
+    // Syscalls added after 0.13.1 are only defined in later versions.
+    let gas_costs = |version| &VersionedConstants::get(version).os_constants.gas_costs;
+    assert!(gas_costs(StarknetVersion::Latest).verify_signature_gas_cost.is_some());
+    assert!(gas_costs(StarknetVersion::V0_13_1_1).verify_signature_gas_cost.is_none());
+    assert!(gas_costs(StarknetVersion::Latest).sha256_process_block_gas_cost.is_some());
+    assert!(gas_costs(StarknetVersion::V0_13_1_1).sha256_process_block_gas_cost.is_none());
+
+    // The cost of a SHA-256 block includes its bitwise builtin usage.
+    let latest_gas_costs = gas_costs(StarknetVersion::Latest);
+    let bitwise_builtin_gas_cost = 594;
+    assert_eq!(
+        latest_gas_costs.sha256_process_block_gas_cost,
+        Some(
+            1852 * latest_gas_costs.step_gas_cost
+                + 65 * latest_gas_costs.range_check_gas_cost
+                + 1115 * bitwise_builtin_gas_cost
+                + latest_gas_costs.syscall_base_gas_cost
+        )
+    );
+}
@@ -85,6 +85,8 @@ pub enum NativeBlockifierInputError {
     MaxValidateStepsPerTxOutOfRange(u32),
     #[error(transparent)]
     InvalidNativeBlockifierInputError(#[from] InvalidNativeBlockifierInputError),
+    #[error("Invalid time budget: {0} seconds.")]
+    InvalidTimeBudget(f64),
     #[error(transparent)]
     ParseError(#[from] ParseError),
     #[error(transparent)]
@@ -1,10 +1,13 @@
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use blockifier::blockifier::block::{
     pre_process_block as pre_process_block_blockifier, BlockInfo, BlockNumberHashPair, GasPrices,
 };
 use blockifier::blockifier::config::TransactionExecutorConfig;
-use blockifier::blockifier::transaction_executor::{TransactionExecutor, TransactionExecutorError};
+use blockifier::blockifier::transaction_executor::{
+    BlockExecutionSummary, TransactionExecutor, TransactionExecutorError,
+};
 use blockifier::bouncer::BouncerConfig;
 use blockifier::context::{BlockContext, ChainInfo, FeeTokenAddresses};
 use blockifier::execution::call_info::CallInfo;
@@ -145,6 +148,9 @@ impl PyBlockExecutor {
             bouncer_config: bouncer_config.into(),
             tx_executor_config: TransactionExecutorConfig {
                 concurrency_config: concurrency_config.into(),
+                skip_duplicate_txs: false,
+                collect_os_input: false,
+                reject_query_txs: false,
             },
             general_config,
             versioned_constants,
@@ -215,14 +221,24 @@ impl PyBlockExecutor {
     }
 
     /// Executes the given transactions on the Blockifier state.
-    /// Stops if and when there is no more room in the block, and returns the executed transactions'
-    /// results as a PyList of (success (bool), serialized result (bytes)) tuples.
-    #[pyo3(signature = (txs_with_class_infos))]
+    /// Stops if and when there is no more room in the block, or the given time budget (in seconds)
+    /// is exhausted, and returns the executed transactions' results as a PyList of
+    /// (success (bool), serialized result (bytes)) tuples; the rest were not executed.
+    #[pyo3(signature = (txs_with_class_infos, time_budget_secs=None))]
     pub fn execute_txs(
         &mut self,
         txs_with_class_infos: Vec<(&PyAny, Option<PyClassInfo>)>,
-    ) -> Py<PyList> {
+        time_budget_secs: Option<f64>,
+    ) -> NativeBlockifierResult<Py<PyList>> {
         let charge_fee = true;
+        let time_budget = time_budget_secs
+            .map(|secs| {
+                Duration::try_from_secs_f64(secs)
+                    .map_err(|_| NativeBlockifierInputError::InvalidTimeBudget(secs))
+            })
+            .transpose()?;
+        self.tx_executor().execution_deadline =
+            time_budget.map(|time_budget| Instant::now() + time_budget);
 
         // Parse Py transactions.
         let (tx_types, txs): (Vec<String>, Vec<Transaction>) = txs_with_class_infos
@@ -271,22 +287,33 @@ impl PyBlockExecutor {
                     (success, PyBytes::new(py, &execution_result).into())
                 })
                 .collect();
-            PyList::new(py, py_serialized_results).into()
+            Ok(PyList::new(py, py_serialized_results).into())
         })
     }
 
-    /// Returns the state diff and a list of contract class hash with the corresponding list of
-    /// visited segment values.
-    pub fn finalize(&mut self) -> NativeBlockifierResult<(PyStateDiff, PyVisitedSegmentsMapping)> {
+    /// Returns the state diff, the compressed state diff (if stateful compression is enabled) and
+    /// a list of contract class hash with the corresponding list of visited segment values.
+    pub fn finalize(
+        &mut self,
+    ) -> NativeBlockifierResult<(PyStateDiff, Option<PyStateDiff>, PyVisitedSegmentsMapping)> {
         log::debug!("Finalizing execution...");
-        let (commitment_state_diff, visited_pcs) = self.tx_executor().finalize()?;
-        let visited_pcs = visited_pcs
+        let BlockExecutionSummary {
+            state_diff: commitment_state_diff,
+            compressed_state_diff,
+            visited_segments,
+            ..
+        } = self.tx_executor().finalize()?;
+        let visited_pcs = visited_segments
             .into_iter()
             .map(|(class_hash, class_visited_pcs_vec)| {
                 (PyFelt::from(class_hash), class_visited_pcs_vec)
             })
             .collect();
-        let finalized_state = (PyStateDiff::from(commitment_state_diff), visited_pcs);
+        let finalized_state = (
+            PyStateDiff::from(commitment_state_diff),
+            compressed_state_diff.map(PyStateDiff::from),
+            visited_pcs,
+        );
         log::debug!("Finalized execution.");
 
         Ok(finalized_state)
@@ -391,6 +418,9 @@ impl PyBlockExecutor {
             },
             tx_executor_config: TransactionExecutorConfig {
                 concurrency_config: concurrency_config.into(),
+                skip_duplicate_txs: false,
+                collect_os_input: false,
+                reject_query_txs: false,
             },
             storage: Box::new(PapyrusStorage::new_for_testing(
                 path,
@@ -463,6 +493,8 @@ impl TryFrom<PyOsConfig> for ChainInfo {
                     py_os_config.fee_token_address.0,
                 )?,
             },
+            signature_verifier: None,
+            execution_policy: None,
         })
     }
 }
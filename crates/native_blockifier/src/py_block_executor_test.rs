@@ -1,14 +1,21 @@
 use std::collections::HashMap;
 
+use assert_matches::assert_matches;
 use blockifier::execution::contract_class::{ContractClass, ContractClassV1};
+use blockifier::invoke_tx_args;
 use blockifier::state::state_api::StateReader;
+use blockifier::test_utils::contracts::FeatureContract;
+use blockifier::test_utils::{create_trivial_calldata, CairoVersion};
+use blockifier::transaction::test_utils::account_invoke_tx;
+use blockifier::transaction::transaction_execution::Transaction;
 use cached::Cached;
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use pretty_assertions::assert_eq;
 use starknet_api::class_hash;
-use starknet_api::core::ClassHash;
+use starknet_api::core::{ClassHash, Nonce};
 use starknet_api::hash::{StarkFelt, StarkHash};
 
+use crate::errors::{NativeBlockifierError, NativeBlockifierInputError};
 use crate::py_block_executor::{PyBlockExecutor, PyGeneralConfig};
 use crate::py_objects::PyConcurrencyConfig;
 use crate::py_state_diff::{PyBlockInfo, PyStateDiff};
@@ -60,6 +67,64 @@ fn global_contract_cache_update() {
     assert_eq!(block_executor.global_contract_cache.lock().cache_size(), 1);
 }
 
+#[test]
+fn execute_query_tx() {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let account_address = account.get_instance_address(0);
+
+    let temp_storage_path = tempfile::tempdir().unwrap().into_path();
+    let mut block_executor = PyBlockExecutor::create_for_testing(
+        PyConcurrencyConfig::default(),
+        PyGeneralConfig::default(),
+        temp_storage_path,
+        4000,
+    );
+    let contracts = [account, test_contract];
+    block_executor
+        .append_block(
+            0,
+            None,
+            PyBlockInfo::default(),
+            PyStateDiff {
+                address_to_class_hash: contracts
+                    .iter()
+                    .map(|contract| {
+                        (contract.get_instance_address(0).into(), contract.get_class_hash().into())
+                    })
+                    .collect(),
+                ..PyStateDiff::default()
+            },
+            HashMap::default(),
+            contracts
+                .iter()
+                .map(|contract| {
+                    let raw_class =
+                        serde_json::to_string(&contract.get_deprecated_contract_class()).unwrap();
+                    (contract.get_class_hash().into(), raw_class)
+                })
+                .collect(),
+        )
+        .unwrap();
+    block_executor
+        .setup_block_execution(PyBlockInfo { block_number: 1, ..PyBlockInfo::default() }, None)
+        .unwrap();
+
+    // Simulation and fee estimation go through the native executor, so it must not reject query
+    // transactions.
+    let tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        sender_address: account_address,
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        only_query: true,
+    }));
+    let tx_execution_info = block_executor.tx_executor().execute(&tx, true).unwrap();
+    assert!(!tx_execution_info.is_reverted());
+    assert_eq!(
+        block_executor.tx_executor().state.get_nonce_at(account_address).unwrap(),
+        Nonce(StarkFelt::ONE)
+    );
+}
+
 #[test]
 fn get_block_id() {
     let max_class_hash = [
@@ -78,3 +143,37 @@ fn get_block_id() {
         expected_max_class_hash_as_py_felt
     );
 }
+
+#[test]
+fn execute_txs_with_invalid_time_budget() {
+    let storage = MockStorage { block_number_to_class_hash: HashMap::default() };
+    let mut block_executor = PyBlockExecutor::create_for_testing_with_storage(storage);
+
+    for time_budget_secs in [-1.0, f64::NAN, f64::INFINITY] {
+        assert_matches!(
+            block_executor.execute_txs(vec![], Some(time_budget_secs)),
+            Err(NativeBlockifierError::NativeBlockifierInputError(
+                NativeBlockifierInputError::InvalidTimeBudget(_)
+            ))
+        );
+    }
+}
+
+#[test]
+fn finalize_returns_compressed_state_diff() {
+    for enable_stateful_compression in [false, true] {
+        let temp_storage_path = tempfile::tempdir().unwrap().into_path();
+        let mut block_executor = PyBlockExecutor::create_for_testing(
+            PyConcurrencyConfig::default(),
+            PyGeneralConfig::default(),
+            temp_storage_path,
+            4000,
+        );
+        block_executor.versioned_constants.enable_stateful_compression =
+            enable_stateful_compression;
+        block_executor.setup_block_execution(PyBlockInfo::default(), None).unwrap();
+
+        let (_state_diff, compressed_state_diff, _visited_pcs) = block_executor.finalize().unwrap();
+        assert_eq!(compressed_state_diff.is_some(), enable_stateful_compression);
+    }
+}